@@ -62,7 +62,9 @@ impl<'a> std::ops::Add<&'a NodeTags> for &'a NodeTags {
             .sorted_by_key(|(a, _)| a)
             .group_by(|(a, _)| a)
             .into_iter()
-            .map(|(cost, dims)| (*cost, dims.into_iter().fold(0.to_dim(), |acc, d| acc + &d.1)))
+            .map(|(cost, dims)| {
+                (cost.clone(), dims.into_iter().fold(0.to_dim(), |acc, d| acc + &d.1))
+            })
             .collect::<Vec<(Cost, TDim)>>();
         let profile = self.profile.unwrap_or(Duration::default())
             + other.profile.unwrap_or(Duration::default());
@@ -273,7 +275,7 @@ impl Annotations {
                     let cost = model.node(node_id).op.cost(&*inputs)?;
                     annotations.node_mut(NodeQId(prefix.into(), node_id)).cost = cost
                         .into_iter()
-                        .map(|(k, v)| (k, if k.is_compute() { v * &multiplier } else { v }))
+                        .map(|(k, v)| (k.clone(), if k.is_compute() { v * &multiplier } else { v }))
                         .collect();
 
                     let nested_subs = model.nested_models(node_id);
@@ -295,4 +297,51 @@ impl Annotations {
         }
         extract_costs_rec(self, model, &[], 1.into())
     }
+
+    /// For the nodes of an inference model with incompletely known outputs, explain which
+    /// rules set their facts and which rules never fired, analysing `loaded`, the model before
+    /// analysis, from the facts given by the model file and the user.
+    pub fn extract_explanations(
+        &mut self,
+        model: &dyn Model,
+        loaded: &tract_hir::infer::InferenceModel,
+    ) -> CliResult<()> {
+        use tract_hir::infer::{Factoid, InferenceModel, InferenceModelExt};
+        let model = if let Some(model) = model.downcast_ref::<InferenceModel>() {
+            model
+        } else {
+            return Ok(());
+        };
+        let bold = Style::new().bold();
+        let known = |o: &OutletId| {
+            let fact = model.outlet_fact(*o).unwrap();
+            fact.datum_type.is_concrete() && fact.shape.is_concrete()
+        };
+        let traces = loaded.explain_analyse()?;
+        for node in model.nodes() {
+            let outputs = (0..node.outputs.len()).map(|ix| OutletId::new(node.id, ix));
+            if outputs.clone().all(|o| known(&o)) {
+                continue;
+            }
+            let mut v = vec![];
+            if node.inputs.iter().all(known) {
+                v.push(format!("{}", bold.paint("Outputs unknown from known inputs")));
+            }
+            let loaded_node = loaded.node_id_by_name(&node.name).ok();
+            if let Some(trace) = loaded_node.and_then(|id| traces.get(&id)) {
+                for r in &trace.refinements {
+                    v.push(format!("Set {} = {} by {}", bold.paint(&r.path), r.value, r.rule));
+                }
+                for s in &trace.stalled {
+                    v.push(format!(
+                        "Stalled {} waiting for {}",
+                        s.rule,
+                        bold.paint(s.unknown.join(", "))
+                    ));
+                }
+            }
+            self.node_mut(node.id.into()).sections.push(v);
+        }
+        Ok(())
+    }
 }
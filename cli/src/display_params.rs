@@ -25,6 +25,7 @@ pub struct DisplayParams {
     pub debug_op: bool,
     pub cost: bool,
     pub profile: bool,
+    pub explain: bool,
     pub node_ids: Option<Vec<TVec<(usize, String)>>>,
     pub op_name: Option<String>,
     pub node_name: Option<String>,
@@ -18,6 +18,9 @@ pub fn handle(
     if options.cost {
         annotations.extract_costs(model)?;
     }
+    if let (true, Some(loaded)) = (options.explain, &params.loaded_model) {
+        annotations.extract_explanations(model, loaded)?;
+    }
     if options.profile {
         let model = params
             .tract_model
@@ -108,7 +111,7 @@ pub fn handle(
             sub_matches.value_of("assert-cost").map(|a| crate::cost::parse_costs(a)).transpose()?;
         if let Some(assert) = assert {
             let assert: HashMap<Cost, TDim> =
-                assert.iter().map(|(c, n)| (*c, n.to_dim())).collect();
+                assert.iter().map(|(c, n)| (c.clone(), n.to_dim())).collect();
             let total = total.cost.iter().cloned().collect::<HashMap<_, _>>();
             if assert != total {
                 bail!("Cost assertion not met: expected {:?} got {:?}", assert, total);
@@ -116,7 +119,9 @@ pub fn handle(
         }
     }
 
-    if options.json {
+    if sub_matches.is_present("properties") {
+        terminal::render_properties(model)?;
+    } else if options.json {
         let export = crate::export::GraphPerfInfo::from(model, &annotations);
         serde_json::to_writer(std::io::stdout(), &export)?;
     } else {
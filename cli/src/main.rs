@@ -28,6 +28,7 @@ mod export;
 mod model;
 mod params;
 mod profile;
+mod quantize;
 mod run;
 #[cfg(feature = "pulse")]
 mod stream_check;
@@ -140,6 +141,8 @@ fn main() -> tract_core::anyhow::Result<()> {
     (@arg pass: --pass +takes_value possible_values(STAGES) "Pass to stop preprocessing after.")
     (@arg declutter_step: --("declutter-step") +takes_value "Stop decluttering process after application of patch number N")
     (@arg optimize_step: --("optimize-step") +takes_value "Stop optimizing process after application of patch number N")
+    (@arg disable_pass: --("disable-pass") +takes_value +multiple number_of_values(1) "Skip an optimizer pass by name (PropConst, Cse, declutter, codegen, fuse, PushSplitDown, ChangeAxes)")
+    (@arg optimizer_report: --("optimizer-report") "Log the time spent and the patches applied by each declutter and optimize pass")
     (@arg extract_decluttered_sub: --("extract-decluttered-sub") +takes_value "Zoom on a subgraph after decluttering by parent node name")
 
     (@arg nnef_cycle: --("nnef-cycle") "Perform NNEF dump and reload before optimizing")
@@ -211,6 +214,8 @@ fn main() -> tract_core::anyhow::Result<()> {
         .long_about("Dumps the Tensorflow graph in human readable form.")
         .arg(Arg::with_name("cost").long("cost").help("Include const information"))
         .arg(Arg::with_name("profile").long("profile").help("Include results for profile run"))
+        .arg(Arg::with_name("explain").long("explain").help("Explain unknown facts of an inference model"))
+        .arg(Arg::with_name("properties").long("properties").help("Only print the model properties"))
         .arg(
             Arg::with_name("assert-cost")
             .takes_value(true)
@@ -348,6 +353,47 @@ fn main() -> tract_core::anyhow::Result<()> {
     let optimize = clap::SubCommand::with_name("optimize").help("Optimize the graph");
     app = app.subcommand(output_options(optimize));
 
+    let quantize = clap::SubCommand::with_name("quantize")
+        .long_about("Calibrate and quantize the model to i8, dumping it as NNEF")
+        .arg(
+            Arg::with_name("calibration")
+                .long("calibration")
+                .takes_value(true)
+                .required(true)
+                .multiple(true)
+                .number_of_values(1)
+                .help("Calibration sample (.npz, one array per input)"),
+        )
+        .arg(
+            Arg::with_name("method")
+                .long("method")
+                .takes_value(true)
+                .possible_values(&["minmax", "percentile", "entropy"])
+                .default_value("minmax")
+                .help("Calibration method"),
+        )
+        .arg(
+            Arg::with_name("percentile")
+                .long("percentile")
+                .takes_value(true)
+                .default_value("99.99")
+                .help("Percentage of values to keep with the percentile method"),
+        )
+        .arg(
+            Arg::with_name("per-tensor")
+                .long("per-tensor")
+                .help("Quantize weights per tensor instead of per output channel"),
+        )
+        .arg(
+            Arg::with_name("output")
+                .short("o")
+                .long("output")
+                .takes_value(true)
+                .required(true)
+                .help("NNEF output (.tgz, .tar or directory)"),
+        );
+    app = app.subcommand(quantize);
+
     let stream_check = clap::SubCommand::with_name("stream-check")
         .long_about("Compare output of streamed and regular exec");
     app = app.subcommand(output_options(stream_check));
@@ -482,16 +528,21 @@ fn handle(matches: clap::ArgMatches, probe: Option<&Probe>) -> CliResult<()> {
     let mut params = match builder_result {
         Ok(params) => params,
         Err(e) => {
-            if let Some(params::ModelBuildingError(ref broken_model, _)) = e.downcast_ref() {
+            if let Some(params::ModelBuildingError(ref broken_model, _, ref loaded)) =
+                e.downcast_ref()
+            {
                 let mut broken_model: Box<dyn Model> =
                     tract_hir::tract_core::dyn_clone::clone(broken_model);
-                let annotations =
+                let mut annotations =
                     crate::annotations::Annotations::from_model(broken_model.as_ref())?;
                 let display_params = if let ("dump", Some(sm)) = matches.subcommand() {
                     display_params_from_clap(&matches, &sm)?
                 } else {
                     crate::display_params::DisplayParams::default()
                 };
+                if let (true, Some(loaded)) = (display_params.explain, loaded) {
+                    annotations.extract_explanations(broken_model.as_ref(), loaded)?;
+                }
 
                 if broken_model.output_outlets().len() == 0 {
                     broken_model.auto_outputs()?;
@@ -521,6 +572,8 @@ fn handle(matches: clap::ArgMatches, probe: Option<&Probe>) -> CliResult<()> {
 
         ("run", Some(m)) => run::handle(&params, m),
 
+        ("quantize", Some(m)) => quantize::handle(&params, &matches, m),
+
         #[cfg(feature = "pulse")]
         ("stream-check", Some(m)) => {
             stream_check::handle(&params, &display_params_from_clap(&matches, m)?)
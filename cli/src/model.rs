@@ -91,6 +91,8 @@ pub trait Model:
 
     fn properties(&self) -> &HashMap<String, Arc<Tensor>>;
 
+    fn symbol_scope(&self) -> &SymbolScope;
+
     fn rename_node(&mut self, id: usize, name: &str) -> TractResult<()>;
 }
 
@@ -195,6 +197,10 @@ where
         &self.properties
     }
 
+    fn symbol_scope(&self) -> &SymbolScope {
+        &self.symbol_scope
+    }
+
     fn rename_node(&mut self, id: usize, name: &str) -> TractResult<()> {
         self.rename_node(id, name)
     }
@@ -68,8 +68,13 @@ pub enum SomeGraphDef {
     Tf(GraphDef),
 }
 
+/// A model that failed to build, the error, and the model as loaded if it is to be explained.
 #[derive(Debug)]
-pub struct ModelBuildingError(pub Box<dyn Model>, pub Box<dyn std::error::Error + Send + Sync>);
+pub struct ModelBuildingError(
+    pub Box<dyn Model>,
+    pub Box<dyn std::error::Error + Send + Sync>,
+    pub Option<Arc<InferenceModel>>,
+);
 
 impl std::fmt::Display for ModelBuildingError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -94,6 +99,8 @@ pub struct Parameters {
 
     pub tract_model: Arc<dyn Model>,
     pub reference_model: Option<Arc<dyn Model>>,
+    /// The inference model as loaded, before analysis, kept to explain it (`dump --explain`).
+    pub loaded_model: Option<Arc<InferenceModel>>,
 
     #[cfg(feature = "conform")]
     pub tf_model: Option<tract_tensorflow::conform::tf::Tensorflow>,
@@ -195,8 +202,9 @@ impl Parameters {
                     (
                         SomeGraphDef::Nnef(proto_model.clone()),
                         Box::new(
-                            nnef.translate(&proto_model)
-                                .map_err(|(g, e)| ModelBuildingError(Box::new(g), e.into()))?,
+                            nnef.translate(&proto_model).map_err(|(g, e)| {
+                                ModelBuildingError(Box::new(g), e.into(), None)
+                            })?,
                         ),
                         Option::<TfExt>::None,
                     )
@@ -204,8 +212,9 @@ impl Parameters {
                     (
                         SomeGraphDef::NoGraphDef,
                         Box::new(
-                            nnef.translate(&proto_model)
-                                .map_err(|(g, e)| ModelBuildingError(Box::new(g), e.into()))?,
+                            nnef.translate(&proto_model).map_err(|(g, e)| {
+                                ModelBuildingError(Box::new(g), e.into(), None)
+                            })?,
                         ),
                         Option::<TfExt>::None,
                     )
@@ -338,7 +347,8 @@ impl Parameters {
                         .nth(0)
                         .unwrap()
                         .parse::<usize>()?;
-                    let (name, tensor) = tensor::for_data(file.path().to_str().unwrap())?;
+                    let (name, tensor) =
+                        tensor::for_data(&raw_model.symbol_scope, file.path().to_str().unwrap())?;
                     Ok(Some((
                         ix,
                         is_input,
@@ -415,7 +425,7 @@ impl Parameters {
 
         if let Some(inputs) = matches.values_of("input") {
             for (ix, v) in inputs.enumerate() {
-                let (name, t) = tensor::for_string(v)?;
+                let (name, t) = tensor::for_string(&raw_model.symbol_scope, v)?;
                 let fact = t.clone().without_value();
                 let fact: F = (&fact).try_into().unwrap();
                 let outlet = if let Some(name) = name.as_ref().filter(|s| s.len() > 0) {
@@ -492,7 +502,7 @@ impl Parameters {
                             let shape = t.shape().to_vec();
                             let dt = raw_model
                                 .input_fact(ix)
-                                .map(|f| f.to_typed_fact().map(|t| t.datum_type).ok())
+                                .map(|f| f.to_typed_fact().map(|t| t.datum_type.clone()).ok())
                                 .ok()
                                 .flatten()
                                 .unwrap_or(t.datum_type());
@@ -503,7 +513,7 @@ impl Parameters {
                                 dt
                             );
                             unsafe {
-                                t.set_datum_type(dt);
+                                t.set_datum_type(dt.clone());
                             }
                             let fact = InferenceFact::dt_shape(dt, shape);
                             raw_model.set_input_fact(ix, (&fact).try_into().unwrap())?;
@@ -614,7 +624,7 @@ impl Parameters {
                         }
                         Err(e) => {
                             if let Some(last_model) = last_model.take() {
-                                return Err(ModelBuildingError(last_model, e.into()))?;
+                                return Err(ModelBuildingError(last_model, e.into(), None))?;
                             } else {
                                 return Err(e);
                             }
@@ -645,7 +655,7 @@ impl Parameters {
             let result = m.analyse(matches.is_present("analyse_fail_fast"));
             match result {
                 Ok(_) => Ok(m),
-                Err(e) => Err(ModelBuildingError(Box::new(m), e.into()).into())
+                Err(e) => Err(ModelBuildingError(Box::new(m), e.into(), None).into())
             }});
         if let Some(ext) = tf_model_extensions {
             #[cfg(feature = "tf")]
@@ -658,13 +668,13 @@ impl Parameters {
             if let Some(steps) = matches.value_of("declutter_step") {
                 dec = dec.stopping_at(steps.parse()?);
             }
-            dec.optimize(&mut m)?;
+            Self::run_optimizer(matches, dec, &mut m)?;
             Ok(m)
         });
         #[cfg(feature = "pulse")]
         {
             if let Some(dim) = concretize_stream_dim {
-                stage!("concretize-stream-dim", typed_model -> typed_model, |m:TypedModel| Ok(m.concretize_dims(&SymbolValues::default().with(stream_symbol(), dim as _))?));
+                stage!("concretize-stream-dim", typed_model -> typed_model, |m:TypedModel| Ok(m.concretize_dims(&SymbolValues::default().with(stream_symbol(&m.symbol_scope), dim as _))?));
                 stage!("concretize-stream-dim-declutter", typed_model -> typed_model, |m:TypedModel| m.into_decluttered());
             } else if let Some(pulse) = pulse {
                 stage!("pulse", typed_model -> pulsed_model, |m:TypedModel| Ok(PulsedModel::new(&m, pulse)?));
@@ -694,12 +704,40 @@ impl Parameters {
             if let Some(steps) = matches.value_of("optimize_step") {
                 opt = opt.stopping_at(steps.parse()?);
             }
-            opt.optimize(&mut m)?;
+            Self::run_optimizer(matches, opt, &mut m)?;
             Ok(m)
         });
         Ok((typed_model.clone().unwrap(), pulsed_model, reference_model))
     }
 
+    fn run_optimizer(
+        matches: &clap::ArgMatches,
+        mut optimizer: tract_core::optim::Optimizer,
+        model: &mut TypedModel,
+    ) -> TractResult<()> {
+        use tract_core::optim::Optimizer;
+        // passes can be disabled in the decluttering or the codegen pipeline
+        let known = [Optimizer::declutter(), Optimizer::codegen()]
+            .iter()
+            .flat_map(|o| o.pass_names())
+            .unique()
+            .collect::<Vec<_>>();
+        for pass in matches.values_of("disable_pass").into_iter().flatten() {
+            ensure!(
+                known.iter().any(|p| p == pass),
+                "No optimizer pass named {}, passes are {:?}",
+                pass,
+                known
+            );
+            optimizer = optimizer.without_pass(pass);
+        }
+        let report = optimizer.optimize_with_report(model)?;
+        if matches.is_present("optimizer_report") {
+            info!("Optimizer passes {:?}:\n{}", optimizer.pass_names(), report);
+        }
+        Ok(())
+    }
+
     #[allow(unused_variables)]
     /// Parses the command-line arguments.
     pub fn from_clap(matches: &clap::ArgMatches, probe: Option<&Probe>) -> CliResult<Parameters> {
@@ -709,6 +747,7 @@ impl Parameters {
 
         info!("Model {:?} loaded", filename);
         info_usage("model loaded", probe);
+        let symbol_scope = raw_model.symbol_scope().clone();
 
         let (need_tensorflow_model, need_reference_model) = match matches.subcommand() {
             ("compare", Some(sm)) => {
@@ -750,7 +789,7 @@ impl Parameters {
 
         if let Some(inputs) = matches.values_of("input") {
             let names = inputs
-                .map(|t| Ok(tensor::for_string(t)?.0))
+                .map(|t| Ok(tensor::for_string(&symbol_scope, t)?.0))
                 .collect::<CliResult<Vec<Option<String>>>>()?;
             if names.iter().all(|s| s.is_some() && s.as_ref().unwrap().len() > 0) {
                 let names: Vec<&str> = names.iter().map(|s| &**s.as_ref().unwrap()).collect();
@@ -770,7 +809,7 @@ impl Parameters {
 
         if let Some(override_facts) = matches.values_of("override_fact") {
             for fact in override_facts {
-                let (name, fact) = tensor::for_string(fact)?;
+                let (name, fact) = tensor::for_string(&symbol_scope, fact)?;
                 let node = raw_model.node_id_by_name(&name.unwrap())?;
                 if let Some(inf) = raw_model.downcast_mut::<InferenceModel>() {
                     inf.set_outlet_fact(OutletId::new(node, 0), fact)?;
@@ -795,7 +834,8 @@ impl Parameters {
             })
             .collect();
 
-        let mut assertions = Assertions::from_clap(matches, &*output_names_and_labels)?;
+        let mut assertions =
+            Assertions::from_clap(matches, &symbol_scope, &*output_names_and_labels)?;
 
         if let Some(sub) = matches.value_of("kaldi_downsample") {
             dispatch_model_mut_no_pulse!(raw_model, |m| Self::kaldi_downsample(m, sub.parse()?))?;
@@ -825,6 +865,12 @@ impl Parameters {
             }
         }
 
+        let explain = matches.subcommand_matches("dump").map_or(false, |m| m.is_present("explain"));
+        let loaded_model = raw_model
+            .downcast_ref::<InferenceModel>()
+            .filter(|_| explain)
+            .map(|m| Arc::new(m.clone()));
+
         Self::pipeline(
             matches,
             probe,
@@ -832,6 +878,12 @@ impl Parameters {
             tf_model_extensions,
             need_reference_model.as_deref(),
         )
+        .map_err(|mut e| {
+            if let Some(broken) = e.downcast_mut::<ModelBuildingError>() {
+                broken.2 = loaded_model.clone();
+            }
+            e
+        })
         .map(|(tract_model, pulsed_model, reference_model)| {
             info!("Model ready");
             info_usage("model ready", probe);
@@ -840,6 +892,7 @@ impl Parameters {
                 pulsed_model,
                 tract_model,
                 reference_model,
+                loaded_model,
                 tf_model,
                 input_values,
                 assertions,
@@ -877,6 +930,7 @@ pub fn display_params_from_clap(
         konst: matches.is_present("const"),
         cost: matches.is_present("cost"),
         profile: matches.is_present("profile"),
+        explain: matches.is_present("explain"),
         left_column_width: 0,
         invariants: matches.is_present("invariants"),
         quiet: matches.is_present("quiet"),
@@ -913,13 +967,14 @@ pub struct Assertions {
 impl Assertions {
     fn from_clap(
         matches: &clap::ArgMatches,
+        symbol_scope: &SymbolScope,
         output_names: &[Vec<String>],
     ) -> CliResult<Assertions> {
         if let Some(sub) = matches.subcommand.as_ref().map(|sub| &sub.matches) {
             let mut assert_outputs: Vec<Option<Arc<Tensor>>> = vec![None; output_names.len()];
             if let Some(values) = sub.values_of("assert-output") {
                 for (ix, o) in values.enumerate() {
-                    let (name, fact) = tensor::for_string(o)?;
+                    let (name, fact) = tensor::for_string(symbol_scope, o)?;
                     info!(
                         "Output assertion #{}: (named: {}) {:?}",
                         ix,
@@ -962,7 +1017,7 @@ impl Assertions {
 
             let assert_output_facts: Option<Vec<InferenceFact>> = matches
                 .values_of("assert-output-fact")
-                .map(|vs| vs.map(|v| tensor::for_string(v).unwrap().1).collect());
+                .map(|vs| vs.map(|v| tensor::for_string(symbol_scope, v).unwrap().1).collect());
             let assert_op_count: Option<Vec<(String, usize)>> = sub
                 .values_of("assert-op-count")
                 .map(|vs| {
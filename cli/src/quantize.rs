@@ -0,0 +1,65 @@
+use crate::tensor;
+use crate::CliResult;
+use crate::Parameters;
+use tract_core::quantize::*;
+use tract_hir::internal::*;
+
+pub fn handle(
+    params: &Parameters,
+    matches: &clap::ArgMatches,
+    sub_matches: &clap::ArgMatches,
+) -> CliResult<()> {
+    let model = params
+        .tract_model
+        .downcast_ref::<TypedModel>()
+        .context("Can only quantize typed models")?;
+    let input_names: Vec<String> =
+        model.input_outlets()?.iter().map(|i| model.node(i.node).name.to_string()).collect();
+    let mut dataset = vec![];
+    for file in sub_matches.values_of("calibration").unwrap() {
+        let mut npz = ndarray_npy::NpzReader::new(
+            std::fs::File::open(file).with_context(|| format!("opening {:?}", file))?,
+        )?;
+        let names = npz.names()?;
+        let sample = input_names
+            .iter()
+            .map(|input| {
+                let npy = names
+                    .iter()
+                    .find(|n| n.trim_end_matches(".npy") == input.as_str())
+                    .with_context(|| format!("No value for input {} in {}", input, file))?;
+                tensor::for_npz(&mut npz, npy)
+            })
+            .collect::<CliResult<TVec<Tensor>>>()?;
+        dataset.push(sample);
+    }
+    let method = match sub_matches.value_of("method").unwrap() {
+        "minmax" => CalibrationMethod::MinMax,
+        "percentile" => {
+            CalibrationMethod::Percentile(sub_matches.value_of("percentile").unwrap().parse()?)
+        }
+        "entropy" => CalibrationMethod::Entropy,
+        m => bail!("Unknown calibration method {}", m),
+    };
+    info!("Calibrating on {} sample(s) with {:?}", dataset.len(), method);
+    let ranges = calibrate(model, &dataset, method)?;
+    let config = QuantizationConfig { per_channel: !sub_matches.is_present("per-tensor") };
+    let quantized = quantize(model, &ranges, &config)?;
+
+    let mut nnef = super::nnef(matches);
+    if !matches.is_present("nnef_tract_core") {
+        // casts and quantized matmuls need tract-core extensions
+        nnef = nnef.with_tract_core();
+    }
+    let path = sub_matches.value_of("output").unwrap();
+    if path.ends_with(".tgz") || path.ends_with(".tar.gz") {
+        let file = std::fs::File::create(path)?;
+        let encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+        nnef.write_to_tar(&quantized, encoder)?;
+    } else if path.ends_with(".tar") {
+        nnef.write_to_tar(&quantized, std::fs::File::create(path)?)?;
+    } else {
+        nnef.write_to_dir(&quantized, path)?;
+    }
+    Ok(())
+}
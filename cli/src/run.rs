@@ -75,7 +75,7 @@ fn run_regular(
                 let mut tokens = set.split("=");
                 let sym = tokens.next().context("--set expect S=12 form")?;
                 let value = tokens.next().context("--set expect S=12 form")?;
-                let sym = Symbol::from(sym.chars().next().unwrap());
+                let sym = crate::tensor::parse_symbol(tract.symbol_scope(), sym);
                 let value: i64 = value.parse().context("Can not parse symbol value in set")?;
                 state.session_state.resolved_symbols =
                     state.session_state.resolved_symbols.with(sym, value);
@@ -166,7 +166,7 @@ fn run_pulse_t(model: &PulsedModel, params: &Parameters) -> CliResult<TVec<Arc<T
     //    println!("output_fact: {:?}", output_fact);
     let output_dim = output_fact
         .dim
-        .eval(&SymbolValues::default().with(stream_symbol(), input_dim as i64))
+        .eval(&SymbolValues::default().with(stream_symbol(&model.symbol_scope), input_dim as i64))
         .to_usize()?;
     let mut output_shape = output_fact.shape.to_vec();
     output_shape[output_fact.axis] =
@@ -24,6 +24,7 @@ pub fn handle(params: &Parameters, options: &DisplayParams) -> CliResult<()> {
         .downcast_ref::<TypedModel>()
         .context("Final model is not Typed. (using --pass ?)")?;
 
+    let s = stream_symbol(&decl.symbol_scope);
     let decl_input_fact = decl.input_fact(0)?;
     let pulsed_input_fact = pulsed.input_fact(0)?;
     let input_pulse = pulsed_input_fact.pulse();
@@ -60,7 +61,7 @@ pub fn handle(params: &Parameters, options: &DisplayParams) -> CliResult<()> {
             let decl = (*decl).clone();
             let fixed_result = decl
                 .with_output_outlets(&[decl_outlet])?
-                .concretize_dims(&SymbolValues::default().with(stream_symbol(), stream_dim as _))?
+                .concretize_dims(&SymbolValues::default().with(s.clone(), stream_dim as _))?
                 .into_runnable()?
                 .run(tvec!(fixed_input.clone()))?
                 .remove(output_slot);
@@ -88,7 +89,7 @@ pub fn handle(params: &Parameters, options: &DisplayParams) -> CliResult<()> {
                 };
                 if offset + input_pulse > stream_dim {
                     debug!("Set known_stream_len: {}", stream_dim);
-                    state.session_state.resolved_symbols[stream_symbol()] = Some(stream_dim as _);
+                    state.session_state.resolved_symbols[s.clone()] = Some(stream_dim as _);
                 };
 
                 let output = state.run(tvec!(pulsed_input.into()))?.remove(output_slot);
@@ -26,18 +26,18 @@ fn parse_dt(dt: &str) -> CliResult<DatumType> {
     })
 }
 
-pub fn parse_spec(size: &str) -> CliResult<InferenceFact> {
+pub fn parse_spec(symbol_scope: &SymbolScope, size: &str) -> CliResult<InferenceFact> {
     if size.len() == 0 {
         return Ok(InferenceFact::default());
     }
     if size.contains("x") && !size.contains(",") {
-        parse_x_spec(size)
+        parse_x_spec(symbol_scope, size)
     } else {
-        parse_coma_spec(size)
+        parse_coma_spec(symbol_scope, size)
     }
 }
 
-pub fn parse_coma_spec(size: &str) -> CliResult<InferenceFact> {
+pub fn parse_coma_spec(symbol_scope: &SymbolScope, size: &str) -> CliResult<InferenceFact> {
     let splits = size.split(",").collect::<Vec<_>>();
 
     if splits.len() < 1 {
@@ -57,7 +57,11 @@ pub fn parse_coma_spec(size: &str) -> CliResult<InferenceFact> {
         shape
             .iter()
             .map(|&s| {
-                Ok(if s == "_" { GenericFactoid::Any } else { GenericFactoid::Only(parse_dim(s)?) })
+                Ok(if s == "_" {
+                    GenericFactoid::Any
+                } else {
+                    GenericFactoid::Only(parse_dim(symbol_scope, s)?)
+                })
             })
             .collect::<CliResult<TVec<DimFact>>>()?,
     );
@@ -69,29 +73,28 @@ pub fn parse_coma_spec(size: &str) -> CliResult<InferenceFact> {
     }
 }
 
-pub fn parse_dim(i: &str) -> CliResult<TDim> {
-    // ensure the magic S is pre-registered
-    #[cfg(feature = "pulse")]
-    let _ = tract_pulse::internal::stream_symbol();
+/// The symbol called `name` in the model scope. `S` is the pulse streaming symbol.
+pub fn parse_symbol(symbol_scope: &SymbolScope, name: &str) -> Symbol {
+    symbol_scope.sym(name)
+}
 
+/// Parses an integer immediately followed by a symbol name (`12S`), or a dimension expression
+/// like `S`, `min(S,12)` or `ceil((S+1)/2)`.
+pub fn parse_dim(symbol_scope: &SymbolScope, i: &str) -> CliResult<TDim> {
     if i.len() == 0 {
         bail!("Can not parse empty string as Dim")
     }
     let number_len = i.chars().take_while(|c| c.is_digit(10)).count();
-    let symbol_len = i.len() - number_len;
-    if symbol_len > 1 {
-        bail!("Can not parse {} as Dim", i)
-    }
-    let number: i64 = if number_len > 0 { i[..number_len].parse()? } else { 1 };
-    if symbol_len == 0 {
-        return Ok(number.to_dim());
+    let name = &i[number_len..];
+    if number_len > 0 && name.len() > 0 && name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        let number: i64 = i[..number_len].parse()?;
+        return Ok(parse_symbol(symbol_scope, name).to_dim() * number);
     }
-    let symbol = i.chars().last().unwrap();
-    let symbol = Symbol::from(symbol);
-    Ok(symbol.to_dim() * number)
+    TDim::parse_with(i, |name| parse_symbol(symbol_scope, name))
+        .with_context(|| format!("Can not parse {} as Dim", i))
 }
 
-pub fn parse_x_spec(size: &str) -> CliResult<InferenceFact> {
+pub fn parse_x_spec(symbol_scope: &SymbolScope, size: &str) -> CliResult<InferenceFact> {
     warn!(
         "Deprecated \"x\" syntax for shape : please use the comma as separator, x is now a symbol."
     );
@@ -118,7 +121,7 @@ pub fn parse_x_spec(size: &str) -> CliResult<InferenceFact> {
                 Ok(if s == "_" {
                     GenericFactoid::Any
                 } else {
-                    GenericFactoid::Only(parse_dim_stream(s)?)
+                    GenericFactoid::Only(parse_dim_stream(symbol_scope, s)?)
                 })
             })
             .collect::<CliResult<TVec<DimFact>>>()?,
@@ -139,14 +142,14 @@ fn parse_values<'a, T: Datum + FromStr>(shape: &[usize], it: Vec<&'a str>) -> Cl
     Ok(tract_ndarray::Array::from_shape_vec(shape, values)?.into())
 }
 
-fn tensor_for_text_data(filename: &str) -> CliResult<Tensor> {
+fn tensor_for_text_data(symbol_scope: &SymbolScope, filename: &str) -> CliResult<Tensor> {
     let mut file = fs::File::open(filename)
         .map_err(|e| format_err!("Reading tensor from {}, {:?}", filename, e))?;
     let mut data = String::new();
     file.read_to_string(&mut data)?;
 
     let mut lines = data.lines();
-    let proto = parse_spec(lines.next().context("Empty data file")?)?;
+    let proto = parse_spec(symbol_scope, lines.next().context("Empty data file")?)?;
     let shape = proto.shape.concretize().unwrap();
 
     let values = lines.flat_map(|l| l.split_whitespace()).collect::<Vec<&str>>();
@@ -161,7 +164,10 @@ fn tensor_for_text_data(filename: &str) -> CliResult<Tensor> {
 }
 
 /// Parses the `data` command-line argument.
-pub fn for_data(filename: &str) -> CliResult<(Option<String>, InferenceFact)> {
+pub fn for_data(
+    symbol_scope: &SymbolScope,
+    filename: &str,
+) -> CliResult<(Option<String>, InferenceFact)> {
     #[allow(unused_imports)]
     use std::convert::TryFrom;
     if filename.ends_with(".pb") {
@@ -185,7 +191,7 @@ pub fn for_data(filename: &str) -> CliResult<(Option<String>, InferenceFact)> {
         let mut npz = ndarray_npy::NpzReader::new(std::fs::File::open(filename)?)?;
         Ok((None, for_npz(&mut npz, inner)?.into()))
     } else {
-        Ok((None, tensor_for_text_data(filename)?.into()))
+        Ok((None, tensor_for_text_data(symbol_scope, filename)?.into()))
     }
 }
 
@@ -233,9 +239,12 @@ pub fn for_npz(npz: &mut ndarray_npy::NpzReader<fs::File>, name: &str) -> CliRes
     bail!("Can not extract tensor from {}", name);
 }
 
-pub fn for_string(value: &str) -> CliResult<(Option<String>, InferenceFact)> {
+pub fn for_string(
+    symbol_scope: &SymbolScope,
+    value: &str,
+) -> CliResult<(Option<String>, InferenceFact)> {
     if value.starts_with("@") {
-        for_data(&value[1..])
+        for_data(symbol_scope, &value[1..])
     } else {
         let (name, value) = if value.contains(":") {
             let mut splits = value.split(":");
@@ -245,7 +254,7 @@ pub fn for_string(value: &str) -> CliResult<(Option<String>, InferenceFact)> {
         };
         if value.contains("=") {
             let mut split = value.split("=");
-            let spec = parse_spec(split.next().unwrap())?;
+            let spec = parse_spec(symbol_scope, split.next().unwrap())?;
             let value = split.next().unwrap().split(",");
             let dt = spec
                 .datum_type
@@ -258,27 +267,27 @@ pub fn for_string(value: &str) -> CliResult<(Option<String>, InferenceFact)> {
             let tensor = dispatch_datum!(parse_values(dt)(&*shape, value.collect()))?;
             Ok((name, tensor.into()))
         } else {
-            Ok((name, parse_spec(value)?))
+            Ok((name, parse_spec(symbol_scope, value)?))
         }
     }
 }
 
 #[cfg(feature = "pulse")]
-fn parse_dim_stream(s: &str) -> CliResult<TDim> {
+fn parse_dim_stream(symbol_scope: &SymbolScope, s: &str) -> CliResult<TDim> {
     use tract_pulse::internal::stream_dim;
     if s == "S" {
-        Ok(stream_dim())
+        Ok(stream_dim(symbol_scope))
     } else if s.ends_with("S") {
         let number: String = s.chars().take_while(|c| c.is_digit(10)).collect();
         let number: i64 = number.parse::<i64>().map(|i| i.into())?;
-        Ok(stream_dim() * number)
+        Ok(stream_dim(symbol_scope) * number)
     } else {
         Ok(s.parse::<i64>().map(|i| i.into())?)
     }
 }
 
 #[cfg(not(feature = "pulse"))]
-fn parse_dim_stream(s: &str) -> CliResult<TDim> {
+fn parse_dim_stream(_symbol_scope: &SymbolScope, s: &str) -> CliResult<TDim> {
     Ok(s.parse::<i64>().map(|i| i.into())?)
 }
 
@@ -322,19 +331,20 @@ pub fn tensor_for_fact(fact: &TypedFact, streaming_dim: Option<usize>) -> CliRes
     }
     #[cfg(pulse)]
     {
-        if fact.shape.stream_info().is_some() {
-            use tract_pulse::fact::StreamFact;
-            use tract_pulse::internal::stream_symbol;
-            let s = stream_symbol();
+        use tract_pulse::fact::StreamFact;
+        if let Some((_, len)) = fact.shape.stream_info() {
+            let s = len.symbols().into_iter().find(|s| s.name() == "S").unwrap();
             if let Some(dim) = streaming_dim {
                 let shape = fact
                     .shape
                     .iter()
                     .map(|d| {
-                        d.eval(&SymbolValues::default().with(s, dim as i64)).to_usize().unwrap()
+                        d.eval(&SymbolValues::default().with(s.clone(), dim as i64))
+                            .to_usize()
+                            .unwrap()
                     })
                     .collect::<TVec<_>>();
-                return Ok(random(&shape, fact.datum_type));
+                return Ok(random(&shape, fact.datum_type.clone()));
             } else {
                 bail!("random tensor requires a streaming dim")
             }
@@ -345,7 +355,7 @@ pub fn tensor_for_fact(fact: &TypedFact, streaming_dim: Option<usize>) -> CliRes
             .shape
             .as_concrete()
             .with_context(|| format!("Expected concrete shape, found: {:?}", fact))?,
-        fact.datum_type,
+        fact.datum_type.clone(),
     ))
 }
 
@@ -21,6 +21,10 @@ pub fn render(
         return Ok(());
     }
     render_prefixed(model, "", &[], annotations, options)?;
+    render_properties(model)
+}
+
+pub fn render_properties(model: &dyn Model) -> CliResult<()> {
     if model.properties().len() > 0 {
         println!("{}", White.bold().paint("# Properties"));
     }
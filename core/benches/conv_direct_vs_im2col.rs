@@ -67,6 +67,7 @@ impl Problem {
                     &mut model,
                     "",
                     input,
+                    &self.input.shape,
                 )
                 .unwrap()
             } else {
@@ -72,6 +72,7 @@ fn mmm(c: &mut Criterion) {
                 });
                 let op = LirMatMulUnary {
                     c_fact: TypedFact::dt_shape(f32::datum_type(), &[8, 64]),
+                    a_storage: unsafe { mmm.a_packed(F32.size_of(), 48) },
                     micro_ops: tract_ndarray::arr0((
                         packed_a.into_arc_tensor(),
                         vec![ProtoFusedSpec::Store],
@@ -75,6 +75,7 @@ mod late_bind;
 pub mod model;
 pub mod optim;
 pub mod plan;
+pub mod quantize;
 
 pub use dyn_clone;
 
@@ -82,7 +83,7 @@ pub use dyn_clone;
 pub mod prelude {
     pub use crate::framework::Framework;
     pub use crate::model::*;
-    pub use crate::plan::{SimplePlan, SimpleState};
+    pub use crate::plan::{PlanCache, SimplePlan, SimpleState};
     pub use std::sync::Arc;
     pub use tract_data::prelude::*;
 
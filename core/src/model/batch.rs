@@ -0,0 +1,261 @@
+//! Making the batch axis of a model symbolic.
+use crate::internal::*;
+use crate::model::translator::Translate;
+use crate::ops::change_axes::{change_axes_or_blocker, AxisChange};
+use crate::ops::konst::Const;
+use crate::ops::source::TypedSource;
+
+impl TypedModel {
+    /// Replace the axis `axis` of input `input`, of size 1, by `symbol`. The model is
+    /// decluttered on the way.
+    ///
+    /// The axis is followed through the graph: other inputs and outputs sharing it become
+    /// symbolic too, reshapes and constants are rewritten to leave it alone. Fails naming the
+    /// offending node if an op mixes values across the axis (a reduction over it, a matmul
+    /// contracting it...).
+    pub fn symbolize_axis(
+        &self,
+        input: usize,
+        axis: usize,
+        symbol: &Symbol,
+    ) -> TractResult<TypedModel> {
+        let outlet = *self
+            .input_outlets()?
+            .get(input)
+            .with_context(|| format!("Model has no input #{}", input))?;
+        let fact = self.outlet_fact(outlet)?;
+        ensure!(
+            axis < fact.rank() && fact.shape[axis] == 1.to_dim(),
+            "Expected axis {} of input #{} to be of size 1, input is {:?}",
+            axis,
+            input,
+            fact
+        );
+        // decluttering takes size 1 axes out of the reshapes, then removing the axis
+        // everywhere and adding it back keeps it out of them
+        let model = movable_consts(self.clone().into_decluttered()?);
+        let (model, _) = propagate(&model, model.input_outlets()?[input], AxisOp::Rm(axis))?;
+        let (model, changes) = propagate(&model, model.input_outlets()?[input], AxisOp::Add(axis))?;
+        let axes = changes
+            .into_iter()
+            .filter_map(|(io, op)| match (io, op) {
+                (InOut::In(ix), AxisOp::Add(axis)) => Some((ix, axis)),
+                _ => None,
+            })
+            .collect();
+        SymbolizeAxes { axes, dim: symbol.to_dim() }.translate_model(&model)
+    }
+
+    /// Add a leading axis of size `symbol` to the inputs and outputs of the model. The model is
+    /// decluttered on the way.
+    ///
+    /// Every input gets the new axis, along with the outputs computed from it. Ops that can
+    /// not work on an extra leading axis make it fail, naming the offending node.
+    pub fn add_batch_axis(&self, symbol: &Symbol) -> TractResult<TypedModel> {
+        let mut model = movable_consts(self.clone().into_decluttered()?);
+        let mut axes = HashMap::new();
+        for input in 0..model.input_outlets()?.len() {
+            if axes.contains_key(&input) {
+                continue;
+            }
+            let (patched, changes) =
+                propagate(&model, model.input_outlets()?[input], AxisOp::Add(0))?;
+            model = patched;
+            for (io, op) in changes {
+                if let (InOut::In(ix), AxisOp::Add(axis)) = (io, op) {
+                    axes.insert(ix, axis);
+                }
+            }
+        }
+        SymbolizeAxes { axes, dim: symbol.to_dim() }.translate_model(&model)
+    }
+}
+
+/// Substitute `MovableConst` to the constants, so the batch axis changes go through them.
+fn movable_consts(mut model: TypedModel) -> TypedModel {
+    for id in 0..model.nodes().len() {
+        if let Some(konst) = model.node(id).op_as::<Const>() {
+            let tensor = konst.0.clone();
+            model.node_mut(id).op = Box::new(MovableConst(tensor));
+        }
+    }
+    model
+}
+
+fn propagate(
+    model: &TypedModel,
+    outlet: OutletId,
+    op: AxisOp,
+) -> TractResult<(TypedModel, TVec<(InOut, AxisOp)>)> {
+    let change = AxisChange { outlet, op };
+    match change_axes_or_blocker(model, &change, &[], &[])? {
+        Ok((patch, changes)) => {
+            let mut model = model.clone();
+            patch.apply(&mut model)?;
+            model.compact()?;
+            Ok((model, changes))
+        }
+        Err(blocker) => bail!(
+            "Batch axis can not go through {}: the op mixes batch items or can not change axes",
+            blocker
+        ),
+    }
+}
+
+/// Rebuilds the model with a symbolic size for the given axes of the given inputs, computing
+/// the downstream facts again.
+#[derive(Debug)]
+struct SymbolizeAxes {
+    axes: HashMap<usize, usize>,
+    dim: TDim,
+}
+
+impl Translate<TypedFact, Box<dyn TypedOp>, TypedFact, Box<dyn TypedOp>> for SymbolizeAxes {
+    fn translate_node(
+        &self,
+        source: &TypedModel,
+        node: &TypedNode,
+        target: &mut TypedModel,
+        mapping: &HashMap<OutletId, OutletId>,
+    ) -> TractResult<TVec<OutletId>> {
+        let input = source.input_outlets()?.iter().position(|o| o.node == node.id);
+        if let Some(&axis) = input.and_then(|ix| self.axes.get(&ix)) {
+            let fact = &node.outputs[0].fact;
+            let mut shape = fact.shape.to_tvec();
+            shape[axis] = self.dim.clone();
+            let fact = TypedFact::dt_shape(fact.datum_type.clone(), &*shape);
+            return target.wire_node(&*node.name, TypedSource::new(fact), &[]);
+        }
+        if let Some(konst) = node.op_as::<MovableConst>() {
+            return target.wire_node(&*node.name, Const(konst.0.clone()), &[]);
+        }
+        let inputs = node.inputs.iter().map(|i| mapping[i]).collect::<TVec<_>>();
+        target.wire_node(&*node.name, node.op.clone(), &inputs)
+    }
+}
+
+/// A constant that takes the axis changes along. Only lives while the batch axis is propagated:
+/// elsewhere, a constant blocks axis changes and keeps its layout.
+#[derive(Debug, Clone, Hash)]
+struct MovableConst(Arc<Tensor>);
+
+impl_dyn_hash!(MovableConst);
+
+impl Op for MovableConst {
+    fn name(&self) -> Cow<str> {
+        "MovableConst".into()
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for MovableConst {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, _inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        Ok(tvec![self.0.clone()])
+    }
+}
+
+impl TypedOp for MovableConst {
+    as_op!();
+
+    fn output_facts(&self, _inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        Ok(tvec!(self.0.as_ref().into()))
+    }
+
+    fn change_axes(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+        _io: InOut,
+        change: &AxisOp,
+    ) -> TractResult<Option<AxisChangeConsequence>> {
+        let mut tensor = self.0.clone().into_tensor();
+        if change.change_tensor(&mut tensor, false).is_err() {
+            return Ok(None);
+        }
+        Ok(Some(AxisChangeConsequence::new(
+            model,
+            node,
+            Some(Box::new(MovableConst(tensor.into_arc_tensor()))),
+            change,
+        )))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ops::{math, matmul, nn};
+
+    fn model() -> TractResult<TypedModel> {
+        let mut model = TypedModel::default();
+        let source = model.add_source("source", TypedFact::dt_shape(f32::datum_type(), &[1, 6]))?;
+        let reshape = model.wire_node(
+            "reshape",
+            AxisOp::Reshape(0, tvec!(1.into(), 6.into()), tvec!(1.into(), 2.into(), 3.into())),
+            &[source],
+        )?;
+        let konst = model.add_const("bias", rctensor3(&[[[1f32, 2., 3.], [4., 5., 6.]]]))?;
+        let add = model.wire_node("add", math::add::bin_typed(), &[reshape[0], konst])?;
+        let a = tensor3(&[[[1f32, 0., 1.], [0., 1., 0.]]]).into_arc_tensor();
+        let mm =
+            model.wire_node("mm", matmul::MatMulUnary::new(a, false, true, true), &[add[0]])?;
+        model.set_output_outlets(&mm)?;
+        Ok(model)
+    }
+
+    #[test]
+    fn symbolize_batch() -> TractResult<()> {
+        let model = model()?;
+        let n = model.symbol_scope.sym("n");
+        let batched = model.symbolize_axis(0, 0, &n)?;
+        assert_eq!(batched.input_fact(0)?.shape.to_tvec(), tvec!(n.to_dim(), 6.to_dim()));
+        let items =
+            [tensor2(&[[0f32, 1., 2., 3., 4., 5.]]), tensor2(&[[1f32, 1., 1., 2., 2., 2.]])];
+        let mut expected = tvec!();
+        for item in &items {
+            expected.push(model.clone().into_runnable()?.run(tvec!(item.clone()))?.remove(0));
+        }
+        let expected = Tensor::stack_tensors(0, &expected)?;
+        let input = Tensor::stack_tensors(0, &items)?;
+        let plan = SimplePlan::new(batched.into_optimized()?)?;
+        let result = plan.run(tvec!(input))?;
+        result[0].close_enough(&expected, false)
+    }
+
+    #[test]
+    fn add_batch_axis() -> TractResult<()> {
+        let model = model()?;
+        let n = model.symbol_scope.sym("n");
+        let batched = model.add_batch_axis(&n)?;
+        assert_eq!(
+            batched.input_fact(0)?.shape.to_tvec(),
+            tvec!(n.to_dim(), 1.to_dim(), 6.to_dim())
+        );
+        let item = tensor2(&[[0f32, 1., 2., 3., 4., 5.]]);
+        let mut expected = model.into_runnable()?.run(tvec!(item.clone()))?.remove(0).into_tensor();
+        expected.insert_axis(0)?;
+        let mut input = item;
+        input.insert_axis(0)?;
+        let input = Tensor::stack_tensors(0, &[&input, &input])?;
+        let result = SimplePlan::new(batched)?.run(tvec!(input))?;
+        result[0].close_enough(&Tensor::stack_tensors(0, &[&expected, &expected])?, false)
+    }
+
+    #[test]
+    fn reject_mixing_batch() -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let source = model.add_source("source", TypedFact::dt_shape(f32::datum_type(), &[1, 4]))?;
+        let sum = model.wire_node("sum", nn::Reduce::new(tvec!(0), nn::Reducer::Sum), &[source])?;
+        model.set_output_outlets(&sum)?;
+        let n = model.symbol_scope.sym("n");
+        let err = model.symbolize_axis(0, 0, &n).unwrap_err();
+        assert!(format!("{:?}", err).contains("\"sum\""));
+        Ok(())
+    }
+}
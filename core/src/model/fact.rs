@@ -243,7 +243,7 @@ impl TypedFact {
     }
 
     pub fn without_value(&self) -> Self {
-        Self::dt_shape(self.datum_type, self.shape.clone())
+        Self::dt_shape(self.datum_type.clone(), self.shape.clone())
     }
 }
 
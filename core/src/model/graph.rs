@@ -41,6 +41,9 @@ where
     /// model properties
     #[educe(Hash(method = "hash_properties"))]
     pub properties: HashMap<String, Arc<Tensor>>,
+    /// symbols of the model dimensions
+    #[educe(Hash(ignore))]
+    pub symbol_scope: SymbolScope,
 }
 
 fn hash_outlet_labels<H: std::hash::Hasher>(it: &HashMap<OutletId, String>, state: &mut H) {
@@ -73,6 +76,7 @@ where
             outputs: vec![],
             outlet_labels: HashMap::new(),
             properties: HashMap::new(),
+            symbol_scope: SymbolScope::default(),
         }
     }
 }
@@ -97,6 +101,14 @@ where
     F: Fact + Hash + Clone + 'static,
     O: fmt::Debug + fmt::Display + AsRef<dyn Op> + AsMut<dyn Op> + Clone + 'static + Hash,
 {
+    /// A hash of the whole model, nodes, facts and ops.
+    pub fn signature(&self) -> u64 {
+        use std::hash::Hasher;
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+
     pub fn add_node(
         &mut self,
         name: impl Into<String>,
@@ -36,6 +36,7 @@
 use std::collections::HashMap;
 use std::str;
 
+mod batch;
 mod fact;
 mod graph;
 mod node;
@@ -27,7 +27,7 @@ where
         &self,
         source: &Graph<TI1, O1>,
     ) -> TractResult<(Graph<TI2, O2>, HashMap<OutletId, OutletId>)> {
-        let mut target = Graph::default();
+        let mut target = Graph { symbol_scope: source.symbol_scope.clone(), ..Graph::default() };
         let mut mapping = HashMap::new();
         for old_id in source.eval_order()? {
             let node = source.node(old_id);
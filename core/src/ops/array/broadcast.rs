@@ -68,11 +68,15 @@ impl OpState for MultiBroadcastToState {
             &inputs[0], &*shape
         ))
     }
+
+    fn save(&self) -> TractResult<TVec<Tensor>> {
+        Ok(tvec!())
+    }
 }
 
 impl TypedOp for MultiBroadcastTo {
     fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
-        let mut fact = TypedFact::dt_shape(inputs[0].datum_type, self.shape.clone());
+        let mut fact = TypedFact::dt_shape(inputs[0].datum_type.clone(), self.shape.clone());
         fact.uniform = inputs[0].uniform.clone();
         Ok(tvec!(fact))
     }
@@ -6,6 +6,8 @@ pub struct DynSlice {
     pub axis: usize,
     pub start_input: bool,
     pub end_input: bool,
+    /// Length of the output along `axis`, usually a fresh symbol.
+    pub len: TDim,
 }
 
 impl DynHash for DynSlice {
@@ -15,8 +17,8 @@ impl DynHash for DynSlice {
 }
 
 impl DynSlice {
-    pub fn new(axis: usize, start_input: bool, end_input: bool) -> DynSlice {
-        DynSlice { axis, start_input, end_input }
+    pub fn new(axis: usize, start_input: bool, end_input: bool, len: TDim) -> DynSlice {
+        DynSlice { axis, start_input, end_input, len }
     }
 
     pub fn suffix(&self) -> String {
@@ -74,7 +76,7 @@ impl EvalOp for DynSlice {
 impl TypedOp for DynSlice {
     fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
         let mut fact = inputs[0].clone();
-        fact.shape.set(self.axis, Symbol::new('l').into());
+        fact.shape.set(self.axis, self.len.clone());
         Ok(tvec!(fact))
     }
 
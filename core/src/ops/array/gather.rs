@@ -79,7 +79,7 @@ impl TypedOp for Gather {
 
     fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
         Ok(tvec!(TypedFact::dt_shape(
-            inputs[0].datum_type,
+            inputs[0].datum_type.clone(),
             &*self
                 .compute_output_shape(&*inputs[0].shape.to_tvec(), &*inputs[1].shape.to_tvec())?
         )))
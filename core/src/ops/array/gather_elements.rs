@@ -40,7 +40,7 @@ impl TypedOp for GatherElements {
     as_op!();
 
     fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
-        Ok(tvec!(TypedFact::dt_shape(inputs[0].datum_type, &*inputs[1].shape.to_tvec())))
+        Ok(tvec!(TypedFact::dt_shape(inputs[0].datum_type.clone(), &*inputs[1].shape.to_tvec())))
     }
 }
 
@@ -48,7 +48,7 @@ impl TypedOp for ScatterNd {
     as_op!();
 
     fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
-        Ok(tvec!(TypedFact::dt_shape(inputs[0].datum_type, inputs[0].shape.to_tvec())))
+        Ok(tvec!(TypedFact::dt_shape(inputs[0].datum_type.clone(), inputs[0].shape.to_tvec())))
     }
 }
 
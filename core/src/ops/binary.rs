@@ -99,6 +99,10 @@ pub trait BinMiniOp:
     fn as_linalg_binop(&self) -> Option<tract_linalg::mmm::BinOp> {
         None
     }
+    #[allow(unused_variables)]
+    fn same_as(&self, other: &dyn BinMiniOp) -> bool {
+        false
+    }
 }
 dyn_clone::clone_trait_object!(BinMiniOp);
 downcast_rs::impl_downcast!(BinMiniOp);
@@ -123,6 +127,14 @@ impl Op for TypedBinOp {
         self.0.validation()
     }
 
+    fn same_as(&self, other: &dyn Op) -> bool {
+        if let Some(other) = other.downcast_ref::<TypedBinOp>() {
+            self.0.same_as(&*other.0)
+        } else {
+            false
+        }
+    }
+
     op_core_mir!();
     op_as_typed_op!();
 }
@@ -145,7 +157,7 @@ impl TypedOp for TypedBinOp {
             bail!("Typed ops require rank match. Invalid inputs for {}: {:?}", self.name(), inputs);
         }
         Ok(tvec!(TypedFact::dt_shape(
-            self.0.result_datum_type(inputs[0].datum_type, inputs[1].datum_type)?,
+            self.0.result_datum_type(inputs[0].datum_type.clone(), inputs[1].datum_type.clone())?,
             &*crate::broadcast::multi_broadcast(&[
                 &inputs[0].shape.to_tvec(),
                 &inputs[1].shape.to_tvec()
@@ -192,7 +204,7 @@ impl TypedOp for TypedBinOp {
         let count: TDim = self.output_facts(inputs)?[0].shape.iter().product();
         Ok(self
             .0
-            .cost_per_element(inputs[0].datum_type)
+            .cost_per_element(inputs[0].datum_type.clone())
             .into_iter()
             .map(|(c, n)| (c, count.clone() * n))
             .collect())
@@ -289,7 +301,7 @@ impl TypedOp for TypedBinOp {
         node: &TypedNode,
     ) -> TractResult<Option<TypedModelPatch>> {
         let inputs = model.node_input_facts(node.id)?;
-        if self.0.result_datum_type(inputs[0].datum_type, inputs[1].datum_type)?
+        if self.0.result_datum_type(inputs[0].datum_type.clone(), inputs[1].datum_type.clone())?
             == inputs[0].datum_type
             && inputs[0] == inputs[1]
         {
@@ -352,6 +364,14 @@ impl Op for UnaryOp {
         self.mini_op.validation()
     }
 
+    fn same_as(&self, other: &dyn Op) -> bool {
+        if let Some(other) = other.downcast_ref::<UnaryOp>() {
+            self.mini_op.same_as(&*other.mini_op) && self.a == other.a
+        } else {
+            false
+        }
+    }
+
     op_core_lir_mir!();
     op_as_typed_op!();
 }
@@ -373,7 +393,7 @@ impl TypedOp for UnaryOp {
             bail!("Rank mismatch: constant: {:?}, input: {:?}", self.a, inputs[0]);
         }
         Ok(tvec!(TypedFact::dt_shape(
-            self.mini_op.result_datum_type(self.a.datum_type(), inputs[0].datum_type)?,
+            self.mini_op.result_datum_type(self.a.datum_type(), inputs[0].datum_type.clone())?,
             &*crate::broadcast::multi_broadcast(&[
                 &*self.a.shape().iter().map(|d| d.to_dim()).collect::<TVec<_>>(),
                 &*inputs[0].shape.to_tvec()
@@ -445,7 +465,7 @@ impl TypedOp for UnaryOp {
         let count: TDim = self.output_facts(inputs)?[0].shape.iter().product();
         let mut cost: TVec<_> = self
             .mini_op
-            .cost_per_element(inputs[0].datum_type)
+            .cost_per_element(inputs[0].datum_type.clone())
             .into_iter()
             .map(|(c, n)| (c, count.clone() * n))
             .collect();
@@ -491,6 +511,14 @@ impl Op for MergeOpUnicast {
         self.0.name().into()
     }
 
+    fn same_as(&self, other: &dyn Op) -> bool {
+        if let Some(other) = other.downcast_ref::<MergeOpUnicast>() {
+            self.0.same_as(&*other.0)
+        } else {
+            false
+        }
+    }
+
     op_core_lir_mir!();
     op_as_typed_op!();
 }
@@ -518,7 +546,7 @@ impl TypedOp for MergeOpUnicast {
         let count: TDim = self.output_facts(inputs)?[0].shape.iter().product();
         Ok(self
             .0
-            .cost_per_element(inputs[0].datum_type)
+            .cost_per_element(inputs[0].datum_type.clone())
             .into_iter()
             .map(|(c, n)| (c, count.clone() * n))
             .collect())
@@ -559,6 +587,10 @@ macro_rules! bin_to_super_type {
                 stringify!($Op)
             }
 
+            fn same_as(&self, other: &dyn $crate::ops::binary::BinMiniOp) -> bool {
+                other.is::<Self>()
+            }
+
             fn eval_uniform_in_place(&self, a: &Tensor, b: &mut Tensor) -> TractResult<()> {
                 $(
                     $(if a.datum_type() == $typ::datum_type() {
@@ -581,7 +613,7 @@ macro_rules! bin_to_super_type {
                         $(
                             $(if a.datum_type().unquantized() == <$typ_dt>::datum_type().unquantized() {
                                 let cab: fn(&mut $typ_dt, &$typ_dt, &$typ_dt, i32, f32) -> () = $cab_dt;
-                                let (zp, scale) = a.datum_type().qparams().map(|q| q.zp_scale()).unwrap_or((0, 1.));
+                                let (zp, scale) = a.datum_type().zp_scale()?;
                                 let a = a.to_scalar::<$typ_dt>()?;
                                 let b = b.as_slice_mut::<$typ_dt>()?;
                                 unsafe {
@@ -620,7 +652,7 @@ macro_rules! bin_to_super_type {
                         $(
                             $(if a.datum_type().unquantized() == <$typ_dt>::datum_type().unquantized() {
                                 let cab: fn(&mut $typ_dt, &$typ_dt, &$typ_dt, i32, f32) -> () = $cab_dt;
-                                let (zp, scale) = a.datum_type().qparams().map(|q| q.zp_scale()).unwrap_or((0, 1.));
+                                let (zp, scale) = a.datum_type().zp_scale()?;
                                 let a = a.as_slice::<$typ_dt>()?;
                                 let b = b.as_slice_mut::<$typ_dt>()?;
                                 unsafe {
@@ -653,7 +685,7 @@ macro_rules! bin_to_super_type {
                         $(
                             $(if a.datum_type().unquantized() == <$typ_dt>::datum_type().unquantized() {
                                 let cab: fn(&mut $typ_dt, &$typ_dt, &$typ_dt, i32, f32) -> () = $cab_dt;
-                                let (zp, scale) = a.datum_type().qparams().map(|q| q.zp_scale()).unwrap_or((0, 1.));
+                                let (zp, scale) = a.datum_type().zp_scale()?;
                                 let a = a.to_array_view::<$typ_dt>()?;
                                 let b = b.to_array_view::<$typ_dt>()?;
                                 let mut c = c.to_array_view_mut::<$typ_dt>()?;
@@ -671,10 +703,20 @@ macro_rules! bin_to_super_type {
             })?
 
             fn operating_datum_type(&self, a: DatumType, b: DatumType) -> TractResult<DatumType> {
-                a.common_super_type(b).ok_or_else(|| format_err!("No super type for {:?} and {:?}", a, b))
+                a.common_super_type(b.clone()).ok_or_else(|| format_err!("No super type for {:?} and {:?}", a, b))
             }
 
             fn result_datum_type(&self, a: DatumType, b: DatumType) -> TractResult<DatumType> {
+                $(
+                    $(
+                        $(if a.unquantized() == <$typ_dt>::datum_type().unquantized()
+                            && a.unquantized() == b.unquantized()
+                            && a.is_quantized() && b.is_quantized() && a != b {
+                                bail!("{} needs operands sharing zero point and scale, got {:?} and {:?}", self.name(), a, b);
+                            }
+                        )*
+                     )*
+                 )?
                 if a.unquantized() == b.unquantized() {
                     if a.is_quantized() || !b.is_quantized() {
                         return Ok(a)
@@ -751,6 +793,10 @@ macro_rules! bin_to_bool {
                 stringify!($Op)
             }
 
+            fn same_as(&self, other: &dyn $crate::ops::binary::BinMiniOp) -> bool {
+                other.is::<Self>()
+            }
+
             fn eval_uniform_in_place(&self, a: &Tensor, b: &mut Tensor) -> TractResult<()> {
                 $(
                     $(if a.datum_type() == $typ::datum_type() {
@@ -807,7 +853,7 @@ macro_rules! bin_to_bool {
             }
 
             fn operating_datum_type(&self, a: DatumType, b: DatumType) -> TractResult<DatumType> {
-                a.common_super_type(b).ok_or_else(|| format_err!("No super type for {:?} and {:?}", a, b).into())
+                a.common_super_type(b.clone()).ok_or_else(|| format_err!("No super type for {:?} and {:?}", a, b).into())
             }
 
             fn result_datum_type(&self, _a: DatumType, _b: DatumType) -> TractResult<DatumType> {
@@ -26,7 +26,7 @@ impl EvalOp for Cast {
     }
 
     fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
-        Ok(tvec!(inputs[0].cast_to_dt(self.to)?.into_owned().into_arc_tensor()))
+        Ok(tvec!(inputs[0].cast_to_dt(self.to.clone())?.into_owned().into_arc_tensor()))
     }
 
     fn state(
@@ -54,17 +54,21 @@ impl OpState for Cast {
                 ) {
                     *i = dim.eval(&session.resolved_symbols).to_i64()?
                 }
-                Ok(tvec!(tmp.cast_to_dt(self.to)?.into_owned().into_arc_tensor()))
+                Ok(tvec!(tmp.cast_to_dt(self.to.clone())?.into_owned().into_arc_tensor()))
             }
         } else {
             <Cast as EvalOp>::eval(self, inputs)
         }
     }
+
+    fn save(&self) -> TractResult<TVec<Tensor>> {
+        Ok(tvec!())
+    }
 }
 
 impl TypedOp for Cast {
     fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
-        Ok(tvec!(TypedFact::dt_shape(self.to, inputs[0].shape.clone())))
+        Ok(tvec!(TypedFact::dt_shape(self.to.clone(), inputs[0].shape.clone())))
     }
 
     fn declutter(
@@ -231,9 +231,9 @@ impl AxisOp {
                 }
             }
             (Reshape(at, from, to), Add(change)) => {
-                if change < at {
+                if change <= at {
                     Some((Some(Reshape(at + 1, from.clone(), to.clone())), Some(Add(*change))))
-                } else if *change > *at + from.len() {
+                } else if *change >= *at + from.len() {
                     Some((
                         Some(Reshape(*at, from.clone(), to.clone())),
                         Some(Add(change + to.len() - from.len())),
@@ -245,7 +245,7 @@ impl AxisOp {
             (Reshape(at, from, to), Rm(change)) => {
                 if change < at {
                     Some((Some(Reshape(at - 1, from.clone(), to.clone())), Some(Rm(*change))))
-                } else if *change > *at + from.len() {
+                } else if *change >= *at + from.len() {
                     Some((
                         Some(Reshape(*at, from.clone(), to.clone())),
                         Some(Rm(change + to.len() - from.len())),
@@ -453,6 +453,7 @@ impl Op for AxisOp {
         }
     }
 
+    impl_op_same_as!();
     op_core_lir_mir!();
     op_as_typed_op!();
 }
@@ -502,6 +503,10 @@ impl OpState for ReshapeState {
             _ => bail!("Only reshape can be stateful"),
         }
     }
+
+    fn save(&self) -> TractResult<TVec<Tensor>> {
+        Ok(tvec!())
+    }
 }
 
 impl TypedOp for AxisOp {
@@ -510,7 +515,7 @@ impl TypedOp for AxisOp {
     fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
         let mut shape = inputs[0].shape.clone();
         self.change_shape(&mut shape, false)?;
-        Ok(tvec!(TypedFact::dt_shape(inputs[0].datum_type, shape)))
+        Ok(tvec!(TypedFact::dt_shape(inputs[0].datum_type.clone(), shape)))
     }
 
     fn invariants(
@@ -645,6 +650,17 @@ pub fn change_axes(
     locked: &[OutletId],
     bounds: &[TVec<OutletId>],
 ) -> TractResult<Option<(TypedModelPatch, TVec<(InOut, AxisOp)>)>> {
+    Ok(change_axes_or_blocker(model, change, locked, bounds)?.ok())
+}
+
+/// Same as `change_axes`, but a change that can not go through the graph comes back as an
+/// `Err` describing the locked interface or the node that stopped it.
+pub(crate) fn change_axes_or_blocker(
+    model: &TypedModel,
+    change: &AxisChange,
+    locked: &[OutletId],
+    bounds: &[TVec<OutletId>],
+) -> TractResult<Result<(TypedModelPatch, TVec<(InOut, AxisOp)>), String>> {
     trace!("Considering change {:?}", change);
     let mut todo_changes = vec![(change.clone(), None)];
     let mut changed_wires = HashMap::new();
@@ -659,7 +675,7 @@ pub fn change_axes(
         for outlet in outlets {
             if locked.contains(&outlet) {
                 trace!("  Change {:?} blocked by locked interface {:?}", change, outlet);
-                return Ok(None);
+                return Ok(Err(format!("locked interface {:?}", outlet)));
             }
             let mut nodes = vec![(outlet.node, InOut::Out(outlet.slot))];
             for inlet in model.outlet_successors(outlet) {
@@ -676,7 +692,7 @@ pub fn change_axes(
                     .with_context(|| format!("Propagating {:?} to node {}", change, node))?;
                 if more.is_none() {
                     trace!("    Propagation of {:?} blocked by {}", change, node);
-                    return Ok(None);
+                    return Ok(Err(format!("{} (applying {:?})", node, c.op)));
                 }
                 let AxisChangeConsequence { substitute_op, wire_changes } = more.unwrap();
                 if let Some(op) = substitute_op {
@@ -766,7 +782,7 @@ pub fn change_axes(
         patch.model.nodes.iter().map(|n| &n.name).collect::<std::collections::HashSet<_>>().len()
             == patch.model.nodes.len()
     );
-    Ok(Some((patch, interface_change)))
+    Ok(Ok((patch, interface_change)))
 }
 
 // a, b, c is a <- b, b <- c, c <- a
@@ -992,6 +1008,22 @@ mod test {
         let op = Rm(2);
         assert_eq!(op.merge_incoming_change(&change), Some((Some(Rm(1)), Some(Move(0, 1)))));
     }
+
+    // RESHAPE-ADD
+
+    //                          Op
+    //             6   ---|Reshape(0, 6, 2x3)|--->        2,3
+    //   Add(0)                                                       Add(0)
+    //           n,6   ---|Reshape(1, 6, 2x3)|--->        n,2,3
+    #[test]
+    pub fn transform_op_add_0_reshape_0() {
+        let change = Add(0);
+        let op = Reshape(0, tvec!(6.into()), tvec!(2.into(), 3.into()));
+        assert_eq!(
+            op.merge_incoming_change(&change),
+            Some((Some(Reshape(1, tvec!(6.into()), tvec!(2.into(), 3.into()))), Some(Add(0))))
+        );
+    }
 }
 
 #[cfg(test)]
@@ -92,13 +92,13 @@ impl DepthWise {
 
 impl TypedOp for DepthWise {
     fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
-        Ok(tvec!(TypedFact::dt_shape(inputs[0].datum_type, &self.output_shape.shape)))
+        Ok(tvec!(TypedFact::dt_shape(inputs[0].datum_type.clone(), &self.output_shape.shape)))
     }
 
     fn cost(&self, inputs: &[&TypedFact]) -> TractResult<TVec<(Cost, TDim)>> {
         let n_output_points = self.patch.output_shape.iter().cloned().product::<usize>();
         Ok(tvec!((
-            Cost::FMA(inputs[0].datum_type),
+            Cost::FMA(inputs[0].datum_type.clone()),
             (self.input_shape.n().unwrap_or(&1) * n_output_points * self.kernel_chw.len()).to_dim()
         )))
     }
@@ -217,7 +217,7 @@ impl TypedOp for Im2Col {
         let input_shape = self.pool_spec.data_format.shape(inputs[0].shape.to_tvec())?;
         let output_shape = self.pool_spec.output_shape(&inputs[0].shape)?;
         Ok(tvec!(TypedFact::dt_shape(
-            inputs[0].datum_type,
+            inputs[0].datum_type.clone(),
             Self::packed_shape(
                 &input_shape,
                 &output_shape,
@@ -236,7 +236,7 @@ impl TypedOp for Im2Col {
         let input_fact = model.outlet_fact(node.inputs[0])?;
         if node.inputs.len() == 2
             && model.outlet_fact(node.inputs[1])?.konst.as_ref().and_then(|t| t.as_uniform())
-                == Some(Tensor::zero_scalar_dt(input_fact.datum_type)?)
+                == Some(Tensor::zero_scalar_dt(input_fact.datum_type.clone())?)
         {
             Ok(Some(
                 TypedModelPatch::replace_single_op(model, node, &node.inputs[0..1], self.clone())?
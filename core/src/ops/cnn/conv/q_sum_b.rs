@@ -57,6 +57,10 @@ impl OpState for QSumBState {
         let n = op.n.eval(&session.resolved_symbols).to_usize()?;
         op.eval(inputs, n)
     }
+
+    fn save(&self) -> TractResult<TVec<Tensor>> {
+        Ok(tvec!())
+    }
 }
 
 impl TypedOp for QSumB {
@@ -53,12 +53,19 @@ impl ConvUnary {
     }
 
     pub fn kernel_as_group_o_ihw(&self) -> TractResult<Arc<Tensor>> {
-        self.kernel_fmt.kernel_as_group_o_ihw(
+        let kernel = self.kernel_fmt.kernel_as_group_o_ihw(
             &self.kernel,
             self.group,
             self.input_channels(),
             self.output_channels(),
-        )
+        )?;
+        if self.kernel.datum_type().qparams().map(|qp| qp.is_per_axis()).unwrap_or(false) {
+            // the axis is gone after the reshape, the parameters are carried by q_params anyway
+            let mut kernel = kernel.into_tensor();
+            unsafe { kernel.set_datum_type(kernel.datum_type().unquantized()) };
+            return Ok(kernel.into_arc_tensor());
+        }
+        Ok(kernel)
     }
 
     fn kernel_as_packed_as(
@@ -143,7 +150,7 @@ impl ConvUnary {
                                 QParamKind::FromInput(*i)
                             }
                         };
-                        Ok((*dt, MatMulQParams { a0, ..qp.clone() }))
+                        Ok((dt.clone(), MatMulQParams { a0, ..qp.clone() }))
                     })
                     .transpose()?,
                 ..self.clone()
@@ -192,7 +199,7 @@ impl ConvUnary {
     ) -> TractResult<OutletId> {
         use crate::ops::matmul::mir_quant as qmm;
 
-        let c_dt = self.q_params.as_ref().unwrap().0;
+        let c_dt = self.q_params.as_ref().unwrap().0.clone();
 
         let params = self.q_params.as_ref().unwrap().1.as_outlet_ids(
             model,
@@ -200,23 +207,21 @@ impl ConvUnary {
             &wires,
             self.kernel.datum_type(),
             b_dt,
-            c_dt,
+            c_dt.clone(),
         )?;
 
-        let a0 = params[0];
-        let a_scale = params[1];
+        let mut a0 = params[0];
+        let mut a_scale = params[1];
         let mut b0 = params[2];
         let b_scale = params[3];
-        let c0 = params[4];
-        let c_scale = params[5];
+        let mut c0 = params[4];
+        let mut c_scale = params[5];
 
         let b = wire_offset_u8_as_i8(model, name, wires[0], "b", &mut b0, "b0")?;
         let b_fact = model.outlet_fact(b)?.clone();
         let (_, m, k, n, mmm) = self.compute_geo(&b_fact)?;
         let output_shape = self.pool_spec.output_shape(&b_fact.shape)?;
 
-        let abc_scale = qmm::combine_scales(model, name, a_scale, b_scale, c_scale)?;
-
         let im2col = model.wire_node(
             format!("{}.im2col", name),
             Im2Col::new(self.pool_spec.clone(), self.group, k, &b_fact.shape, mmm.clone())?,
@@ -251,7 +256,7 @@ impl ConvUnary {
             )?[0];
         }
 
-        let b_dt = model.outlet_fact(b)?.datum_type;
+        let b_dt = model.outlet_fact(b)?.datum_type.clone();
         let (mmm_output_shape, c_axis, h_axis) = self.mmm_output_shape(&output_shape)?;
         let mut geometry = MatMulGeometry::from(SymbolicMatMulGeometry {
             b_datum_type: b_dt,
@@ -283,6 +288,18 @@ impl ConvUnary {
         } else {
             (has_group + has_n, 1 + has_n + has_group)
         };
+        for (param_name, param) in
+            [("a0", &mut a0), ("a_scale", &mut a_scale), ("c0", &mut c0), ("c_scale", &mut c_scale)]
+        {
+            *param =
+                qmm::wire_per_channel(model, name, param_name, *param, wire, m_axis, self.group)?;
+        }
+        for param in &[b0, b_scale] {
+            if model.outlet_fact(*param)?.shape.volume() != 1.to_dim() {
+                bail!("Per-channel quantization is not supported for convolution input")
+            }
+        }
+        let abc_scale = qmm::combine_scales(model, name, a_scale, b_scale, c_scale)?;
         let wire = qmm::compensate_zero_points(
             model,
             name,
@@ -319,12 +336,13 @@ impl ConvUnary {
         mut wire: OutletId,
     ) -> TractResult<OutletId> {
         let b_fact = model.outlet_fact(wire)?.clone();
-        let b_dt = b_fact.datum_type;
+        let b_dt = b_fact.datum_type.clone();
         let c_dt = crate::ops::matmul::output_type(b_fact.datum_type);
 
         let output_shape = self.pool_spec.output_shape(&b_fact.shape)?;
         let (_, m, k, n, mmm) = self.compute_geo(model.outlet_fact(wire)?)?;
-        let padding = model.add_const(format!("{}.b0", name), Tensor::zero_dt(b_dt, &[])?)?;
+        let padding =
+            model.add_const(format!("{}.b0", name), Tensor::zero_dt(b_dt.clone(), &[])?)?;
 
         wire = model.wire_node(
             format!("{}.im2col", name),
@@ -350,8 +368,8 @@ impl ConvUnary {
             mmm,
             c_dt,
             mmm_output_shape.clone().into(),
-            m.to_usize().unwrap(),
-            k.to_usize().unwrap(),
+            m,
+            k,
             geometry,
             c_axis,
             h_axis,
@@ -418,23 +436,33 @@ impl ConvUnary {
         Ok(wire[0])
     }
 
+    /// Wire the convolution as a matmul reading the patches straight from the input.
+    ///
+    /// The patches offsets are computed from the input shape, so it must be concrete: symbolic
+    /// input shapes are wired by `wire_as_im2col_pair` instead.
     pub unsafe fn wire_as_lazy_im2col(
         &self,
         model: &mut TypedModel,
         name: &str,
         mut wire: OutletId,
+        input_full_shape: &[usize],
     ) -> TractResult<OutletId> {
         let mut b_fact = model.outlet_fact(wire)?.clone();
         let (geo, m, k, n, mmm) = self.compute_geo(&b_fact)?;
-        let input_shape = b_fact.shape.as_concrete().unwrap().to_vec();
-        let mut geo = geo.to_concrete(&input_shape)?.into_owned();
-        let mut input_shape: DataShape = self.pool_spec.data_format.shape(input_shape.into())?;
+        let mut geo = geo.to_concrete(input_full_shape)?.into_owned();
+        let mut input_shape: DataShape =
+            self.pool_spec.data_format.shape(input_full_shape.into())?;
         let padding = self.pool_spec.computed_padding(input_shape.hw_dims());
         if padding.iter().any(|axis| axis.pad_before != 0 || axis.pad_after != 0) {
             let mut pads = vec![(0, 0); b_fact.rank()];
             for (ix, ax) in padding.iter().enumerate() {
                 pads[input_shape.h_axis() + ix] = (ax.pad_before, ax.pad_after);
             }
+            let padded_shape: TVec<usize> = input_full_shape
+                .iter()
+                .zip(pads.iter())
+                .map(|(d, (before, after))| d + before + after)
+                .collect();
             let op = crate::ops::array::Pad {
                 mode: crate::ops::array::PadMode::Constant(
                     Tensor::zero_scalar_dt(b_fact.datum_type)?.into_arc_tensor(),
@@ -445,14 +473,13 @@ impl ConvUnary {
             let valid_pool_spec =
                 PoolSpec { padding: ops::cnn::PaddingSpec::Valid, ..self.pool_spec.clone() };
             b_fact = model.outlet_fact(wire)?.clone();
-            let concrete_shape = b_fact.shape.as_concrete().unwrap();
-            input_shape = valid_pool_spec.data_format.shape(concrete_shape.into())?;
             geo = valid_pool_spec
                 .compute_geo(&b_fact.shape)?
-                .to_concrete(&*concrete_shape)?
+                .to_concrete(&padded_shape)?
                 .into_owned();
+            input_shape = valid_pool_spec.data_format.shape(padded_shape)?;
         }
-        let c_dt = crate::ops::matmul::output_type(b_fact.datum_type);
+        let c_dt = crate::ops::matmul::output_type(b_fact.datum_type.clone());
         let c_stride = input_shape.c_stride();
         let size_of_b = b_fact.datum_type.size_of() as isize;
         let n_bytes_offsets: Vec<isize> =
@@ -469,12 +496,8 @@ impl ConvUnary {
         let b_storage = mmm.b_virtual_input(Box::new(virtual_input), k);
         let (mmm_output_shape, c_axis, h_axis) = self.mmm_output_shape(&geo.output_shape)?;
 
-        let geometry = MatMulGeometry::Concrete(ConcreteMatMulGeometry {
-            m,
-            k,
-            n: n.to_usize().unwrap(),
-            b_storage,
-        });
+        let geometry =
+            MatMulGeometry::Concrete(ConcreteMatMulGeometry { m, k, n: n.to_usize()?, b_storage });
         let wire = self.wire_lir_matmatmul(
             model,
             name,
@@ -482,7 +505,7 @@ impl ConvUnary {
             mmm,
             c_dt,
             mmm_output_shape.into(),
-            m.to_usize().unwrap(),
+            m,
             k,
             geometry,
             c_axis,
@@ -498,8 +521,8 @@ impl ConvUnary {
         input_fact: &TypedFact,
     ) -> TractResult<(PoolGeometry, usize, usize, TDim, Box<dyn MatMatMul>)> {
         let a_dt = self.kernel.datum_type();
-        let b_dt = input_fact.datum_type;
-        let c_dt = crate::ops::matmul::output_type(b_dt);
+        let b_dt = input_fact.datum_type.clone();
+        let c_dt = crate::ops::matmul::output_type(b_dt.clone());
 
         let geo = self.pool_spec.compute_geo(&input_fact.shape)?;
 
@@ -510,7 +533,7 @@ impl ConvUnary {
             self.pool_spec.output_shape(&input_fact.shape)?.hw_dims().iter().cloned().product();
 
         let mmm = tract_linalg::ops()
-            .mmm(a_dt, b_dt, c_dt, Some(m), Some(k), n.to_usize().ok())
+            .mmm(a_dt.clone(), b_dt.clone(), c_dt.clone(), Some(m), Some(k), n.to_usize().ok())
             .with_context(|| format!("No multiplier for {:?}x{:?} to {:?}", a_dt, b_dt, c_dt,))?;
 
         Ok((geo, m, k, n, mmm))
@@ -531,6 +554,8 @@ impl ConvUnary {
         c_n_axis: usize,
     ) -> TractResult<OutletId> {
         let kernels = self.kernel_as_packed_as(&mmm.a_pack(), k, m)?;
+        let a_dt = kernels.iter().next().context("No kernel")?.datum_type();
+        let a_storage = unsafe { mmm.a_packed(a_dt.size_of(), k) };
         let shape = kernels.shape();
         let mut fused_ops = dispatch_copy!(Self::bias_as_non_linear(mmm.internal_type())(self))?;
         for fo in &mut fused_ops {
@@ -543,6 +568,7 @@ impl ConvUnary {
             format!("{}.matmatmul", name),
             LirMatMulUnary {
                 c_fact: TypedFact::dt_shape(c_datum_type, mmm_output_shape.clone()),
+                a_storage,
                 micro_ops,
                 c_m_axis,
                 c_n_axis,
@@ -556,13 +582,14 @@ impl ConvUnary {
         Ok(wire)
     }
 
-    pub fn to_depth_wise<T>(&self, input: &TypedFact) -> TractResult<Box<dyn TypedOp>>
+    /// The depthwise convolution op, for the concrete input shape its patch is computed from.
+    pub fn to_depth_wise<T>(&self, input_full_shape: &[usize]) -> TractResult<Box<dyn TypedOp>>
     where
         T: Datum + Clone + ::ndarray::LinalgScalar + PartialEq + Sum,
     {
-        let input_shape = input.shape.as_concrete().unwrap();
+        let shape: ShapeFact = input_full_shape.into();
         let ConcretePoolGeometry { input_shape, patch, output_shape } =
-            self.pool_spec.compute_geo(&input.shape)?.to_concrete(input_shape)?.into_owned();
+            self.pool_spec.compute_geo(&shape)?.to_concrete(input_full_shape)?.into_owned();
         let op = DepthWise::new(
             patch,
             input_shape,
@@ -663,7 +690,7 @@ impl ConvUnary {
                     a_trans,
                     b_trans: trans_data,
                     c_trans: trans_data,
-                    output_type: q_params.0,
+                    output_type: q_params.0.clone(),
                     params: q_params.1.clone(),
                 };
                 patch.wire_node(&*node.name, op, &inputs)?[0]
@@ -786,7 +813,7 @@ impl TypedOp for ConvUnary {
 
         let mut fact = self.pool_spec.output_facts(inputs)?.remove(0);
         if let Some((dt, _qp)) = self.q_params.as_ref() {
-            fact.datum_type = *dt;
+            fact.datum_type = dt.clone();
         }
         Ok(tvec!(fact))
     }
@@ -860,7 +887,7 @@ impl TypedOp for ConvUnary {
                 (self.kernel.len() + self.bias.as_ref().map(|b| b.len()).unwrap_or(0)).to_dim()
             ),
             (
-                Cost::FMA(inputs[0].datum_type),
+                Cost::FMA(inputs[0].datum_type.clone()),
                 shape.n().cloned().unwrap_or(one)
                     * shape.c()
                     * n_output_channels
@@ -992,7 +1019,7 @@ impl TypedOp for ConvUnary {
         let spatial_rank = input_shape.hw_rank();
         let kernel_spatial_shape = &self.kernel.shape()[self.kernel_fmt.h_axis()..][..spatial_rank];
         unsafe {
-            let dt = input_fact.datum_type;
+            let dt = input_fact.datum_type.clone();
             if self.q_params.is_some() {
                 let mut patch = TypedModelPatch::default();
                 let inputs = node
@@ -1003,7 +1030,7 @@ impl TypedOp for ConvUnary {
                 let wire = self.wire_as_quant_im2col(
                     &mut patch,
                     &node.name,
-                    model.node_input_facts(node.id)?[0].datum_type,
+                    model.node_input_facts(node.id)?[0].datum_type.clone(),
                     &inputs,
                 )?;
                 patch.shunt_outside(model, node.id.into(), wire)?;
@@ -1076,30 +1103,25 @@ impl TypedOp for ConvUnary {
                 patch.shunt_outside(model, OutletId::new(node.id, 0), wire)?;
                 patch.obliterate(node.id)?;
                 return Ok(Some(patch));
-            } else if input_fact
-                .shape
-                .as_concrete()
-                .map(|s| {
-                    should_use_lazy(
-                        &self.pool_spec.data_format.shape(s.into()).unwrap(),
-                        &self.pool_spec,
-                        self.group,
-                    )
-                })
-                .unwrap_or(false)
+            }
+            // lazy im2col and depthwise compute their offsets from the input shape: a symbolic
+            // input goes through the im2col pair, resolving its geometry at run time
+            let concrete_shape = input_fact.shape.as_concrete();
+            if let Some(shape) =
+                concrete_shape.filter(|_| should_use_lazy(&self.pool_spec, self.group))
             {
                 let mut patch = TypedModelPatch::new("wire_as_lazy_im2col");
                 let mut wire = patch.tap_model(model, node.inputs[0])?;
-                wire = self.wire_as_lazy_im2col(&mut patch, &*node.name, wire)?;
+                wire = self.wire_as_lazy_im2col(&mut patch, &*node.name, wire, shape)?;
                 patch.shunt_outside(model, OutletId::new(node.id, 0), wire)?;
                 patch.obliterate(node.id)?;
                 return Ok(Some(patch));
-            } else if self.group != 1
-                && self.group == self.output_channels()
-                && self.group == self.input_channels()
-                && input_fact.shape.as_concrete().is_some()
-            {
-                let op = dispatch_floatlike!(Self::to_depth_wise(dt)(self, &input_fact))
+            } else if let Some(shape) = concrete_shape.filter(|_| {
+                self.group != 1
+                    && self.group == self.output_channels()
+                    && self.group == self.input_channels()
+            }) {
+                let op = dispatch_floatlike!(Self::to_depth_wise(dt)(self, shape))
                     .context("in to_depth_wise")?;
                 return Ok(Some(TypedModelPatch::single_unary_op(model, node, op)?));
             } else {
@@ -1118,7 +1140,7 @@ impl TypedOp for ConvUnary {
     as_op!();
 }
 
-fn should_use_lazy(_input_shape: &DataShape, pool_spec: &PoolSpec, group: usize) -> bool {
+fn should_use_lazy(pool_spec: &PoolSpec, group: usize) -> bool {
     group == 1 && pool_spec.kernel_shape.iter().product::<usize>() > 5
 }
 
@@ -1158,4 +1180,46 @@ mod test {
         let output = op.eval(input).unwrap();
         assert_eq!(&*output[0], &tensor4(&[[[[8i32, 12], [20, 24]]]]));
     }
+
+    #[test]
+    fn codegen_with_symbolic_input() -> TractResult<()> {
+        // a kernel this large would use lazy im2col on a concrete input
+        let kernel =
+            Tensor::from_shape(&[1, 2, 7], &(0..14).map(|i| i as f32).collect::<Vec<_>>())?;
+        let op = ConvUnary {
+            pool_spec: PoolSpec {
+                data_format: CHW,
+                kernel_shape: tvec!(7),
+                padding: PaddingSpec::Valid,
+                dilations: None,
+                strides: None,
+                output_channel_override: Some(1),
+            },
+            kernel_fmt: KernelFormat::OIHW,
+            kernel: kernel.into_arc_tensor(),
+            group: 1,
+            bias: None,
+            q_params: None,
+        };
+        let mut model = TypedModel::default();
+        let t = model.symbol_scope.sym("t");
+        let shape = [2.to_dim(), TDim::from(&t)];
+        let source = model.add_source("source", TypedFact::dt_shape(f32::datum_type(), &shape))?;
+        let conv = model.wire_node("conv", op, &[source])?;
+        model.set_output_outlets(&conv)?;
+        let optimized = model.clone().into_optimized()?;
+        assert!(optimized.nodes().iter().any(|n| n.op_is::<Im2Col>()));
+        let reference = SimplePlan::new(model)?;
+        let plan = SimplePlan::new(optimized)?;
+        for len in &[7usize, 9, 20] {
+            let input = Tensor::from_shape(
+                &[2, *len],
+                &(0..2 * len).map(|i| i as f32).collect::<Vec<_>>(),
+            )?;
+            let expected = reference.run(tvec!(input.clone()))?;
+            let found = plan.run(tvec!(input))?;
+            found[0].close_enough(&expected[0], true)?;
+        }
+        Ok(())
+    }
 }
@@ -179,7 +179,7 @@ impl TypedOp for DeconvUnary {
         }
         let x_fact = inputs[0];
         let output_shape = super::output_shape(&self.pool_spec, &*x_fact.shape, &self.adjustments)?;
-        Ok(tvec!(TypedFact::dt_shape(x_fact.datum_type, &output_shape)))
+        Ok(tvec!(TypedFact::dt_shape(x_fact.datum_type.clone(), &output_shape)))
     }
 
     fn invariants(
@@ -38,9 +38,9 @@ impl EvalOp for MaxPool {
 impl TypedOp for MaxPool {
     fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
         let mut facts = self.pool_spec.output_facts(inputs)?;
-        if let Some(idt) = self.with_index_outputs {
+        if let Some(idt) = &self.with_index_outputs {
             facts.push(facts[0].clone());
-            facts[1].datum_type = idt;
+            facts[1].datum_type = idt.clone();
         }
         Ok(facts)
     }
@@ -114,9 +114,9 @@ impl EvalOp for LirMaxPool {
 impl TypedOp for LirMaxPool {
     fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
         let mut facts = self.pool_spec.output_facts(inputs)?;
-        if let Some(idt) = self.with_index_outputs {
+        if let Some(idt) = &self.with_index_outputs {
             facts.push(facts[0].clone());
-            facts[1].datum_type = idt;
+            facts[1].datum_type = idt.clone();
         }
         Ok(facts)
     }
@@ -172,10 +172,15 @@ impl LirMaxPool {
         unsafe {
             values.set_datum_type(input_dt);
         }
-        if let Some(dt) = self.with_index_outputs {
+        if let Some(dt) = &self.with_index_outputs {
             Ok(tvec!(
                 values.into_arc_tensor(),
-                indices.unwrap().into_tensor().cast_to_dt(dt)?.into_owned().into_arc_tensor()
+                indices
+                    .unwrap()
+                    .into_tensor()
+                    .cast_to_dt(dt.clone())?
+                    .into_owned()
+                    .into_arc_tensor()
             ))
         } else {
             Ok(tvec!(values.into_arc_tensor()))
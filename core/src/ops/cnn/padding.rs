@@ -285,6 +285,19 @@ mod tests {
         assert_eq!(PS::same(&3usize, 3usize, 1, 3, true), ComputedPaddedDim::new(3, 1, 0, 0));
     }
 
+    #[test]
+    fn valid_symbolic() {
+        let sym = SymbolScope::default().sym("S");
+        let s: TDim = sym.clone().into();
+        let computed = PS::valid(&s, 3usize, 1, 2);
+        assert_eq!(computed.convoluted, s.clone().div_ceil(2) - 1);
+        for len in 3..10 {
+            let values = SymbolValues::default().with(sym.clone(), len as i64);
+            let expected = PS::valid(&len, 3usize, 1, 2).convoluted;
+            assert_eq!(computed.convoluted.eval(&values), expected.to_dim());
+        }
+    }
+
     #[test]
     fn valid_1() {
         assert_eq!(PS::valid(&10usize, 2usize, 1, 3), ComputedPaddedDim::new(10, 3, 0, 0));
@@ -50,6 +50,10 @@ pub trait ElementWiseMiniOp:
     fn info(&self) -> TractResult<Vec<String>> {
         Ok(vec![])
     }
+    #[allow(unused_variables)]
+    fn same_as(&self, other: &dyn ElementWiseMiniOp) -> bool {
+        false
+    }
 }
 
 impl Hash for Box<dyn ElementWiseMiniOp> {
@@ -78,6 +82,14 @@ impl Op for ElementWiseOp {
         self.0.validation()
     }
 
+    fn same_as(&self, other: &dyn Op) -> bool {
+        if let Some(other) = other.downcast_ref::<ElementWiseOp>() {
+            self.0.same_as(&*other.0)
+        } else {
+            false
+        }
+    }
+
     op_core_lir_mir!();
     op_as_typed_op!();
 }
@@ -108,7 +120,7 @@ impl EvalOp for ElementWiseOp {
 impl TypedOp for ElementWiseOp {
     fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
         let mut fact = inputs[0].clone().without_value();
-        if let Some(dt) = self.0.output_type(fact.datum_type) {
+        if let Some(dt) = self.0.output_type(fact.datum_type.clone()) {
             fact.datum_type = dt;
         }
         Ok(tvec!(fact))
@@ -140,7 +152,7 @@ impl TypedOp for ElementWiseOp {
         let count: TDim = inputs[0].shape.iter().product();
         Ok(self
             .0
-            .cost_per_element(inputs[0].datum_type)
+            .cost_per_element(inputs[0].datum_type.clone())
             .into_iter()
             .map(|(c, n)| (c, count.clone() * n))
             .collect())
@@ -176,13 +188,16 @@ macro_rules! element_wise {
         $(; validation: $validation:expr )?
     ) => {
         #[derive(Debug, Clone, Educe)]
-        #[educe(Hash)]
+        #[educe(Hash, PartialEq)]
         pub struct $Op { $( $( $(#[$meta])? pub $var: $var_typ),* )? }
         tract_data::internal::impl_dyn_hash!($Op);
         impl $crate::ops::element_wise::ElementWiseMiniOp for $Op {
             fn name(&self) -> String {
                 format!("{}{}", self.prefix(), stringify!($Op))
             }
+            fn same_as(&self, other: &dyn $crate::ops::element_wise::ElementWiseMiniOp) -> bool {
+                other.downcast_ref::<Self>().map(|other| other == self).unwrap_or(false)
+            }
             fn eval_in_place(&self, t: &mut Tensor) -> TractResult<()> {
                 $(
                     $(if t.datum_type() == $typ::datum_type() {
@@ -199,7 +214,7 @@ macro_rules! element_wise {
                            let dt = t.datum_type();
                            let t: &mut[$typ_dt] = t.as_slice_mut::<$typ_dt>()?;
                            let f: fn(&Self, &mut[$typ_dt], DatumType) -> TractResult<()> = |_, xs, dt| {
-                            let (zp, scale) = dt.zp_scale();
+                            let (zp, scale) = dt.zp_scale()?;
                             xs.iter_mut().for_each(|x| {
                                 let x_f32 = (*x as f32 - zp as f32) * scale;
                                 *x = (($f_f32(x_f32) / scale) + zp as f32).as_()
@@ -265,13 +280,16 @@ macro_rules! element_wise_oop {
         $(; validation: $validation:expr )?
     ) => {
         #[derive(Debug, Clone, Educe)]
-        #[educe(Hash)]
+        #[educe(Hash, PartialEq)]
         pub struct $Op { $( $($(#[$meta])? pub $var: $var_typ),* )? }
         tract_data::internal::impl_dyn_hash!($Op);
         impl $crate::ops::element_wise::ElementWiseMiniOp for $Op {
             fn name(&self) -> String {
                 format!("{}{}", self.prefix(), stringify!($Op))
             }
+            fn same_as(&self, other: &dyn $crate::ops::element_wise::ElementWiseMiniOp) -> bool {
+                other.downcast_ref::<Self>().map(|other| other == self).unwrap_or(false)
+            }
             fn output_type(&self, input_type: DatumType) -> Option<DatumType> {
                 $(
                     $(if input_type == $typ::datum_type() {
@@ -97,7 +97,7 @@ impl TypedOp for Iff {
             inputs[2].shape.to_tvec(),
         ])
         .unwrap();
-        Ok(tvec!(TypedFact::dt_shape(inputs[1].datum_type, shape)))
+        Ok(tvec!(TypedFact::dt_shape(inputs[1].datum_type.clone(), shape)))
     }
 
     fn invariants(
@@ -89,7 +89,7 @@ bin_to_super_type!(mul, Mul,
          else {
              match c.datum_type() {
                  DatumType::QI8(params) => {
-                    let (zp, scale) = params.zp_scale();
+                    let (zp, scale) = params.zp_scale()?;
                     let a = a.to_array_view::<i8>()?;
                     let b = b.to_array_view::<i8>()?;
                     let c = c.to_array_view_mut::<i8>()?;
@@ -100,7 +100,7 @@ bin_to_super_type!(mul, Mul,
                     Ok(true)
                  }
                  DatumType::QU8(params) => {
-                    let (zp, scale) = params.zp_scale();
+                    let (zp, scale) = params.zp_scale()?;
                     let a = a.to_array_view::<u8>()?;
                     let b = b.to_array_view::<u8>()?;
                     let c = c.to_array_view_mut::<u8>()?;
@@ -179,12 +179,24 @@ bin_to_super_type!(rem, Rem,
                    [f32, i8, i16, i32, i64, u8, u16, u32, u64, f16, f64] => |c, a, b| *c = a.clone() % b);
 
 bin_to_super_type!(min, Min, flip:commute, linalg:Min,
+                   q: [i8, u8] => min_quant;
                    [f32, f64] => |c,a,b| *c = a.min(*b),
                    [i8, i16, i32, i64, u8, u16, u32, u64] => |c, a, b| *c = *a.min(b));
 bin_to_super_type!(max, Max, flip:commute, linalg:Max,
+                   q: [i8, u8] => max_quant;
                    [f32, f64] => |c,a,b| *c = a.max(*b),
                    [i8, i16, i32, i64, u8, u16, u32, u64] => |c, a, b| *c = *a.max(b));
 
+// min and max are monotonic, so operands sharing zero point and scale (checked by
+// result_datum_type) can be compared raw
+fn min_quant<T: PrimInt>(c: &mut T, a: &T, b: &T, _: i32, _: f32) {
+    *c = *a.min(b)
+}
+
+fn max_quant<T: PrimInt>(c: &mut T, a: &T, b: &T, _: i32, _: f32) {
+    *c = *a.max(b)
+}
+
 bin_to_super_type!(pow, Pow,
                    flip: flip_pow,
                    [f32, f64] => |c,a,b| *c = a.powf(*b),
@@ -261,7 +273,7 @@ fn declutter_unary_mul_magic_values(
     } else if a.is_uniform() && a.cast_to_scalar::<f64>()?.is_zero() {
         let mut patch = TypedModelPatch::default();
         let fact = model.outlet_fact(node.inputs[0])?;
-        let zero = Tensor::zero_dt(fact.datum_type, &[])?;
+        let zero = Tensor::zero_dt(fact.datum_type.clone(), &[])?;
         let zero = patch.add_const(format!("{}.zero", node.name), zero)?;
         let shape = crate::broadcast::multi_broadcast(&[
             fact.shape.to_vec(),
@@ -332,7 +344,7 @@ fn declutter_as_shift(
                 node,
                 &node.inputs[0..=0],
                 UnaryOp {
-                    a: shift.cast_to_dt(input.datum_type)?.into_owned().into_arc_tensor(),
+                    a: shift.cast_to_dt(input.datum_type.clone())?.into_owned().into_arc_tensor(),
                     mini_op,
                 },
             )?));
@@ -535,7 +547,7 @@ element_wise!(tanh, Tanh,
  [f32] => |_, xs| { (tract_linalg::ops().tanh_f32)().run(xs) },
  [f16, f64] => |_, xs| { xs.iter_mut().for_each(|x| *x = x.tanh()); Ok(()) };
  q: [i8, u8] => f32::tanh;
- cost: |dt| {tvec!((Cost::FMA(dt), 11), (Cost::Div(dt), 1))}
+ cost: |dt: DatumType| {tvec!((Cost::FMA(dt.clone()), 11), (Cost::Div(dt), 1))}
 );
 
 element_wise!(acosh, Acosh, [f16, f32, f64] => |_, xs| { 
@@ -601,6 +613,32 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn max_quantized() -> TractResult<()> {
+        let dt = DatumType::QI8(QParams::ZpScale { zero_point: 3, scale: 0.5 });
+        let mut model = TypedModel::default();
+        let a = model.add_source("a", TypedFact::dt_shape(dt.clone(), &[3]))?;
+        let b = model.add_source("b", TypedFact::dt_shape(dt.clone(), &[3]))?;
+        let c = model.wire_node("c", max::bin_typed(), &[a, b])?;
+        model.set_output_outlets(&c)?;
+        let a = tensor1(&[-1i8, 4, 7]).cast_to_dt(dt.clone())?.into_owned();
+        let b = tensor1(&[2i8, 1, 9]).cast_to_dt(dt.clone())?.into_owned();
+        let result = SimplePlan::new(&model)?.run(tvec!(a, b))?;
+        assert_eq!(result[0].datum_type(), dt);
+        assert_eq!(result[0].to_array_view::<i8>()?, tensor1(&[2i8, 4, 9]).to_array_view::<i8>()?);
+        Ok(())
+    }
+
+    #[test]
+    fn min_rejects_operands_of_different_quantization() -> TractResult<()> {
+        let qi8 = |zero_point| DatumType::QI8(QParams::ZpScale { zero_point, scale: 0.5 });
+        let mut model = TypedModel::default();
+        let a = model.add_source("a", TypedFact::dt_shape(qi8(0), &[3]))?;
+        let b = model.add_source("b", TypedFact::dt_shape(qi8(3), &[3]))?;
+        assert!(model.wire_node("c", min::bin_typed(), &[a, b]).is_err());
+        Ok(())
+    }
+
     #[test]
     fn div_as_shift() -> TractResult<()> {
         let mut model = TypedModel::default();
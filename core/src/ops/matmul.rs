@@ -1,5 +1,7 @@
+pub mod lir_dynamic_quant_unary;
 pub mod lir_unary;
 pub mod mir;
+pub mod mir_block_quant_unary;
 pub mod mir_quant;
 pub mod mir_quant_unary;
 pub mod mir_unary;
@@ -11,6 +13,7 @@ use tract_linalg::mmm::FusedSpec;
 use tract_ndarray::prelude::*;
 
 pub use self::mir::MatMul;
+pub use self::mir_block_quant_unary::BlockQuantMatMulUnary;
 pub use self::mir_quant::{MatMulQParams, QMatMul};
 pub use self::mir_unary::MatMulUnary;
 use self::pack::MatMatMulPack;
@@ -75,7 +78,7 @@ pub(super) fn eval(
         let (m, k, n, c_shape) = compute_shape(a.shape(), b.shape(), a_trans, b_trans, c_trans)?;
         let dt = output_type(a.datum_type());
         let mm = tract_linalg::ops()
-            .mmm(a.datum_type(), b.datum_type(), dt, Some(m), Some(k), Some(n))
+            .mmm(a.datum_type(), b.datum_type(), dt.clone(), Some(m), Some(k), Some(n))
             .with_context(|| {
                 format!(
                     "No matrix multiplier for {:?}x{:?} to {:?}",
@@ -130,7 +133,7 @@ pub(super) fn eval(
                 n,
                 &[
                     FusedSpec::AddMatMul {
-                        a: mm.a_packed(a.datum_type().size_of(), k).wrap(&packed_a.view()),
+                        a: mm.a_packed(a.datum_type().size_of(), k).wrap(&packed_a.view())?,
                         b: mm.b_packed(b.datum_type().size_of(), k).wrap(&packed_b.view())?,
                         k,
                     },
@@ -0,0 +1,363 @@
+use super::mir_quant::QParamKind;
+use super::mir_quant_unary::QMatMulUnary;
+use crate::internal::*;
+use crate::ops::binary::{TypedBinOp, UnaryOp};
+use crate::ops::cast::Cast;
+use crate::ops::math::Mul;
+use crate::ops::quant::{
+    dynamic_quantize_linear_f32_u8, offset_u8_as_i8_elementwise, scale_and_zero_point,
+    DynamicQuantizeLinearU8,
+};
+use tract_linalg::mmm::{BinOp, FusedSpec, MatMatMul};
+use tract_ndarray::indices;
+use tract_ndarray::prelude::*;
+
+/// Fused "DynamicQuantizeLinear, integer matmul, rescale" chain: the f32 input is quantized
+/// on the fly (with ONNX DynamicQuantizeLinear rounding), multiplied by the constant i8 A with
+/// the integer kernel, and the i32 result is scaled back to f32 by the kernel fused ops.
+#[derive(Clone, Educe, Debug)]
+#[educe(Hash)]
+pub struct LirDynamicQMatMulUnary {
+    /// A, m x k, packed for the mmm
+    pub packed_a: Arc<Tensor>,
+    /// i32 sums of the rows of A
+    pub a_row_sums: Arc<Tensor>,
+    pub a0: i32,
+    #[educe(Hash(method = "hash_f32"))]
+    pub a_scale: f32,
+    pub m: usize,
+    pub k: usize,
+    pub b_trans: bool,
+    pub c_trans: bool,
+    pub mmm: Box<dyn MatMatMul>,
+}
+
+impl_dyn_hash!(LirDynamicQMatMulUnary);
+
+impl LirDynamicQMatMulUnary {
+    fn c_shape<D: DimLike>(&self, b_shape: &[D]) -> TractResult<TVec<D>> {
+        let rank = b_shape.len();
+        let (k, n) = if self.b_trans {
+            (&b_shape[rank - 1], &b_shape[rank - 2])
+        } else {
+            (&b_shape[rank - 2], &b_shape[rank - 1])
+        };
+        if k.to_dim() != self.k.to_dim() {
+            bail!("Inconsistent matmul: k is {} for A, {} for B", self.k, k);
+        }
+        let mut c_shape: TVec<D> = b_shape[..rank - 2].into();
+        if self.c_trans {
+            c_shape.push(n.clone());
+            c_shape.push(self.m.into());
+        } else {
+            c_shape.push(self.m.into());
+            c_shape.push(n.clone());
+        }
+        Ok(c_shape)
+    }
+}
+
+impl Op for LirDynamicQMatMulUnary {
+    fn name(&self) -> Cow<str> {
+        "LirDynamicQMatMulUnary".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![
+            format!("m:{} k:{} b_trans:{} c_trans:{}", self.m, self.k, self.b_trans, self.c_trans),
+            format!("a0:{} a_scale:{} with {}", self.a0, self.a_scale, self.mmm),
+        ])
+    }
+
+    op_core_lir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for LirDynamicQMatMulUnary {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let b = &inputs[0];
+        let rank = b.rank();
+        let c_shape = self.c_shape(b.shape())?;
+        let (k_axis, n_axis) =
+            if self.b_trans { (rank - 1, rank - 2) } else { (rank - 2, rank - 1) };
+        let n = b.shape()[n_axis];
+
+        // same range, scale and rounding as DynamicQuantizeLinear, offset from u8 to i8
+        let (b_scale, zero_point) = scale_and_zero_point(b.to_array_view::<f32>()?);
+        let b0 = zero_point as i32 - 128;
+        let qb = b.to_array_view::<f32>()?.mapv(|x| {
+            offset_u8_as_i8_elementwise(dynamic_quantize_linear_f32_u8(x, b_scale, zero_point))
+        });
+        let b_col_sums = qb.mapv(|x| x as i32).sum_axis(Axis(k_axis));
+        let qb = qb.into_tensor();
+
+        // (a - a0)(b - b0) = ab - b0.sum(a, k) - a0.sum(b, k) + k.a0.b0
+        let (a0, k) = (self.a0, self.k as i32);
+        let row_term: Tensor =
+            self.a_row_sums.to_array_view::<i32>()?.mapv(|s| k * a0 * b0 - b0 * s).into();
+        let scale = b_scale * self.a_scale;
+        let mut c = Tensor::zero::<f32>(&c_shape)?;
+        let (c_m_axis, c_n_axis) =
+            (rank - 2 + self.c_trans as usize, rank - 2 + !self.c_trans as usize);
+        unsafe {
+            let c_storage = self.mmm.c_view(c_m_axis, c_n_axis);
+            let b_storage = self.mmm.b_late_packing_with_axes(k_axis, n_axis);
+            for prefix in indices(&b.shape()[..rank - 2]) {
+                let mut b_view = qb.view();
+                let mut c_view = c.view_mut();
+                let mut col_sums = b_col_sums.view();
+                for (axis, &dim) in prefix.slice().iter().enumerate() {
+                    b_view.offset_axis_unchecked(axis, dim as isize);
+                    c_view.offset_axis_unchecked(axis, dim as isize);
+                    col_sums.index_axis_inplace(Axis(0), dim);
+                }
+                let col_term: Tensor = col_sums.mapv(|s| -a0 * s).into();
+                self.mmm.run(
+                    self.m,
+                    n,
+                    &[
+                        FusedSpec::AddMatMul {
+                            k: self.k,
+                            a: self.mmm.a_packed(1, self.k).wrap(&self.packed_a.view())?,
+                            b: b_storage.wrap(&b_view)?,
+                        },
+                        FusedSpec::BinPerRow(&row_term, BinOp::Add),
+                        FusedSpec::BinPerCol(&col_term, BinOp::Add),
+                        FusedSpec::ScaleToF32(scale),
+                        FusedSpec::Store(c_storage.wrap(&c_view)),
+                    ],
+                )?;
+            }
+        }
+        Ok(tvec!(c.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for LirDynamicQMatMulUnary {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        if inputs[0].datum_type != f32::datum_type() {
+            bail!("LirDynamicQMatMulUnary expects f32 input, got {:?}", inputs[0].datum_type);
+        }
+        let c_shape = self.c_shape(&inputs[0].shape.to_tvec())?;
+        Ok(tvec!(TypedFact::dt_shape(f32::datum_type(), c_shape)))
+    }
+
+    fn cost(&self, inputs: &[&TypedFact]) -> TractResult<TVec<(Cost, TDim)>> {
+        let c_len: TDim = self.output_facts(inputs)?[0].shape.iter().product();
+        Ok(tvec!(
+            (Cost::FMA(i32::datum_type()), c_len * self.k),
+            (Cost::Params(i8::datum_type()), (self.m * self.k).to_dim())
+        ))
+    }
+
+    as_op!();
+}
+
+/// Static value of a matmul quantization parameter, if it is a scalar.
+fn static_param(
+    model: &TypedModel,
+    node: &TypedNode,
+    param: &QParamKind,
+) -> TractResult<Option<Arc<Tensor>>> {
+    let t = match param {
+        QParamKind::Attr(t) => t.clone(),
+        QParamKind::FromInput(i) => {
+            if let Some(t) = &model.outlet_fact(node.inputs[*i])?.konst {
+                t.clone()
+            } else {
+                return Ok(None);
+            }
+        }
+        QParamKind::FromQType => return Ok(None),
+    };
+    Ok(Some(t).filter(|t| t.len() == 1))
+}
+
+/// Skip the axis insertions of rank broadcasting.
+fn skip_added_axes(model: &TypedModel, mut outlet: OutletId) -> OutletId {
+    while let Some(AxisOp::Add(_)) = model.node(outlet.node).op_as::<AxisOp>() {
+        outlet = model.node(outlet.node).inputs[0];
+    }
+    outlet
+}
+
+/// Recognize DynamicQuantizeLinear -> unscaled i32 QMatMulUnary -> Cast to f32 -> Mul by the
+/// input scale (times a constant weight scale), and replace it by a LirDynamicQMatMulUnary.
+pub(super) fn fuse_dynamic_quantization(
+    op: &QMatMulUnary,
+    model: &TypedModel,
+    node: &TypedNode,
+) -> TractResult<Option<TypedModelPatch>> {
+    let dql = model.node(node.inputs[0].node);
+    if !dql.op_is::<DynamicQuantizeLinearU8>()
+        || op.output_type != i32::datum_type()
+        || op.bias.is_some()
+        || op.a.rank() < 2
+        || op.a.shape()[..op.a.rank() - 2].iter().any(|d| *d != 1)
+        || !(op.a.datum_type() == i8::datum_type() || op.a.datum_type() == u8::datum_type())
+    {
+        return Ok(None);
+    }
+    match op.params.b0 {
+        QParamKind::FromInput(i) if node.inputs[i] == OutletId::new(dql.id, 2) => (),
+        _ => return Ok(None),
+    }
+    let mut a0 = if let Some(a0) = static_param(model, node, &op.params.a0)? {
+        a0.cast_to_scalar::<i32>()?
+    } else {
+        return Ok(None);
+    };
+    for (param, value) in
+        &[(&op.params.a_scale, 1.0), (&op.params.b_scale, 1.0), (&op.params.c_scale, 1.0)]
+    {
+        match static_param(model, node, param)? {
+            Some(t) if t.cast_to_scalar::<f32>()? == *value => (),
+            _ => return Ok(None),
+        }
+    }
+    match static_param(model, node, &op.params.c0)? {
+        Some(t) if t.cast_to_scalar::<i32>()? == 0 => (),
+        _ => return Ok(None),
+    }
+
+    // i32 output -> Cast to f32 -> Mul by scale
+    let cast = if let Some(cast) = model.single_succ(node.id)? { cast } else { return Ok(None) };
+    if cast.op_as::<Cast>().map(|c| c.to.clone()) != Some(f32::datum_type())
+        || cast.outputs[0].successors.len() != 1
+    {
+        return Ok(None);
+    }
+    let mul = model.node(cast.outputs[0].successors[0].node);
+    if !mul.op_as::<TypedBinOp>().map(|op| op.0.is::<Mul>()).unwrap_or(false)
+        || mul.outputs[0].fact.shape != cast.outputs[0].fact.shape
+    {
+        return Ok(None);
+    }
+    let scale = if mul.inputs[0].node == cast.id { mul.inputs[1] } else { mul.inputs[0] };
+    let scale = skip_added_axes(model, scale);
+    let a_scale = if scale == OutletId::new(dql.id, 1) {
+        1.0
+    } else if let Some(unary) = model.node(scale.node).op_as::<UnaryOp>() {
+        let input = skip_added_axes(model, model.node(scale.node).inputs[0]);
+        if !unary.mini_op.is::<Mul>()
+            || unary.a.len() != 1
+            || unary.a.datum_type() != f32::datum_type()
+            || input != OutletId::new(dql.id, 1)
+        {
+            return Ok(None);
+        }
+        unary.a.cast_to_scalar::<f32>()?
+    } else {
+        return Ok(None);
+    };
+
+    let mut a = op.a.clone().into_tensor();
+    while a.rank() > 2 {
+        a.remove_axis(0)?;
+    }
+    if op.a_trans {
+        a = a.permute_axes(&[1, 0])?;
+    }
+    if a.datum_type() == u8::datum_type() {
+        a = a.into_arc_tensor().offset_u8_as_i8().into_tensor();
+        a0 -= 128;
+    }
+    let (m, k) = (a.shape()[0], a.shape()[1]);
+    let b_fact = model.outlet_fact(dql.inputs[0])?;
+    let n_axis = b_fact.rank() - 1 - op.b_trans as usize;
+    let n = b_fact.shape[n_axis].to_usize().ok();
+    let mmm = tract_linalg::ops()
+        .mmm(i8::datum_type(), i8::datum_type(), i32::datum_type(), Some(m), Some(k), n)
+        .context("No matrix multiplier for i8xi8 to i32")?;
+    if !FusedSpec::ScaleToF32(1.0).supports_accumulator(mmm.internal_type()) {
+        return Ok(None);
+    }
+    let packed_a = unsafe {
+        let mut packed_a = Tensor::uninitialized_aligned_dt(
+            i8::datum_type(),
+            &[mmm.a_pack().len(k, m)],
+            mmm.a_pack().alignment(),
+        )?;
+        mmm.a_pack().pack(&mut packed_a.view_mut(), &a.view(), 1, 0);
+        packed_a
+    };
+    let a_row_sums = a.to_array_view::<i8>()?.mapv(|x| x as i32).sum_axis(Axis(1)).into_tensor();
+    let fused = LirDynamicQMatMulUnary {
+        packed_a: packed_a.into_arc_tensor(),
+        a_row_sums: a_row_sums.into_arc_tensor(),
+        a0,
+        a_scale,
+        m,
+        k,
+        b_trans: op.b_trans,
+        c_trans: op.c_trans,
+        mmm,
+    };
+    let mut patch = TypedModelPatch::new("fuse dynamic quantization");
+    let input = patch.tap_model(model, dql.inputs[0])?;
+    let wire = patch.wire_node(&*node.name, fused, &[input])?[0];
+    patch.shunt_outside(model, mul.id.into(), wire)?;
+    Ok(Some(patch))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ops::matmul::{MatMulQParams, QMatMul};
+
+    // the chain of ops ONNX DynamicQuantizeLinear -> MatMulInteger -> Cast -> Mul translates to
+    fn dynamic_quant_model(w: Tensor, w0: Tensor, w_scale: f32) -> TractResult<TypedModel> {
+        let mut model = TypedModel::default();
+        let x = model.add_source("x", TypedFact::dt_shape(f32::datum_type(), &[2, 3, 4]))?;
+        let dql = model.wire_node("dql", DynamicQuantizeLinearU8::new(), &[x])?;
+        let w = model.add_const("w", w)?;
+        let bias = model.add_const("bias", tensor0(0i32))?;
+        let w0 = model.add_const("w0", w0)?;
+        let params = MatMulQParams {
+            a0: 3.into(),
+            a_scale: tensor0(1f32).into(),
+            b0: 4.into(),
+            b_scale: tensor0(1f32).into(),
+            c0: tensor0(0i32).into(),
+            c_scale: tensor0(1f32).into(),
+        };
+        let op = QMatMul::new(false, false, false, i32::datum_type(), params);
+        let wire = model.wire_node("matmul", op, &[dql[0], w, bias, dql[2], w0])?;
+        let wire = model.wire_node("cast", crate::ops::cast::cast(f32::datum_type()), &wire)?;
+        let w_scale = model.add_const("w_scale", tensor0(w_scale))?;
+        let mut scale = model.wire_node("scale", TypedBinOp(Box::new(Mul)), &[dql[1], w_scale])?;
+        for axis in 0..3 {
+            scale = model.wire_node(format!("scale.add-{}", axis), AxisOp::Add(0), &scale)?;
+        }
+        let wire = model.wire_node("rescale", TypedBinOp(Box::new(Mul)), &[wire[0], scale[0]])?;
+        model.set_output_outlets(&wire)?;
+        Ok(model)
+    }
+
+    fn check(model: TypedModel) -> TractResult<()> {
+        let x: Vec<f32> = (0..24).map(|i| ((i * 7) % 13) as f32 / 3.0 - 1.7).collect();
+        let x = tvec!(Tensor::from_shape(&[2, 3, 4], &x)?);
+        let expected = model.clone().into_decluttered()?.into_runnable()?.run(x.clone())?;
+        let optimized = model.into_optimized()?;
+        assert!(optimized.nodes().iter().any(|n| n.op_is::<LirDynamicQMatMulUnary>()));
+        let found = optimized.into_runnable()?.run(x)?;
+        assert_eq!(found[0].shape(), &[2, 3, 5]);
+        found[0].close_enough(&expected[0], false)
+    }
+
+    #[test]
+    fn fuse_i8_weights() -> TractResult<()> {
+        let w: Vec<i8> = (0..20).map(|i| ((i * 37) % 41) as i8 - 20).collect();
+        check(dynamic_quant_model(Tensor::from_shape(&[1, 4, 5], &w)?, tensor0(3i8), 0.02)?)
+    }
+
+    #[test]
+    fn fuse_u8_weights() -> TractResult<()> {
+        let w: Vec<u8> = (0..20).map(|i| ((i * 37) % 251) as u8).collect();
+        check(dynamic_quant_model(Tensor::from_shape(&[1, 4, 5], &w)?, tensor0(128u8), 0.01)?)
+    }
+}
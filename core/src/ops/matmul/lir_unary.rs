@@ -14,6 +14,7 @@ pub enum ProtoFusedSpec {
     AddRowColProducts(AttrOrInput, AttrOrInput),
     AddUnicast(AttrOrInput),
     QScale(usize, RoundingPolicy, i32),
+    QScalePerRow(AttrOrInput),
     Store,
 }
 
@@ -35,6 +36,7 @@ impl ProtoFusedSpec {
                 FusedSpec::AddUnicast(output_spec.wrap(&v.tensor(inputs).view()))
             },
             ProtoFusedSpec::QScale(s, rp, m) => FusedSpec::QScale(*s, *rp, *m),
+            ProtoFusedSpec::QScalePerRow(v) => FusedSpec::QScalePerRow(v.tensor(inputs)),
             ProtoFusedSpec::Store => FusedSpec::Store(output),
         }
     }
@@ -85,6 +87,8 @@ pub struct LirMatMulUnary {
     pub c_fact: TypedFact,
     pub c_m_axis: usize,
     pub c_n_axis: usize,
+    /// How the A panels are read from the micro_ops tensors.
+    pub a_storage: InputStoreSpec,
     pub micro_ops: ArrayD<(Arc<Tensor>, Vec<ProtoFusedSpec>)>,
     pub c_final_shape: ShapeFact,
     pub geometry: MatMulGeometry,
@@ -163,6 +167,10 @@ impl OpState for State {
             )
         }
     }
+
+    fn save(&self) -> TractResult<TVec<Tensor>> {
+        Ok(tvec!())
+    }
 }
 
 impl EvalOp for LirMatMulUnary {
@@ -206,8 +214,7 @@ fn eval(
 ) -> TractResult<TVec<Arc<Tensor>>> {
     unsafe {
         debug_assert!(op.micro_ops.len() > 0);
-        let size_of_a = (&*op.micro_ops.as_ptr()).0.datum_type().size_of();
-        let mut c = Tensor::uninitialized_dt(op.c_fact.datum_type, &c_shape)?;
+        let mut c = Tensor::uninitialized_dt(op.c_fact.datum_type.clone(), &c_shape)?;
         let c_storage = op.mmm.c_view(c_m_axis, c_n_axis);
         if op
             .c_fact
@@ -234,7 +241,7 @@ fn eval(
                 let c_store = c_storage.wrap(&c_view);
                 let mut f = tvec!(FusedSpec::AddMatMul {
                     k: geometry.k,
-                    a: op.mmm.a_packed(size_of_a, geometry.k).wrap(&pa.view()),
+                    a: op.a_storage.wrap(&pa.view())?,
                     b: geometry
                         .b_storage
                         .wrap(&TensorView::at_prefix_unchecked(&inputs[0], &*b_prefix))?,
@@ -248,7 +255,7 @@ fn eval(
             let mut f = Vec::with_capacity(fused.len() + 1);
             f.push(FusedSpec::AddMatMul {
                 k: geometry.k,
-                a: op.mmm.a_packed(size_of_a, geometry.k).wrap(&pa.view()),
+                a: op.a_storage.wrap(&pa.view())?,
                 b: geometry.b_storage.wrap(&inputs[0].view())?,
             });
             for ix in 0..fused.len() {
@@ -314,12 +321,12 @@ impl TypedOp for LirMatMulUnary {
             }
         }
 
-        if let Some(cast) = succ.op_as::<ops::cast::Cast>().map(|cast| cast.to) {
+        if let Some(cast) = succ.op_as::<ops::cast::Cast>().map(|cast| cast.to.clone()) {
             if (cast.unquantized() == i8::datum_type() || cast.unquantized() == u8::datum_type())
                 && self.c_fact.datum_type == i32::datum_type()
             {
                 let at = self.micro_ops.iter().nth(0).unwrap().0.datum_type();
-                let bt = model.outlet_fact(node.inputs[0])?.datum_type;
+                let bt = model.outlet_fact(node.inputs[0])?.datum_type.clone();
                 let mmm = tract_linalg::ops()
                     .mmm(
                         at,
@@ -352,11 +359,23 @@ impl TypedOp for LirMatMulUnary {
                 );
             }
         } else if let Some(op) = succ.op_as::<ops::binary::UnaryOp>() {
+            if op.mini_op.is::<ops::quant::Scale>() {
+                return self.fuse_per_row_scale(model, node, &op.a);
+            }
             let binop =
                 if let Some(op) = op.mini_op.as_linalg_binop() { op } else { return Ok(None) };
-            let shape = op.a.shape().into();
-            return self.fuse_binary(model, node, &shape, op.a.clone().into(), binop, &[]);
+            // quantized min and max compare raw values, the other ops need the zero point
+            if op.a.datum_type().is_quantized() && binop != BinOp::Min && binop != BinOp::Max {
+                return Ok(None);
+            }
+            // the kernel applies the op on its accumulator
+            let a = op.a.cast_to_dt(self.mmm.internal_type())?.into_owned().into_arc_tensor();
+            let shape = a.shape().into();
+            return self.fuse_binary(model, node, &shape, a.into(), binop, &[]);
         } else if let Some(op) = succ.op_as::<ops::binary::TypedBinOp>() {
+            if let Some(patch) = self.fuse_row_col_products(model, node, &*op.0)? {
+                return Ok(Some(patch));
+            }
             let mut binop =
                 if let Some(op) = op.0.as_linalg_binop() { op } else { return Ok(None) };
             let flipped = succ.inputs[0].node == node.id;
@@ -365,9 +384,15 @@ impl TypedOp for LirMatMulUnary {
             }
             let other_outlet = succ.inputs[flipped as usize];
             let other_fact = model.outlet_fact(other_outlet)?;
+            if other_fact.datum_type != self.mmm.internal_type() {
+                return Ok(None);
+            }
             let value = node.inputs.len().into();
             return self.fuse_binary(model, node, &other_fact.shape, value, binop, &[other_outlet]);
         } else if let Some(op) = succ.op_as::<ops::binary::MergeOpUnicast>() {
+            if let Some(patch) = self.fuse_row_col_products(model, node, &*op.0)? {
+                return Ok(Some(patch));
+            }
             if self.c_n_axis == self.c_final_shape.rank() - 2
                 && self.c_m_axis == self.c_final_shape.rank() - 1
                 && self.micro_ops.len() == 1
@@ -430,6 +455,95 @@ impl LirMatMulUnary {
         self.fuse_op(model, node, &array, additional_inputs)
     }
 
+    /// Per-row constant times per-column vector, as in zero point compensation with
+    /// per-channel zero points.
+    fn fuse_row_col_products(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+        op: &dyn crate::ops::binary::BinMiniOp,
+    ) -> TractResult<Option<TypedModelPatch>> {
+        use crate::ops;
+        let succ = model.node(node.outputs[0].successors[0].node);
+        let slot = node.outputs[0].successors[0].slot;
+        let negate = if op.is::<ops::math::Sub>() && slot == 0 {
+            true
+        } else if op.is::<ops::math::Add>() {
+            false
+        } else {
+            return Ok(None);
+        };
+        let other = model.node(succ.inputs[1 - slot].node);
+        let rows = if let Some(mul) =
+            other.op_as::<ops::binary::UnaryOp>().filter(|mul| mul.mini_op.is::<ops::math::Mul>())
+        {
+            &mul.a
+        } else {
+            return Ok(None);
+        };
+        let cols = other.inputs[0];
+        if self.mmm.internal_type() != i32::datum_type()
+            || rows.datum_type() != i32::datum_type()
+            || model.outlet_fact(cols)?.datum_type != i32::datum_type()
+        {
+            return Ok(None);
+        }
+        let mut rows_shape: ShapeFact = rows.shape().into();
+        let mut cols_shape = model.outlet_fact(cols)?.shape.clone();
+        for axis_change in self.reshape_post.iter().rev() {
+            if axis_change.recip().change_shape(&mut rows_shape, true).is_err()
+                || axis_change.recip().change_shape(&mut cols_shape, true).is_err()
+            {
+                return Ok(None);
+            }
+        }
+        let m = &self.c_fact.shape[self.c_m_axis];
+        let n = &self.c_fact.shape[self.c_n_axis];
+        if rows_shape[self.c_m_axis] != *m
+            || rows_shape.volume() != *m
+            || cols_shape[self.c_n_axis] != *n
+            || cols_shape.volume() != *n
+        {
+            return Ok(None);
+        }
+        let mut rows = rows.clone().into_tensor().into_shape(&[rows.len()])?;
+        if negate {
+            rows.as_slice_mut::<i32>()?.iter_mut().for_each(|x| *x = -*x);
+        }
+        self.fuse_op_with_broadcast(
+            model,
+            node,
+            &[ProtoFusedSpec::AddRowColProducts(rows.into(), node.inputs.len().into())],
+            &[cols],
+        )
+    }
+
+    fn fuse_per_row_scale(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+        scale: &Arc<Tensor>,
+    ) -> TractResult<Option<TypedModelPatch>> {
+        if !FusedSpec::QScalePerRow(scale).supports_accumulator(self.mmm.internal_type()) {
+            return Ok(None);
+        }
+        let mut scale_shape: ShapeFact = scale.shape().into();
+        for axis_change in self.reshape_post.iter().rev() {
+            if axis_change.recip().change_shape(&mut scale_shape, true).is_err() {
+                return Ok(None);
+            }
+        }
+        let m = &self.c_fact.shape[self.c_m_axis];
+        if scale_shape.volume() == 1.to_dim()
+            || scale_shape[self.c_m_axis] != *m
+            || scale_shape.volume() != *m
+        {
+            return Ok(None);
+        }
+        let scale = scale.clone().into_tensor().into_shape(&[scale.len()])?;
+        self.fuse_op_with_broadcast(model, node, &[ProtoFusedSpec::QScalePerRow(scale.into())], &[])
+    }
+
     fn fuse_binary(
         &self,
         model: &TypedModel,
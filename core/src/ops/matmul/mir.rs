@@ -67,7 +67,7 @@ impl TypedOp for MatMul {
             self.b_trans,
             self.c_trans,
         )?;
-        Ok(tvec!(TypedFact::dt_shape(output_type(inputs[0].datum_type), c_shape)))
+        Ok(tvec!(TypedFact::dt_shape(output_type(inputs[0].datum_type.clone()), c_shape)))
     }
 
     fn declutter(
@@ -75,6 +75,9 @@ impl TypedOp for MatMul {
         model: &TypedModel,
         node: &TypedNode,
     ) -> TractResult<Option<TypedModelPatch>> {
+        if let Some(patch) = crate::ops::nn::declutter_attention_pattern(model, node)? {
+            return Ok(Some(patch));
+        }
         let a_fact = model.outlet_fact(node.inputs[0])?;
         let b_fact = model.outlet_fact(node.inputs[1])?;
         let konst_ix = if a_fact.konst.is_some() {
@@ -103,7 +106,7 @@ impl TypedOp for MatMul {
         cost(
             &inputs[0].shape.to_tvec(),
             &inputs[1].shape.to_tvec(),
-            inputs[0].datum_type,
+            inputs[0].datum_type.clone(),
             self.a_trans,
             self.b_trans,
         )
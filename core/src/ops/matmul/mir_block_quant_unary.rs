@@ -0,0 +1,252 @@
+use super::lir_unary::ProtoFusedSpec;
+use super::mir_unary::wire_lir_mat_mul_unary;
+use super::*;
+use crate::internal::*;
+use tract_linalg::frame::{BlockQuantFormat, BlockQuantWeights};
+use tract_linalg::mmm::{FusedSpec, MatMatMul};
+use tract_ndarray::indices;
+use tract_ndarray::prelude::*;
+
+/// MatMulUnary variant for f32 computations with A stored as block quantized weights.
+///
+/// A stays quantized in the model and in the matrix multiplier: each of its panels is
+/// dequantized to f32 in the scratch space when the regular f32 kernels need it.
+#[derive(Debug, Clone, new, Hash)]
+pub struct BlockQuantMatMulUnary {
+    /// A as a m x k matrix
+    pub a: BlockQuantWeights,
+    pub b_trans: bool,
+    pub c_trans: bool,
+}
+
+impl_dyn_hash!(BlockQuantMatMulUnary);
+
+impl BlockQuantMatMulUnary {
+    /// Block quantize the weights of a f32 MatMulUnary, if they are a single matrix.
+    pub fn from_mat_mul_unary(
+        op: &MatMulUnary,
+        format: BlockQuantFormat,
+        block_len: usize,
+    ) -> TractResult<Option<BlockQuantMatMulUnary>> {
+        let rank = op.a.rank();
+        if op.a.datum_type() != f32::datum_type()
+            || rank < 2
+            || op.a.shape()[..rank - 2].iter().any(|d| *d != 1)
+        {
+            return Ok(None);
+        }
+        let mut a = op.a.clone().into_tensor();
+        while a.rank() > 2 {
+            a.remove_axis(0)?;
+        }
+        if op.a_trans {
+            a = a.permute_axes(&[1, 0])?;
+        }
+        let (m, k) = (a.shape()[0], a.shape()[1]);
+        let a = BlockQuantWeights::quantize(format, block_len, m, k, a.as_slice::<f32>()?)?;
+        Ok(Some(BlockQuantMatMulUnary { a, b_trans: op.b_trans, c_trans: op.c_trans }))
+    }
+
+    fn a_shape(&self, rank: usize) -> TVec<usize> {
+        let mut shape: TVec<usize> = tvec!(1; rank.saturating_sub(2));
+        shape.push(self.a.m);
+        shape.push(self.a.k);
+        shape
+    }
+
+    fn mmm(&self, n: Option<usize>) -> TractResult<Box<dyn MatMatMul>> {
+        let dt = f32::datum_type();
+        tract_linalg::ops()
+            .mmm(dt.clone(), dt.clone(), dt, Some(self.a.m), Some(self.a.k), n)
+            .context("No matrix multiplier for f32")
+    }
+}
+
+impl Op for BlockQuantMatMulUnary {
+    fn name(&self) -> Cow<str> {
+        "BlockQuantMatMulUnary".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![
+            format!("b_trans:{:?} c_trans:{:?}", self.b_trans, self.c_trans),
+            format!(
+                "A: {}x{} {:?} by blocks of {}",
+                self.a.m, self.a.k, self.a.format, self.a.block_len
+            ),
+        ])
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for BlockQuantMatMulUnary {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let b = &inputs[0];
+        let rank = b.rank();
+        let (m, k, n, c_shape) =
+            compute_shape(&self.a_shape(rank), b.shape(), false, self.b_trans, self.c_trans)?;
+        let mmm = self.mmm(Some(n))?;
+        let (k_axis, n_axis) =
+            if self.b_trans { (rank - 1, rank - 2) } else { (rank - 2, rank - 1) };
+        let (c_m_axis, c_n_axis) =
+            (rank - 2 + self.c_trans as usize, rank - 2 + !self.c_trans as usize);
+        let mut c = Tensor::zero::<f32>(&c_shape)?;
+        unsafe {
+            let a_storage = mmm.a_virtual_input(Box::new(self.a.clone()), k);
+            let b_storage = mmm.b_late_packing_with_axes(k_axis, n_axis);
+            let c_storage = mmm.c_view(c_m_axis, c_n_axis);
+            for prefix in indices(&c_shape[..rank - 2]) {
+                let mut b_view = b.view();
+                let mut c_view = c.view_mut();
+                for (axis, &dim) in prefix.slice().iter().enumerate() {
+                    b_view.offset_axis_unchecked(axis, dim as isize);
+                    c_view.offset_axis_unchecked(axis, dim as isize);
+                }
+                mmm.run(
+                    m,
+                    n,
+                    &[
+                        FusedSpec::AddMatMul {
+                            k,
+                            a: a_storage.wrap(&self.a.data.view())?,
+                            b: b_storage.wrap(&b_view)?,
+                        },
+                        FusedSpec::Store(c_storage.wrap(&c_view)),
+                    ],
+                )?;
+            }
+        }
+        Ok(tvec!(c.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for BlockQuantMatMulUnary {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        if inputs[0].datum_type != f32::datum_type() {
+            bail!("BlockQuantMatMulUnary only operates on f32, got {:?}", inputs[0].datum_type);
+        }
+        let (_m, _k, _n, c_shape) = compute_shape(
+            &self.a_shape(inputs[0].rank()).iter().map(|d| d.to_dim()).collect::<TVec<_>>(),
+            &inputs[0].shape,
+            false,
+            self.b_trans,
+            self.c_trans,
+        )?;
+        Ok(tvec!(TypedFact::dt_shape(f32::datum_type(), c_shape)))
+    }
+
+    fn cost(&self, inputs: &[&TypedFact]) -> TractResult<TVec<(Cost, TDim)>> {
+        let mut cost = super::cost(
+            &self.a_shape(inputs[0].rank()),
+            &inputs[0].shape.to_tvec(),
+            f32::datum_type(),
+            false,
+            self.b_trans,
+        )?;
+        cost.push((Cost::Params(u8::datum_type()), self.a.data.len().to_dim()));
+        cost.push((Cost::Params(f32::datum_type()), self.a.scales.len().to_dim()));
+        Ok(cost)
+    }
+
+    fn codegen(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+    ) -> TractResult<Option<TypedModelPatch>> {
+        let b = args_1!(model.node_input_facts(node.id)?);
+        let (m, k, n, c_shape) = compute_shape(
+            &self.a_shape(b.rank()).iter().map(|d| d.to_dim()).collect::<TVec<_>>(),
+            &b.shape,
+            false,
+            self.b_trans,
+            self.c_trans,
+        )?;
+        let (m, k) = (m.to_usize()?, k.to_usize()?);
+        let mmm = self.mmm(n.to_usize().ok())?;
+        let a_storage = unsafe { mmm.a_virtual_input(Box::new(self.a.clone()), k) };
+        let quantized_as = ArrayD::from_elem(
+            &self.a_shape(b.rank())[..b.rank() - 2],
+            (self.a.data.clone(), vec![ProtoFusedSpec::Store]),
+        );
+        Ok(Some(wire_lir_mat_mul_unary(
+            model,
+            node,
+            mmm,
+            a_storage,
+            quantized_as,
+            (self.b_trans, self.c_trans),
+            (m, k, n, c_shape),
+        )?))
+    }
+
+    as_op!();
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::lir_unary::LirMatMulUnary;
+    use super::*;
+
+    #[test]
+    fn block_quant_matches_dequantized_weights() -> TractResult<()> {
+        let a = Tensor::from_shape(
+            &[3, 8],
+            &(0..24).map(|i| i as f32 / 10.0 - 1.2).collect::<Vec<_>>(),
+        )?;
+        let mmu = MatMulUnary::new(a.into_arc_tensor(), false, false, false);
+        let op = BlockQuantMatMulUnary::from_mat_mul_unary(&mmu, BlockQuantFormat::Q4, 4)?.unwrap();
+        let mut model = TypedModel::default();
+        let source = model.add_source("b", TypedFact::dt_shape(f32::datum_type(), &[8, 5]))?;
+        let output = model.wire_node("mm", op.clone(), &[source])?;
+        model.set_output_outlets(&output)?;
+        let b = Tensor::from_shape(
+            &[8, 5],
+            &(0..40).map(|i| (i % 7) as f32 - 3.0).collect::<Vec<_>>(),
+        )?;
+        let expected = eval(&op.a.dequantize()?, &b, false, false, false)?;
+        let decluttered = model.clone().into_runnable()?.run(tvec!(b.clone()))?;
+        decluttered[0].close_enough(&expected, true)?;
+        let optimized = model.into_optimized()?;
+        assert!(optimized.nodes().iter().all(|n| !n.op_is::<BlockQuantMatMulUnary>()));
+        let found = optimized.into_runnable()?.run(tvec!(b))?;
+        found[0].close_enough(&expected, true)?;
+        Ok(())
+    }
+
+    #[test]
+    fn block_quant_stays_quantized_with_symbolic_n() -> TractResult<()> {
+        let a = Tensor::from_shape(
+            &[6, 8],
+            &(0..48).map(|i| (i % 11) as f32 / 5.0 - 1.0).collect::<Vec<_>>(),
+        )?;
+        let mmu = MatMulUnary::new(a.into_arc_tensor(), false, true, false);
+        let op = BlockQuantMatMulUnary::from_mat_mul_unary(&mmu, BlockQuantFormat::Q8, 4)?.unwrap();
+        let mut model = TypedModel::default();
+        let n = model.symbol_scope.sym("n");
+        let shape = [TDim::from(&n), 8.to_dim()];
+        let source = model.add_source("b", TypedFact::dt_shape(f32::datum_type(), &shape))?;
+        let output = model.wire_node("mm", op.clone(), &[source])?;
+        model.set_output_outlets(&output)?;
+        let optimized = model.into_optimized()?;
+        let lir = optimized
+            .nodes()
+            .iter()
+            .find_map(|n| n.op_as::<LirMatMulUnary>())
+            .context("Expected a LirMatMulUnary")?;
+        assert!(lir.micro_ops.iter().all(|(a, _)| a.datum_type() == u8::datum_type()));
+        let b = Tensor::from_shape(
+            &[5, 8],
+            &(0..40).map(|i| (i % 5) as f32 - 2.0).collect::<Vec<_>>(),
+        )?;
+        let expected = eval(&op.a.dequantize()?, &b, false, true, false)?;
+        let found = optimized.into_runnable()?.run(tvec!(b))?;
+        found[0].close_enough(&expected, true)?;
+        Ok(())
+    }
+}
@@ -92,9 +92,9 @@ pub struct MatMulQParams {
 impl MatMulQParams {
     pub fn noop_static(dt: DatumType) -> MatMulQParams {
         MatMulQParams {
-            a0: QParamKind::Attr(Tensor::zero_scalar_dt(dt).unwrap().into_arc_tensor()),
+            a0: QParamKind::Attr(Tensor::zero_scalar_dt(dt.clone()).unwrap().into_arc_tensor()),
             a_scale: QParamKind::Attr(rctensor0(1f32)),
-            b0: QParamKind::Attr(Tensor::zero_scalar_dt(dt).unwrap().into_arc_tensor()),
+            b0: QParamKind::Attr(Tensor::zero_scalar_dt(dt.clone()).unwrap().into_arc_tensor()),
             b_scale: QParamKind::Attr(rctensor0(1f32)),
             c0: QParamKind::Attr(Tensor::zero_scalar_dt(dt).unwrap().into_arc_tensor()),
             c_scale: QParamKind::Attr(rctensor0(1f32)),
@@ -202,10 +202,14 @@ impl MatMulQParams {
                     matches!(x0, QParamKind::FromQType) && matches!(x_scale, QParamKind::FromQType),
                     "Quantization cannot be specified both in the type and in params"
                 );
-                let (zp, scale) = qp.zp_scale();
-                let zp = tensor0(zp);
+                let (zp, scale) = if qp.is_per_axis() {
+                    let (zps, scales) = qp.zp_scale_vecs();
+                    (tensor1(&zps), tensor1(&scales))
+                } else {
+                    let (zp, scale) = qp.zp_scale()?;
+                    (tensor0(zp), tensor0(scale))
+                };
                 let zp = model.add_const(format!("{}.{}", node_name, x0_name), zp)?;
-                let scale = tensor0(scale);
                 let scale = model.add_const(format!("{}.{}", node_name, x_scale_name), scale)?;
                 params_outlets.push(zp);
                 params_outlets.push(scale)
@@ -287,7 +291,7 @@ impl EvalOp for QMatMul {
             &input_outlets,
             inputs[0].datum_type(),
             inputs[1].datum_type(),
-            self.output_type,
+            self.output_type.clone(),
         )?;
 
         let a = wire_offset_u8_as_i8(&mut model, "adhoc", a, "a", &mut params[0], "a0")?;
@@ -305,7 +309,7 @@ impl EvalOp for QMatMul {
             Some(bias),
             self.c_trans,
             result,
-            self.output_type,
+            self.output_type.clone(),
             &params,
         )?;
         model.set_output_outlets(&[result])?;
@@ -348,7 +352,7 @@ impl TypedOp for QMatMul {
             anyhow::ensure!(inputs[2].shape.iter().product::<TDim>() == 1.to_dim());
         };
 
-        Ok(tvec!(TypedFact::dt_shape(self.output_type, c_shape)))
+        Ok(tvec!(TypedFact::dt_shape(self.output_type.clone(), c_shape)))
     }
 
     fn declutter(
@@ -421,7 +425,7 @@ impl TypedOp for QMatMul {
                 t_konst,
                 t_var,
                 self.c_trans ^ flip,
-                self.output_type,
+                self.output_type.clone(),
                 new_params,
             ),
         )
@@ -432,7 +436,7 @@ impl TypedOp for QMatMul {
         cost(
             &inputs[0].shape.to_tvec(),
             &inputs[1].shape.to_tvec(),
-            inputs[0].datum_type,
+            inputs[0].datum_type.clone(),
             self.a_trans,
             self.b_trans,
         )
@@ -467,9 +471,9 @@ impl TypedOp for QMatMul {
             &mut patch,
             &*node.name,
             &input_outlets,
-            model.node_input_facts(node.id)?[0].datum_type,
-            model.node_input_facts(node.id)?[1].datum_type,
-            self.output_type,
+            model.node_input_facts(node.id)?[0].datum_type.clone(),
+            model.node_input_facts(node.id)?[1].datum_type.clone(),
+            self.output_type.clone(),
         )?;
 
         let a = wire_offset_u8_as_i8(&mut patch, &node.name, a, "a", &mut params[0], "a0")?;
@@ -487,7 +491,7 @@ impl TypedOp for QMatMul {
             Some(bias),
             self.c_trans,
             result,
-            self.output_type,
+            self.output_type.clone(),
             &params,
         )?;
         patch.shunt_outside(model, node.id.into(), result)?;
@@ -555,6 +559,13 @@ pub(crate) fn wire_matmul_quant(
     let m_axis = rank - 2 + c_trans as usize;
     let n_axis = rank - 1 - c_trans as usize;
 
+    let mut params: TVec<OutletId> = params.into();
+    let names = ["a0", "a_scale", "b0", "b_scale", "c0", "c_scale"];
+    let axes = [m_axis, m_axis, n_axis, n_axis, m_axis, m_axis];
+    for ix in 0..6 {
+        params[ix] = wire_per_channel(model, name, names[ix], params[ix], result, axes[ix], 1)?;
+    }
+
     if let Some(bias) = bias {
         result = wire_with_rank_broadcast(
             &format!("{}.add_bias", &name),
@@ -594,6 +605,59 @@ pub(crate) fn wire_matmul_quant(
     requant(model, name, result, output_type, abc_scale, params[4])
 }
 
+/// Vector quantization parameters apply per channel: rows of A and C, columns of B.
+///
+/// Make a 1D vector broadcast along `axis` of `target`, splitting it in `group` first if needed.
+pub(crate) fn wire_per_channel(
+    model: &mut TypedModel,
+    name: &str,
+    param_name: &str,
+    param: OutletId,
+    target: OutletId,
+    axis: usize,
+    group: usize,
+) -> TractResult<OutletId> {
+    let param_fact = model.outlet_fact(param)?.clone();
+    if param_fact.rank() != 1 || param_fact.shape[0] == 1.to_dim() {
+        return Ok(param);
+    }
+    let target_fact = model.outlet_fact(target)?.clone();
+    let mut wire = param;
+    let mut first = axis;
+    if group > 1 {
+        first = axis - 1;
+        let len = param_fact.shape[0].clone();
+        wire = model.wire_node(
+            format!("{}.{}.split_groups", name, param_name),
+            AxisOp::Reshape(0, tvec!(len.clone()), tvec!(group.to_dim(), len / group)),
+            &[wire],
+        )?[0];
+    }
+    let channels: TDim = target_fact.shape[first..=axis].iter().product();
+    ensure!(
+        param_fact.shape[0] == channels,
+        "Expected {} quantization parameters, got {:?}",
+        channels,
+        param_fact
+    );
+    for _ in 0..first {
+        wire = model.wire_node(
+            format!("{}.{}.add_axis_{}", name, param_name, model.outlet_fact(wire)?.rank()),
+            AxisOp::Add(0),
+            &[wire],
+        )?[0];
+    }
+    while model.outlet_fact(wire)?.rank() < target_fact.rank() {
+        let rank = model.outlet_fact(wire)?.rank();
+        wire = model.wire_node(
+            format!("{}.{}.add_axis_{}", name, param_name, rank),
+            AxisOp::Add(rank),
+            &[wire],
+        )?[0];
+    }
+    Ok(wire)
+}
+
 pub(crate) fn combine_scales(
     model: &mut TypedModel,
     name: &str,
@@ -1383,4 +1447,54 @@ mod test {
             },
         ));
     }
+
+    #[test]
+    fn per_channel_a_and_c() {
+        let a = arr2(&[[12i8, -7, 3], [100, 4, -90]]);
+        let b = arr2(&[[5i8, -3], [20, 8], [-13, 1]]);
+        let (a0, a_scale) = ([1i32, -2], [0.5f32, 0.25]);
+        let (c0, c_scale) = ([0i8, 3], [2.0f32, 1.5]);
+        let b_scale = 0.1f32;
+
+        let reference = Array2::from_shape_fn((2, 2), |(m, n)| {
+            let c: f32 = (0..3)
+                .map(|k| {
+                    (a[(m, k)] as f32 - a0[m] as f32) * a_scale[m] * b[(k, n)] as f32 * b_scale
+                })
+                .sum();
+            (round_ties_to_right(c / c_scale[m]) + c0[m] as i32).max(-128).min(127) as i8
+        });
+
+        for opt in &[false, true] {
+            let mut model = TypedModel::default();
+            let a_dt = DatumType::QI8(QParams::per_axis(0, &a0, &a_scale));
+            let a_wire = model.add_source("a", TypedFact::dt_shape(a_dt.clone(), &[2, 3])).unwrap();
+            let b_wire =
+                model.add_source("b", TypedFact::dt_shape(i8::datum_type(), &[3, 2])).unwrap();
+            let bias = model.add_const("bias", tensor0(0i32)).unwrap();
+            let qparams = MatMulQParams {
+                a0: QParamKind::FromQType,
+                a_scale: QParamKind::FromQType,
+                b0: QParamKind::Attr(rctensor0(0i8)),
+                b_scale: QParamKind::Attr(rctensor0(b_scale)),
+                c0: QParamKind::Attr(rctensor1(&c0)),
+                c_scale: QParamKind::Attr(rctensor1(&c_scale)),
+            };
+            let result = model
+                .wire_node(
+                    "qmm",
+                    QMatMul::new(false, false, false, i8::datum_type(), qparams),
+                    &[a_wire, b_wire, bias],
+                )
+                .unwrap();
+            model.set_output_outlets(&result).unwrap();
+            let model = if *opt { model.into_optimized().unwrap() } else { model };
+            let mut a = a.clone().into_tensor();
+            unsafe { a.set_datum_type(a_dt) };
+            let mut outputs =
+                model.into_runnable().unwrap().run(tvec!(a, b.clone().into_tensor())).unwrap();
+            let found = outputs.remove(0).into_tensor();
+            assert_eq!(found, reference.clone().into_tensor());
+        }
+    }
 }
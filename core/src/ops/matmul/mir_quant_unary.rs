@@ -2,7 +2,9 @@ use ops::matmul::mir_quant::wire_matmul_quant;
 
 use crate::internal::*;
 use crate::ops;
-use crate::ops::matmul::mir_quant::{combine_scales, requant, wire_offset_u8_as_i8};
+use crate::ops::matmul::mir_quant::{
+    combine_scales, requant, wire_offset_u8_as_i8, wire_per_channel,
+};
 use crate::ops::matmul::*;
 use mir_quant::MatMulQParams;
 use mir_quant::QParamKind;
@@ -74,7 +76,7 @@ impl EvalOp for QMatMulUnary {
             &input_outlets,
             self.a.datum_type(),
             inputs[0].datum_type(),
-            self.output_type,
+            self.output_type.clone(),
         )?;
         let a = wire_offset_u8_as_i8(&mut model, "adhoc", a, "a", &mut params[0], "a0")?;
         let b = wire_offset_u8_as_i8(&mut model, "adhoc", b, "b", &mut params[2], "b0")?;
@@ -96,7 +98,7 @@ impl EvalOp for QMatMulUnary {
             bias,
             self.c_trans,
             result,
-            self.output_type,
+            self.output_type.clone(),
             &params,
         )?;
         model.set_output_outlets(&[result])?;
@@ -137,7 +139,7 @@ impl TypedOp for QMatMulUnary {
             };
         }
 
-        Ok(tvec!(TypedFact::dt_shape(self.output_type, c_shape)))
+        Ok(tvec!(TypedFact::dt_shape(self.output_type.clone(), c_shape)))
     }
 
     fn invariants(&self, inputs: &[&TypedFact], outputs: &[&TypedFact]) -> TractResult<Invariants> {
@@ -292,8 +294,8 @@ impl TypedOp for QMatMulUnary {
                     &*node.name,
                     &input_outlets,
                     self.a.datum_type(),
-                    model.node_input_facts(node.id)?[0].datum_type,
-                    self.output_type,
+                    model.node_input_facts(node.id)?[0].datum_type.clone(),
+                    self.output_type.clone(),
                 )?;
 
                 let scale = combine_scales(
@@ -341,7 +343,11 @@ impl TypedOp for QMatMulUnary {
                         &[wire, *w],
                     )?[0];
                 }
-                wire = requant(&mut patch, &node.name, wire, self.output_type, scale, c0)?;
+                let m_axis = patch.outlet_fact(wire)?.rank() - 2 + self.c_trans as usize;
+                let scale =
+                    wire_per_channel(&mut patch, &node.name, "scale", scale, wire, m_axis, 1)?;
+                let c0 = wire_per_channel(&mut patch, &node.name, "c0", c0, wire, m_axis, 1)?;
+                wire = requant(&mut patch, &node.name, wire, self.output_type.clone(), scale, c0)?;
                 patch.shunt_outside(model, OutletId::new(node.id, 0), wire)?;
                 return Ok(Some(patch));
             }
@@ -353,7 +359,7 @@ impl TypedOp for QMatMulUnary {
         cost(
             self.a.shape(),
             &inputs[0].shape.to_tvec(),
-            inputs[0].datum_type,
+            inputs[0].datum_type.clone(),
             self.a_trans,
             self.b_trans,
         )
@@ -364,6 +370,11 @@ impl TypedOp for QMatMulUnary {
         model: &TypedModel,
         node: &TypedNode,
     ) -> TractResult<Option<TypedModelPatch>> {
+        if let Some(patch) =
+            super::lir_dynamic_quant_unary::fuse_dynamic_quantization(self, model, node)?
+        {
+            return Ok(Some(patch));
+        }
         let mut patch = TypedModelPatch::default();
         let t_a = self.a.offset_u8_as_i8();
 
@@ -401,8 +412,8 @@ impl TypedOp for QMatMulUnary {
             &*node.name,
             &input_outlets,
             self.a.datum_type(),
-            model.node_input_facts(node.id)?[0].datum_type,
-            self.output_type,
+            model.node_input_facts(node.id)?[0].datum_type.clone(),
+            self.output_type.clone(),
         )?;
 
         let a = wire_offset_u8_as_i8(&mut patch, &node.name, a, "a", &mut params[0], "a0")?;
@@ -425,7 +436,7 @@ impl TypedOp for QMatMulUnary {
             bias,
             self.c_trans,
             result,
-            self.output_type,
+            self.output_type.clone(),
             &params,
         )?;
         patch.shunt_outside(model, node.id.into(), result)?;
@@ -1001,4 +1012,60 @@ mod test {
         }
         .check()
     }
+
+    #[test]
+    fn per_channel_a() {
+        let a = arr2(&[[12i8, -7, 3], [100, 4, -90]]);
+        let b = arr2(&[[5i8, -3], [20, 8], [-13, 1]]);
+        let (a0, a_scale) = ([1i32, -2], [0.5f32, 0.25]);
+
+        let reference = Array2::from_shape_fn((2, 2), |(m, n)| {
+            let c: f32 = (0..3)
+                .map(|k| (a[(m, k)] as f32 - a0[m] as f32) * a_scale[m] * b[(k, n)] as f32)
+                .sum();
+            round_ties_to_right(c).max(-128).min(127) as i8
+        });
+
+        for opt in &[false, true] {
+            let mut model = TypedModel::default();
+            let mut a = a.clone().into_tensor();
+            unsafe { a.set_datum_type(DatumType::QI8(QParams::per_axis(0, &a0, &a_scale))) };
+            let b_wire =
+                model.add_source("b", TypedFact::dt_shape(i8::datum_type(), &[3, 2])).unwrap();
+            let qparams = MatMulQParams {
+                a0: QParamKind::FromQType,
+                a_scale: QParamKind::FromQType,
+                b0: tensor0(0i8).into(),
+                b_scale: tensor0(1f32).into(),
+                c0: tensor0(0i8).into(),
+                c_scale: tensor0(1f32).into(),
+            };
+            let op = QMatMulUnary::new(
+                a.into_arc_tensor(),
+                None,
+                false,
+                false,
+                false,
+                i8::datum_type(),
+                qparams,
+            );
+            let result = model.wire_node("qmmu", op, &[b_wire]).unwrap();
+            model.set_output_outlets(&result).unwrap();
+            let model = if *opt { model.into_optimized().unwrap() } else { model };
+            if *opt {
+                // requantization is fused in the matrix multiplication kernel
+                let output = model.output_outlets().unwrap()[0].node;
+                assert!(model.node(output).op_is::<lir_unary::LirMatMulUnary>(), "{:#?}", model);
+            }
+            let mut outputs =
+                model.into_runnable().unwrap().run(tvec!(b.clone().into_tensor())).unwrap();
+            let found = outputs.remove(0).into_tensor().into_array::<i8>().unwrap();
+            assert!(
+                reference.iter().zip(found.iter()).all(|(r, f)| (*r as i32 - *f as i32).abs() <= 1),
+                "reference: {:?}, tract: {:?}",
+                reference,
+                found
+            );
+        }
+    }
 }
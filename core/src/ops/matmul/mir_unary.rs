@@ -1,6 +1,7 @@
-use super::lir_unary::{ConcreteMatMulGeometry, LirMatMulUnary, MatMulGeometry, ProtoFusedSpec};
+use super::lir_unary::{LirMatMulUnary, MatMulGeometry, ProtoFusedSpec, SymbolicMatMulGeometry};
 use super::*;
 use crate::internal::*;
+use tract_linalg::mmm::{InputStoreSpec, MatMatMul};
 use tract_ndarray::prelude::*;
 
 /// The pseudo Unary matrix multiplier. A is constant, B is the input
@@ -60,7 +61,7 @@ impl TypedOp for MatMulUnary {
             self.b_trans,
             self.c_trans,
         )?;
-        let c_dt = output_type(inputs[0].datum_type);
+        let c_dt = output_type(inputs[0].datum_type.clone());
         Ok(tvec!(TypedFact::dt_shape(c_dt, c_shape)))
     }
 
@@ -151,32 +152,30 @@ impl TypedOp for MatMulUnary {
         node: &TypedNode,
     ) -> TractResult<Option<TypedModelPatch>> {
         let b = args_1!(model.node_input_facts(node.id)?);
-        if let Some(b_shape) = b.shape.as_concrete() {
-            return Ok(Some(self.new_mat_mul_unary_finite(model, node, &b_shape, b.datum_type)?));
-        }
-        Ok(None)
+        Ok(Some(self.new_mat_mul_unary(model, node, &b.shape.to_tvec(), b.datum_type.clone())?))
     }
 
     as_op!();
 }
 
 impl MatMulUnary {
-    fn new_mat_mul_unary_finite(
+    fn new_mat_mul_unary(
         &self,
         model: &TypedModel,
         node: &TypedNode,
-        b_shape: &[usize],
+        b_shape: &[TDim],
         b_dt: DatumType,
     ) -> TractResult<TypedModelPatch> {
-        let mut patch = TypedModelPatch::default();
-        let mut wire = patch.tap_model(model, node.inputs[0])?;
-
         let c_dt = output_type(self.a.datum_type());
+        let a_shape: TVec<TDim> = self.a.shape().iter().map(|d| d.to_dim()).collect();
         let (m, k, n, c_shape) =
-            compute_shape(&self.a.shape(), b_shape, self.a_trans, self.b_trans, self.c_trans)?;
+            compute_shape(&a_shape, b_shape, self.a_trans, self.b_trans, self.c_trans)?;
+        // m and k come from the constant A, only n (and B prefix axes) can be symbolic
+        let m = m.to_usize()?;
+        let k = k.to_usize()?;
 
         let mmm = tract_linalg::ops()
-            .mmm(self.a.datum_type(), b_dt, c_dt, Some(m), Some(k), Some(n))
+            .mmm(self.a.datum_type(), b_dt.clone(), c_dt.clone(), Some(m), Some(k), n.to_usize().ok())
             .with_context(|| {
                 format!(
                     "No matrix multiplier for {:?}x{:?} to {:?}",
@@ -202,45 +201,16 @@ impl MatMulUnary {
                 );
                 (pa.into_arc_tensor(), vec![ProtoFusedSpec::Store])
             });
-        unsafe {
-            let mut packed_b_shape: TVec<usize> = b_shape[..b_shape.len() - 2].into();
-            packed_b_shape.push(mmm.b_pack().len(k, n));
-            wire = patch.wire_node(
-                format!("{}.pack", &*node.name),
-                super::MatMatMulPack {
-                    packer: mmm.b_pack(),
-                    trans: self.b_trans,
-                    output_shape: packed_b_shape,
-                },
-                &[wire],
-            )?[0];
-            let b_storage = mmm.b_packed(b_dt.size_of(), k);
-            let rank = c_shape.len();
-            let mut strides = natural_strides(&c_shape);
-            let mut overrided_shape = c_shape.clone();
-            if self.c_trans {
-                overrided_shape.swap(rank - 2, rank - 1);
-                strides.swap(rank - 2, rank - 1);
-            }
-            let geometry = ConcreteMatMulGeometry { m, k, n, b_storage };
-            wire = patch.wire_node(
-                format!("{}.matmatmul", &*node.name),
-                LirMatMulUnary {
-                    c_fact: TypedFact::dt_shape(c_dt, &c_shape),
-                    geometry: MatMulGeometry::Concrete(geometry),
-                    micro_ops: packed_as,
-                    c_m_axis: rank - 2 + self.c_trans as usize,
-                    c_n_axis: rank - 2 + !self.c_trans as usize,
-                    c_final_shape: c_shape.into(),
-                    reshape_post: vec![],
-                    mmm,
-                },
-                &[wire],
-            )?[0];
-            patch.shunt_outside(model, OutletId::new(node.id, 0), wire)?;
-            patch.obliterate(node.id)?;
-        }
-        Ok(patch)
+        let a_storage = unsafe { mmm.a_packed(self.a.datum_type().size_of(), k) };
+        wire_lir_mat_mul_unary(
+            model,
+            node,
+            mmm,
+            a_storage,
+            packed_as,
+            (self.b_trans, self.c_trans),
+            (m, k, n, c_shape),
+        )
     }
 
     fn declutter_precusor_is_concat(
@@ -428,3 +398,93 @@ pub(super) fn mir_unary_invariants(
     };
     Ok(invars.into_iter().collect())
 }
+
+/// Wire the packing of B and the LirMatMulUnary computing `node` from the A panels read
+/// through `a_storage`, in place of `node`. `n` may be symbolic: the packing and the matmul geometry are
+/// then resolved at run time from the session symbols.
+pub(super) fn wire_lir_mat_mul_unary(
+    model: &TypedModel,
+    node: &TypedNode,
+    mmm: Box<dyn MatMatMul>,
+    a_storage: InputStoreSpec,
+    packed_as: ArrayD<(Arc<Tensor>, Vec<ProtoFusedSpec>)>,
+    (b_trans, c_trans): (bool, bool),
+    (m, k, n, c_shape): (usize, usize, TDim, TVec<TDim>),
+) -> TractResult<TypedModelPatch> {
+    let mut patch = TypedModelPatch::default();
+    let mut wire = patch.tap_model(model, node.inputs[0])?;
+    let b_dt = model.outlet_fact(node.inputs[0])?.datum_type.clone();
+    let c_dt = node.outputs[0].fact.datum_type.clone();
+    wire = patch.wire_node(
+        format!("{}.pack", &*node.name),
+        super::MatMatMulPack { packer: mmm.b_pack(), trans: b_trans },
+        &[wire],
+    )?[0];
+    let mut geometry = MatMulGeometry::from(SymbolicMatMulGeometry {
+        m: m.to_dim(),
+        k: k.to_dim(),
+        n: n.clone(),
+        mmm: mmm.clone(),
+        b_datum_type: b_dt,
+    });
+    if n.to_usize().is_ok() {
+        geometry = geometry.optimize_if(Some(&SymbolValues::default()))?;
+    }
+    let rank = c_shape.len();
+    wire = patch.wire_node(
+        format!("{}.matmatmul", &*node.name),
+        LirMatMulUnary {
+            c_fact: TypedFact::dt_shape(c_dt, &*c_shape),
+            geometry,
+            a_storage,
+            micro_ops: packed_as,
+            c_m_axis: rank - 2 + c_trans as usize,
+            c_n_axis: rank - 2 + !c_trans as usize,
+            c_final_shape: c_shape.into(),
+            reshape_post: vec![],
+            mmm,
+        },
+        &[wire],
+    )?[0];
+    patch.shunt_outside(model, OutletId::new(node.id, 0), wire)?;
+    patch.obliterate(node.id)?;
+    Ok(patch)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn codegen_with_symbolic_n() -> TractResult<()> {
+        let a = Tensor::from_shape(&[2, 3], &[1f32, 2., 3., 4., 5., 6.])?;
+        let mut model = TypedModel::default();
+        let n = model.symbol_scope.sym("n");
+        let shape = [3.to_dim(), TDim::from(&n)];
+        let source = model.add_source("source", TypedFact::dt_shape(f32::datum_type(), &shape))?;
+        let mm = MatMulUnary::new(a.clone().into_arc_tensor(), false, false, false);
+        let mm = model.wire_node("mm", mm, &[source])?;
+        model.set_output_outlets(&mm)?;
+        let optimized = model.clone().into_optimized()?;
+        let lir = optimized
+            .nodes()
+            .iter()
+            .find_map(|n| n.op_as::<LirMatMulUnary>())
+            .context("Expected a LirMatMulUnary")?;
+        assert!(!lir.geometry.is_concrete());
+        let plan = SimplePlan::new(optimized)?;
+        for len in &[1usize, 4, 7] {
+            let b = Tensor::from_shape(
+                &[3, *len],
+                &(0..3 * len).map(|i| i as f32).collect::<Vec<_>>(),
+            )?;
+            let expected = a
+                .to_array_view::<f32>()?
+                .into_dimensionality::<Ix2>()?
+                .dot(&b.to_array_view::<f32>()?.into_dimensionality::<Ix2>()?);
+            let result = plan.run(tvec!(b))?;
+            assert_eq!(result[0].to_array_view::<f32>()?, expected.into_dyn().view());
+        }
+        Ok(())
+    }
+}
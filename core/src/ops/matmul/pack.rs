@@ -8,7 +8,6 @@ use tract_linalg::frame::Packer;
 pub struct MatMatMulPack {
     pub(crate) packer: Packer,
     pub(crate) trans: bool,
-    pub(crate) output_shape: TVec<usize>,
 }
 
 impl DynHash for MatMatMulPack {
@@ -17,6 +16,19 @@ impl DynHash for MatMatMulPack {
     }
 }
 
+impl MatMatMulPack {
+    /// Packed shape for an input of the given shape: the prefix axes are kept, the last two
+    /// collapse to the packed panels. Works on symbolic shapes as well as concrete ones.
+    pub(crate) fn output_shape<D: DimLike>(&self, input: &[D]) -> TVec<D> {
+        let rank = input.len();
+        let k = input[rank - 2 + self.trans as usize].clone();
+        let mn = input[rank - 1 - self.trans as usize].clone();
+        let mut shape: TVec<D> = input[..rank - 2].into();
+        shape.push(self.packer.len(k, mn));
+        shape
+    }
+}
+
 impl Op for MatMatMulPack {
     fn name(&self) -> Cow<str> {
         "MatMatMulPack".into()
@@ -38,10 +50,10 @@ impl EvalOp for MatMatMulPack {
     fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
         let b = args_1!(inputs);
         let dt = b.datum_type();
+        let output_shape = self.output_shape(b.shape());
         unsafe {
             let mut packed =
-                Tensor::uninitialized_aligned_dt(dt, &*self.output_shape, self.packer.alignment())
-                    .unwrap();
+                Tensor::uninitialized_aligned_dt(dt, &*output_shape, self.packer.alignment())?;
             for prefix in indices(&b.shape()[..b.rank() - 2]) {
                 self.packer.pack(
                     &mut packed.view_at_prefix_mut(prefix.slice())?,
@@ -57,7 +69,10 @@ impl EvalOp for MatMatMulPack {
 
 impl TypedOp for MatMatMulPack {
     fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
-        Ok(tvec!(TypedFact::dt_shape(inputs[0].datum_type, &self.output_shape)))
+        Ok(tvec!(TypedFact::dt_shape(
+            inputs[0].datum_type.clone(),
+            self.output_shape(&inputs[0].shape.to_tvec()),
+        )))
     }
 
     as_op!();
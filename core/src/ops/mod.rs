@@ -45,7 +45,7 @@ pub enum Validation {
     Accurate,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
 pub enum Cost {
     Div(DatumType),
     FMA(DatumType),
@@ -72,6 +72,18 @@ pub trait OpState: fmt::Debug + Send + dyn_clone::DynClone {
         op: &dyn Op,
         inputs: TVec<Arc<Tensor>>,
     ) -> TractResult<TVec<Arc<Tensor>>>;
+
+    /// Capture the state as tensors, for `SimpleState::save_states`. States that only read the
+    /// session and have nothing to keep between runs return no tensors.
+    fn save(&self) -> TractResult<TVec<Tensor>> {
+        bail!("{:?} does not support saving", self)
+    }
+
+    /// Reload what `save` captured in a freshly created state.
+    fn restore(&mut self, tensors: TVec<Tensor>) -> TractResult<()> {
+        ensure!(tensors.is_empty(), "Unexpected saved tensors for a state without data");
+        Ok(())
+    }
 }
 dyn_clone::clone_trait_object!(OpState);
 
@@ -0,0 +1,320 @@
+use crate::internal::*;
+use tract_ndarray::{s, Ix3};
+
+/// Scaled dot-product attention where each query only sees the key at its own position and
+/// the `left_context` keys before it.
+///
+/// Inputs are Q [.., T, d], K [.., T, d] and V [.., T, dv], the output is [.., T, dv].
+#[derive(Clone, Debug, new, Educe)]
+#[educe(Hash)]
+pub struct CausalAttention {
+    pub left_context: usize,
+    #[educe(Hash(method = "hash_f32"))]
+    pub scale: f32,
+}
+
+impl_dyn_hash!(CausalAttention);
+
+impl CausalAttention {
+    /// Attention of `q` over keys and values extending `k.len - q.len` positions further in
+    /// the past than the queries. Keys before `first_key` are ignored.
+    pub fn attend(
+        &self,
+        q: &Tensor,
+        k: &Tensor,
+        v: &Tensor,
+        first_key: usize,
+    ) -> TractResult<Tensor> {
+        let rank = q.rank();
+        let (tq, tk, d, dv) =
+            (q.shape()[rank - 2], k.shape()[rank - 2], q.shape()[rank - 1], v.shape()[rank - 1]);
+        ensure!(tk >= tq, "Expected at least as many keys ({}) as queries ({})", tk, tq);
+        let batch = q.shape()[..rank - 2].iter().product::<usize>();
+        let mut shape: TVec<usize> = q.shape().into();
+        shape[rank - 1] = dv;
+        let q = q.to_array_view::<f32>()?.into_shape((batch, tq, d))?;
+        let k = k.to_array_view::<f32>()?.into_shape((batch, tk, d))?;
+        let v = v.to_array_view::<f32>()?.into_shape((batch, tk, dv))?;
+        let mut output = tract_ndarray::Array::<f32, Ix3>::zeros((batch, tq, dv));
+        let mut scores = vec![0f32; self.left_context + 1];
+        for b in 0..batch {
+            for i in 0..tq {
+                let last = i + tk - tq;
+                let first = last.saturating_sub(self.left_context).max(first_key);
+                if first > last {
+                    continue;
+                }
+                let query = q.slice(s![b, i, ..]);
+                let scores = &mut scores[..last - first + 1];
+                for (score, j) in scores.iter_mut().zip(first..) {
+                    *score = query.dot(&k.slice(s![b, j, ..])) * self.scale;
+                }
+                let max = scores.iter().fold(std::f32::MIN, |a, s| a.max(*s));
+                scores.iter_mut().for_each(|s| *s = (*s - max).exp());
+                let sum: f32 = scores.iter().sum();
+                let mut out = output.slice_mut(s![b, i, ..]);
+                for (score, j) in scores.iter().zip(first..) {
+                    out.scaled_add(score / sum, &v.slice(s![b, j, ..]));
+                }
+            }
+        }
+        Ok(output.into_tensor().into_shape(&shape)?)
+    }
+}
+
+/// Recognise self-attention with a causal banded mask, as spelled by frameworks:
+/// `MatMul(softmax(MatMul(Q, Kᵀ) * scale + mask), V)`, `node` being the last MatMul. The
+/// softmax is the expanded one (max, sub, exp, sum and division along the last axis), the
+/// mask a constant (so for a given length) added to the scores, zero for each query and the
+/// keys in its left context, -1e4 or lower elsewhere.
+pub(crate) fn declutter_attention_pattern(
+    model: &TypedModel,
+    node: &TypedNode,
+) -> TractResult<Option<TypedModelPatch>> {
+    use crate::ops::binary::{TypedBinOp, UnaryOp};
+    use crate::ops::element_wise::ElementWiseOp;
+    use crate::ops::math;
+    use crate::ops::matmul::MatMul;
+    use crate::ops::nn::{Reduce, Reducer};
+
+    // the node computing an outlet, if it is only used `uses` times and not a model output
+    let prec = |outlet: OutletId, uses: usize| -> TractResult<Option<&TypedNode>> {
+        if model.outlet_successors(outlet).len() != uses
+            || model.output_outlets()?.contains(&outlet)
+        {
+            return Ok(None);
+        }
+        Ok(Some(model.node(outlet.node)))
+    };
+    let bin = |node: &TypedNode, is: fn(&TypedBinOp) -> bool| {
+        node.op_as::<TypedBinOp>().filter(|op| is(op)).is_some()
+    };
+    let reduce_last = |node: &TypedNode, reducer: Reducer| -> TractResult<bool> {
+        let rank = model.outlet_fact(node.inputs[0])?.rank();
+        Ok(node
+            .op_as::<Reduce>()
+            .map(|op| op.reducer == reducer && &*op.axes == &[rank - 1])
+            .unwrap_or(false))
+    };
+    macro_rules! tryo {
+        ($e: expr) => {
+            if let Some(it) = $e {
+                it
+            } else {
+                return Ok(None);
+            }
+        };
+    }
+
+    let mm = tryo!(node.op_as::<MatMul>());
+    if mm.a_trans || mm.b_trans || mm.c_trans {
+        return Ok(None);
+    }
+    let v = node.inputs[1];
+    // probabilities: exp / sum(exp), or exp * recip(sum(exp)) once decluttered
+    let probs = tryo!(prec(node.inputs[0], 1)?);
+    let (exp, sum) = if bin(probs, |op| op.0.is::<math::Div>()) {
+        (probs.inputs[0], probs.inputs[1])
+    } else if bin(probs, |op| op.0.is::<math::Mul>()) {
+        let recip = |ix: usize| -> TractResult<Option<OutletId>> {
+            let recip = tryo!(prec(probs.inputs[ix], 1)?);
+            let is_recip = recip.op_as::<ElementWiseOp>().map(|op| op.0.is::<math::Recip>());
+            Ok(Some(recip.inputs[0]).filter(|_| is_recip == Some(true)))
+        };
+        if let Some(sum) = recip(1)? {
+            (probs.inputs[0], sum)
+        } else {
+            (probs.inputs[1], tryo!(recip(0)?))
+        }
+    } else {
+        return Ok(None);
+    };
+    let sum = tryo!(prec(sum, 1)?);
+    if !reduce_last(sum, Reducer::Sum)? || sum.inputs[0] != exp {
+        return Ok(None);
+    }
+    let exp = tryo!(prec(exp, 2)?);
+    if exp.op_as::<ElementWiseOp>().map(|op| op.0.is::<math::Exp>()) != Some(true) {
+        return Ok(None);
+    }
+    let normed = tryo!(prec(exp.inputs[0], 1)?);
+    if !bin(normed, |op| op.0.is::<math::Sub>()) {
+        return Ok(None);
+    }
+    let max = tryo!(prec(normed.inputs[1], 1)?);
+    if !reduce_last(max, Reducer::Max)? || max.inputs[0] != normed.inputs[0] {
+        return Ok(None);
+    }
+    let masked = tryo!(prec(normed.inputs[0], 2)?);
+    let add = tryo!(masked.op_as::<UnaryOp>().filter(|op| op.mini_op.is::<math::Add>()));
+    let left_context = tryo!(banded_mask_context(&add.a)?);
+    let mut scores = tryo!(prec(masked.inputs[0], 1)?);
+    let mut scale = 1.0;
+    if let Some(mul) = scores.op_as::<UnaryOp>().filter(|op| op.mini_op.is::<math::Mul>()) {
+        if mul.a.len() != 1 || mul.a.datum_type() != f32::datum_type() {
+            return Ok(None);
+        }
+        scale = mul.a.as_slice::<f32>()?[0];
+        scores = tryo!(prec(scores.inputs[0], 1)?);
+    }
+    let qk = tryo!(scores.op_as::<MatMul>());
+    if qk.a_trans || !qk.b_trans || qk.c_trans {
+        return Ok(None);
+    }
+    let (q, k) = (scores.inputs[0], scores.inputs[1]);
+    let facts =
+        [q, k, v].iter().map(|o| model.outlet_fact(*o)).collect::<TractResult<TVec<_>>>()?;
+    let len = facts[0].shape[facts[0].rank().saturating_sub(2)].to_usize().ok();
+    if len != Some(add.a.shape()[add.a.rank() - 1]) {
+        return Ok(None);
+    }
+    let op = CausalAttention::new(left_context, scale);
+    if op.output_facts(&facts).is_err() {
+        return Ok(None);
+    }
+    let mut patch = TypedModelPatch::default();
+    let taps =
+        [q, k, v].iter().map(|o| patch.tap_model(model, *o)).collect::<TractResult<TVec<_>>>()?;
+    let attention = patch.wire_node(&node.name, op, &taps)?;
+    patch.shunt_outside(model, node.id.into(), attention[0])?;
+    Ok(Some(patch.with_context("causal attention")))
+}
+
+/// Left context of a (.., T, T) additive mask letting each query see itself and the keys
+/// just before it.
+fn banded_mask_context(mask: &Tensor) -> TractResult<Option<usize>> {
+    let rank = mask.rank();
+    if mask.datum_type() != f32::datum_type()
+        || rank < 2
+        || mask.shape()[..rank - 2].iter().any(|d| *d != 1)
+        || mask.shape()[rank - 1] != mask.shape()[rank - 2]
+    {
+        return Ok(None);
+    }
+    let len = mask.shape()[rank - 1];
+    let mask = mask.to_array_view::<f32>()?.into_shape((len, len))?;
+    let left_context = mask.row(len - 1).iter().filter(|x| **x == 0.0).count().saturating_sub(1);
+    let banded = mask.indexed_iter().all(|((i, j), x)| {
+        if j <= i && i - j <= left_context {
+            *x == 0.0
+        } else {
+            *x <= -1e4
+        }
+    });
+    Ok(Some(left_context).filter(|_| banded))
+}
+
+impl Op for CausalAttention {
+    fn name(&self) -> Cow<str> {
+        "CausalAttention".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("left context: {} scale: {}", self.left_context, self.scale)])
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for CausalAttention {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let (q, k, v) = args_3!(inputs);
+        Ok(tvec!(self.attend(&q, &k, &v, 0)?.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for CausalAttention {
+    as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let (q, k, v) = (inputs[0], inputs[1], inputs[2]);
+        ensure!(
+            [q, k, v].iter().all(|f| f.datum_type == f32::datum_type()),
+            "CausalAttention operates on f32"
+        );
+        let rank = q.rank();
+        ensure!(rank >= 2 && k.rank() == rank && v.rank() == rank, "Inconsistent ranks");
+        ensure!(
+            q.shape[..rank - 1] == k.shape[..rank - 1]
+                && q.shape[rank - 1] == k.shape[rank - 1]
+                && k.shape[..rank - 1] == v.shape[..rank - 1],
+            "Incompatible shapes for attention: Q {:?} K {:?} V {:?}",
+            q,
+            k,
+            v
+        );
+        let mut shape = q.shape.clone();
+        shape.set(rank - 1, v.shape[rank - 1].clone());
+        Ok(tvec!(TypedFact::dt_shape(f32::datum_type(), shape)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn window_is_causal_and_bounded() -> TractResult<()> {
+        // one-hot values and equal scores: each query averages the values it can see
+        let q = Tensor::zero::<f32>(&[4, 2])?;
+        let k = Tensor::zero::<f32>(&[4, 2])?;
+        let v =
+            tensor2(&[[1f32, 0., 0., 0.], [0., 1., 0., 0.], [0., 0., 1., 0.], [0., 0., 0., 1.]]);
+        let op = CausalAttention::new(1, 1.0);
+        let found = op.eval(tvec!(q.into(), k.into(), v.into()))?;
+        let expected = tensor2(&[
+            [1f32, 0., 0., 0.],
+            [0.5, 0.5, 0., 0.],
+            [0., 0.5, 0.5, 0.],
+            [0., 0., 0.5, 0.5],
+        ]);
+        assert_eq!(*found[0], expected);
+        Ok(())
+    }
+
+    #[test]
+    fn declutter_masked_softmax_attention() -> TractResult<()> {
+        use crate::ops::{math, matmul::MatMul, nn};
+        let (len, left_context) = (5, 2);
+        let mut model = TypedModel::default();
+        let x = model.add_source("x", TypedFact::dt_shape(f32::datum_type(), &[len, 3]))?;
+        let scores = model.wire_node("qk", MatMul::default().with_b_trans(true), &[x, x])?[0];
+        let scores =
+            model.wire_node("scale", math::mul::unary(rctensor2(&[[0.5f32]])), &[scores])?[0];
+        let mut mask = tract_ndarray::Array2::<f32>::from_elem((len, len), -1e9);
+        for i in 0..len {
+            for j in i.saturating_sub(left_context)..=i {
+                mask[(i, j)] = 0.0;
+            }
+        }
+        let mask = mask.into_arc_tensor();
+        let masked = model.wire_node("mask", math::add::unary(mask), &[scores])?[0];
+        let max = nn::Reduce::new(tvec!(1), nn::Reducer::Max);
+        let max = model.wire_node("max", max, &[masked])?[0];
+        let normed = model.wire_node("normed", math::sub::bin_typed(), &[masked, max])?[0];
+        let exp = model.wire_node("exp", math::exp(), &[normed])?[0];
+        let sum = nn::Reduce::new(tvec!(1), nn::Reducer::Sum);
+        let sum = model.wire_node("sum", sum, &[exp])?[0];
+        let probs = model.wire_node("probs", math::div::bin_typed(), &[exp, sum])?[0];
+        let output = model.wire_node("output", MatMul::default(), &[probs, x])?;
+        model.set_output_outlets(&output)?;
+
+        let decluttered = model.clone().into_decluttered()?;
+        let attention = decluttered
+            .nodes()
+            .iter()
+            .find_map(|n| n.op_as::<CausalAttention>())
+            .context("No CausalAttention")?;
+        assert_eq!((attention.left_context, attention.scale), (left_context, 0.5));
+        let input =
+            Tensor::from_shape(&[len, 3], &(0..15).map(|i| i as f32 / 7.).collect::<Vec<_>>())?;
+        let expected = model.into_runnable()?.run(tvec!(input.clone()))?;
+        let found = decluttered.into_runnable()?.run(tvec!(input))?;
+        found[0].close_enough(&expected[0], true)
+    }
+}
@@ -1,13 +1,18 @@
+mod attention;
 mod data_formats;
 mod reduce;
+mod running_reduce;
 
+pub use self::attention::CausalAttention;
+pub(crate) use self::attention::declutter_attention_pattern;
 pub use self::data_formats::{BaseDataShape, DataFormat, DataShape, SymDataShape};
 pub use self::reduce::{Reduce, Reducer};
+pub use self::running_reduce::{RunningReduce, RunningReducer};
 
 pub use crate::internal::*;
 
 element_wise!(sigmoid, Sigmoid, [f32] => |_, xs| {
     (tract_linalg::ops().sigmoid_f32)().run(xs)
 };
-    cost: |dt| {tvec!((Cost::FMA(dt), 11), (Cost::Div(dt), 1))}
+    cost: |dt: DatumType| {tvec!((Cost::FMA(dt.clone()), 11), (Cost::Div(dt), 1))}
 );
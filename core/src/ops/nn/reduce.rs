@@ -56,7 +56,7 @@ impl Reducer {
             .enumerate()
             .map(|(ax, &d)| if axes.contains(&ax) { 1 } else { d })
             .collect();
-        let (zp, scale) = input.datum_type().zp_scale();
+        let (zp, scale) = input.datum_type().zp_scale()?;
         Ok(unsafe {
             match self {
                 ArgMax(last) => {
@@ -278,7 +278,7 @@ impl TypedOp for Reduce {
         let dt = if let Reducer::ArgMax(_) | Reducer::ArgMin(_) = self.reducer {
             DatumType::I64
         } else {
-            inputs[0].datum_type
+            inputs[0].datum_type.clone()
         };
         Ok(tvec!(TypedFact::dt_shape(dt, shape)))
     }
@@ -0,0 +1,148 @@
+use crate::internal::*;
+use num_traits::Float;
+use tract_ndarray::{Axis, Zip};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RunningReducer {
+    Sum,
+    Mean,
+    Max,
+    Min,
+}
+
+impl RunningReducer {
+    /// Replace in place each frame along `axis` by the reduction of the frames up to it.
+    ///
+    /// `acc` holds the running value (the running sum for Mean), with the shape of `data` but
+    /// 1 along `axis`, after `seen` frames. The first `skip` frames are left untouched and not
+    /// accumulated. Returns the number of frames seen at the end of `data`.
+    pub fn accumulate(
+        &self,
+        axis: usize,
+        data: &mut Tensor,
+        acc: &mut Tensor,
+        seen: usize,
+        skip: usize,
+    ) -> TractResult<usize> {
+        dispatch_floatlike!(Self::accumulate_t(data.datum_type())(
+            self, axis, data, acc, seen, skip
+        ))
+    }
+
+    fn accumulate_t<T: Datum + Float>(
+        &self,
+        axis: usize,
+        data: &mut Tensor,
+        acc: &mut Tensor,
+        mut seen: usize,
+        skip: usize,
+    ) -> TractResult<usize> {
+        let mut data = data.to_array_view_mut::<T>()?;
+        let mut acc = acc.to_array_view_mut::<T>()?;
+        let mut acc = acc.index_axis_mut(Axis(axis), 0);
+        for frame in skip..data.shape()[axis] {
+            let count = T::from(seen + 1).unwrap();
+            Zip::from(&mut acc).and(data.index_axis_mut(Axis(axis), frame)).for_each(|a, x| {
+                *a = match self {
+                    _ if seen == 0 => *x,
+                    RunningReducer::Sum | RunningReducer::Mean => *a + *x,
+                    RunningReducer::Max => a.max(*x),
+                    RunningReducer::Min => a.min(*x),
+                };
+                *x = if *self == RunningReducer::Mean { *a / count } else { *a };
+            });
+            seen += 1;
+        }
+        Ok(seen)
+    }
+}
+
+/// Cumulative reduction: output frame i along `axis` reduces the input frames 0 to i.
+#[derive(Clone, Debug, new, Hash)]
+pub struct RunningReduce {
+    pub axis: usize,
+    pub reducer: RunningReducer,
+}
+
+impl_dyn_hash!(RunningReduce);
+
+impl Op for RunningReduce {
+    fn name(&self) -> Cow<str> {
+        format!("RunningReduce<{:?}>", self.reducer).into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("axis: {}", self.axis)])
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for RunningReduce {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let mut data = inputs[0].clone().into_tensor();
+        let mut acc_shape: TVec<usize> = data.shape().into();
+        acc_shape[self.axis] = 1;
+        let mut acc = Tensor::zero_dt(data.datum_type(), &acc_shape)?;
+        self.reducer.accumulate(self.axis, &mut data, &mut acc, 0, 0)?;
+        Ok(tvec!(data.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for RunningReduce {
+    as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        ensure!(inputs[0].datum_type.is_float(), "RunningReduce operates on floats");
+        ensure!(self.axis < inputs[0].rank(), "Invalid axis {} for {:?}", self.axis, inputs[0]);
+        Ok(tvec!(TypedFact::dt_shape(inputs[0].datum_type.clone(), inputs[0].shape.clone())))
+    }
+
+    fn invariants(
+        &self,
+        inputs: &[&TypedFact],
+        _outputs: &[&TypedFact],
+    ) -> TractResult<Invariants> {
+        let axes = (0..inputs[0].rank())
+            .filter(|axis| *axis != self.axis)
+            .map(|axis| AxisInfo::simple(axis))
+            .collect::<TVec<_>>();
+        Ok(axes.into())
+    }
+
+    fn change_axes(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+        _io: InOut,
+        change: &AxisOp,
+    ) -> TractResult<Option<AxisChangeConsequence>> {
+        if let Some(axis) = change.transform_axis(self.axis) {
+            let op = Some(Box::new(RunningReduce { axis, ..self.clone() }) as _);
+            Ok(Some(AxisChangeConsequence::new(model, node, op, change)))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn running_reductions() -> TractResult<()> {
+        let input = tvec!(tensor2(&[[1f32, 4.0], [3.0, 2.0], [2.0, 6.0]]).into_arc_tensor());
+        let run = |reducer| RunningReduce::new(0, reducer).eval(input.clone());
+        assert_eq!(*run(RunningReducer::Sum)?[0], tensor2(&[[1f32, 4.0], [4.0, 6.0], [6.0, 12.0]]));
+        assert_eq!(*run(RunningReducer::Mean)?[0], tensor2(&[[1f32, 4.0], [2.0, 3.0], [2.0, 4.0]]));
+        assert_eq!(*run(RunningReducer::Max)?[0], tensor2(&[[1f32, 4.0], [3.0, 4.0], [3.0, 6.0]]));
+        assert_eq!(*run(RunningReducer::Min)?[0], tensor2(&[[1f32, 4.0], [1.0, 2.0], [1.0, 2.0]]));
+        Ok(())
+    }
+}
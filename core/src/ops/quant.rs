@@ -4,6 +4,7 @@ use crate::ops::math::QScale;
 use num_traits::AsPrimitive;
 use tract_linalg::lut::Lut;
 use tract_linalg::mmm::RoundingPolicy;
+use tract_ndarray::ArrayViewD;
 
 use super::math::round_ties_to_even;
 
@@ -148,7 +149,7 @@ impl TypedOp for DequantizeLinearF32 {
         dequant: &TypedNode,
     ) -> TractResult<Option<TypedModelPatch>> {
         let mut current = dequant;
-        let incoming_dt = model.node_input_facts(dequant.id)?[0].datum_type;
+        let incoming_dt = model.node_input_facts(dequant.id)?[0].datum_type.clone();
         while let Some(quant) = model.single_succ(current.id)? {
             let q_params = if let Some(op) = quant.op_as::<ElementWiseOp>() {
                 if let Some(mop) = op.0.downcast_ref::<QuantizeLinearU8>() {
@@ -169,7 +170,7 @@ impl TypedOp for DequantizeLinearF32 {
                 loop {
                     if let Some(op) = next
                         .op
-                        .quantize(model, dequant, dt, scale, zero_point)
+                        .quantize(model, dequant, dt.clone(), scale, zero_point)
                         .with_context(|| format!("Quantizing {}", next))?
                     {
                         wire = patch.wire_node(&*next.name, op, [wire].as_ref())?[0];
@@ -186,8 +187,8 @@ impl TypedOp for DequantizeLinearF32 {
                 // or else make a lookup table
                 if incoming_dt == DatumType::I8 || incoming_dt == DatumType::U8 {
                     let mut adhoc_model = TypedModel::default();
-                    let mut wire =
-                        adhoc_model.add_source("ad-hoc", TypedFact::dt_shape(dt, &[256]))?;
+                    let mut wire = adhoc_model
+                        .add_source("ad-hoc", TypedFact::dt_shape(dt.clone(), &[256]))?;
                     let mut next = model.single_succ(dequant.id)?.unwrap();
                     let mut name = None;
                     // plug in dequant
@@ -246,9 +247,113 @@ impl TypedOp for DequantizeLinearF32 {
     as_op!();
 }
 
+pub(crate) fn dynamic_quantize_linear_f32_u8(x: f32, scale: f32, zero_point: u8) -> u8 {
+    (((x / scale).round() as i32) + zero_point as i32)
+        .max(u8::min_value() as i32)
+        .min(u8::max_value() as i32) as u8
+}
+
+fn dynamic_quantize_linear_u8(scale: f32, zero_point: u8, xs: &[f32], ys: &mut [u8]) {
+    xs.iter()
+        .zip(ys.iter_mut())
+        .for_each(|(x, y)| *y = dynamic_quantize_linear_f32_u8(*x, scale, zero_point));
+}
+
+/// ONNX DynamicQuantizeLinear range: min and max of the values, extended to include zero.
+pub(crate) fn scale_and_zero_point<'a>(v: ArrayViewD<'a, f32>) -> (f32, u8) {
+    // get the min and max of v and extend it to have zero included
+    // in the interval [min, max]
+    let (min, max) = v.fold((0., 0.), |(a_min, a_max), &v| {
+        if v < a_min {
+            (v, a_max)
+        } else if v > a_max {
+            (a_min, v)
+        } else {
+            (a_min, a_max)
+        }
+    });
+
+    // quantize range
+    let min_t = u8::min_value() as f32;
+    let max_t = u8::max_value() as f32;
+
+    let scale = (max - min) / max_t;
+
+    let zero_point = -min / scale;
+    let zero_point = zero_point.round();
+    // clipping to [0, 255]
+    let zero_point = zero_point.max(min_t);
+    let zero_point = zero_point.min(max_t);
+
+    let zero_point: u8 = zero_point as u8;
+
+    (scale, zero_point)
+}
+
+#[derive(Clone, Debug, new, Hash)]
+pub struct DynamicQuantizeLinearU8;
+
+impl Op for DynamicQuantizeLinearU8 {
+    fn name(&self) -> Cow<str> {
+        "DynamicQuantizeLinearU8".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![])
+    }
+
+    fn validation(&self) -> Validation {
+        Validation::Accurate
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl_dyn_hash!(DynamicQuantizeLinearU8);
+
+impl EvalOp for DynamicQuantizeLinearU8 {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = &inputs[0];
+        let a_input = input.to_array_view::<f32>()?;
+        let (scale, zero_point) = scale_and_zero_point(a_input);
+
+        let mut dst = unsafe { Tensor::uninitialized_dt(u8::datum_type(), input.shape())? };
+        // We cannot use quantize_linear_u8 here because it does `x * scale.recip()`
+        // instead of `x / scale`. This change some number enough to be rounded to another integer.
+        dynamic_quantize_linear_u8(
+            scale,
+            zero_point,
+            input.as_slice::<f32>()?,
+            dst.as_slice_mut::<u8>()?,
+        );
+
+        let quantized_tensor = dst.into_arc_tensor();
+        let scale_tensor = rctensor0(scale);
+        let zero_point_tensor = rctensor0(zero_point);
+
+        Ok(tvec!(quantized_tensor, scale_tensor, zero_point_tensor))
+    }
+}
+
+impl TypedOp for DynamicQuantizeLinearU8 {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let mut quantized_fact = inputs[0].clone();
+        quantized_fact.datum_type = u8::datum_type();
+        let scale_fact = TypedFact::dt_shape(f32::datum_type(), &[0; 0]);
+        let zero_fact = TypedFact::dt_shape(u8::datum_type(), &[0; 0]);
+        Ok(tvec!(quantized_fact, scale_fact, zero_fact))
+    }
+
+    as_op!();
+}
+
 element_wise_oop!(lookup_table,
  LookupTable {
-     #[educe(Hash(method="hash_lookup_table"))]
+     #[educe(Hash(method="hash_lookup_table"), PartialEq(method="eq_lookup_table"))]
      table: Box<dyn Lut>
  },
  [i8] => i8 |op, xs, ys| {
@@ -270,6 +375,10 @@ fn hash_lookup_table<H: std::hash::Hasher>(lut: &Box<dyn Lut>, h: &mut H) {
     Hash::hash_slice(lut.table(), h)
 }
 
+fn eq_lookup_table(a: &Box<dyn Lut>, b: &Box<dyn Lut>) -> bool {
+    a.table() == b.table()
+}
+
 #[derive(Debug, Clone, Hash)]
 pub struct Scale;
 impl_dyn_hash!(Scale);
@@ -279,6 +388,10 @@ impl crate::ops::binary::BinMiniOp for Scale {
         "Scale"
     }
 
+    fn same_as(&self, other: &dyn crate::ops::binary::BinMiniOp) -> bool {
+        other.is::<Self>()
+    }
+
     fn result_datum_type(&self, a: DatumType, b: DatumType) -> TractResult<DatumType> {
         if a != f32::datum_type() {
             bail!("Scale left operand must be f32, got {:?}", a);
@@ -460,10 +573,12 @@ impl ElementWiseMiniOp for OffsetU8asI8 {
     fn name(&self) -> String {
         format!("{}{}", self.prefix(), stringify!(OffsetU8asI8))
     }
+    fn same_as(&self, other: &dyn ElementWiseMiniOp) -> bool {
+        other.is::<Self>()
+    }
     fn output_type(&self, input_type: DatumType) -> Option<DatumType> {
         Some(if let DatumType::QU8(qp) = input_type {
-            let (zp, scale) = qp.zp_scale();
-            DatumType::QI8(QParams::ZpScale { zero_point: zp - 128, scale })
+            DatumType::QI8(qp.map_zero_points(|zp| zp - 128))
         } else if input_type == DatumType::U8 {
             DatumType::I8
         } else {
@@ -487,3 +602,56 @@ impl ElementWiseMiniOp for OffsetU8asI8 {
 pub fn offset_u8_as_i8() -> ElementWiseOp {
     ElementWiseOp(Box::new(OffsetU8asI8 {}))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tract_ndarray::arr1;
+
+    // Data for tests is from:
+    // https://github.com/onnx/onnx/blob/master/docs/Operators.md#DynamicQuantizeLinear
+    #[test]
+    fn test_scale_and_zero_point() {
+        let data: [(&[f32], f32, u8); 3] = [
+            (&[0., 2., -3., -2.5, 1.34, 0.5], 0.0196078438, 153),
+            (&[-1., -2.1, -1.3, -2.5, -3.34, -4.], 0.0156862754, 255),
+            (&[1., 2.1, 1.3, 2.5, 3.34, 4., 1.5, 2.6, 3.9, 4., 3., 2.345], 0.0156862754, 0),
+        ];
+
+        let epsilon = 0.00000001;
+        for (v, scale_ok, zero_point_ok) in &data {
+            let v = arr1(v).into_dyn();
+            let v = v.view();
+            let (scale, zero_point) = scale_and_zero_point(v);
+            assert!((scale - scale_ok).abs() < epsilon);
+            assert_eq!(zero_point, *zero_point_ok);
+        }
+    }
+
+    #[test]
+    fn test_dynamic_quantize_linear_u8() {
+        let data: [(&[f32], &[u8]); 3] = [
+            (&[0., 2., -3., -2.5, 1.34, 0.5], &[153, 255, 0, 26, 221, 179]),
+            (&[-1., -2.1, -1.3, -2.5, -3.34, -4.], &[191, 121, 172, 96, 42, 0]),
+            (
+                &[1., 2.1, 1.3, 2.5, 3.34, 4., 1.5, 2.6, 3.9, 4., 3., 2.345],
+                &[64, 134, 83, 159, 213, 255, 96, 166, 249, 255, 191, 149],
+            ),
+        ];
+
+        for (v, quantized_ok) in &data {
+            let v = arr1(v).into_dyn();
+            let (scale, zero_point) = scale_and_zero_point(v.view());
+
+            // same shape of v but with u8 type, values will be overwritten
+            let mut quantized = v.mapv(|_| 0 as u8);
+            dynamic_quantize_linear_u8(
+                scale,
+                zero_point,
+                v.as_slice().unwrap(),
+                quantized.as_slice_mut().unwrap(),
+            );
+            assert_eq!(quantized.as_slice().unwrap(), *quantized_ok);
+        }
+    }
+}
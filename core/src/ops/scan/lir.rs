@@ -194,7 +194,7 @@ impl OpState for State {
                     .and_then(|d| d.to_usize().ok())
                     .unwrap_or(shape[output.axis] * iters);
                 shape[output.axis] = scanning_dim;
-                let t = unsafe { Tensor::uninitialized_dt(fact.datum_type, &*shape)? };
+                let t = unsafe { Tensor::uninitialized_dt(fact.datum_type.clone(), &*shape)? };
                 outputs.push((slot, t));
             }
             if let Some(slot) = output.last_value_slot {
@@ -262,6 +262,21 @@ impl OpState for State {
 
         Ok(outputs.into_iter().map(Arc::new).collect())
     }
+
+    fn save(&self) -> TractResult<TVec<Tensor>> {
+        let body = self.mutable.model_state.save_states()?;
+        let mut tensors = tvec!(tensor0(self.mutable.position as i64), tensor1(&body));
+        tensors.extend(self.mutable.hidden_state.iter().cloned());
+        Ok(tensors)
+    }
+
+    fn restore(&mut self, tensors: TVec<Tensor>) -> TractResult<()> {
+        ensure!(tensors.len() >= 2, "Expected scan position and body states");
+        self.mutable.position = *tensors[0].to_scalar::<i64>()? as usize;
+        self.mutable.model_state.restore_states(tensors[1].as_slice::<u8>()?)?;
+        self.mutable.hidden_state = tensors.into_iter().skip(2).collect();
+        Ok(())
+    }
 }
 
 impl TypedOp for LirScan {
@@ -284,14 +299,15 @@ impl TypedOp for LirScan {
         for (ix, output) in self.output_mapping.iter().enumerate() {
             let fact = self.plan.model().output_fact(ix)?;
             if let Some(slot) = output.last_value_slot {
-                outputs.push((slot, TypedFact::dt_shape(fact.datum_type, fact.shape.clone())));
+                outputs
+                    .push((slot, TypedFact::dt_shape(fact.datum_type.clone(), fact.shape.clone())));
             }
             if let Some(slot) = output.full_slot {
                 let mut shape = fact.shape.clone();
                 let scanning_dim =
                     output.full_dim_hint.clone().unwrap_or(shape[output.axis].clone() * &iters);
                 shape.set(output.axis, scanning_dim);
-                outputs.push((slot, TypedFact::dt_shape(fact.datum_type, shape)));
+                outputs.push((slot, TypedFact::dt_shape(fact.datum_type.clone(), shape)));
             }
         }
         outputs.sort_by_key(|a| a.0);
@@ -12,6 +12,10 @@ impl OpState for SourceState {
     ) -> TractResult<TVec<Arc<Tensor>>> {
         Ok(tvec!(session.inputs[&self.0].clone()))
     }
+
+    fn save(&self) -> TractResult<TVec<Tensor>> {
+        Ok(tvec!())
+    }
 }
 
 #[derive(Debug, Clone, new, Hash)]
@@ -76,7 +80,7 @@ impl TypedOp for TypedSource {
         let shape: TVec<_> = self.fact.shape.iter().map(|d| d.eval(values)).collect();
         target.wire_node(
             &node.name,
-            Self { fact: TypedFact::dt_shape(self.fact.datum_type, &*shape) },
+            Self { fact: TypedFact::dt_shape(self.fact.datum_type.clone(), &*shape) },
             &[],
         )
     }
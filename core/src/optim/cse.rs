@@ -0,0 +1,125 @@
+use crate::internal::*;
+use crate::ops::konst::Const;
+use crate::ops::source::TypedSource;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// Common subexpression elimination: merges the nodes running the same op on the same inputs,
+/// and the constants holding equal tensors.
+#[derive(Clone, Default)]
+pub struct Cse {
+    // one run finds all the merges, following the merged nodes downstream
+    done: bool,
+}
+
+impl std::fmt::Debug for Cse {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(fmt, "Cse")
+    }
+}
+
+impl super::TypedPass for Cse {
+    fn reset(&mut self) -> TractResult<()> {
+        self.done = false;
+        Ok(())
+    }
+
+    fn next(&mut self, model: &TypedModel) -> TractResult<Option<TypedModelPatch>> {
+        if self.done {
+            return Ok(None);
+        }
+        self.done = true;
+        let mut patch = TypedModelPatch::default();
+        let outputs = model.output_outlets()?;
+        // nodes kept so far, by op hash and inputs
+        let mut kept: HashMap<(u64, TVec<OutletId>), Vec<usize>> = HashMap::new();
+        // merged node -> kept node
+        let mut merged: HashMap<usize, usize> = HashMap::new();
+        for id in model.eval_order()? {
+            let node = model.node(id);
+            if node.op_is::<TypedSource>()
+                || !node.op.is_stateless()
+                || node.op.validation() == Validation::Random
+            {
+                continue;
+            }
+            let inputs: TVec<OutletId> = node
+                .inputs
+                .iter()
+                .map(|i| OutletId::new(merged.get(&i.node).copied().unwrap_or(i.node), i.slot))
+                .collect();
+            let candidates = kept.entry((op_key(node), inputs)).or_default();
+            let found =
+                candidates.iter().copied().find(|&c| same_op(&*model.node(c).op, &*node.op));
+            match found {
+                Some(original) if !outputs.iter().any(|o| o.node == id) => {
+                    for slot in 0..node.outputs.len() {
+                        let tap = patch.tap_model(model, OutletId::new(original, slot))?;
+                        patch.shunt_outside(model, OutletId::new(id, slot), tap)?;
+                    }
+                    merged.insert(id, original);
+                }
+                _ => candidates.push(id),
+            }
+        }
+        Ok(Some(patch).filter(|p| !p.is_empty()))
+    }
+}
+
+// constants are keyed by type and shape only: comparing the few candidates is cheaper than
+// hashing all the weights of the model
+fn op_key(node: &TypedNode) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    if let Some(konst) = node.op_as::<Const>() {
+        konst.0.datum_type().hash(&mut hasher);
+        konst.0.shape().hash(&mut hasher);
+    } else {
+        node.op.hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+// ops without a same_as implementation are never merged
+fn same_op(a: &dyn TypedOp, b: &dyn TypedOp) -> bool {
+    if let (Some(a), Some(b)) =
+        (a.as_op().downcast_ref::<Const>(), b.as_op().downcast_ref::<Const>())
+    {
+        return a.0 == b.0;
+    }
+    a.same_as(b.as_op())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ops::math;
+    use crate::optim::Optimizer;
+
+    #[test]
+    fn merge_duplicates() -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let source = model.add_source("source", TypedFact::dt_shape(f32::datum_type(), &[2]))?;
+        let mut branches = tvec!();
+        for ix in 0..2 {
+            let tanh = model.wire_node(format!("tanh.{}", ix), math::tanh(), &[source])?[0];
+            let konst = model.add_const(format!("konst.{}", ix), rctensor1(&[1f32, 2.]))?;
+            branches.push(
+                model.wire_node(format!("add.{}", ix), math::add::bin_typed(), &[tanh, konst])?[0],
+            );
+        }
+        let other = model.add_const("other", rctensor1(&[3f32, 4.]))?;
+        let add = model.wire_node("add", math::add::bin_typed(), &branches)?;
+        let mul = model.wire_node("mul", math::mul::bin_typed(), &[add[0], other])?;
+        model.set_output_outlets(&mul)?;
+
+        let input = tensor1(&[0f32, 1.]);
+        let expected = model.clone().into_runnable()?.run(tvec!(input.clone()))?;
+        Optimizer::new().with_pass(Cse::default()).optimize(&mut model)?;
+        let count = |name: &str| model.nodes().iter().filter(|n| n.op().name() == name).count();
+        assert_eq!(count("Const"), 2);
+        assert_eq!(count("Tanh"), 1);
+        assert_eq!(count("Add"), 2);
+        let found = model.into_runnable()?.run(tvec!(input))?;
+        found[0].close_enough(&expected[0], false)
+    }
+}
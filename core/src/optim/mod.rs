@@ -1,42 +1,152 @@
 use crate::internal::*;
 use std::collections::HashSet;
-use std::fmt::Debug;
+use std::fmt::{self, Debug};
+use std::time::{Duration, Instant};
 use tract_itertools::Itertools;
 
 pub mod change_axes;
+mod cse;
 mod op_optim;
+pub mod pattern;
 mod prop_const;
 mod push_split_down;
 
-use self::change_axes::ChangeAxes;
-use self::prop_const::PropConst;
-use self::push_split_down::PushSplitDown;
-use op_optim::OpOptim;
+pub use self::change_axes::ChangeAxes;
+pub use self::cse::Cse;
+pub use self::op_optim::OpOptim;
+use self::pattern::{RewriteRules, Rule};
+pub use self::prop_const::PropConst;
+pub use self::push_split_down::PushSplitDown;
 
 pub trait TypedPass: Debug + Send + Sync + dyn_clone::DynClone {
+    /// Name of the pass, used to disable or reorder it in an `Optimizer`.
+    fn name(&self) -> String {
+        format!("{:?}", self)
+    }
     fn reset(&mut self) -> TractResult<()>;
     fn next(&mut self, model: &TypedModel) -> TractResult<Option<TypedModelPatch>>;
 }
 
 dyn_clone::clone_trait_object!(TypedPass);
 
+/// One run of a pass: how long it took and the patches it applied.
+#[derive(Clone, Debug)]
+pub struct PassRun {
+    pub iteration: usize,
+    pub pass: String,
+    pub duration: Duration,
+    pub patches: Vec<String>,
+}
+
+/// What an optimizer did to a model, pass run by pass run.
+#[derive(Clone, Debug, Default)]
+pub struct OptimizerReport {
+    pub runs: Vec<PassRun>,
+}
+
+impl OptimizerReport {
+    /// Total time spent and patches applied by each pass, in order of first run.
+    pub fn by_pass(&self) -> Vec<(String, Duration, usize)> {
+        let mut totals: Vec<(String, Duration, usize)> = vec![];
+        for run in &self.runs {
+            if let Some(total) = totals.iter_mut().find(|t| t.0 == run.pass) {
+                total.1 += run.duration;
+                total.2 += run.patches.len();
+            } else {
+                totals.push((run.pass.clone(), run.duration, run.patches.len()));
+            }
+        }
+        totals
+    }
+}
+
+impl fmt::Display for OptimizerReport {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        for (pass, duration, patches) in self.by_pass() {
+            writeln!(fmt, "{:>20} {:>10.3?} {:>6} patches", pass, duration, patches)?;
+        }
+        for run in &self.runs {
+            for patch in &run.patches {
+                writeln!(fmt, "#{} {}: {}", run.iteration, run.pass, patch)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Default)]
 pub struct Optimizer {
     passes: Vec<Box<dyn TypedPass>>,
     steps: Option<usize>,
+    max_iterations: Option<usize>,
 }
 
 impl Optimizer {
     fn passes(passes: Vec<Box<dyn TypedPass>>) -> Optimizer {
-        Optimizer { passes, steps: None }
+        Optimizer { passes, ..Optimizer::default() }
+    }
+
+    /// An optimizer running no pass, to be filled with `with_pass`.
+    pub fn new() -> Optimizer {
+        Optimizer::default()
     }
 
+    /// Stop after applying `steps` patches.
     pub fn stopping_at(self, steps: usize) -> Optimizer {
         Optimizer { steps: Some(steps), ..self }
     }
 
+    /// Stop after running all the passes `iterations` times, even if they could still change
+    /// the model.
+    pub fn max_iterations(self, iterations: usize) -> Optimizer {
+        Optimizer { max_iterations: Some(iterations), ..self }
+    }
+
+    pub fn pass_names(&self) -> Vec<String> {
+        self.passes.iter().map(|p| p.name()).collect()
+    }
+
+    /// Append `pass` to the pipeline.
+    pub fn with_pass(mut self, pass: impl TypedPass + 'static) -> Optimizer {
+        self.passes.push(Box::new(pass));
+        self
+    }
+
+    /// Insert `pass` in the pipeline at position `index`.
+    pub fn with_pass_at(mut self, index: usize, pass: impl TypedPass + 'static) -> Optimizer {
+        self.passes.insert(index.min(self.passes.len()), Box::new(pass));
+        self
+    }
+
+    /// Run `rules` first in each iteration of the pipeline.
+    pub fn with_rules(self, rules: Vec<Rule>) -> Optimizer {
+        self.with_pass_at(0, RewriteRules::new(rules))
+    }
+
+    /// Remove the passes named `name`.
+    pub fn without_pass(mut self, name: &str) -> Optimizer {
+        self.passes.retain(|p| p.name() != name);
+        self
+    }
+
+    /// Keep only the passes named in `names`, in this order.
+    pub fn with_pass_order(mut self, names: &[&str]) -> TractResult<Optimizer> {
+        let mut passes = vec![];
+        for name in names {
+            let ix =
+                self.passes.iter().position(|p| p.name() == *name).with_context(|| {
+                    format!("No pass named {} in {:?}", name, self.pass_names())
+                })?;
+            passes.push(self.passes.remove(ix));
+        }
+        self.passes = passes;
+        Ok(self)
+    }
+
     pub fn declutter() -> Optimizer {
         Optimizer::passes(vec![
             Box::new(PropConst),
+            Box::new(Cse::default()),
             Box::new(OpOptim("declutter", TypedOp::declutter, 0)),
             Box::new(PushSplitDown),
             Box::new(ChangeAxes),
@@ -54,22 +164,31 @@ impl Optimizer {
     }
 
     pub fn optimize(&self, model: &mut TypedModel) -> TractResult<()> {
+        self.optimize_with_report(model).map(|_| ())
+    }
+
+    /// Optimize `model`, reporting the time spent in each pass and the patches applied.
+    pub fn optimize_with_report(&self, model: &mut TypedModel) -> TractResult<OptimizerReport> {
         #[cfg(all(debug_assertions, feature = "paranoid_assertions"))]
         {
             model.check_consistent_facts()?;
         }
         let mut seen = HashSet::new();
+        let mut report = OptimizerReport::default();
         model.compact()?;
         let mut counter = 0;
         for i in 0.. {
-            let new_counter = self.run_all_passes(i, counter, model, &mut seen)?;
+            if self.max_iterations.map(|max| i >= max).unwrap_or(false) {
+                break;
+            }
+            let new_counter = self.run_all_passes(i, counter, model, &mut seen, &mut report)?;
             if new_counter == counter {
-                return Ok(());
+                break;
             }
             counter = new_counter;
             model.compact()?;
         }
-        unreachable!()
+        Ok(report)
     }
 
     pub fn run_all_passes(
@@ -78,10 +197,11 @@ impl Optimizer {
         mut counter: usize,
         model: &mut TypedModel,
         seen: &mut HashSet<String>,
+        report: &mut OptimizerReport,
     ) -> TractResult<usize> {
         let mut passes = self.passes.clone();
         for p in passes.iter_mut() {
-            counter = self.run_one_pass_outer(i, p.as_mut(), counter, model, seen)?;
+            counter = self.run_one_pass_outer(i, p.as_mut(), counter, model, seen, report)?;
             model.compact()?;
         }
         Ok(counter)
@@ -94,9 +214,10 @@ impl Optimizer {
         mut counter: usize,
         model: &mut TypedModel,
         seen: &mut HashSet<String>,
+        report: &mut OptimizerReport,
     ) -> TractResult<usize> {
         loop {
-            let new_counter = self.run_one_pass_inner(i, p, counter, model, seen)?;
+            let new_counter = self.run_one_pass_inner(i, p, counter, model, seen, report)?;
             if new_counter == counter {
                 return Ok(counter);
             }
@@ -112,12 +233,15 @@ impl Optimizer {
         mut counter: usize,
         model: &mut TypedModel,
         seen: &mut HashSet<String>,
+        report: &mut OptimizerReport,
     ) -> TractResult<usize> {
+        let start = Instant::now();
+        let mut patches = vec![];
         p.reset()?;
         while let Some(mut patch) = p.next(&model)? {
             if let Some(steps) = self.steps {
                 if counter >= steps {
-                    return Ok(counter);
+                    break;
                 }
             }
             patch.push_context(format!("{:?}/{}", p, i));
@@ -136,10 +260,15 @@ impl Optimizer {
                     seen.insert(watchdog);
                 }
             }
-            debug!("applying patch #{}: {}", counter, patch.context.iter().rev().join(" >> "),);
+            let context = patch.context.iter().rev().join(" >> ");
+            debug!("applying patch #{}: {}", counter, context);
             patch.apply(model)?;
+            patches.push(context);
             counter += 1;
         }
+        let duration = start.elapsed();
+        debug!("pass {} ran in {:?}, applied {} patches", p.name(), duration, patches.len());
+        report.runs.push(PassRun { iteration: i, pass: p.name(), duration, patches });
         #[cfg(all(debug_assertions, feature = "paranoid_assertions"))]
         {
             model.check_edges().with_context(|| format!("after declutter pass {:?}", p))?;
@@ -150,3 +279,45 @@ impl Optimizer {
         Ok(counter)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ops::identity::Identity;
+
+    fn model() -> TractResult<TypedModel> {
+        let mut model = TypedModel::default();
+        let source = model.add_source("source", TypedFact::dt_shape(f32::datum_type(), &[2]))?;
+        let identity = model.wire_node("identity", Identity, &[source])?;
+        model.set_output_outlets(&identity)?;
+        Ok(model)
+    }
+
+    fn has_identity(model: &TypedModel) -> bool {
+        model.nodes().iter().any(|n| n.op_is::<Identity>())
+    }
+
+    #[test]
+    fn configure_passes() -> TractResult<()> {
+        let mut model = self::model()?;
+        Optimizer::declutter().without_pass("declutter").optimize(&mut model)?;
+        assert!(has_identity(&model));
+
+        assert!(Optimizer::declutter().with_pass_order(&["missing"]).is_err());
+        let optimizer = Optimizer::declutter().with_pass_order(&["declutter", "PropConst"])?;
+        assert_eq!(optimizer.pass_names(), vec!["declutter", "PropConst"]);
+        let report = optimizer.optimize_with_report(&mut model)?;
+        assert!(!has_identity(&model));
+        assert_eq!(report.by_pass()[0].0, "declutter");
+        assert_eq!(report.by_pass()[0].2, 1);
+        assert!(report.runs[0].patches[0].contains("identity"));
+
+        let mut model = self::model()?;
+        let optimizer = Optimizer::new().with_pass(OpOptim("declutter", TypedOp::declutter, 0));
+        optimizer.clone().max_iterations(0).optimize(&mut model)?;
+        assert!(has_identity(&model));
+        optimizer.optimize(&mut model)?;
+        assert!(!has_identity(&model));
+        Ok(())
+    }
+}
@@ -0,0 +1,384 @@
+//! Declarative graph rewriting.
+//!
+//! A `Pattern` describes a fragment of the graph from its output up to its inputs: which op
+//! runs at each node, predicates on it, and which nodes feed which. Outlets of a match can be
+//! bound to names. A name bound twice must match the same outlet, so a pattern can describe a
+//! DAG fragment and not just a tree.
+//!
+//! A `Rule` pairs a pattern with the patch replacing its matches. Rules run as an optimizer
+//! pass, `RewriteRules`, added to an `Optimizer` pipeline with `Optimizer::with_rules` or
+//! `Optimizer::with_pass`.
+use crate::internal::*;
+use crate::ops::binary::{BinMiniOp, TypedBinOp, UnaryOp};
+use crate::ops::element_wise::{ElementWiseMiniOp, ElementWiseOp};
+use std::fmt;
+use tract_itertools::Itertools;
+
+type NodePredicate = Arc<dyn Fn(&TypedModel, &TypedNode) -> bool + Send + Sync>;
+type Rewrite =
+    Arc<dyn Fn(&TypedModel, &Match) -> TractResult<Option<TypedModelPatch>> + Send + Sync>;
+
+#[derive(Clone)]
+pub struct Pattern {
+    predicate: Option<NodePredicate>,
+    inputs: Option<Vec<Pattern>>,
+    binding: Option<String>,
+}
+
+impl Pattern {
+    /// Matches any outlet, typically the inputs of the fragment.
+    pub fn any() -> Pattern {
+        Pattern { predicate: None, inputs: None, binding: None }
+    }
+
+    /// Matches the outputs of the nodes satisfying `predicate`.
+    pub fn node<F>(predicate: F) -> Pattern
+    where
+        F: Fn(&TypedModel, &TypedNode) -> bool + Send + Sync + 'static,
+    {
+        Pattern { predicate: Some(Arc::new(predicate)), inputs: None, binding: None }
+    }
+
+    /// Matches the outputs of the nodes running an op of type `O`.
+    pub fn op<O: TypedOp>() -> Pattern {
+        Self::node(|_, node| node.op_is::<O>())
+    }
+
+    /// Matches the outputs of the nodes running an op of type `O` satisfying `predicate`.
+    pub fn op_where<O, F>(predicate: F) -> Pattern
+    where
+        O: TypedOp,
+        F: Fn(&O) -> bool + Send + Sync + 'static,
+    {
+        Self::node(move |_, node| node.op_as::<O>().map(&predicate).unwrap_or(false))
+    }
+
+    /// Matches the binary operators (`TypedBinOp`) computing `M`.
+    pub fn binary<M: BinMiniOp>() -> Pattern {
+        Self::op_where(|op: &TypedBinOp| op.0.is::<M>())
+    }
+
+    /// Matches the binary operators with a constant operand (`UnaryOp`) computing `M`.
+    pub fn unary<M: BinMiniOp>() -> Pattern {
+        Self::op_where(|op: &UnaryOp| op.mini_op.is::<M>())
+    }
+
+    /// Matches the element wise operators computing `M`.
+    pub fn element_wise<M: ElementWiseMiniOp>() -> Pattern {
+        Self::op_where(|op: &ElementWiseOp| op.0.is::<M>())
+    }
+
+    /// Constrains the inputs of the node, one pattern per input.
+    pub fn with_inputs(self, inputs: impl IntoIterator<Item = Pattern>) -> Pattern {
+        Pattern { inputs: Some(inputs.into_iter().collect()), ..self }
+    }
+
+    /// Binds the matched outlet to `name`.
+    pub fn bind(self, name: impl Into<String>) -> Pattern {
+        Pattern { binding: Some(name.into()), ..self }
+    }
+
+    /// Tries and match the pattern with its output at `outlet`.
+    pub fn find(&self, model: &TypedModel, outlet: OutletId) -> Option<Match> {
+        let mut found = Match { root: outlet, outlets: HashMap::new() };
+        if self.matches(model, outlet, &mut found) {
+            Some(found)
+        } else {
+            None
+        }
+    }
+
+    fn matches(&self, model: &TypedModel, outlet: OutletId, found: &mut Match) -> bool {
+        if let Some(bound) = self.binding.as_ref().and_then(|name| found.outlets.get(name)) {
+            if *bound != outlet {
+                return false;
+            }
+        }
+        if let Some(predicate) = &self.predicate {
+            let node = model.node(outlet.node);
+            if !predicate(model, node) {
+                return false;
+            }
+            if let Some(inputs) = &self.inputs {
+                if inputs.len() != node.inputs.len()
+                    || !inputs
+                        .iter()
+                        .zip(node.inputs.iter())
+                        .all(|(p, i)| p.matches(model, *i, found))
+                {
+                    return false;
+                }
+            }
+        }
+        if let Some(name) = &self.binding {
+            found.outlets.insert(name.clone(), outlet);
+        }
+        true
+    }
+}
+
+impl fmt::Debug for Pattern {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        if self.predicate.is_some() {
+            write!(fmt, "node")?;
+        } else {
+            write!(fmt, "any")?;
+        }
+        if let Some(name) = &self.binding {
+            write!(fmt, " as {}", name)?;
+        }
+        if let Some(inputs) = &self.inputs {
+            write!(fmt, "({})", inputs.iter().map(|i| format!("{:?}", i)).join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// A match of a pattern: its output and the outlets bound to names.
+#[derive(Clone, Debug)]
+pub struct Match {
+    root: OutletId,
+    outlets: HashMap<String, OutletId>,
+}
+
+impl Match {
+    /// The output of the matched fragment.
+    pub fn root(&self) -> OutletId {
+        self.root
+    }
+
+    /// The outlet bound to `name`.
+    pub fn outlet(&self, name: &str) -> TractResult<OutletId> {
+        self.outlets.get(name).copied().with_context(|| format!("No outlet bound to {}", name))
+    }
+
+    /// The node whose output is bound to `name`.
+    pub fn node<'m>(&self, model: &'m TypedModel, name: &str) -> TractResult<&'m TypedNode> {
+        Ok(model.node(self.outlet(name)?.node))
+    }
+
+    /// Patch replacing the fragment by `op`, wired to the outlets bound to `inputs`.
+    pub fn replace_with(
+        &self,
+        model: &TypedModel,
+        op: impl Into<Box<dyn TypedOp>>,
+        inputs: &[&str],
+    ) -> TractResult<TypedModelPatch> {
+        let mut patch = TypedModelPatch::default();
+        let taps = inputs
+            .iter()
+            .map(|name| patch.tap_model(model, self.outlet(name)?))
+            .collect::<TractResult<TVec<_>>>()?;
+        let wire = patch.wire_node(&*model.node(self.root.node).name, op, &taps)?;
+        patch.shunt_outside(model, self.root, wire[0])?;
+        Ok(patch)
+    }
+}
+
+/// A pattern and the rewrite of its matches.
+#[derive(Clone)]
+pub struct Rule {
+    name: String,
+    pattern: Pattern,
+    rewrite: Rewrite,
+}
+
+impl Rule {
+    /// `rewrite` builds the patch for a match of `pattern`, or returns None to leave it alone.
+    pub fn new<F>(name: impl Into<String>, pattern: Pattern, rewrite: F) -> Rule
+    where
+        F: Fn(&TypedModel, &Match) -> TractResult<Option<TypedModelPatch>> + Send + Sync + 'static,
+    {
+        Rule { name: name.into(), pattern, rewrite: Arc::new(rewrite) }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Patch for a match of the rule with its output at one of the outputs of `node`.
+    pub fn apply(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+    ) -> TractResult<Option<TypedModelPatch>> {
+        for slot in 0..node.outputs.len() {
+            if let Some(found) = self.pattern.find(model, OutletId::new(node.id, slot)) {
+                let patch = (self.rewrite)(model, &found)
+                    .with_context(|| format!("Rewriting {} with rule {}", node, self.name))?;
+                if let Some(mut patch) = patch {
+                    patch.push_context(format!("rule {} on {}", self.name, node));
+                    if patch.dont_apply_twice.is_none() {
+                        patch.dont_apply_twice =
+                            Some(format!("rule {} on {}", self.name, node.name));
+                    }
+                    return Ok(Some(patch));
+                }
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl fmt::Debug for Rule {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}: {:?}", self.name, self.pattern)
+    }
+}
+
+/// Optimizer pass running a set of rules over the graph.
+#[derive(Clone, Debug)]
+pub struct RewriteRules {
+    rules: Vec<Rule>,
+    cursor: usize,
+}
+
+impl RewriteRules {
+    pub fn new(rules: Vec<Rule>) -> RewriteRules {
+        RewriteRules { rules, cursor: 0 }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+}
+
+impl super::TypedPass for RewriteRules {
+    fn name(&self) -> String {
+        "rules".to_string()
+    }
+
+    fn reset(&mut self) -> TractResult<()> {
+        self.cursor = 0;
+        Ok(())
+    }
+
+    fn next(&mut self, model: &TypedModel) -> TractResult<Option<TypedModelPatch>> {
+        for (ix, &id) in model.eval_order()?.iter().enumerate().skip(self.cursor) {
+            let node = model.node(id);
+            for rule in &self.rules {
+                if let Some(patch) = rule.apply(model, node)? {
+                    self.cursor = ix + patch.dont_apply_twice.is_some() as usize;
+                    return Ok(Some(patch));
+                }
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ops::math;
+    use crate::optim::Optimizer;
+
+    #[derive(Debug, Clone, Default, Hash)]
+    struct Marker;
+
+    impl Op for Marker {
+        fn name(&self) -> Cow<str> {
+            "Marker".into()
+        }
+
+        op_core_mir!();
+        op_as_typed_op!();
+    }
+
+    impl_dyn_hash!(Marker);
+
+    impl EvalOp for Marker {
+        fn is_stateless(&self) -> bool {
+            true
+        }
+
+        fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+            Ok(inputs)
+        }
+    }
+
+    impl TypedOp for Marker {
+        fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+            Ok(tvec!(inputs[0].clone()))
+        }
+
+        as_op!();
+    }
+
+    fn x_plus_tanh_x() -> Pattern {
+        let x = || Pattern::any().bind("x");
+        Pattern::binary::<math::Add>()
+            .with_inputs(vec![x(), Pattern::element_wise::<math::Tanh>().with_inputs(vec![x()])])
+    }
+
+    #[test]
+    fn match_dag_fragment() -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let fact = TypedFact::dt_shape(f32::datum_type(), &[2]);
+        let a = model.add_source("a", fact.clone())?;
+        let b = model.add_source("b", fact)?;
+        let tanh_a = model.wire_node("tanh_a", math::tanh(), &[a])?[0];
+        let tanh_b = model.wire_node("tanh_b", math::tanh(), &[b])?[0];
+        let same = model.wire_node("same", math::add::bin_typed(), &[a, tanh_a])?[0];
+        let other = model.wire_node("other", math::add::bin_typed(), &[a, tanh_b])?[0];
+        model.set_output_outlets(&[same, other])?;
+
+        let found = x_plus_tanh_x().find(&model, same).context("Expected a match")?;
+        assert_eq!(found.root(), same);
+        assert_eq!(found.outlet("x")?, a);
+        assert!(x_plus_tanh_x().find(&model, other).is_none());
+
+        let rule = Rule::new("x+tanh(x)", x_plus_tanh_x(), |model, found| {
+            Ok(Some(found.replace_with(model, Marker, &["x"])?))
+        });
+        let patch = rule.apply(&model, model.node(same.node))?.context("Expected a patch")?;
+        patch.apply(&mut model)?;
+        model.compact()?;
+        assert_eq!(model.nodes().iter().filter(|n| n.op_is::<Marker>()).count(), 1);
+        assert_eq!(model.node(model.output_outlets()?[0].node).op().name(), "Marker");
+        Ok(())
+    }
+
+    #[test]
+    fn rewrite_into_itself_terminates() -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let source = model.add_source("source", TypedFact::dt_shape(f32::datum_type(), &[2]))?;
+        let tanh = model.wire_node("tanh", math::tanh(), &[source])?;
+        model.set_output_outlets(&tanh)?;
+        let rule = Rule::new(
+            "tanh to tanh",
+            Pattern::element_wise::<math::Tanh>().with_inputs(vec![Pattern::any().bind("x")]),
+            |model, found| Ok(Some(found.replace_with(model, math::tanh(), &["x"])?)),
+        );
+        let report = Optimizer::new()
+            .with_pass(RewriteRules::new(vec![rule]))
+            .optimize_with_report(&mut model)?;
+        assert_eq!(report.by_pass()[0].2, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn rules_run_in_declutter() -> TractResult<()> {
+        let rule = Rule::new(
+            "drop marker",
+            Pattern::op::<Marker>().with_inputs(vec![Pattern::any().bind("x")]),
+            |model, found| {
+                let mut patch = TypedModelPatch::default();
+                let x = patch.tap_model(model, found.outlet("x")?)?;
+                patch.shunt_outside(model, found.root(), x)?;
+                Ok(Some(patch))
+            },
+        );
+        let mut model = TypedModel::default();
+        let source = model.add_source("source", TypedFact::dt_shape(f32::datum_type(), &[2]))?;
+        let marker = model.wire_node("marker", Marker, &[source])?;
+        let tanh = model.wire_node("tanh", math::tanh(), &marker)?;
+        model.set_output_outlets(&tanh)?;
+        model.declutter()?;
+        assert!(model.nodes().iter().any(|n| n.op_is::<Marker>()));
+        Optimizer::declutter().with_rules(vec![rule]).optimize(&mut model)?;
+        assert!(model.nodes().iter().all(|n| !n.op_is::<Marker>()));
+        assert_eq!(model.nodes().len(), 2);
+        Ok(())
+    }
+}
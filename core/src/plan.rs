@@ -6,6 +6,11 @@ use crate::internal::*;
 use crate::model::order::eval_order_for_nodes;
 use crate::model::{Fact, Graph, OutletId};
 
+mod cache;
+mod snapshot;
+
+pub use self::cache::PlanCache;
+
 #[derive(Default)]
 pub struct SessionState {
     pub inputs: HashMap<usize, Arc<Tensor>>,
@@ -120,6 +125,15 @@ where
     }
 }
 
+/// Deduce symbol values from a dimension expression and its actual value.
+pub(crate) fn resolve(symbols: &mut SymbolValues, expected: &TDim, provided: i64) {
+    match expected {
+        TDim::Sym(s) => symbols[s.clone()] = Some(provided),
+        TDim::MulInt(x, expr) => resolve(symbols, expr, provided / *x),
+        _ => (),
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct SimpleState<F, O, M, P>
 where
@@ -173,6 +187,57 @@ where
         Ok(())
     }
 
+    /// Serialize the op states, like the buffers a pulsed model keeps between pulses, and the
+    /// session data they rely on (resolved symbols, session tensors), as a versioned blob tied
+    /// to the model signature (`Graph::signature`).
+    ///
+    /// Fails if a stateful op does not support saving its state.
+    pub fn save_states(&self) -> TractResult<Vec<u8>> {
+        let mut saved = snapshot::Snapshot::default();
+        for (symbol, value) in self.session_state.resolved_symbols.iter() {
+            saved.symbols.push((symbol.name().to_string(), value));
+        }
+        saved.symbols.sort();
+        for (name, tensor) in &self.session_state.tensors {
+            saved.tensors.push((name.clone(), tensor.clone()));
+        }
+        saved.tensors.sort_by(|a, b| a.0.cmp(&b.0));
+        for (id, state) in self.states.iter().enumerate() {
+            if let Some(state) = state {
+                let tensors = state
+                    .save()
+                    .with_context(|| format!("Saving state of {}", self.model().node(id)))?;
+                if tensors.len() > 0 {
+                    saved.states.push((id, tensors));
+                }
+            }
+        }
+        snapshot::write_states(self.model().signature(), &saved)
+    }
+
+    /// Rebuild the op states and the session data from a blob produced by `save_states` on the
+    /// same model.
+    pub fn restore_states(&mut self, bytes: &[u8]) -> TractResult<()> {
+        let saved = snapshot::read_states(self.model().signature(), bytes)?;
+        self.reset_op_states()?;
+        let scope = self.model().symbol_scope.clone();
+        self.session_state.resolved_symbols = SymbolValues::default();
+        for (name, value) in saved.symbols {
+            self.session_state.resolved_symbols[scope.sym(&name)] = Some(value);
+        }
+        self.session_state.tensors.extend(saved.tensors);
+        for (id, tensors) in saved.states {
+            let node = self.model().nodes().get(id).map(|n| n.to_string());
+            let node = node.with_context(|| format!("Saved state for missing node #{}", id))?;
+            self.states[id]
+                .as_mut()
+                .with_context(|| format!("Saved state for stateless node {}", node))?
+                .restore(tensors)
+                .with_context(|| format!("Restoring state of {}", node))?;
+        }
+        Ok(())
+    }
+
     pub fn run(&mut self, inputs: TVec<Tensor>) -> TractResult<TVec<Arc<Tensor>>> {
         self.run_plan_with_eval(inputs, self::eval)
     }
@@ -252,7 +317,7 @@ where
                     for (o, v) in node.outputs.iter().zip(vs.iter()) {
                         if let Ok(f) = o.fact.to_typed_fact() {
                             for (dim_abstract, dim_concrete) in f.shape.iter().zip(v.shape()) {
-                                Self::resolve(
+                                resolve(
                                     &mut session_state.resolved_symbols,
                                     &dim_abstract,
                                     *dim_concrete as i64,
@@ -305,14 +370,6 @@ where
         Ok(())
     }
 
-    fn resolve(symbols: &mut SymbolValues, expected: &TDim, provided: i64) {
-        match expected {
-            TDim::Sym(s) => symbols[*s] = Some(provided),
-            TDim::MulInt(x, expr) => Self::resolve(symbols, expr, provided / *x),
-            _ => (),
-        }
-    }
-
     pub fn set_input(&mut self, input: usize, t: Tensor) -> TractResult<()> {
         let outlet: OutletId = *self
             .model()
@@ -324,8 +381,12 @@ where
         let model = plan.model.borrow();
         if let Ok(fact) = model.outlet_fact(outlet)?.to_typed_fact() {
             for (expected, provided) in fact.shape.iter().zip(t.shape()) {
-                Self::resolve(&mut session_state.resolved_symbols, &expected, *provided as i64)
+                resolve(&mut session_state.resolved_symbols, &expected, *provided as i64)
             }
+            model
+                .symbol_scope
+                .check(&session_state.resolved_symbols)
+                .with_context(|| format!("Setting input {}", input))?;
         }
         self.plan
             .borrow()
@@ -470,3 +531,132 @@ where
     .with_context(|| format!("Evaluating {}", node));
     r
 }
+
+#[cfg(test)]
+mod test {
+    use crate::internal::*;
+
+    #[test]
+    fn set_input_checks_assertions() -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let n = model.symbol_scope.sym("n");
+        model.symbol_scope.add_assertion(model.symbol_scope.parse_assertion("(n)%2==0")?)?;
+        let source = model.add_source("source", TypedFact::dt_shape(f32::datum_type(), &[n]))?;
+        model.set_output_outlets(&[source])?;
+        let runnable = model.into_runnable()?;
+        runnable.run(tvec!(tensor1(&[0f32, 1.])))?;
+        assert!(runnable.run(tvec!(tensor1(&[0f32, 1., 2.]))).is_err());
+        Ok(())
+    }
+
+    /// Sums its inputs in a session tensor (like a tensorflow variable) if `in_session`, or
+    /// in its own state, which it can not save.
+    #[derive(Debug, Clone, Hash)]
+    struct Accumulate {
+        in_session: bool,
+    }
+    impl_dyn_hash!(Accumulate);
+
+    impl Op for Accumulate {
+        fn name(&self) -> Cow<str> {
+            "Accumulate".into()
+        }
+        op_core_mir!();
+        op_as_typed_op!();
+    }
+
+    impl EvalOp for Accumulate {
+        fn is_stateless(&self) -> bool {
+            false
+        }
+
+        fn state(
+            &self,
+            session: &mut SessionState,
+            _node_id: usize,
+        ) -> TractResult<Option<Box<dyn OpState>>> {
+            if self.in_session {
+                session.tensors.insert("acc".to_string(), tensor0(0f32));
+                Ok(Some(Box::new(InSession)))
+            } else {
+                Ok(Some(Box::new(InState(0.0))))
+            }
+        }
+    }
+
+    #[derive(Debug, Clone)]
+    struct InSession;
+
+    impl OpState for InSession {
+        fn eval(
+            &mut self,
+            session: &mut SessionState,
+            _op: &dyn Op,
+            inputs: TVec<Arc<Tensor>>,
+        ) -> TractResult<TVec<Arc<Tensor>>> {
+            let acc = session.tensors.get_mut("acc").unwrap().to_scalar_mut::<f32>()?;
+            *acc += inputs[0].as_slice::<f32>()?.iter().sum::<f32>();
+            Ok(tvec!(rctensor0(*acc)))
+        }
+
+        fn save(&self) -> TractResult<TVec<Tensor>> {
+            Ok(tvec!())
+        }
+    }
+
+    #[derive(Debug, Clone)]
+    struct InState(f32);
+
+    impl OpState for InState {
+        fn eval(
+            &mut self,
+            _session: &mut SessionState,
+            _op: &dyn Op,
+            inputs: TVec<Arc<Tensor>>,
+        ) -> TractResult<TVec<Arc<Tensor>>> {
+            self.0 += inputs[0].as_slice::<f32>()?.iter().sum::<f32>();
+            Ok(tvec!(rctensor0(self.0)))
+        }
+    }
+
+    impl TypedOp for Accumulate {
+        as_op!();
+
+        fn output_facts(&self, _inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+            Ok(tvec!(TypedFact::dt_shape(f32::datum_type(), &[0usize; 0])))
+        }
+    }
+
+    fn accumulator(in_session: bool) -> TractResult<TypedRunnableModel<TypedModel>> {
+        let mut model = TypedModel::default();
+        let n = model.symbol_scope.sym("n");
+        let source = model.add_source("source", TypedFact::dt_shape(f32::datum_type(), &[n]))?;
+        let acc = model.wire_node("acc", Accumulate { in_session }, &[source])?;
+        model.set_output_outlets(&acc)?;
+        model.into_runnable()
+    }
+
+    #[test]
+    fn save_and_restore_session() -> TractResult<()> {
+        let plan = accumulator(true)?;
+        let mut state = TypedSimpleState::new(&plan)?;
+        state.run(tvec!(tensor1(&[1f32, 2.])))?;
+        let saved = state.save_states()?;
+        let mut restored = TypedSimpleState::new(&plan)?;
+        restored.restore_states(&saved)?;
+        let n = restored.model().symbol_scope.sym("n");
+        assert_eq!(restored.session_state.resolved_symbols[n], Some(2));
+        let input = tvec!(tensor1(&[4f32]));
+        assert_eq!(restored.run(input.clone())?, state.run(input)?);
+        Ok(())
+    }
+
+    #[test]
+    fn save_fails_on_states_without_save() -> TractResult<()> {
+        let plan = accumulator(false)?;
+        let mut state = TypedSimpleState::new(&plan)?;
+        state.run(tvec!(tensor1(&[1f32, 2.])))?;
+        assert!(state.save_states().is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,152 @@
+use std::sync::Mutex;
+
+use crate::internal::*;
+
+/// Optimized plans of a symbolic model, one for each binding of its symbols.
+///
+/// Plans are built on demand by concretizing and optimizing the model. Only the `capacity`
+/// most recently used plans are kept.
+#[derive(Debug)]
+pub struct PlanCache {
+    model: TypedModel,
+    symbols: Vec<Symbol>,
+    capacity: usize,
+    plans: Mutex<Vec<(Vec<i64>, Arc<TypedSimplePlan<TypedModel>>)>>,
+}
+
+impl PlanCache {
+    /// A cache for a typed model, preferably decluttered, keeping up to 8 plans.
+    ///
+    /// Plans are keyed by the symbols of the inputs shapes: the others follow from them.
+    pub fn new(model: TypedModel) -> PlanCache {
+        let mut symbols = std::collections::HashSet::new();
+        for input in &model.inputs {
+            let fact = &model.nodes[input.node].outputs[input.slot].fact;
+            symbols.extend(fact.shape.iter().flat_map(|d| d.symbols()));
+        }
+        let mut symbols: Vec<Symbol> = symbols.into_iter().collect();
+        symbols.sort();
+        PlanCache { model, symbols, capacity: 8, plans: Mutex::new(vec![]) }
+    }
+
+    /// Keep up to `capacity` plans.
+    pub fn with_capacity(self, capacity: usize) -> PlanCache {
+        PlanCache { capacity: capacity.max(1), ..self }
+    }
+
+    /// The symbolic model.
+    pub fn model(&self) -> &TypedModel {
+        &self.model
+    }
+
+    /// The optimized plan for these symbol values, built if it is not in the cache.
+    ///
+    /// Only the values of the input symbols are used: the plan is built from them alone, so it
+    /// is the same for every binding sharing them.
+    pub fn plan(&self, values: &SymbolValues) -> TractResult<Arc<TypedSimplePlan<TypedModel>>> {
+        let key = self
+            .symbols
+            .iter()
+            .map(|s| values[s.clone()].with_context(|| format!("No value for symbol {}", s)))
+            .collect::<TractResult<Vec<i64>>>()?;
+        if let Some(plan) = Self::lookup(&mut self.plans.lock().unwrap(), &key) {
+            return Ok(plan);
+        }
+        let mut bound = SymbolValues::default();
+        for (symbol, value) in self.symbols.iter().zip(&key) {
+            bound[symbol.clone()] = Some(*value);
+        }
+        self.model.symbol_scope.check(&bound)?;
+        let model = self
+            .model
+            .concretize_dims(&bound)
+            .and_then(|m| m.into_optimized())
+            .with_context(|| format!("Building plan for {:?}", bound))?;
+        let plan = Arc::new(SimplePlan::new(model)?);
+        let mut plans = self.plans.lock().unwrap();
+        // another thread may have built the same plan in the meantime
+        if let Some(plan) = Self::lookup(&mut plans, &key) {
+            return Ok(plan);
+        }
+        plans.push((key, plan.clone()));
+        if plans.len() > self.capacity {
+            plans.remove(0);
+        }
+        Ok(plan)
+    }
+
+    /// Find the plan for `key`, and mark it as the most recently used.
+    fn lookup(
+        plans: &mut Vec<(Vec<i64>, Arc<TypedSimplePlan<TypedModel>>)>,
+        key: &[i64],
+    ) -> Option<Arc<TypedSimplePlan<TypedModel>>> {
+        let ix = plans.iter().position(|(k, _)| k == key)?;
+        let hit = plans.remove(ix);
+        let plan = hit.1.clone();
+        plans.push(hit);
+        Some(plan)
+    }
+
+    /// Run the plan matching the inputs shapes.
+    pub fn run(&self, inputs: TVec<Tensor>) -> TractResult<TVec<Arc<Tensor>>> {
+        let mut values = SymbolValues::default();
+        for (ix, input) in inputs.iter().enumerate() {
+            let fact = self.model.input_fact(ix)?;
+            for (expected, provided) in fact.shape.iter().zip(input.shape()) {
+                super::resolve(&mut values, &expected, *provided as i64);
+            }
+        }
+        self.plan(&values)?.run(inputs)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ops::math;
+
+    #[test]
+    fn plans_by_binding() -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let n = model.symbol_scope.sym("n");
+        let shape = [TDim::from(&n), 2.into()];
+        let source = model.add_source("source", TypedFact::dt_shape(f32::datum_type(), &shape))?;
+        let mul = model.wire_node("mul", math::mul::unary(rctensor2(&[[2f32]])), &[source])?;
+        model.set_output_outlets(&mul)?;
+        let cache = PlanCache::new(model).with_capacity(1);
+
+        let result = cache.run(tvec!(tensor2(&[[1f32, 2.]])))?;
+        assert_eq!(*result[0], tensor2(&[[2f32, 4.]]));
+        let result = cache.run(tvec!(tensor2(&[[1f32, 2.], [3., 4.]])))?;
+        assert_eq!(*result[0], tensor2(&[[2f32, 4.], [6., 8.]]));
+
+        let two = SymbolValues::default().with(n.clone(), 2);
+        let plan = cache.plan(&two)?;
+        assert_eq!(plan.model().input_fact(0)?.shape.as_concrete(), Some(&[2usize, 2][..]));
+        assert!(Arc::ptr_eq(&plan, &cache.plan(&two)?));
+        cache.plan(&SymbolValues::default().with(n.clone(), 3))?;
+        assert!(!Arc::ptr_eq(&plan, &cache.plan(&two)?));
+        assert!(cache.plan(&SymbolValues::default()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn plans_ignore_other_symbols() -> TractResult<()> {
+        use crate::ops::array::MultiBroadcastTo;
+        let mut model = TypedModel::default();
+        let n = model.symbol_scope.sym("n");
+        let m = model.symbol_scope.sym("m");
+        let shape = [TDim::from(&n), 1.into()];
+        let source = model.add_source("source", TypedFact::dt_shape(f32::datum_type(), &shape))?;
+        let shape = ShapeFact::from_dims(&[TDim::from(&n), TDim::from(&m)]);
+        let broadcast = model.wire_node("broadcast", MultiBroadcastTo::new(shape), &[source])?;
+        model.set_output_outlets(&broadcast)?;
+        let cache = PlanCache::new(model);
+
+        let plan = cache.plan(&SymbolValues::default().with(n.clone(), 2).with(m.clone(), 3))?;
+        assert_eq!(plan.model().output_fact(0)?.shape.to_tvec(), tvec!(2.into(), m.to_dim()));
+        let other = cache.plan(&SymbolValues::default().with(n, 2).with(m, 4))?;
+        assert!(Arc::ptr_eq(&plan, &other));
+        Ok(())
+    }
+}
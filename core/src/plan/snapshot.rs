@@ -0,0 +1,204 @@
+//! Byte format of the op states saved by `SimpleState::save_states`.
+//!
+//! All integers are little endian: the magic `TRST`, the format version (u32), the model
+//! signature (u64), the number of resolved symbols (u32) and for each its name and value (i64),
+//! the number of session tensors (u32) and for each its name and the tensor, then the number of
+//! saved states (u32) and for each state its node id (u32), the number of tensors (u32) and the
+//! tensors.
+//!
+//! A name is its length (u32), then utf-8. A tensor is its datum type name (u8 length, then
+//! utf-8), its quantization parameters (u8 tag, then the parameters), its rank (u32), its
+//! dimensions (u64) and its raw data.
+use crate::internal::*;
+
+const MAGIC: &[u8; 4] = b"TRST";
+const VERSION: u32 = 2;
+
+/// The session data the op states rely on (resolved symbols, session tensors like variables),
+/// and what each stateful node saved.
+#[derive(Debug, Default, PartialEq)]
+pub(super) struct Snapshot {
+    pub symbols: Vec<(String, i64)>,
+    pub tensors: Vec<(String, Tensor)>,
+    pub states: Vec<(usize, TVec<Tensor>)>,
+}
+
+pub(super) fn write_states(signature: u64, snapshot: &Snapshot) -> TractResult<Vec<u8>> {
+    let mut bytes = MAGIC.to_vec();
+    bytes.extend(VERSION.to_le_bytes().iter());
+    bytes.extend(signature.to_le_bytes().iter());
+    bytes.extend((snapshot.symbols.len() as u32).to_le_bytes().iter());
+    for (name, value) in &snapshot.symbols {
+        write_name(&mut bytes, name);
+        bytes.extend(value.to_le_bytes().iter());
+    }
+    bytes.extend((snapshot.tensors.len() as u32).to_le_bytes().iter());
+    for (name, t) in &snapshot.tensors {
+        write_name(&mut bytes, name);
+        write_tensor(&mut bytes, t).with_context(|| format!("Saving {}", name))?;
+    }
+    bytes.extend((snapshot.states.len() as u32).to_le_bytes().iter());
+    for (node, tensors) in &snapshot.states {
+        bytes.extend((*node as u32).to_le_bytes().iter());
+        bytes.extend((tensors.len() as u32).to_le_bytes().iter());
+        for t in tensors {
+            write_tensor(&mut bytes, t).with_context(|| format!("Saving {:?}", t))?;
+        }
+    }
+    Ok(bytes)
+}
+
+pub(super) fn read_states(signature: u64, bytes: &[u8]) -> TractResult<Snapshot> {
+    let mut reader = Reader(bytes);
+    ensure!(reader.bytes(4)? == MAGIC, "Not a tract state");
+    let version = reader.u32()?;
+    ensure!(version == VERSION, "Unsupported state format version {}", version);
+    let found = reader.u64()?;
+    ensure!(
+        found == signature,
+        "State was saved from a different model (signature {:x}, expected {:x})",
+        found,
+        signature
+    );
+    let mut snapshot = Snapshot::default();
+    for _ in 0..reader.u32()? {
+        snapshot.symbols.push((reader.name()?, reader.u64()? as i64));
+    }
+    for _ in 0..reader.u32()? {
+        snapshot.tensors.push((reader.name()?, reader.tensor()?));
+    }
+    for _ in 0..reader.u32()? {
+        let node = reader.u32()? as usize;
+        let tensors = (0..reader.u32()?).map(|_| reader.tensor()).collect::<TractResult<_>>()?;
+        snapshot.states.push((node, tensors));
+    }
+    ensure!(reader.0.is_empty(), "Trailing bytes after saved states");
+    Ok(snapshot)
+}
+
+fn write_name(bytes: &mut Vec<u8>, name: &str) {
+    bytes.extend((name.len() as u32).to_le_bytes().iter());
+    bytes.extend(name.as_bytes());
+}
+
+fn write_tensor(bytes: &mut Vec<u8>, t: &Tensor) -> TractResult<()> {
+    let dt = t.datum_type();
+    ensure!(dt.is_copy(), "Only plain data tensors can be saved");
+    let name = format!("{:?}", dt.unquantized());
+    bytes.push(name.len() as u8);
+    bytes.extend(name.as_bytes());
+    match dt.qparams() {
+        None => bytes.push(0),
+        Some(QParams::MinMax { min, max }) => {
+            bytes.push(1);
+            bytes.extend(min.to_le_bytes().iter());
+            bytes.extend(max.to_le_bytes().iter());
+        }
+        Some(QParams::ZpScale { zero_point, scale }) => {
+            bytes.push(2);
+            bytes.extend(zero_point.to_le_bytes().iter());
+            bytes.extend(scale.to_le_bytes().iter());
+        }
+        Some(QParams::PerAxis { axis, zero_points, scales }) => {
+            bytes.push(3);
+            bytes.extend((axis as u32).to_le_bytes().iter());
+            bytes.extend((zero_points.len() as u32).to_le_bytes().iter());
+            zero_points.iter().for_each(|zp| bytes.extend(zp.to_le_bytes().iter()));
+            scales.iter().for_each(|s| bytes.extend(s.to_le_bytes().iter()));
+        }
+    }
+    bytes.extend((t.rank() as u32).to_le_bytes().iter());
+    for d in t.shape() {
+        bytes.extend((*d as u64).to_le_bytes().iter());
+    }
+    bytes.extend(unsafe { t.as_bytes() });
+    Ok(())
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, len: usize) -> TractResult<&'a [u8]> {
+        ensure!(self.0.len() >= len, "Truncated state");
+        let (bytes, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> TractResult<u8> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u32(&mut self) -> TractResult<u32> {
+        let b = self.bytes(4)?;
+        Ok(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn u64(&mut self) -> TractResult<u64> {
+        let b = self.bytes(8)?;
+        Ok(u64::from_le_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]))
+    }
+
+    fn name(&mut self) -> TractResult<String> {
+        let len = self.u32()? as usize;
+        Ok(std::str::from_utf8(self.bytes(len)?)?.to_string())
+    }
+
+    fn f32(&mut self) -> TractResult<f32> {
+        Ok(f32::from_bits(self.u32()?))
+    }
+
+    fn tensor(&mut self) -> TractResult<Tensor> {
+        let name_len = self.u8()? as usize;
+        let name = std::str::from_utf8(self.bytes(name_len)?)?;
+        let dt: DatumType = name.parse()?;
+        let qparams = match self.u8()? {
+            0 => None,
+            1 => Some(QParams::MinMax { min: self.f32()?, max: self.f32()? }),
+            2 => Some(QParams::ZpScale { zero_point: self.u32()? as i32, scale: self.f32()? }),
+            3 => {
+                let axis = self.u32()? as usize;
+                let len = self.u32()? as usize;
+                let zero_points =
+                    (0..len).map(|_| Ok(self.u32()? as i32)).collect::<TractResult<Vec<_>>>()?;
+                let scales = (0..len).map(|_| self.f32()).collect::<TractResult<Vec<_>>>()?;
+                Some(QParams::per_axis(axis, &zero_points, &scales))
+            }
+            tag => bail!("Invalid quantization tag {}", tag),
+        };
+        let dt = match (dt.clone(), qparams) {
+            (_, None) => dt,
+            (DatumType::I8, Some(q)) => DatumType::QI8(q),
+            (DatumType::U8, Some(q)) => DatumType::QU8(q),
+            _ => bail!("Quantization parameters for {:?}", dt),
+        };
+        let rank = self.u32()? as usize;
+        let shape =
+            (0..rank).map(|_| Ok(self.u64()? as usize)).collect::<TractResult<TVec<_>>>()?;
+        let len = shape.iter().product::<usize>() * dt.size_of();
+        unsafe { Tensor::from_raw_dt(dt, &shape, self.bytes(len)?) }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn round_trip() -> TractResult<()> {
+        let qi8 = DatumType::QI8(QParams::ZpScale { zero_point: 3, scale: 0.5 });
+        let states = Snapshot {
+            symbols: vec![("S".to_string(), 12), ("batch_size".to_string(), -1)],
+            tensors: vec![("var".to_string(), tensor1(&[1f32, 2.0]))],
+            states: vec![
+                (1, tvec!(tensor0(12i64), tensor2(&[[1f32, 2.0], [3.0, 4.0]]))),
+                (4, tvec!(tensor1(&[1i8, -2, 3]).cast_to_dt(qi8)?.into_owned())),
+            ],
+        };
+        let bytes = write_states(42, &states)?;
+        assert_eq!(read_states(42, &bytes)?, states);
+        assert!(read_states(43, &bytes).is_err());
+        assert!(read_states(42, &bytes[..bytes.len() - 1]).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,258 @@
+use crate::internal::*;
+use crate::ops::konst::Const;
+
+const BINS: usize = 2048;
+const QUANTIZED_BINS: usize = 128;
+
+/// How activation ranges are picked from the values seen during calibration.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CalibrationMethod {
+    /// Extreme values.
+    MinMax,
+    /// Keep this percentage of the values (for instance 99.99), clipping the rest evenly on
+    /// both sides.
+    Percentile(f32),
+    /// Clip the absolute values where the KL divergence between the float and the 8-bit
+    /// quantized distributions is the smallest.
+    Entropy,
+}
+
+/// Range of the values of an activation.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ActivationRange {
+    pub min: f32,
+    pub max: f32,
+}
+
+impl ActivationRange {
+    fn union(self, other: ActivationRange) -> ActivationRange {
+        ActivationRange { min: self.min.min(other.min), max: self.max.max(other.max) }
+    }
+
+    fn of(values: &[f32]) -> Option<ActivationRange> {
+        let mut values = values.iter().filter(|v| v.is_finite());
+        let first = *values.next()?;
+        Some(values.fold(ActivationRange { min: first, max: first }, |r, &v| ActivationRange {
+            min: r.min.min(v),
+            max: r.max.max(v),
+        }))
+    }
+
+    /// Union of the ranges, or None if any of them is missing.
+    pub fn union_of(ranges: impl IntoIterator<Item = Option<ActivationRange>>) -> Option<Self> {
+        let mut ranges = ranges.into_iter();
+        let first = ranges.next()??;
+        ranges.try_fold(first, |acc, r| Some(acc.union(r?)))
+    }
+
+    /// Asymmetric i8 quantization parameters. The range is extended to contain zero so that
+    /// zero is exactly representable.
+    pub fn qparams(&self) -> QParams {
+        let min = self.min.min(0.0);
+        let max = self.max.max(0.0);
+        let scale = if max > min { (max - min) / 255.0 } else { 1.0 };
+        let zero_point = (-128.0 - min / scale).round().max(-128.0).min(127.0) as i32;
+        QParams::ZpScale { zero_point, scale }
+    }
+}
+
+struct Histogram {
+    low: f32,
+    high: f32,
+    abs: bool,
+    bins: Vec<u64>,
+}
+
+impl Histogram {
+    fn new(range: ActivationRange, abs: bool) -> Histogram {
+        let (low, high) =
+            if abs { (0.0, range.min.abs().max(range.max.abs())) } else { (range.min, range.max) };
+        Histogram { low, high, abs, bins: vec![0; BINS] }
+    }
+
+    fn width(&self) -> f32 {
+        (self.high - self.low) / BINS as f32
+    }
+
+    fn add(&mut self, values: &[f32]) {
+        let width = self.width();
+        for v in values.iter().filter(|v| v.is_finite()) {
+            let v = if self.abs { v.abs() } else { *v };
+            let bin = if width > 0.0 { ((v - self.low) / width) as usize } else { 0 };
+            self.bins[bin.min(BINS - 1)] += 1;
+        }
+    }
+
+    fn percentile(&self, percentile: f32) -> ActivationRange {
+        let total: u64 = self.bins.iter().sum();
+        let clipped = (total as f64 * (100.0 - percentile as f64).max(0.0) / 200.0) as u64;
+        let mut seen = 0;
+        let low_bin = self.bins.iter().position(|&b| {
+            seen += b;
+            seen > clipped
+        });
+        seen = 0;
+        let high_bin = self.bins.iter().rposition(|&b| {
+            seen += b;
+            seen > clipped
+        });
+        let width = self.width();
+        ActivationRange {
+            min: self.low + low_bin.unwrap_or(0) as f32 * width,
+            max: self.low + (high_bin.unwrap_or(BINS - 1) + 1) as f32 * width,
+        }
+    }
+
+    /// Threshold on absolute values minimizing the KL divergence (TensorRT algorithm).
+    fn entropy_threshold(&self) -> f32 {
+        let mut best = (f64::INFINITY, BINS);
+        for i in QUANTIZED_BINS..=BINS {
+            let mut p: Vec<f64> = self.bins[..i].iter().map(|&b| b as f64).collect();
+            p[i - 1] += self.bins[i..].iter().sum::<u64>() as f64;
+            let mut q = vec![0f64; i];
+            for j in 0..QUANTIZED_BINS {
+                let start = j * i / QUANTIZED_BINS;
+                let end = (j + 1) * i / QUANTIZED_BINS;
+                let chunk = &self.bins[start..end];
+                let nonzero = chunk.iter().filter(|&&b| b != 0).count();
+                if nonzero == 0 {
+                    continue;
+                }
+                let mean = chunk.iter().sum::<u64>() as f64 / nonzero as f64;
+                for k in start..end {
+                    if self.bins[k] != 0 {
+                        q[k] = mean;
+                    }
+                }
+            }
+            let p_sum: f64 = p.iter().sum();
+            let q_sum: f64 = q.iter().sum();
+            if p_sum == 0.0 || q_sum == 0.0 {
+                continue;
+            }
+            let kl: f64 = p
+                .iter()
+                .zip(q.iter())
+                .filter(|(p, _)| **p > 0.0)
+                .map(|(p, q)| {
+                    let p = p / p_sum;
+                    let q = (q / q_sum).max(f64::EPSILON);
+                    p * (p / q).ln()
+                })
+                .sum();
+            if kl < best.0 {
+                best = (kl, i);
+            }
+        }
+        self.low + best.1 as f32 * self.width()
+    }
+}
+
+/// Run the model on all the samples, calling `f` for every f32 value it computes (constants
+/// excluded).
+fn observe(
+    model: &TypedModel,
+    dataset: &[TVec<Tensor>],
+    mut f: impl FnMut(OutletId, &[f32]),
+) -> TractResult<()> {
+    let plan = SimplePlan::new(model)?;
+    let mut state = SimpleState::new(&plan)?;
+    for (ix, sample) in dataset.iter().enumerate() {
+        state
+            .run_plan_with_eval(sample.clone(), |session, op_state, node, inputs| {
+                let outputs = crate::plan::eval(session, op_state, node, inputs)?;
+                if !node.op_is::<Const>() {
+                    for (slot, output) in outputs.iter().enumerate() {
+                        if output.datum_type() == f32::datum_type() {
+                            f(OutletId::new(node.id, slot), output.as_slice::<f32>()?);
+                        }
+                    }
+                }
+                Ok::<_, TractError>(outputs)
+            })
+            .with_context(|| format!("Running calibration sample #{}", ix))?;
+    }
+    Ok(())
+}
+
+/// Run the model on a calibration dataset (one input vector per sample) and pick a range for
+/// all the f32 values it computes.
+pub fn calibrate(
+    model: &TypedModel,
+    dataset: &[TVec<Tensor>],
+    method: CalibrationMethod,
+) -> TractResult<HashMap<OutletId, ActivationRange>> {
+    if dataset.is_empty() {
+        bail!("Calibration requires at least one sample")
+    }
+    let mut ranges: HashMap<OutletId, ActivationRange> = HashMap::new();
+    observe(model, dataset, |outlet, values| {
+        if let Some(range) = ActivationRange::of(values) {
+            let range = ranges.get(&outlet).map(|r| r.union(range)).unwrap_or(range);
+            ranges.insert(outlet, range);
+        }
+    })?;
+    if method == CalibrationMethod::MinMax {
+        return Ok(ranges);
+    }
+    let abs = method == CalibrationMethod::Entropy;
+    let mut histograms: HashMap<OutletId, Histogram> =
+        ranges.iter().map(|(outlet, range)| (*outlet, Histogram::new(*range, abs))).collect();
+    observe(model, dataset, |outlet, values| {
+        if let Some(histogram) = histograms.get_mut(&outlet) {
+            histogram.add(values)
+        }
+    })?;
+    for (outlet, range) in ranges.iter_mut() {
+        let histogram = &histograms[outlet];
+        *range = match method {
+            CalibrationMethod::Percentile(p) => histogram.percentile(p),
+            CalibrationMethod::Entropy => {
+                let threshold = histogram.entropy_threshold();
+                ActivationRange { min: range.min.max(-threshold), max: range.max.min(threshold) }
+            }
+            CalibrationMethod::MinMax => unreachable!(),
+        };
+    }
+    Ok(ranges)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn qparams() {
+        let qp = ActivationRange { min: -1.0, max: 3.0 }.qparams();
+        let (zp, scale) = qp.zp_scale().unwrap();
+        assert_eq!(scale, 4.0 / 255.0);
+        assert_eq!(zp, -64);
+        assert_eq!(
+            ActivationRange { min: 0.0, max: 0.0 }.qparams().zp_scale().unwrap(),
+            (-128, 1.0)
+        );
+    }
+
+    #[test]
+    fn percentile_clips_outliers() {
+        let mut values: Vec<f32> = (0..1000).map(|i| i as f32 / 1000.0).collect();
+        values.push(100.0);
+        let range = ActivationRange::of(&values).unwrap();
+        let mut histogram = Histogram::new(range, false);
+        histogram.add(&values);
+        let clipped = histogram.percentile(99.0);
+        assert!(clipped.max < 2.0);
+        assert_eq!(histogram.percentile(100.0), ActivationRange { min: 0.0, max: 100.0 });
+    }
+
+    #[test]
+    fn entropy_clips_outliers() {
+        let mut values: Vec<f32> = (0..10000).map(|i| ((i % 200) as f32 - 100.0) / 100.0).collect();
+        values.push(50.0);
+        let range = ActivationRange::of(&values).unwrap();
+        let mut histogram = Histogram::new(range, true);
+        histogram.add(&values);
+        let threshold = histogram.entropy_threshold();
+        assert!(threshold >= 1.0 && threshold < 10.0, "{}", threshold);
+    }
+}
@@ -0,0 +1,48 @@
+//! A small float model to exercise quantization with, here and in the serialization tests of
+//! other crates.
+use crate::internal::*;
+use crate::ops;
+use crate::ops::cnn::{ConvUnary, KernelFormat};
+use crate::ops::matmul::MatMulUnary;
+
+/// Conv (3 input and 4 output channels, kernel of 3), Relu, then MatMulUnary down to 2
+/// channels, on a 1x3x8 NCHW input. The conv kernel is stored in `kernel_fmt`.
+pub fn conv_relu_matmul(kernel_fmt: KernelFormat) -> TractResult<TypedModel> {
+    let mut model = TypedModel::default();
+    let source = model.add_source("input", TypedFact::dt_shape(f32::datum_type(), &[1, 3, 8]))?;
+    let kernel: Vec<f32> = (0..36).map(|i| ((i * 7) % 11) as f32 / 11.0 - 0.5).collect();
+    let kernel = Tensor::from_shape(&[4, 3, 3], &kernel)?;
+    let kernel = match kernel_fmt {
+        KernelFormat::OIHW => kernel,
+        KernelFormat::HWIO => kernel.permute_axes(&[2, 1, 0])?,
+    };
+    let conv = ConvUnary::new(
+        ops::cnn::PoolSpec::new(
+            ops::nn::DataFormat::NCHW,
+            tvec!(3),
+            ops::cnn::PaddingSpec::Valid,
+            None,
+            None,
+            Some(4),
+        ),
+        kernel_fmt,
+        kernel.into_arc_tensor(),
+        1,
+        Some(rctensor1(&[0.1f32, -0.2, 0.3, 0.0])),
+        None,
+    );
+    let wire = model.wire_node("conv", conv, &[source])?;
+    let wire = model.wire_node("relu", ops::math::max::unary(rctensor3(&[[[0f32]]])), &wire)?;
+    let a = Tensor::from_shape(&[1, 2, 4], &[0.5f32, -0.25, 0.75, 1.0, -1.0, 0.5, 0.25, -0.5])?;
+    let mm = MatMulUnary::new(a.into_arc_tensor(), false, false, false);
+    let wire = model.wire_node("matmul", mm, &wire)?;
+    model.set_output_outlets(&wire)?;
+    Ok(model)
+}
+
+/// An input for `conv_relu_matmul`, values in [-1, 1] varying with `seed`.
+pub fn sample(seed: usize) -> TVec<Tensor> {
+    let data: Vec<f32> =
+        (0..24).map(|i| (((i + seed) * 13) % 17) as f32 / 17.0 * 2.0 - 1.0).collect();
+    tvec!(Tensor::from_shape(&[1, 3, 8], &data).unwrap())
+}
@@ -0,0 +1,413 @@
+//! Post-training static quantization.
+//!
+//! `calibrate` runs a float model on a few representative inputs and records the range of
+//! every activation. `quantize` then uses these ranges to rewrite MatMulUnary, ConvUnary and
+//! the element-wise ops that have a quantized form into i8, with weights quantized
+//! symmetrically. Casts are inserted wherever quantized and float parts of the graph meet, so
+//! the quantized model keeps the float interface of the original one.
+//!
+//! `quantize_weights` is the weight-only alternative: it needs no calibration, stores the
+//! constant operand of f32 MatMulUnary in blocks of i8 or 4-bit values and keeps activations
+//! in f32.
+use crate::internal::*;
+use crate::ops::binary::{BinMiniOp, TypedBinOp, UnaryOp};
+use crate::ops::cast::cast;
+use crate::ops::cnn::{ConvUnary, KernelFormat};
+use crate::ops::element_wise::ElementWiseOp;
+use crate::ops::math::{Add, Max, Min, Sub};
+use crate::ops::matmul::mir_quant_unary::QMatMulUnary;
+use crate::ops::matmul::{BlockQuantMatMulUnary, MatMulQParams, MatMulUnary};
+use tract_linalg::frame::BlockQuantFormat;
+use tract_ndarray::Axis;
+
+mod calibration;
+#[doc(hidden)]
+pub mod fixture;
+
+pub use calibration::{calibrate, ActivationRange, CalibrationMethod};
+
+#[derive(Clone, Debug)]
+pub struct QuantizationConfig {
+    /// Quantize weights with one scale per output channel instead of one per tensor.
+    pub per_channel: bool,
+}
+
+impl Default for QuantizationConfig {
+    fn default() -> QuantizationConfig {
+        QuantizationConfig { per_channel: true }
+    }
+}
+
+/// Quantize a float model, using activation ranges computed by `calibrate`.
+///
+/// Ops without a calibrated range for their inputs and outputs are left in float.
+pub fn quantize(
+    model: &TypedModel,
+    ranges: &HashMap<OutletId, ActivationRange>,
+    config: &QuantizationConfig,
+) -> TractResult<TypedModel> {
+    let mut quantizer = Quantizer {
+        source: model,
+        ranges,
+        config,
+        target: TypedModel { symbol_scope: model.symbol_scope.clone(), ..TypedModel::default() },
+        float: HashMap::new(),
+        quant: HashMap::new(),
+    };
+    for id in model.eval_order()? {
+        let node = model.node(id);
+        quantizer.translate_node(node).with_context(|| format!("Quantizing {}", node))?;
+    }
+    let inputs = model.input_outlets()?.iter().map(|i| quantizer.float[i]).collect();
+    let outputs = model
+        .output_outlets()?
+        .iter()
+        .map(|o| quantizer.float_wire(*o))
+        .collect::<TractResult<Vec<_>>>()?;
+    let mut target = quantizer.target;
+    for (o, wire) in model.output_outlets()?.iter().zip(outputs.iter()) {
+        if let Some(label) = model.outlet_label(*o) {
+            target.set_outlet_label(*wire, label.to_string())?;
+        }
+    }
+    target.inputs = inputs;
+    target.outputs = outputs;
+    target.properties = model.properties.clone();
+    Ok(target)
+}
+
+/// Replace the weights of f32 MatMulUnary by block quantized weights.
+pub fn quantize_weights(
+    model: &TypedModel,
+    format: BlockQuantFormat,
+    block_len: usize,
+) -> TractResult<TypedModel> {
+    let mut model = model.clone();
+    for id in model.eval_order()? {
+        let node = model.node(id);
+        if let Some(op) = node.op_as::<MatMulUnary>() {
+            if let Some(bq) = BlockQuantMatMulUnary::from_mat_mul_unary(op, format, block_len)? {
+                let patch = TypedModelPatch::replace_single_op(&model, node, &node.inputs, bq)?;
+                patch.apply(&mut model)?;
+            }
+        }
+    }
+    model.compact()?;
+    Ok(model)
+}
+
+struct Quantizer<'a> {
+    source: &'a TypedModel,
+    ranges: &'a HashMap<OutletId, ActivationRange>,
+    config: &'a QuantizationConfig,
+    target: TypedModel,
+    float: HashMap<OutletId, OutletId>,
+    quant: HashMap<OutletId, OutletId>,
+}
+
+impl<'a> Quantizer<'a> {
+    fn outlet_name(&self, outlet: OutletId) -> String {
+        let name = &self.source.node(outlet.node).name;
+        if outlet.slot == 0 {
+            name.to_string()
+        } else {
+            format!("{}.{}", name, outlet.slot)
+        }
+    }
+
+    fn range(&self, outlet: OutletId) -> Option<ActivationRange> {
+        self.ranges.get(&outlet).copied()
+    }
+
+    fn float_wire(&mut self, outlet: OutletId) -> TractResult<OutletId> {
+        if let Some(wire) = self.float.get(&outlet) {
+            return Ok(*wire);
+        }
+        let name = format!("{}.dequant", self.outlet_name(outlet));
+        let wire = self.target.wire_node(name, cast(f32::datum_type()), &[self.quant[&outlet]])?[0];
+        self.float.insert(outlet, wire);
+        Ok(wire)
+    }
+
+    /// `outlet` as i8 with `qparams`, for the `ix`-th input of `consumer`.
+    fn quant_wire(
+        &mut self,
+        outlet: OutletId,
+        qparams: QParams,
+        consumer: &str,
+        ix: usize,
+    ) -> TractResult<OutletId> {
+        let dt = DatumType::QI8(qparams.clone());
+        if let Some(&wire) = self.quant.get(&outlet) {
+            if self.target.outlet_fact(wire)?.datum_type == dt {
+                return Ok(wire);
+            }
+            let name = format!("{}.requant_{}", consumer, ix);
+            return Ok(self.target.wire_node(name, cast(dt), &[wire])?[0]);
+        }
+        let float = self.float_wire(outlet)?;
+        if self.range(outlet).map(|r| r.qparams()) == Some(qparams) {
+            let name = format!("{}.quant", self.outlet_name(outlet));
+            let wire = self.target.wire_node(name, cast(dt), &[float])?[0];
+            self.quant.insert(outlet, wire);
+            Ok(wire)
+        } else {
+            let name = format!("{}.quant_{}", consumer, ix);
+            Ok(self.target.wire_node(name, cast(dt), &[float])?[0])
+        }
+    }
+
+    /// Requantize the output of a quantized op to the parameters of its calibrated range.
+    fn requant_output(&mut self, node: &TypedNode, wire: OutletId) -> TractResult<OutletId> {
+        let qparams = self.range(node.id.into()).unwrap().qparams();
+        let dt = DatumType::QI8(qparams);
+        if self.target.outlet_fact(wire)?.datum_type == dt {
+            Ok(wire)
+        } else {
+            Ok(self.target.wire_node(format!("{}.requant", node.name), cast(dt), &[wire])?[0])
+        }
+    }
+
+    fn translate_node(&mut self, node: &TypedNode) -> TractResult<()> {
+        if let Some(wire) = self.quantize_node(node)? {
+            self.quant.insert(node.id.into(), wire);
+            return Ok(());
+        }
+        let outputs = if self.source.input_outlets()?.contains(&node.id.into()) {
+            tvec!(self.target.add_source(&*node.name, node.outputs[0].fact.clone())?)
+        } else {
+            let inputs = node
+                .inputs
+                .iter()
+                .map(|i| self.float_wire(*i))
+                .collect::<TractResult<TVec<_>>>()?;
+            self.target.wire_node(&*node.name, node.op.clone(), &inputs)?
+        };
+        for (ix, wire) in outputs.into_iter().enumerate() {
+            self.float.insert(OutletId::new(node.id, ix), wire);
+        }
+        Ok(())
+    }
+
+    /// Wire the quantized form of the node, if it has one, returning its i8 output.
+    fn quantize_node(&mut self, node: &TypedNode) -> TractResult<Option<OutletId>> {
+        if node.outputs.len() != 1
+            || node.outputs[0].fact.datum_type != f32::datum_type()
+            || self.source.input_outlets()?.contains(&node.id.into())
+        {
+            return Ok(None);
+        }
+        if node.op_is::<AxisOp>() || node.op_is::<crate::ops::array::Slice>() {
+            // these ops do not care about the values: stay quantized if the input is
+            if let Some(&input) = self.quant.get(&node.inputs[0]) {
+                return Ok(Some(self.target.wire_node(&*node.name, node.op.clone(), &[input])?[0]));
+            }
+            return Ok(None);
+        }
+        let output_range = if let Some(range) = self.range(node.id.into()) {
+            range
+        } else {
+            return Ok(None);
+        };
+        let input_ranges: TVec<Option<ActivationRange>> =
+            node.inputs.iter().map(|i| self.range(*i)).collect();
+        if let Some(op) = node.op_as::<MatMulUnary>() {
+            if let (Some(input_range), DatumType::F32) = (input_ranges[0], op.a.datum_type()) {
+                let m_axis = op.a.rank() - 2 + op.a_trans as usize;
+                let (a, _) = self.quantize_weights(&op.a, m_axis)?;
+                let b = self.quant_wire(node.inputs[0], input_range.qparams(), &node.name, 0)?;
+                let op = QMatMulUnary::new(
+                    a.into_arc_tensor(),
+                    None,
+                    op.a_trans,
+                    op.b_trans,
+                    op.c_trans,
+                    DatumType::QI8(output_range.qparams()),
+                    MatMulQParams::all_from_qtype(),
+                );
+                return Ok(Some(self.target.wire_node(&*node.name, op, &[b])?[0]));
+            }
+        } else if let Some(op) = node.op_as::<ConvUnary>() {
+            if let (Some(input_range), DatumType::F32, None) =
+                (input_ranges[0], op.kernel.datum_type(), &op.q_params)
+            {
+                let input_qparams = input_range.qparams();
+                let o_axis = match op.kernel_fmt {
+                    KernelFormat::OIHW => 0,
+                    KernelFormat::HWIO => op.kernel.rank() - 1,
+                };
+                let (kernel, scales) = self.quantize_weights(&op.kernel, o_axis)?;
+                // the bias is added to the i32 accumulator
+                let bias = if let Some(bias) = &op.bias {
+                    let bias = bias.cast_to::<f32>()?;
+                    let input_scale = input_qparams.zp_scale()?.1;
+                    let bias: Vec<i32> = bias
+                        .as_slice::<f32>()?
+                        .iter()
+                        .enumerate()
+                        .map(|(ix, b)| {
+                            let scale = scales[ix.min(scales.len() - 1)] * input_scale;
+                            (b / scale).round() as i32
+                        })
+                        .collect();
+                    Some(rctensor1(&bias))
+                } else {
+                    None
+                };
+                let input = self.quant_wire(node.inputs[0], input_qparams, &node.name, 0)?;
+                let op = ConvUnary {
+                    kernel: kernel.into_arc_tensor(),
+                    bias,
+                    q_params: Some((
+                        DatumType::QI8(output_range.qparams()),
+                        MatMulQParams::all_from_qtype(),
+                    )),
+                    ..op.clone()
+                };
+                return Ok(Some(self.target.wire_node(&*node.name, op, &[input])?[0]));
+            }
+        } else if let Some(op) = node.op_as::<ElementWiseOp>() {
+            // the quantized form computes in place: use a range fitting both input and output
+            let range = ActivationRange::union_of([input_ranges[0], Some(output_range)]);
+            if let Some(qparams) = range.map(|r| r.qparams()) {
+                let dt = DatumType::QI8(qparams.clone());
+                let mut probe = Tensor::zero_dt(dt.clone(), &[1])?;
+                if op.0.output_type(dt).is_none() && op.0.eval_in_place(&mut probe).is_ok() {
+                    let input = self.quant_wire(node.inputs[0], qparams, &node.name, 0)?;
+                    let wire = self.target.wire_node(&*node.name, op.clone(), &[input])?[0];
+                    return Ok(Some(self.requant_output(node, wire)?));
+                }
+            }
+        } else if let Some(op) = node.op_as::<UnaryOp>() {
+            let konst = op.a.cast_to::<f32>()?;
+            let konst_range = ActivationRange {
+                min: konst.as_slice::<f32>()?.iter().fold(f32::INFINITY, |a, b| a.min(*b)),
+                max: konst.as_slice::<f32>()?.iter().fold(f32::NEG_INFINITY, |a, b| a.max(*b)),
+            };
+            let range =
+                ActivationRange::union_of([input_ranges[0], Some(konst_range), Some(output_range)]);
+            if let (Some(range), true) = (range, has_quantized_form(&*op.mini_op)) {
+                let dt = DatumType::QI8(range.qparams());
+                let input = self.quant_wire(node.inputs[0], range.qparams(), &node.name, 0)?;
+                let op = UnaryOp {
+                    mini_op: op.mini_op.clone(),
+                    a: konst.cast_to_dt(dt)?.into_owned().into_arc_tensor(),
+                };
+                let wire = self.target.wire_node(&*node.name, op, &[input])?[0];
+                return Ok(Some(self.requant_output(node, wire)?));
+            }
+        } else if let Some(op) = node.op_as::<TypedBinOp>() {
+            let range = ActivationRange::union_of(
+                input_ranges.iter().copied().chain(std::iter::once(Some(output_range))),
+            );
+            if let (Some(range), true) = (range, has_quantized_form(&*op.0)) {
+                let a = self.quant_wire(node.inputs[0], range.qparams(), &node.name, 0)?;
+                let b = self.quant_wire(node.inputs[1], range.qparams(), &node.name, 1)?;
+                let wire = self.target.wire_node(&*node.name, op.clone(), &[a, b])?[0];
+                return Ok(Some(self.requant_output(node, wire)?));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Symmetric i8 quantization of weights, per slice along `axis` if configured so. Also
+    /// returns the scales.
+    fn quantize_weights(&self, weights: &Tensor, axis: usize) -> TractResult<(Tensor, Vec<f32>)> {
+        let weights = weights.cast_to::<f32>()?;
+        let view = weights.to_array_view::<f32>()?;
+        let scale = |amax: f32| if amax > 0.0 { amax / 127.0 } else { 1.0 };
+        let amax = |values: tract_ndarray::ArrayViewD<f32>| {
+            values.iter().fold(0f32, |acc, v| acc.max(v.abs()))
+        };
+        let qparams;
+        let scales: Vec<f32>;
+        if self.config.per_channel && weights.shape()[axis] > 1 {
+            scales = view.axis_iter(Axis(axis)).map(|s| scale(amax(s))).collect();
+            qparams = QParams::per_axis(axis, &vec![0; scales.len()], &scales);
+        } else {
+            scales = vec![scale(amax(view))];
+            qparams = QParams::ZpScale { zero_point: 0, scale: scales[0] };
+        }
+        Ok((weights.cast_to_dt(DatumType::QI8(qparams))?.into_owned(), scales))
+    }
+}
+
+/// Binary ops computing on i8 operands sharing their zero point and scale with the output.
+fn has_quantized_form(op: &dyn BinMiniOp) -> bool {
+    op.is::<Add>() || op.is::<Sub>() || op.is::<Max>() || op.is::<Min>()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use fixture::sample;
+
+    fn float_model() -> TypedModel {
+        fixture::conv_relu_matmul(KernelFormat::OIHW).unwrap()
+    }
+
+    #[test]
+    fn quantize_conv_relu_matmul() {
+        let model = float_model();
+        let dataset: Vec<_> = (0..4).map(sample).collect();
+        let ranges = calibrate(&model, &dataset, CalibrationMethod::MinMax).unwrap();
+        let quantized = quantize(&model, &ranges, &QuantizationConfig::default()).unwrap();
+        assert!(quantized.nodes().iter().any(|n| n.op_is::<QMatMulUnary>()));
+        assert!(quantized
+            .nodes()
+            .iter()
+            .any(|n| n.op_as::<ConvUnary>().map(|c| c.q_params.is_some()).unwrap_or(false)));
+        assert_eq!(
+            quantized.outlet_fact(quantized.output_outlets().unwrap()[0]).unwrap().datum_type,
+            f32::datum_type()
+        );
+        let output_range = ranges[&model.output_outlets().unwrap()[0]];
+        let tolerance = 4.0 * output_range.qparams().zp_scale().unwrap().1 + 0.02;
+        for optimized in &[false, true] {
+            let quantized = if *optimized {
+                quantized.clone().into_optimized().unwrap()
+            } else {
+                quantized.clone()
+            };
+            for input in &dataset {
+                let expected = model.clone().into_runnable().unwrap().run(input.clone()).unwrap();
+                let found = quantized.clone().into_runnable().unwrap().run(input.clone()).unwrap();
+                let expected = expected[0].as_slice::<f32>().unwrap();
+                let found = found[0].as_slice::<f32>().unwrap();
+                assert!(
+                    expected.iter().zip(found.iter()).all(|(e, f)| (e - f).abs() <= tolerance),
+                    "expected: {:?}, found: {:?}",
+                    expected,
+                    found
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn uncalibrated_ops_stay_float() {
+        let model = float_model();
+        let quantized = quantize(&model, &HashMap::new(), &QuantizationConfig::default()).unwrap();
+        assert!(quantized.nodes().iter().all(|n| !n.outputs[0].fact.datum_type.is_quantized()));
+    }
+
+    #[test]
+    fn quantize_weights_of_matmul() {
+        let model = float_model();
+        let quantized = quantize_weights(&model, BlockQuantFormat::Q8, 4).unwrap();
+        assert!(quantized.nodes().iter().any(|n| n.op_is::<BlockQuantMatMulUnary>()));
+        assert!(quantized.nodes().iter().all(|n| !n.op_is::<MatMulUnary>()));
+        let expected = model.into_runnable().unwrap().run(sample(0)).unwrap();
+        for quantized in &[quantized.clone(), quantized.clone().into_optimized().unwrap()] {
+            let found = quantized.clone().into_runnable().unwrap().run(sample(0)).unwrap();
+            let expected = expected[0].as_slice::<f32>().unwrap();
+            let found = found[0].as_slice::<f32>().unwrap();
+            assert!(
+                expected.iter().zip(found.iter()).all(|(e, f)| (e - f).abs() <= 0.01),
+                "expected: {:?}, found: {:?}",
+                expected,
+                found
+            );
+        }
+    }
+}
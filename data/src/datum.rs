@@ -6,6 +6,7 @@ use crate::tensor::Tensor;
 use crate::TVec;
 use num_complex::Complex;
 use std::hash::Hash;
+use std::sync::Arc;
 use std::{fmt, ops};
 
 mod arrays;
@@ -36,10 +37,12 @@ impl std::str::FromStr for Blob {
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
 pub enum QParams {
     MinMax { min: f32, max: f32 },
     ZpScale { zero_point: i32, scale: f32 },
+    /// One zero point and scale per slice along `axis`. Build it with `QParams::per_axis`.
+    PerAxis { axis: usize, zero_points: Arc<[i32]>, scales: Arc<[f32]> },
 }
 
 impl Eq for QParams {}
@@ -63,23 +66,86 @@ impl Hash for QParams {
                 zero_point.hash(state);
                 scale.to_bits().hash(state);
             }
+            QParams::PerAxis { axis, zero_points, scales } => {
+                2.hash(state);
+                axis.hash(state);
+                zero_points.hash(state);
+                scales.iter().for_each(|s| s.to_bits().hash(state));
+            }
         }
     }
 }
 
 impl QParams {
-    pub fn zp_scale(&self) -> (i32, f32) {
+    /// Per-axis parameters.
+    pub fn per_axis(axis: usize, zero_points: &[i32], scales: &[f32]) -> QParams {
+        assert_eq!(zero_points.len(), scales.len());
+        QParams::PerAxis { axis, zero_points: zero_points.into(), scales: scales.into() }
+    }
+
+    pub fn is_per_axis(&self) -> bool {
+        matches!(self, QParams::PerAxis { .. })
+    }
+
+    /// Zero point and scale for the whole tensor.
+    ///
+    /// Per-axis parameters only have one if all the slices share it.
+    pub fn zp_scale(&self) -> anyhow::Result<(i32, f32)> {
         match self {
             QParams::MinMax { min, max } => {
                 let scale = (max - min) / 255.;
-                ((-(min + max) / 2. / scale) as i32, scale)
+                Ok(((-(min + max) / 2. / scale) as i32, scale))
+            }
+            QParams::ZpScale { zero_point, scale } => Ok((*zero_point, *scale)),
+            QParams::PerAxis { zero_points, scales, .. } => {
+                anyhow::ensure!(
+                    zero_points.iter().all(|zp| *zp == zero_points[0])
+                        && scales.iter().all(|s| *s == scales[0]),
+                    "Per-axis quantization parameters have no single zero point and scale"
+                );
+                Ok((zero_points[0], scales[0]))
+            }
+        }
+    }
+
+    /// Zero points and scales, one per slice along the axis (or a single one for the whole
+    /// tensor).
+    pub fn zp_scale_vecs(&self) -> (Vec<i32>, Vec<f32>) {
+        match self {
+            QParams::PerAxis { zero_points, scales, .. } => (zero_points.to_vec(), scales.to_vec()),
+            _ => {
+                let (zp, scale) = self.zp_scale().unwrap();
+                (vec![zp], vec![scale])
+            }
+        }
+    }
+
+    /// Parameters for the `ix`-th slice along the axis.
+    pub fn at(&self, ix: usize) -> QParams {
+        match self {
+            QParams::PerAxis { zero_points, scales, .. } => {
+                QParams::ZpScale { zero_point: zero_points[ix], scale: scales[ix] }
+            }
+            _ => self.clone(),
+        }
+    }
+
+    /// Map the zero points (per-tensor parameters are turned into ZpScale).
+    pub fn map_zero_points(&self, f: impl Fn(i32) -> i32) -> QParams {
+        match self {
+            QParams::PerAxis { axis, zero_points, scales } => {
+                let zero_points: Vec<i32> = zero_points.iter().map(|zp| f(*zp)).collect();
+                QParams::per_axis(*axis, &zero_points, scales)
+            }
+            _ => {
+                let (zp, scale) = self.zp_scale().unwrap();
+                QParams::ZpScale { zero_point: f(zp), scale }
             }
-            QParams::ZpScale { zero_point, scale } => (*zero_point, *scale),
         }
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
 pub enum DatumType {
     Bool,
     U8,
@@ -111,21 +177,21 @@ impl DatumType {
         use DatumType::*;
         if *self == String || *self == TDim || *self == Blob || *self == Bool || self.is_quantized()
         {
-            tvec!(*self)
+            tvec!(self.clone())
         } else if self.is_complex_float() {
-            [ComplexF16, ComplexF32, ComplexF64].iter().filter(|s| s.size_of() >= self.size_of()).copied().collect()
+            [ComplexF16, ComplexF32, ComplexF64].iter().filter(|s| s.size_of() >= self.size_of()).cloned().collect()
         } else if self.is_complex_signed() {
-            [ComplexI16, ComplexI32, ComplexI64].iter().filter(|s| s.size_of() >= self.size_of()).copied().collect()
+            [ComplexI16, ComplexI32, ComplexI64].iter().filter(|s| s.size_of() >= self.size_of()).cloned().collect()
         } else if self.is_float() {
-            [F16, F32, F64].iter().filter(|s| s.size_of() >= self.size_of()).copied().collect()
+            [F16, F32, F64].iter().filter(|s| s.size_of() >= self.size_of()).cloned().collect()
         } else if self.is_signed() {
             [I8, I16, I32, I64, TDim]
                 .iter()
                 .filter(|s| s.size_of() >= self.size_of())
-                .copied()
+                .cloned()
                 .collect()
         } else {
-            [U8, U16, U32, U64].iter().filter(|s| s.size_of() >= self.size_of()).copied().collect()
+            [U8, U16, U32, U64].iter().filter(|s| s.size_of() >= self.size_of()).cloned().collect()
         }
     }
 
@@ -135,10 +201,10 @@ impl DatumType {
         let mut iter = i.into_iter();
         let mut current = match iter.next() {
             None => return None,
-            Some(it) => *it.borrow(),
+            Some(it) => it.borrow().clone(),
         };
         while let Some(n) = iter.next() {
-            match current.common_super_type(*n.borrow()) {
+            match current.common_super_type(n.borrow().clone()) {
                 None => return None,
                 Some(it) => current = it,
             }
@@ -195,7 +261,7 @@ impl DatumType {
 
     pub fn qparams(&self) -> Option<QParams> {
         match self {
-            DatumType::QI8(qparams) | DatumType::QU8(qparams) => Some(*qparams),
+            DatumType::QI8(qparams) | DatumType::QU8(qparams) => Some(qparams.clone()),
             _ => None,
         }
     }
@@ -204,19 +270,20 @@ impl DatumType {
         match self {
             DatumType::QI8(_) => DatumType::QI8(qparams),
             DatumType::QU8(_) => DatumType::QI8(qparams),
-            _ => *self,
+            _ => self.clone(),
         }
     }
+    /// Zero point and scale for the whole tensor, (0, 1) if it is not quantized.
     #[inline(always)]
-    pub fn zp_scale(&self) -> (i32, f32) {
-        self.qparams().map(|q| q.zp_scale()).unwrap_or((0, 1.))
+    pub fn zp_scale(&self) -> anyhow::Result<(i32, f32)> {
+        self.qparams().map(|q| q.zp_scale()).unwrap_or(Ok((0, 1.)))
     }
 
     pub fn unquantized(&self) -> DatumType {
         match self {
             DatumType::QI8(_) => DatumType::I8,
             DatumType::QU8(_) => DatumType::U8,
-            _ => *self,
+            _ => self.clone(),
         }
     }
 
@@ -259,7 +326,7 @@ impl DatumType {
             | DatumType::U8
             | DatumType::U16
             | DatumType::U32
-            | DatumType::U64 => Tensor::zero_dt(*self, &[1]).unwrap(),
+            | DatumType::U64 => Tensor::zero_dt(self.clone(), &[1]).unwrap(),
             DatumType::I8 | DatumType::QI8(_) => tensor0(i8::MIN),
             DatumType::I16 => tensor0(i16::MIN),
             DatumType::I32 => tensor0(i32::MIN),
@@ -442,4 +509,25 @@ mod tests {
         let t_i64: Tensor = tensor1(&[0i64]);
         t_i64.cast_to::<bool>().unwrap();
     }
+
+    #[test]
+    fn test_cast_per_axis() {
+        let qp = QParams::per_axis(1, &[0, 10], &[0.5, 0.25]);
+        let mut t = tensor2(&[[2i8, 14], [-4, 6]]);
+        unsafe { t.set_datum_type(DatumType::QI8(qp.clone())) };
+        let f = t.cast_to::<f32>().unwrap();
+        assert_eq!(*f, tensor2(&[[1f32, 1.0], [-2.0, -1.0]]));
+        let back = f.cast_to_dt(DatumType::QI8(qp)).unwrap();
+        assert_eq!(back.as_slice::<i8>().unwrap(), &[2, 14, -4, 6]);
+    }
+
+    #[test]
+    fn test_per_axis_params() {
+        let a = QParams::per_axis(0, &[1, 2], &[0.5, 0.25]);
+        let b = QParams::per_axis(0, &[1, 2], &[0.5, 0.25]);
+        assert_eq!(a, b);
+        assert_eq!(a.at(1), QParams::ZpScale { zero_point: 2, scale: 0.25 });
+        assert!(a.zp_scale().is_err());
+        assert_eq!(a.map_zero_points(|zp| zp - 128).zp_scale_vecs().0, vec!(-127, -126));
+    }
 }
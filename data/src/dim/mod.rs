@@ -3,9 +3,11 @@ use num_traits::Zero;
 use std::fmt;
 use std::ops;
 
+mod sym;
 mod tree;
 
-pub use self::tree::{Symbol, SymbolValues, TDim, UndeterminedSymbol};
+pub use self::sym::{Assertion, Symbol, SymbolScope, SymbolValues};
+pub use self::tree::{TDim, UndeterminedSymbol};
 use crate::{ TractError, TractResult };
 
 /// A super-trait for value acting as tensor dimensions in tract.
@@ -49,6 +51,12 @@ pub trait DimLike:
         (self.clone() + other - 1) / other
     }
 
+    /// Smallest of two dimensions.
+    fn mini(self, other: Self) -> Self;
+
+    /// Largest of two dimensions.
+    fn maxi(self, other: Self) -> Self;
+
     /// Convert to regular integer.
     fn to_i64(&self) -> TractResult<i64>;
 
@@ -110,6 +118,18 @@ impl DimLike for TDim {
         Ok(((TDim::Mul(num) * num_int).reduce(), denum_int as u64))
     }
 
+    fn divceil(&self, other: usize) -> Self {
+        self.clone().div_ceil(other as u64)
+    }
+
+    fn mini(self, other: Self) -> Self {
+        TDim::mini(self, other)
+    }
+
+    fn maxi(self, other: Self) -> Self {
+        TDim::maxi(self, other)
+    }
+
     fn to_i64(&self) -> TractResult<i64> {
         TDim::to_i64(self)
     }
@@ -137,6 +157,14 @@ impl DimLike for usize {
         Ok((self / gcd, (other / gcd) as u64))
     }
 
+    fn mini(self, other: Self) -> Self {
+        std::cmp::min(self, other)
+    }
+
+    fn maxi(self, other: Self) -> Self {
+        std::cmp::max(self, other)
+    }
+
     fn to_i64(&self) -> TractResult<i64> {
         Ok(*self as i64)
     }
@@ -180,11 +208,11 @@ mod tests {
     use super::*;
 
     lazy_static::lazy_static! {
-        static ref S: Symbol = crate::dim::Symbol::new('S');
+        static ref S: Symbol = crate::dim::SymbolScope::default().sym("S");
     }
 
     pub fn s() -> TDim {
-        (*S).into()
+        S.clone().into()
     }
 
     #[test]
@@ -214,8 +242,10 @@ mod tests {
 
     #[test]
     fn div_sym_sym_complex() {
+        let scope = SymbolScope::default();
+        let (s, b): (TDim, TDim) = (scope.sym("s").into(), scope.sym("b").into());
         assert_eq!(
-            (256.to_dim() * 's' * 'b').maybe_div(&(1.to_dim() * 's' * 'b')).unwrap(),
+            (256.to_dim() * &s * &b).maybe_div(&(1.to_dim() * &s * &b)).unwrap(),
             (256.into(), 1)
         );
     }
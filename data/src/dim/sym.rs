@@ -0,0 +1,670 @@
+use super::TDim;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+/// Owner of a set of named symbols.
+///
+/// Each model owns a scope, so symbols from two models never get mixed up even if they share
+/// a name. Clones of a scope are the same scope.
+///
+/// A scope also holds the assertions made about its symbols (see `Assertion`).
+#[derive(Clone, Default)]
+pub struct SymbolScope(Arc<Mutex<ScopeData>>);
+
+#[derive(Default)]
+struct ScopeData {
+    names: Vec<Arc<str>>,
+    assertions: Vec<Assertion>,
+    facts: Arc<ScopeFacts>,
+}
+
+/// What the assertions tell the simplification about the symbols of a scope.
+///
+/// Shared with the snapshots taken for simplification, and copied on write by the rare
+/// assertions made after a snapshot.
+#[derive(Clone, Default)]
+pub(super) struct ScopeFacts {
+    lower_bounds: HashMap<Arc<str>, i64>,
+    upper_bounds: HashMap<Arc<str>, i64>,
+    multiples: HashMap<Arc<str>, u64>,
+    substitutions: HashMap<Arc<str>, TDim>,
+    greater_or_equal: Vec<(TDim, TDim)>,
+}
+
+impl SymbolScope {
+    /// The symbol called `name` in this scope, created if needed.
+    pub fn sym(&self, name: &str) -> Symbol {
+        let mut data = self.0.lock().unwrap();
+        let name = if let Some(found) = data.names.iter().find(|n| &***n == name) {
+            found.clone()
+        } else {
+            let name: Arc<str> = name.into();
+            data.names.push(name.clone());
+            name
+        };
+        Symbol { scope: self.clone(), name }
+    }
+
+    /// The symbol called `name` in this scope, if it exists.
+    pub fn get(&self, name: &str) -> Option<Symbol> {
+        let data = self.0.lock().unwrap();
+        let name = data.names.iter().find(|n| &***n == name)?.clone();
+        Some(Symbol { scope: self.clone(), name })
+    }
+
+    /// A new symbol, called `prefix` or `prefix` followed by a number if `prefix` is taken.
+    pub fn new_with_prefix(&self, prefix: &str) -> Symbol {
+        let mut data = self.0.lock().unwrap();
+        let taken = |name: &str| data.names.iter().any(|n| &**n == name);
+        let name: Arc<str> = if !taken(prefix) {
+            prefix.into()
+        } else {
+            (1..).map(|i| format!("{}{}", prefix, i)).find(|n| !taken(n)).unwrap().into()
+        };
+        data.names.push(name.clone());
+        Symbol { scope: self.clone(), name }
+    }
+
+    /// All the symbols of this scope, in creation order.
+    pub fn symbols(&self) -> Vec<Symbol> {
+        let data = self.0.lock().unwrap();
+        data.names.iter().map(|name| Symbol { scope: self.clone(), name: name.clone() }).collect()
+    }
+
+    /// Parse a dimension expression as `TDim` displays it, names refering to symbols of this
+    /// scope.
+    ///
+    /// Sums and products of integers, symbols, parenthesized expressions and `min(..)` or
+    /// `max(..)` of comma separated expressions. Floor division and remainder by an integer are
+    /// written with `/` and `%`, with the same precedence as `*` and left to right: `2*S/3` is
+    /// `(2*S)/3`. Ceiled division is written `ceil((expr)/integer)`.
+    pub fn parse_tdim(&self, s: &str) -> anyhow::Result<TDim> {
+        TDim::parse_with(s, |name| self.sym(name))
+    }
+
+    /// Parse an assertion as it displays: two dimension expressions separated by `>=`, `<=`,
+    /// `>`, `<` or `==`, `(expr)%k==0` standing for a multiple of `k`.
+    pub fn parse_assertion(&self, s: &str) -> anyhow::Result<Assertion> {
+        let (pos, op) = ["==", ">=", "<=", ">", "<"]
+            .iter()
+            .find_map(|op| s.find(op).map(|pos| (pos, *op)))
+            .ok_or_else(|| anyhow::format_err!("No comparison operator in assertion {}", s))?;
+        let (lhs, rhs) = (s[..pos].trim(), s[pos + op.len()..].trim());
+        if op == "==" && rhs == "0" {
+            // look for "(expr)%k" before parsing, as it may simplify away
+            if let Some((a, k)) = lhs.rsplit_once('%') {
+                let a = a.trim();
+                let mut depth = 0;
+                let wrapped = a.starts_with('(')
+                    && a.char_indices().all(|(ix, c)| {
+                        depth += (c == '(') as i32 - (c == ')') as i32;
+                        depth > 0 || ix == a.len() - 1
+                    });
+                if let (true, Ok(k)) = (wrapped, k.trim().parse::<u64>()) {
+                    return Ok(Assertion::MultipleOf(self.parse_tdim(a)?, k));
+                }
+            }
+        }
+        let lhs = self.parse_tdim(lhs)?;
+        let rhs = self.parse_tdim(rhs)?;
+        Ok(match op {
+            "==" => Assertion::Equal(lhs, rhs),
+            ">=" => Assertion::GreaterOrEqual(lhs, rhs),
+            "<=" => Assertion::GreaterOrEqual(rhs, lhs),
+            ">" => Assertion::GreaterOrEqual(lhs, rhs + 1),
+            _ => Assertion::GreaterOrEqual(rhs, lhs + 1),
+        })
+    }
+
+    /// Assert a fact about symbols of this scope.
+    ///
+    /// Lower and upper integer bounds of a symbol, divisibility of a symbol and equality of a
+    /// symbol to an expression are used by `TDim` simplification. Other assertions are only
+    /// used by `TDim::prove_positive_or_zero` and checked by `SymbolScope::check`.
+    pub fn add_assertion(&self, assertion: Assertion) -> anyhow::Result<()> {
+        let assertion = match assertion {
+            Assertion::Equal(lhs, rhs) => {
+                let (lhs, rhs) = (lhs.reduce(), rhs.reduce());
+                match (lhs, rhs) {
+                    (lhs, rhs) if lhs == rhs => return Ok(()),
+                    (TDim::Sym(s), rhs) | (rhs, TDim::Sym(s)) if self.owns(&s) => {
+                        anyhow::ensure!(
+                            !rhs.symbols().contains(&s),
+                            "Can not assert {}=={}, it is recursive",
+                            s,
+                            rhs
+                        );
+                        let mut data = self.0.lock().unwrap();
+                        Arc::make_mut(&mut data.facts)
+                            .substitutions
+                            .insert(s.name.clone(), rhs.clone());
+                        Assertion::Equal(TDim::Sym(s), rhs)
+                    }
+                    (lhs, rhs) => Assertion::Equal(lhs, rhs),
+                }
+            }
+            Assertion::MultipleOf(a, k) => {
+                let a = a.reduce();
+                if let TDim::Sym(s) = &a {
+                    if self.owns(s) {
+                        use num_integer::Integer;
+                        let mut data = self.0.lock().unwrap();
+                        let facts = Arc::make_mut(&mut data.facts);
+                        let multiple = facts.multiples.entry(s.name.clone()).or_insert(1);
+                        *multiple = multiple.lcm(&k);
+                    }
+                }
+                Assertion::MultipleOf(a, k)
+            }
+            Assertion::GreaterOrEqual(lhs, rhs) => {
+                let diff = lhs.clone() - &rhs;
+                for s in diff.symbols() {
+                    if !self.owns(&s) {
+                        continue;
+                    }
+                    let sym = TDim::Sym(s.clone());
+                    if let Ok(low) = (sym.clone() - &diff).to_i64() {
+                        let mut data = self.0.lock().unwrap();
+                        let facts = Arc::make_mut(&mut data.facts);
+                        let bound = facts.lower_bounds.entry(s.name.clone()).or_insert(low);
+                        *bound = (*bound).max(low);
+                    } else if let Ok(high) = (sym + &diff).to_i64() {
+                        let mut data = self.0.lock().unwrap();
+                        let facts = Arc::make_mut(&mut data.facts);
+                        let bound = facts.upper_bounds.entry(s.name.clone()).or_insert(high);
+                        *bound = (*bound).min(high);
+                    }
+                }
+                let mut data = self.0.lock().unwrap();
+                Arc::make_mut(&mut data.facts).greater_or_equal.push((lhs.clone(), rhs.clone()));
+                Assertion::GreaterOrEqual(lhs, rhs)
+            }
+        };
+        self.0.lock().unwrap().assertions.push(assertion);
+        Ok(())
+    }
+
+    /// All the assertions of this scope, in the order they were made.
+    pub fn assertions(&self) -> Vec<Assertion> {
+        self.0.lock().unwrap().assertions.clone()
+    }
+
+    /// Check the assertions that can be decided with the symbol values.
+    pub fn check(&self, values: &SymbolValues) -> anyhow::Result<()> {
+        for assertion in self.assertions() {
+            if assertion.eval(values) == Some(false) {
+                anyhow::bail!("Assertion {} does not hold for {:?}", assertion, values)
+            }
+        }
+        Ok(())
+    }
+
+    fn facts(&self) -> Arc<ScopeFacts> {
+        self.0.lock().unwrap().facts.clone()
+    }
+
+    fn owns(&self, s: &Symbol) -> bool {
+        self.same_as(&s.scope)
+    }
+
+    fn same_as(&self, other: &SymbolScope) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+
+    fn id(&self) -> usize {
+        Arc::as_ptr(&self.0) as *const u8 as usize
+    }
+}
+
+impl fmt::Debug for SymbolScope {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let data = self.0.lock().unwrap();
+        write!(f, "SymbolScope({})", data.names.join(", "))?;
+        for assertion in &data.assertions {
+            write!(f, " {}", assertion)?;
+        }
+        Ok(())
+    }
+}
+
+/// The facts about the symbols of an expression, read once from their scopes so simplifying
+/// the expression does not lock a scope for each of its nodes.
+#[derive(Default)]
+pub(super) struct Snapshot(Vec<(SymbolScope, Arc<ScopeFacts>)>);
+
+impl Snapshot {
+    pub(super) fn of(d: &TDim) -> Snapshot {
+        let mut snapshot = Snapshot::default();
+        for s in d.symbols() {
+            if !snapshot.0.iter().any(|(scope, _)| scope.same_as(&s.scope)) {
+                snapshot.0.push((s.scope.clone(), s.scope.facts()));
+            }
+        }
+        snapshot
+    }
+
+    /// Look up the facts of the scope of `s`. Symbols introduced by a substitution may
+    /// come from a scope missing in the snapshot: it is read then.
+    pub(super) fn with<R>(&self, s: &Symbol, f: impl FnOnce(&ScopeFacts) -> R) -> R {
+        if let Some((_, facts)) = self.0.iter().find(|(scope, _)| scope.same_as(&s.scope)) {
+            f(facts)
+        } else {
+            f(&s.scope.facts())
+        }
+    }
+
+    pub(super) fn lower_bound(&self, s: &Symbol) -> i64 {
+        self.with(s, |f| f.lower_bounds.get(&s.name).copied().unwrap_or(0))
+    }
+
+    pub(super) fn upper_bound(&self, s: &Symbol) -> Option<i64> {
+        self.with(s, |f| f.upper_bounds.get(&s.name).copied())
+    }
+
+    pub(super) fn multiple_of(&self, s: &Symbol) -> u64 {
+        self.with(s, |f| f.multiples.get(&s.name).copied().unwrap_or(1))
+    }
+
+    pub(super) fn substitution(&self, s: &Symbol) -> Option<TDim> {
+        self.with(s, |f| f.substitutions.get(&s.name).cloned())
+    }
+
+    /// Does any `lhs >= rhs` assertion of the scope of `s` satisfy `pred`?
+    pub(super) fn any_greater_or_equal(
+        &self,
+        s: &Symbol,
+        mut pred: impl FnMut(&TDim, &TDim) -> bool,
+    ) -> bool {
+        self.with(s, |f| f.greater_or_equal.iter().any(|(lhs, rhs)| pred(lhs, rhs)))
+    }
+}
+
+/// A fact about the symbols of a scope, assumed by shape reasoning.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Assertion {
+    /// The first expression is greater or equal to the second one.
+    GreaterOrEqual(TDim, TDim),
+    /// Both expressions are equal. A symbol on either side is replaced by the other side when
+    /// simplifying expressions.
+    Equal(TDim, TDim),
+    /// The expression is a multiple of the integer.
+    MultipleOf(TDim, u64),
+}
+
+impl Assertion {
+    /// Whether the assertion holds for these symbol values, if they are enough to tell.
+    pub fn eval(&self, values: &SymbolValues) -> Option<bool> {
+        let value = |d: &TDim| d.eval(values).to_i64().ok();
+        match self {
+            Assertion::GreaterOrEqual(lhs, rhs) => Some(value(lhs)? >= value(rhs)?),
+            Assertion::Equal(lhs, rhs) => Some(value(lhs)? == value(rhs)?),
+            Assertion::MultipleOf(a, k) => Some(value(a)?.rem_euclid(*k as i64) == 0),
+        }
+    }
+}
+
+impl fmt::Display for Assertion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Assertion::GreaterOrEqual(lhs, rhs) => write!(f, "{}>={}", lhs, rhs),
+            Assertion::Equal(lhs, rhs) => write!(f, "{}=={}", lhs, rhs),
+            Assertion::MultipleOf(a, k) => write!(f, "({})%{}==0", a, k),
+        }
+    }
+}
+
+/// A named dimension, unknown until run time, belonging to a `SymbolScope`.
+#[derive(Clone)]
+pub struct Symbol {
+    scope: SymbolScope,
+    name: Arc<str>,
+}
+
+impl Symbol {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn scope(&self) -> &SymbolScope {
+        &self.scope
+    }
+}
+
+impl PartialEq for Symbol {
+    fn eq(&self, other: &Symbol) -> bool {
+        self.scope.same_as(&other.scope) && self.name == other.name
+    }
+}
+
+impl Eq for Symbol {}
+
+impl std::hash::Hash for Symbol {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.name.hash(state)
+    }
+}
+
+impl PartialOrd for Symbol {
+    fn partial_cmp(&self, other: &Symbol) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Symbol {
+    fn cmp(&self, other: &Symbol) -> std::cmp::Ordering {
+        self.name.cmp(&other.name).then(self.scope.id().cmp(&other.scope.id()))
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+impl fmt::Debug for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SymbolValues(HashMap<Symbol, Option<i64>>);
+
+impl SymbolValues {
+    pub fn with(mut self, s: Symbol, v: i64) -> Self {
+        self[s] = Some(v);
+        self
+    }
+
+    /// The symbols that have a value.
+    pub fn iter(&self) -> impl Iterator<Item = (&Symbol, i64)> {
+        self.0.iter().filter_map(|(s, v)| v.map(|v| (s, v)))
+    }
+}
+
+impl std::ops::Index<Symbol> for SymbolValues {
+    type Output = Option<i64>;
+    fn index(&self, index: Symbol) -> &Self::Output {
+        self.0.get(&index).unwrap_or(&None)
+    }
+}
+
+impl std::ops::IndexMut<Symbol> for SymbolValues {
+    fn index_mut(&mut self, index: Symbol) -> &mut Self::Output {
+        self.0.entry(index).or_default()
+    }
+}
+
+/// Parses integer expressions only: symbols can only be parsed in a scope, with
+/// `SymbolScope::parse_tdim`.
+impl std::str::FromStr for TDim {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> anyhow::Result<TDim> {
+        let dim = SymbolScope::default().parse_tdim(s)?;
+        anyhow::ensure!(
+            dim.symbols().is_empty(),
+            "Can't parse {} as TDim without a symbol scope",
+            s
+        );
+        Ok(dim)
+    }
+}
+
+impl TDim {
+    /// Parse a dimension expression with the syntax of `SymbolScope::parse_tdim`, looking up
+    /// symbols by name with `symbol`.
+    pub fn parse_with(s: &str, symbol: impl Fn(&str) -> Symbol) -> anyhow::Result<TDim> {
+        let mut parser =
+            Parser { symbol: &symbol, chars: s.chars().filter(|c| !c.is_whitespace()).peekable() };
+        let dim = parser.expr()?;
+        if let Some(c) = parser.chars.next() {
+            anyhow::bail!("Can't parse {} as TDim: unexpected {:?}", s, c)
+        }
+        Ok(dim)
+    }
+}
+
+struct Parser<'s, I: Iterator<Item = char>> {
+    symbol: &'s dyn Fn(&str) -> Symbol,
+    chars: std::iter::Peekable<I>,
+}
+
+impl<'s, I: Iterator<Item = char>> Parser<'s, I> {
+    fn expr(&mut self) -> anyhow::Result<TDim> {
+        let mut dim = self.term()?;
+        loop {
+            match self.chars.peek() {
+                Some('+') => {
+                    self.chars.next();
+                    dim = dim + self.term()?;
+                }
+                Some('-') => {
+                    self.chars.next();
+                    dim = dim - self.term()?;
+                }
+                _ => return Ok(dim),
+            }
+        }
+    }
+
+    fn term(&mut self) -> anyhow::Result<TDim> {
+        let mut dim = self.atom()?;
+        loop {
+            match self.chars.peek() {
+                Some('*') => {
+                    self.chars.next();
+                    dim = dim * self.atom()?;
+                }
+                Some('/') => {
+                    self.chars.next();
+                    dim = dim / self.divisor()?;
+                }
+                Some('%') => {
+                    self.chars.next();
+                    dim = dim % self.divisor()?;
+                }
+                _ => return Ok(dim),
+            }
+        }
+    }
+
+    fn atom(&mut self) -> anyhow::Result<TDim> {
+        match self.chars.peek().copied() {
+            Some('(') => {
+                self.chars.next();
+                let dim = self.expr()?;
+                self.expect(')')?;
+                Ok(dim)
+            }
+            Some(c) if c.is_alphabetic() || c == '_' => {
+                let mut name = String::new();
+                while let Some(c) = self.chars.peek().filter(|c| c.is_alphanumeric() || **c == '_')
+                {
+                    name.push(*c);
+                    self.chars.next();
+                }
+                if self.chars.peek() != Some(&'(') {
+                    return Ok((self.symbol)(&name).into());
+                }
+                self.chars.next();
+                match &*name {
+                    "min" | "max" => {
+                        let mut terms = vec![self.expr()?];
+                        while self.chars.peek() == Some(&',') {
+                            self.chars.next();
+                            terms.push(self.expr()?);
+                        }
+                        self.expect(')')?;
+                        let dim = if name == "min" { TDim::Min(terms) } else { TDim::Max(terms) };
+                        Ok(dim.reduce())
+                    }
+                    "ceil" => {
+                        self.expect('(')?;
+                        let dim = self.expr()?;
+                        self.expect(')')?;
+                        self.expect('/')?;
+                        let q = self.divisor()?;
+                        self.expect(')')?;
+                        Ok(dim.div_ceil(q))
+                    }
+                    _ => anyhow::bail!("Unknown function {}", name),
+                }
+            }
+            Some(_) => Ok(TDim::Val(self.int()?)),
+            None => anyhow::bail!("Unexpected end of expression"),
+        }
+    }
+
+    fn expect(&mut self, c: char) -> anyhow::Result<()> {
+        match self.chars.next() {
+            Some(found) if found == c => Ok(()),
+            found => anyhow::bail!("Expected {:?}, found {:?}", c, found),
+        }
+    }
+
+    fn divisor(&mut self) -> anyhow::Result<u64> {
+        let q = self.int()?;
+        anyhow::ensure!(q > 0, "Can only divide by a positive integer");
+        Ok(q as u64)
+    }
+
+    fn int(&mut self) -> anyhow::Result<i64> {
+        let mut digits = String::new();
+        if self.chars.peek() == Some(&'-') {
+            digits.push(self.chars.next().unwrap());
+        }
+        while let Some(c) = self.chars.peek().filter(|c| c.is_digit(10)) {
+            digits.push(*c);
+            self.chars.next();
+        }
+        Ok(digits.parse::<i64>()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scopes_do_not_mix() {
+        let (a, b) = (SymbolScope::default(), SymbolScope::default());
+        assert_eq!(a.sym("batch_size"), a.sym("batch_size"));
+        assert_ne!(a.sym("batch_size"), b.sym("batch_size"));
+        let values = SymbolValues::default().with(a.sym("batch_size"), 4);
+        assert_eq!(values[a.sym("batch_size")], Some(4));
+        assert_eq!(values[b.sym("batch_size")], None);
+    }
+
+    #[test]
+    fn fresh_names() {
+        let scope = SymbolScope::default();
+        let l = scope.sym("l");
+        assert_eq!(scope.new_with_prefix("l").name(), "l1");
+        assert_eq!(scope.new_with_prefix("x").name(), "x");
+        assert_eq!(scope.sym("l"), l);
+    }
+
+    #[test]
+    fn parse_display() {
+        let scope = SymbolScope::default();
+        let (a, b): (TDim, TDim) = (scope.sym("seq_len").into(), scope.sym("b").into());
+        for dim in &[
+            a.clone(),
+            TDim::from(-3),
+            a.clone() + -2,
+            (a.clone() + 1) * 3,
+            (a.clone() - 2) * (b.clone() + 1),
+            (a.clone() * 2 + b.clone()).div_ceil(4),
+            a.clone() * b.clone() - 7,
+            a.clone().mini(b.clone() + 2),
+            (a.clone() - 1).maxi(b.clone() * 2).maxi(3.into()),
+            (a.clone() + 3) % 4 * 2,
+            (a.clone() % 3) / 2 + b.clone().div_ceil(2),
+            a.clone().mini(b.clone()).div_ceil(3) * b.clone(),
+            b.clone() * (a.clone() / 3),
+            (a.clone() * 2) / 3,
+        ] {
+            let parsed = scope.parse_tdim(&dim.to_string()).unwrap();
+            for (x, y) in &[(5, 7), (10, 3)] {
+                let values =
+                    SymbolValues::default().with(scope.sym("seq_len"), *x).with(scope.sym("b"), *y);
+                assert_eq!(parsed.eval(&values), dim.eval(&values), "{}", dim);
+            }
+        }
+        assert!(scope.parse_tdim("a+").is_err());
+        assert!(scope.parse_tdim("(a+1").is_err());
+        assert!(scope.parse_tdim("floor(a)").is_err());
+        assert_eq!(scope.parse_tdim("min(seq_len,b)/2").unwrap(), (a.mini(b.clone())) / 2);
+        assert_eq!(scope.parse_tdim("2*b%3").unwrap(), (b.clone() * 2) % 3);
+        assert_eq!(scope.parse_tdim("2*b/3").unwrap(), (b.clone() * 2) / 3);
+        assert_eq!(scope.parse_tdim("b/3*2").unwrap(), (b / 3) * 2);
+        assert_eq!("(3+1)*2".parse::<TDim>().unwrap(), 8.into());
+        assert!("a".parse::<TDim>().is_err());
+    }
+
+    #[test]
+    fn assert_bounds() {
+        let scope = SymbolScope::default();
+        let n: TDim = scope.sym("n").into();
+        assert_eq!(n.clone().maxi(0.into()), n);
+        assert_ne!(n.clone().mini(16.into()), n);
+        scope.add_assertion(scope.parse_assertion("n<=16").unwrap()).unwrap();
+        scope.add_assertion(scope.parse_assertion("n>2").unwrap()).unwrap();
+        assert_eq!(n.clone().mini(16.into()), n);
+        assert_eq!(n.clone().maxi(3.into()), n);
+        assert_eq!((n.clone() - 3).maxi(0.into()), n.clone() - 3);
+        assert_eq!(n.inclusive_bounds(), (Some(3), Some(16)));
+        assert!((n.clone() - 3).prove_positive_or_zero());
+        assert!(!(n.clone() - 4).prove_positive_or_zero());
+    }
+
+    #[test]
+    fn assert_relation() {
+        let scope = SymbolScope::default();
+        let (n, m): (TDim, TDim) = (scope.sym("n").into(), scope.sym("m").into());
+        assert!(!(n.clone() - &m).prove_positive_or_zero());
+        scope.add_assertion(scope.parse_assertion("n>=m+2").unwrap()).unwrap();
+        assert!((n.clone() - &m).prove_strict_positive());
+        assert_eq!(n.clone().maxi(m.clone()), n);
+        assert_eq!(n.clone().mini(m.clone() + 1), m + 1);
+    }
+
+    #[test]
+    fn assert_multiple() {
+        let scope = SymbolScope::default();
+        let n: TDim = scope.sym("n").into();
+        scope.add_assertion(scope.parse_assertion("(n)%4==0").unwrap()).unwrap();
+        assert_eq!(n.clone() % 2, 0.into());
+        assert_eq!((n.clone() + 1) % 4, 1.into());
+        assert_eq!(n.clone().div_ceil(4), n.clone() / 4);
+        assert_eq!(n.clone() / 4 * 4, n);
+        assert_eq!(n.clone() / 2 * 6, n * 3);
+    }
+
+    #[test]
+    fn assert_equal() {
+        let scope = SymbolScope::default();
+        let (a, b): (TDim, TDim) = (scope.sym("a").into(), scope.sym("b").into());
+        scope.add_assertion(scope.parse_assertion("b==2*a").unwrap()).unwrap();
+        assert_eq!(b.clone() - &a, a.clone());
+        assert_eq!(b.clone().reduce(), a.clone() * 2);
+        assert!(scope.add_assertion(scope.parse_assertion("a==a+1").unwrap()).is_err());
+    }
+
+    #[test]
+    fn assert_check() {
+        let scope = SymbolScope::default();
+        scope.add_assertion(scope.parse_assertion("n>=m").unwrap()).unwrap();
+        scope.add_assertion(scope.parse_assertion("(n)%8==0").unwrap()).unwrap();
+        let values = |n, m| SymbolValues::default().with(scope.sym("n"), n).with(scope.sym("m"), m);
+        assert!(scope.check(&values(16, 3)).is_ok());
+        assert!(scope.check(&values(16, 17)).is_err());
+        assert!(scope.check(&values(12, 3)).is_err());
+        assert!(scope.check(&SymbolValues::default().with(scope.sym("m"), 3)).is_ok());
+        for assertion in scope.assertions() {
+            assert_eq!(scope.parse_assertion(&assertion.to_string()).unwrap(), assertion);
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use super::sym::{Snapshot, Symbol, SymbolValues};
 use itertools::Itertools;
 use num_traits::{AsPrimitive, PrimInt, Zero};
 use std::collections::HashMap;
@@ -16,69 +17,6 @@ impl std::error::Error for UndeterminedSymbol {}
 
 macro_rules! b( ($e:expr) => { Box::new($e) } );
 
-lazy_static::lazy_static! {
-    static ref SYMBOL_TABLE: std::sync::Mutex<Vec<char>> = std::sync::Mutex::new(Vec::new());
-}
-
-#[derive(Copy, Clone, PartialEq, Eq, Ord, PartialOrd, Hash, Debug)]
-pub struct Symbol(char, usize);
-
-impl Symbol {
-    pub fn new(c: char) -> Symbol {
-        let mut table = SYMBOL_TABLE.lock().unwrap();
-        table.push(c);
-        Symbol(c, table.len() - 1)
-    }
-}
-
-impl From<char> for Symbol {
-    fn from(c: char) -> Symbol {
-        let mut table = SYMBOL_TABLE.lock().unwrap();
-        if let Some(pos) = table.iter().position(|s| *s == c) {
-            Symbol(c, pos)
-        } else {
-            table.push(c);
-            Symbol(c, table.len() - 1)
-        }
-    }
-}
-
-impl From<char> for TDim {
-    fn from(c: char) -> TDim {
-        Symbol::from(c).into()
-    }
-}
-
-#[derive(Clone, Debug, Default)]
-pub struct SymbolValues(Vec<Option<i64>>);
-
-impl SymbolValues {
-    pub fn with(mut self, s: Symbol, v: i64) -> Self {
-        self[s] = Some(v);
-        self
-    }
-}
-
-impl std::ops::Index<Symbol> for SymbolValues {
-    type Output = Option<i64>;
-    fn index(&self, index: Symbol) -> &Self::Output {
-        if index.1 < self.0.len() {
-            &self.0[index.1]
-        } else {
-            &None
-        }
-    }
-}
-
-impl std::ops::IndexMut<Symbol> for SymbolValues {
-    fn index_mut(&mut self, index: Symbol) -> &mut Self::Output {
-        if index.1 >= self.0.len() {
-            self.0.resize_with(index.1 + 1, Default::default)
-        }
-        &mut self.0[index.1]
-    }
-}
-
 #[derive(Clone, PartialEq, Eq, Ord, PartialOrd, Hash, Debug)]
 pub enum TDim {
     Sym(Symbol),
@@ -87,6 +25,10 @@ pub enum TDim {
     Mul(Vec<TDim>),
     MulInt(i64, Box<TDim>),
     Div(Box<TDim>, u64),
+    Min(Vec<TDim>),
+    Max(Vec<TDim>),
+    Mod(Box<TDim>, u64),
+    DivCeil(Box<TDim>, u64),
 }
 
 use TDim::*;
@@ -94,17 +36,30 @@ use TDim::*;
 impl fmt::Display for TDim {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         match &self {
-            Sym(sym) => write!(fmt, "{}", sym.0),
+            Sym(sym) => write!(fmt, "{}", sym),
             Val(it) => write!(fmt, "{}", it),
             Add(it) => write!(fmt, "{}", it.iter().map(|x| format!("{}", x)).join("+")),
-            Mul(it) => write!(fmt, "{}", it.iter().map(|x| format!("{}", x)).join("*")),
-            MulInt(a, b) => write!(fmt, "{}*{}", a, b),
+            Mul(it) => write!(fmt, "{}", it.iter().map(|x| x.factor_string()).join("*")),
+            MulInt(a, b) => write!(fmt, "{}*{}", a, b.factor_string()),
             Div(a, b) => write!(fmt, "({})/{}", a, b),
+            Min(it) => write!(fmt, "min({})", it.iter().join(",")),
+            Max(it) => write!(fmt, "max({})", it.iter().join(",")),
+            Mod(a, b) => write!(fmt, "({})%{}", a, b),
+            DivCeil(a, b) => write!(fmt, "ceil(({})/{})", a, b),
         }
     }
 }
 
 impl TDim {
+    /// Display, with parentheses around sums, divisions and remainders so that the result
+    /// can be parsed back: `*`, `/` and `%` have the same precedence.
+    fn factor_string(&self) -> String {
+        match self {
+            Add(_) | Div(..) | Mod(..) => format!("({})", self),
+            _ => format!("{}", self),
+        }
+    }
+
     pub fn is_one(&self) -> bool {
         self == &Val(1)
     }
@@ -119,22 +74,27 @@ impl TDim {
 
     pub fn eval(&self, values: &SymbolValues) -> TDim {
         match self {
-            Sym(sym) => values[*sym].map(|s| Val(s)).unwrap_or(Sym(*sym)),
+            Sym(sym) => values[sym.clone()].map(|s| Val(s)).unwrap_or_else(|| Sym(sym.clone())),
             Val(v) => Val(*v),
             Add(terms) => terms.iter().fold(Val(0), |acc, it| -> TDim { acc + it.eval(values) }),
             Mul(terms) => terms.iter().fold(Val(1), |acc, it| -> TDim { acc * it.eval(values) }),
             Div(a, q) => a.eval(values) / *q as i64,
             MulInt(p, a) => a.eval(values) * *p,
+            Min(terms) => Min(terms.iter().map(|t| t.eval(values)).collect()).reduce(),
+            Max(terms) => Max(terms.iter().map(|t| t.eval(values)).collect()).reduce(),
+            Mod(a, q) => Mod(b!(a.eval(values)), *q).reduce(),
+            DivCeil(a, q) => DivCeil(b!(a.eval(values)), *q).reduce(),
         }
     }
 
     pub fn reduce(self) -> TDim {
-        self.simplify()
-            .wiggle()
+        let facts = Snapshot::of(&self);
+        self.simplify_with(&facts)
+            .wiggle(&facts)
             .into_iter()
             .sorted()
             .unique()
-            .map(|e| e.simplify())
+            .map(|e| e.simplify_with(&facts))
             .min_by_key(|e| e.cost())
             .unwrap()
     }
@@ -145,50 +105,53 @@ impl TDim {
             Sym(_) | Val(_) => 1,
             Add(terms) => 2 * terms.iter().map(TDim::cost).sum::<usize>(),
             Mul(terms) => 3 * terms.iter().map(TDim::cost).sum::<usize>(),
-            Div(a, _) => 3 * a.cost(),
+            Div(a, _) | Mod(a, _) | DivCeil(a, _) => 3 * a.cost(),
             MulInt(_, a) => 2 * a.cost(),
+            Min(terms) | Max(terms) => 2 * terms.iter().map(TDim::cost).sum::<usize>(),
         }
     }
 
-    fn wiggle(&self) -> Vec<TDim> {
+    fn wiggle(&self, facts: &Snapshot) -> Vec<TDim> {
         use self::TDim::*;
         match self {
-            Sym(_) | Val(_) | Mul(_) => vec![self.clone()],
+            Sym(_) | Val(_) | Mul(_) | Min(_) | Max(_) | Mod(..) | DivCeil(..) => {
+                vec![self.clone()]
+            }
             Add(terms) => {
                 let mut forms = vec![];
-                let sub_wiggle = terms.iter().map(|e| e.wiggle()).multi_cartesian_product();
+                let sub_wiggle = terms.iter().map(|e| e.wiggle(facts)).multi_cartesian_product();
                 for sub in sub_wiggle {
-                    for (ix, num, q) in sub.iter().enumerate().find_map(|(ix, t)| {
-                        if let Div(a, q) = t {
-                            Some((ix, a, q))
-                        } else {
-                            None
+                    for (ix, num, q) in sub.iter().enumerate().find_map(|(ix, t)| match t {
+                        Div(a, q) => Some((ix, (**a).clone(), *q)),
+                        DivCeil(a, q) => {
+                            Some((ix, Add(vec![(**a).clone(), Val(*q as i64 - 1)]), *q))
                         }
+                        _ => None,
                     }) {
                         let new_num = sub
                             .iter()
                             .enumerate()
                             .map(|(ix2, t)| {
                                 if ix2 != ix {
-                                    MulInt(*q as i64, b!(t.clone()))
+                                    MulInt(q as i64, b!(t.clone()))
                                 } else {
-                                    (**num).clone()
+                                    num.clone()
                                 }
                             })
                             .collect();
-                        forms.push(Div(b!(Add(new_num)), *q))
+                        forms.push(Div(b!(Add(new_num)), q))
                     }
                     forms.push(Add(sub.into()));
                 }
                 forms
             }
-            MulInt(p, a) => a.wiggle().into_iter().map(|a| MulInt(*p, b!(a))).collect(),
+            MulInt(p, a) => a.wiggle(facts).into_iter().map(|a| MulInt(*p, b!(a))).collect(),
             Div(a, q) => {
                 let mut forms = vec![];
-                for num in a.wiggle() {
+                for num in a.wiggle(facts) {
                     if let Add(terms) = &num {
                         let (integer, non_integer): (Vec<_>, Vec<_>) =
-                            terms.into_iter().cloned().partition(|a| a.gcd() % q == 0);
+                            terms.into_iter().cloned().partition(|a| a.gcd(facts) % q == 0);
                         let mut new_terms = integer.iter().map(|i| i.div(*q)).collect::<Vec<_>>();
                         if non_integer.len() > 0 {
                             new_terms.push(Div(b!(Add(non_integer)), *q));
@@ -203,6 +166,11 @@ impl TDim {
     }
 
     pub fn simplify(self) -> TDim {
+        let facts = Snapshot::of(&self);
+        self.simplify_with(&facts)
+    }
+
+    fn simplify_with(self, facts: &Snapshot) -> TDim {
         use self::TDim::*;
         use num_integer::Integer;
         match self {
@@ -210,7 +178,7 @@ impl TDim {
                 let mut reduced: HashMap<TDim, i64> = HashMap::new();
                 // factorize common sub-expr
                 while let Some(item) = terms.pop() {
-                    let term = item.simplify();
+                    let term = item.simplify_with(facts);
                     match term {
                         Add(items) => {
                             terms.extend(items.into_iter());
@@ -249,7 +217,7 @@ impl TDim {
             }
             Mul(terms) => {
                 let (ints, mut rest): (i64, Vec<TDim>) =
-                    terms.into_iter().fold((1, vec![]), |acc, t| match t.simplify() {
+                    terms.into_iter().fold((1, vec![]), |acc, t| match t.simplify_with(facts) {
                         MulInt(a, p) => {
                             (acc.0 * a, acc.1.into_iter().chain(Some(p.as_ref().clone())).collect())
                         }
@@ -273,34 +241,47 @@ impl TDim {
             }
             MulInt(p, a) => {
                 if let MulInt(p2, a) = *a {
-                    return MulInt(p * p2, a).simplify();
+                    return MulInt(p * p2, a).simplify_with(facts);
                 } else if let Val(p2) = *a {
                     return Val(p * p2);
                 }
-                let a = a.simplify();
+                let a = a.simplify_with(facts);
                 if p == 0 {
                     Val(0)
                 } else if p == 1 {
                     a
                 } else if let Add(terms) = &a {
-                    Add(terms.clone().into_iter().map(|a| MulInt(p, b!(a)).simplify()).collect())
+                    Add(terms
+                        .clone()
+                        .into_iter()
+                        .map(|a| MulInt(p, b!(a)).simplify_with(facts))
+                        .collect())
                 } else if let Val(p2) = a {
                     Val(p * p2)
                 } else if let MulInt(p2, a) = a {
                     MulInt(p * p2, a)
+                } else if matches!(&a, Div(n, q) if p % *q as i64 == 0 && n.gcd(facts) % *q == 0) {
+                    // the division is exact
+                    if let Div(n, q) = a {
+                        MulInt(p / q as i64, n).simplify_with(facts)
+                    } else {
+                        unreachable!()
+                    }
                 } else {
                     MulInt(p, b!(a))
                 }
             }
             Div(a, q) => {
                 if q == 1 {
-                    return a.simplify();
+                    return a.simplify_with(facts);
                 } else if let Div(a, q2) = *a {
-                    return Div(a, q * q2).simplify();
+                    return Div(a, q * q2).simplify_with(facts);
                 }
-                let a = a.simplify();
+                let a = a.simplify_with(facts);
                 if let Val(a) = a {
                     Val(a / q as i64)
+                } else if matches!(&a, Mod(_, q2) if *q2 <= q) {
+                    Val(0)
                 } else if let MulInt(-1, a) = a {
                     MulInt(-1, b!(Div(a, q)))
                 } else if let Add(mut terms) = a {
@@ -319,7 +300,7 @@ impl TDim {
                             -1,
                             b!(Div(
                                 b!(Add(terms.into_iter().map(|t| MulInt(-1, b!(t))).collect())
-                                    .simplify()),
+                                    .simplify_with(facts)),
                                 q
                             )),
                         )
@@ -337,7 +318,11 @@ impl TDim {
                         };
                         if let Some(val) = offset {
                             terms.push(Val(-val * q as i64));
-                            Add(vec![Val(val), Div(b!(Add(terms).simplify()), q)])
+                            Add(vec![Val(val), Div(b!(Add(terms).simplify_with(facts)), q)])
+                        } else if v == q as i64 - 1 && !Add(terms.clone()).has_division() {
+                            // (a+q-1)/q is ceil(a/q)
+                            terms.retain(|t| t != &Val(v));
+                            DivCeil(b!(Add(terms).simplify_with(facts)), q)
                         } else {
                             Div(b!(Add(terms)), q)
                         }
@@ -346,7 +331,7 @@ impl TDim {
                     }
                 } else if let MulInt(p, a) = a {
                     if p == q as i64 {
-                        a.simplify()
+                        a.simplify_with(facts)
                     } else {
                         let gcd = p.abs().gcd(&(q as i64));
                         if gcd == p {
@@ -354,7 +339,7 @@ impl TDim {
                         } else if gcd == q as i64 {
                             MulInt(p / gcd, a)
                         } else if gcd > 1 {
-                            Div(b!(MulInt(p / gcd, a)), q / gcd as u64).simplify()
+                            Div(b!(MulInt(p / gcd, a)), q / gcd as u64).simplify_with(facts)
                         } else {
                             Div(b!(MulInt(p, a)), q)
                         }
@@ -363,25 +348,177 @@ impl TDim {
                     Div(b!(a), q)
                 }
             }
+            Min(terms) => Self::simplify_extremum(terms, false, facts),
+            Max(terms) => Self::simplify_extremum(terms, true, facts),
+            Mod(a, q) => {
+                if q == 1 {
+                    return Val(0);
+                } else if let Mod(a, q2) = *a {
+                    if q2 % q == 0 {
+                        return Mod(a, q).simplify_with(facts);
+                    } else if q2 <= q {
+                        return Mod(a, q2).simplify_with(facts);
+                    }
+                    return Mod(b!(Mod(a, q2).simplify_with(facts)), q);
+                }
+                let a = a.simplify_with(facts);
+                if let Val(v) = a {
+                    Val(v.rem_euclid(q as i64))
+                } else if a.gcd(facts) % q == 0 {
+                    Val(0)
+                } else if let Add(terms) = a {
+                    // multiples of q do not change the remainder
+                    let terms = terms
+                        .into_iter()
+                        .filter_map(|t| match t {
+                            Val(v) => Some(Val(v.rem_euclid(q as i64))),
+                            t if t.gcd(facts) % q == 0 => None,
+                            t => Some(t),
+                        })
+                        .collect();
+                    match Add(terms).simplify_with(facts) {
+                        Val(v) => Val(v.rem_euclid(q as i64)),
+                        a => Mod(b!(a), q),
+                    }
+                } else {
+                    Mod(b!(a), q)
+                }
+            }
+            DivCeil(a, q) => {
+                if q == 1 {
+                    return a.simplify_with(facts);
+                } else if let DivCeil(a, q2) = *a {
+                    return DivCeil(a, q * q2).simplify_with(facts);
+                }
+                let a = a.simplify_with(facts);
+                if let Val(v) = a {
+                    Val(Integer::div_ceil(&v, &(q as i64)))
+                } else if a.has_division() {
+                    // let the floor division rules deal with nested divisions
+                    Div(b!(Add(vec![a, Val(q as i64 - 1)])), q).simplify_with(facts)
+                } else if a.gcd(facts) % q == 0 {
+                    a.div(q)
+                } else if let Add(terms) = a {
+                    // pull out the multiples of q, and the constant offset r as
+                    // ceil((a+r)/q) == 1+(a+r-1)/q to keep a single form for each expression
+                    let mut integer = vec![];
+                    let mut rest = vec![];
+                    let mut offset = 0;
+                    for t in terms {
+                        match t {
+                            Val(v) => {
+                                offset = v.rem_euclid(q as i64);
+                                integer.push(Val((v - offset) / q as i64));
+                            }
+                            t if t.gcd(facts) % q == 0 => integer.push(t.div(q)),
+                            t => rest.push(t),
+                        }
+                    }
+                    if integer.len() == 0 {
+                        DivCeil(b!(Add(rest)), q)
+                    } else if offset == 0 {
+                        integer.push(
+                            DivCeil(b!(Add(rest).simplify_with(facts)), q).simplify_with(facts),
+                        );
+                        Add(integer).simplify_with(facts)
+                    } else {
+                        rest.push(Val(offset - 1));
+                        integer.push(Val(1));
+                        integer
+                            .push(Div(b!(Add(rest).simplify_with(facts)), q).simplify_with(facts));
+                        Add(integer).simplify_with(facts)
+                    }
+                } else {
+                    DivCeil(b!(a), q)
+                }
+            }
+            Sym(s) => {
+                if let Some(value) = facts.substitution(&s) {
+                    value.simplify_with(facts)
+                } else {
+                    Sym(s)
+                }
+            }
             _ => self,
         }
     }
 
-    fn gcd(&self) -> u64 {
+    /// Is this a division, or a sum with a division among its terms?
+    fn has_division(&self) -> bool {
+        match self {
+            Div(..) | DivCeil(..) => true,
+            MulInt(_, a) => a.has_division(),
+            Add(terms) => terms.iter().any(|t| t.has_division()),
+            _ => false,
+        }
+    }
+
+    /// Simplify a `Min`, or a `Max` if `max` is set: nested extrema of the same kind are
+    /// flattened, and of two operands that can be compared only the relevant one is kept.
+    fn simplify_extremum(terms: Vec<TDim>, max: bool, facts: &Snapshot) -> TDim {
+        let mut todo = terms;
+        let mut kept: Vec<TDim> = vec![];
+        'terms: while let Some(term) = todo.pop() {
+            let term = match term.simplify_with(facts) {
+                Min(nested) if !max => {
+                    todo.extend(nested.into_iter());
+                    continue;
+                }
+                Max(nested) if max => {
+                    todo.extend(nested.into_iter());
+                    continue;
+                }
+                it => it,
+            };
+            for k in kept.iter_mut() {
+                let diff = Add(vec![term.clone(), MulInt(-1, b!(k.clone()))]).simplify_with(facts);
+                if diff.prove_positive_or_zero_with(facts) {
+                    if max {
+                        *k = term;
+                    }
+                    continue 'terms;
+                } else if MulInt(-1, b!(diff))
+                    .simplify_with(facts)
+                    .prove_positive_or_zero_with(facts)
+                {
+                    if !max {
+                        *k = term;
+                    }
+                    continue 'terms;
+                }
+            }
+            kept.push(term);
+        }
+        kept.sort();
+        if kept.len() == 1 {
+            kept.remove(0)
+        } else if max {
+            Max(kept)
+        } else {
+            Min(kept)
+        }
+    }
+
+    fn gcd(&self, facts: &Snapshot) -> u64 {
         use self::TDim::*;
         use num_integer::Integer;
         match self {
             Val(v) => v.abs() as u64,
-            Sym(_) => 1,
+            Sym(s) => facts.multiple_of(s),
             Add(terms) => {
                 let (head, tail) = terms.split_first().unwrap();
-                tail.iter().fold(head.gcd(), |a, b| a.gcd(&b.gcd()))
+                tail.iter().fold(head.gcd(facts), |a, b| a.gcd(&b.gcd(facts)))
             }
-            MulInt(p, a) => a.gcd() * p.abs() as u64,
+            MulInt(p, a) => a.gcd(facts) * p.abs() as u64,
             Mul(_) => 1,
-            Div(a, q) => {
-                if a.gcd() % *q == 0 {
-                    a.gcd() / *q
+            Min(terms) | Max(terms) => {
+                let (head, tail) = terms.split_first().unwrap();
+                tail.iter().fold(head.gcd(facts), |a, b| a.gcd(&b.gcd(facts)))
+            }
+            Mod(a, q) => a.gcd(facts).gcd(q),
+            Div(a, q) | DivCeil(a, q) => {
+                if a.gcd(facts) % *q == 0 {
+                    a.gcd(facts) / *q
                 } else {
                     1
                 }
@@ -397,7 +534,7 @@ impl TDim {
         }
         match self {
             Val(v) => Val(v / d as i64),
-            Sym(_) => panic!(),
+            Sym(_) => Div(b!(self.clone()), d),
             Add(terms) => Add(terms.iter().map(|t| t.div(d)).collect()),
             Mul(_) => Div(Box::new(self.clone()), d),
             MulInt(p, a) => {
@@ -409,18 +546,142 @@ impl TDim {
                 }
             }
             Div(a, q) => Div(a.clone(), q * d),
+            Min(terms) => Min(terms.iter().map(|t| t.div(d)).collect()),
+            Max(terms) => Max(terms.iter().map(|t| t.div(d)).collect()),
+            Mod(..) | DivCeil(..) => Div(Box::new(self.clone()), d),
         }
     }
 
     pub fn div_ceil(self, rhs: u64) -> TDim {
-        TDim::Div(Box::new(Add(vec![self, Val(rhs as i64 - 1)])), rhs).reduce()
+        TDim::DivCeil(Box::new(self), rhs).reduce()
     }
 
-    pub fn slope(&self, sym: Symbol) -> (i64, u64) {
-        fn slope_rec(d: &TDim, sym: Symbol) -> (i64, i64) {
+    /// Smallest of `self` and `other`.
+    pub fn mini(self, other: TDim) -> TDim {
+        TDim::Min(vec![self, other]).reduce()
+    }
+
+    /// Largest of `self` and `other`.
+    pub fn maxi(self, other: TDim) -> TDim {
+        TDim::Max(vec![self, other]).reduce()
+    }
+
+    /// Lower and upper bounds of the expression values, as far as the symbols assertions tell.
+    ///
+    /// Symbols stand for dimensions, so they are assumed positive or zero unless asserted
+    /// otherwise.
+    pub fn inclusive_bounds(&self) -> (Option<i64>, Option<i64>) {
+        self.inclusive_bounds_with(&Snapshot::of(self))
+    }
+
+    fn inclusive_bounds_with(&self, facts: &Snapshot) -> (Option<i64>, Option<i64>) {
+        use num_integer::Integer;
+        fn fold(
+            bounds: impl Iterator<Item = Option<i64>>,
+            f: impl Fn(i64, i64) -> i64,
+            all: bool,
+        ) -> Option<i64> {
+            let mut acc: Option<i64> = None;
+            for b in bounds {
+                match (acc, b) {
+                    (_, None) if all => return None,
+                    (_, None) => (),
+                    (None, Some(b)) => acc = Some(b),
+                    (Some(a), Some(b)) => acc = Some(f(a, b)),
+                }
+            }
+            acc
+        }
+        match self {
+            Val(v) => (Some(*v), Some(*v)),
+            Sym(s) => (Some(facts.lower_bound(s)), facts.upper_bound(s)),
+            Add(terms) => {
+                let bounds: Vec<_> = terms.iter().map(|t| t.inclusive_bounds_with(facts)).collect();
+                (
+                    fold(bounds.iter().map(|b| b.0), |a, b| a + b, true),
+                    fold(bounds.iter().map(|b| b.1), |a, b| a + b, true),
+                )
+            }
+            MulInt(p, a) => {
+                let (low, high) = a.inclusive_bounds_with(facts);
+                let (low, high) = (low.map(|l| l * p), high.map(|h| h * p));
+                if *p >= 0 {
+                    (low, high)
+                } else {
+                    (high, low)
+                }
+            }
+            Mul(terms) => {
+                let bounds: Vec<_> = terms.iter().map(|t| t.inclusive_bounds_with(facts)).collect();
+                if bounds.iter().all(|b| b.0.map(|l| l >= 0).unwrap_or(false)) {
+                    (
+                        fold(bounds.iter().map(|b| b.0), |a, b| a * b, true),
+                        fold(bounds.iter().map(|b| b.1), |a, b| a * b, true),
+                    )
+                } else {
+                    (None, None)
+                }
+            }
+            Div(a, q) => {
+                let (low, high) = a.inclusive_bounds_with(facts);
+                (low.map(|l| l / *q as i64), high.map(|h| h / *q as i64))
+            }
+            DivCeil(a, q) => {
+                let (low, high) = a.inclusive_bounds_with(facts);
+                let q = *q as i64;
+                (low.map(|l| l.div_ceil(&q)), high.map(|h| h.div_ceil(&q)))
+            }
+            Mod(_, q) => (Some(0), Some(*q as i64 - 1)),
+            Min(terms) => {
+                let bounds: Vec<_> = terms.iter().map(|t| t.inclusive_bounds_with(facts)).collect();
+                (
+                    fold(bounds.iter().map(|b| b.0), i64::min, true),
+                    fold(bounds.iter().map(|b| b.1), i64::min, false),
+                )
+            }
+            Max(terms) => {
+                let bounds: Vec<_> = terms.iter().map(|t| t.inclusive_bounds_with(facts)).collect();
+                (
+                    fold(bounds.iter().map(|b| b.0), i64::max, false),
+                    fold(bounds.iter().map(|b| b.1), i64::max, true),
+                )
+            }
+        }
+    }
+
+    /// Can the expression be proven positive or zero from its bounds and the assertions on
+    /// its symbols?
+    pub fn prove_positive_or_zero(&self) -> bool {
+        self.prove_positive_or_zero_with(&Snapshot::of(self))
+    }
+
+    fn prove_positive_or_zero_with(&self, facts: &Snapshot) -> bool {
+        if self.inclusive_bounds_with(facts).0.map(|low| low >= 0).unwrap_or(false) {
+            return true;
+        }
+        let s = if let Some(s) = self.symbols().into_iter().next() {
+            s
+        } else {
+            return false;
+        };
+        facts.any_greater_or_equal(&s, |lhs, rhs| {
+            // self is (lhs - rhs) plus something positive
+            let rest = Add(vec![self.clone(), MulInt(-1, b!(lhs.clone())), rhs.clone()])
+                .simplify_with(facts);
+            rest.inclusive_bounds_with(facts).0.map(|low| low >= 0).unwrap_or(false)
+        })
+    }
+
+    /// Can the expression be proven strictly positive?
+    pub fn prove_strict_positive(&self) -> bool {
+        (self.clone() - 1).prove_positive_or_zero()
+    }
+
+    pub fn slope(&self, sym: &Symbol) -> (i64, u64) {
+        fn slope_rec(d: &TDim, sym: &Symbol) -> (i64, i64) {
             match d {
                 Val(_) => (0, 1),
-                Sym(s) => ((sym == *s) as i64, 1),
+                Sym(s) => ((sym == s) as i64, 1),
                 Add(terms) => terms
                     .iter()
                     .map(|d| slope_rec(d, sym))
@@ -433,10 +694,22 @@ impl TDim {
                     let (n, d) = slope_rec(a, sym);
                     (p * n, d)
                 }
-                Div(a, q) => {
+                Div(a, q) | DivCeil(a, q) => {
                     let (n, d) = slope_rec(a, sym);
                     (n, d * *q as i64)
                 }
+                Mod(..) => (0, 1),
+                // extrema of affine terms end up following the smallest (or largest) slope
+                Min(terms) => terms
+                    .iter()
+                    .map(|d| slope_rec(d, sym))
+                    .min_by(|a, b| (a.0 * b.1).cmp(&(b.0 * a.1)))
+                    .unwrap(),
+                Max(terms) => terms
+                    .iter()
+                    .map(|d| slope_rec(d, sym))
+                    .max_by(|a, b| (a.0 * b.1).cmp(&(b.0 * a.1)))
+                    .unwrap(),
             }
         }
         let (p, q) = slope_rec(self, sym);
@@ -446,13 +719,15 @@ impl TDim {
     pub fn symbols(&self) -> std::collections::HashSet<Symbol> {
         match self {
             Val(_) => maplit::hashset!(),
-            Sym(s) => maplit::hashset!(*s),
-            Add(terms) | Mul(terms) => terms.iter().fold(maplit::hashset!(), |mut set, v| {
-                set.extend(v.symbols().into_iter());
-                set
-            }),
+            Sym(s) => maplit::hashset!(s.clone()),
+            Add(terms) | Mul(terms) | Min(terms) | Max(terms) => {
+                terms.iter().fold(maplit::hashset!(), |mut set, v| {
+                    set.extend(v.symbols().into_iter());
+                    set
+                })
+            }
             MulInt(_, a) => a.symbols(),
-            Div(a, _) => a.symbols(),
+            Div(a, _) | Mod(a, _) | DivCeil(a, _) => a.symbols(),
         }
     }
 }
@@ -555,7 +830,7 @@ impl From<Symbol> for TDim {
 
 impl<'a> From<&'a Symbol> for TDim {
     fn from(it: &'a Symbol) -> Self {
-        TDim::Sym(*it)
+        TDim::Sym(it.clone())
     }
 }
 
@@ -681,7 +956,7 @@ impl<I: AsPrimitive<u64> + PrimInt> ops::Div<I> for TDim {
 
 impl<I: AsPrimitive<u64> + PrimInt> ops::RemAssign<I> for TDim {
     fn rem_assign(&mut self, rhs: I) {
-        *self += -(self.clone() / rhs.as_() * rhs.as_());
+        *self = TDim::Mod(Box::new(std::mem::take(self)), rhs.as_()).reduce()
     }
 }
 
@@ -693,20 +968,6 @@ impl<I: AsPrimitive<u64> + PrimInt> ops::Rem<I> for TDim {
     }
 }
 
-impl std::str::FromStr for TDim {
-    type Err = anyhow::Error;
-    fn from_str(s: &str) -> Result<TDim, Self::Err> {
-        let first = s.chars().next().unwrap();
-        if first.is_digit(10) || first == '-' {
-            Ok(s.parse::<i64>()?.into())
-        } else if first.is_alphabetic() && s.len() == 1 {
-            Ok(Symbol::from(first).into())
-        } else {
-            anyhow::bail!("Can't parse {} as TDim", s)
-        }
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -714,11 +975,11 @@ mod tests {
     macro_rules! b( ($e:expr) => { Box::new($e) } );
 
     lazy_static::lazy_static! {
-        static ref S: Symbol = crate::dim::Symbol::new('S');
+        static ref S: Symbol = crate::dim::SymbolScope::default().sym("S");
     }
 
     fn s() -> TDim {
-        (*S).into()
+        S.clone().into()
     }
 
     fn neg(a: &TDim) -> TDim {
@@ -799,9 +1060,9 @@ mod tests {
 
     #[test]
     fn substitution() {
-        let x = Symbol::new('x');
-        let e: TDim = x.into();
-        assert_eq!(e.eval(&SymbolValues::default().with(x, 2)).to_i64().unwrap(), 2);
+        let x = crate::dim::SymbolScope::default().sym("x");
+        let e: TDim = x.clone().into();
+        assert_eq!(e.eval(&SymbolValues::default().with(x.clone(), 2)).to_i64().unwrap(), 2);
         let e = e + 3;
         assert_eq!(e.eval(&SymbolValues::default().with(x, 2)).to_i64().unwrap(), 5);
     }
@@ -822,8 +1083,9 @@ mod tests {
     fn reduce_muls() {
         let e: TDim = Val(1) * s();
         assert_eq!(e, s());
-        let e: TDim = s() * 'b' * 1;
-        assert_eq!(e, s() * 'b');
+        let b: TDim = S.scope().sym("b").into();
+        let e: TDim = s() * &b * 1;
+        assert_eq!(e, s() * &b);
     }
 
     #[test]
@@ -919,4 +1181,38 @@ mod tests {
         let e = (s() - 3 + 1).div_ceil(1);
         assert_eq!(e, s() + -2);
     }
+
+    #[test]
+    fn reduce_min_max() {
+        assert_eq!(s().mini(s() + 2), s());
+        assert_eq!(s().maxi(s() + 2), s() + 2);
+        assert_eq!(TDim::from(3).mini(5.into()), 3.into());
+        assert_eq!(s().mini(3.into()).mini(2.into()), s().mini(2.into()));
+        assert_eq!(s().maxi(3.into()), TDim::from(3).maxi(s()));
+    }
+
+    #[test]
+    fn reduce_mod() {
+        assert_eq!((s() * 4 + 3) % 2, 1.into());
+        assert_eq!((s() + 7) % 2, (s() + 1) % 2);
+        assert_eq!(s() % 4 % 2, s() % 2);
+        assert_eq!(s() % 2 % 4, s() % 2);
+        assert_eq!((s() * 6) % 3, 0.into());
+    }
+
+    #[test]
+    fn reduce_div_ceil() {
+        assert_eq!((s() * 2 + 5).div_ceil(2), s() + 3);
+        assert_eq!((s() * 3 - 1).div_ceil(3), s());
+        assert_eq!(TDim::from(7).div_ceil(2), 4.into());
+    }
+
+    #[test]
+    fn eval_min_max_mod_div_ceil() {
+        let e = (s() % 3 + s().mini(10.into())).maxi(s().div_ceil(2));
+        for (x, expected) in &[(4, 5), (20, 12), (27, 14)] {
+            let values = SymbolValues::default().with(S.clone(), *x);
+            assert_eq!(e.eval(&values), TDim::from(*expected));
+        }
+    }
 }
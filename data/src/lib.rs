@@ -17,7 +17,7 @@ pub type TractResult<T> = anyhow::Result<T>;
 
 pub mod prelude {
     pub use crate::datum::{round_ties_to_even, Blob, Datum, DatumType, QParams};
-    pub use crate::dim::{Symbol, SymbolValues, TDim, ToDim};
+    pub use crate::dim::{Assertion, Symbol, SymbolScope, SymbolValues, TDim, ToDim};
     pub use crate::f16::*;
     pub use crate::tensor::litteral::*;
     pub use crate::tensor::{natural_strides, IntoArcTensor, IntoTensor, Tensor};
@@ -27,6 +27,8 @@ pub struct Tensor {
     len: usize,
     layout: alloc::Layout,
     data: *mut u8,
+    /// Set when `data` is borrowed from a shared buffer instead of owned by the tensor.
+    storage: Option<Arc<dyn AsRef<[u8]> + Send + Sync>>,
 }
 
 unsafe impl Send for Tensor {}
@@ -106,7 +108,7 @@ impl Drop for Tensor {
                     .for_each(|s| std::ptr::drop_in_place(s as *mut TDim));
             }
         }
-        if !self.data.is_null() && self.layout.size() > 0 {
+        if !self.data.is_null() && self.layout.size() > 0 && self.storage.is_none() {
             unsafe { alloc::dealloc(self.data, self.layout) }
         }
     }
@@ -120,7 +122,8 @@ impl Tensor {
 
     /// Create an uninitialized tensor (dt as regular parameter).
     pub unsafe fn uninitialized_dt(dt: DatumType, shape: &[usize]) -> anyhow::Result<Tensor> {
-        Self::uninitialized_aligned_dt(dt, shape, dt.alignment())
+        let alignment = dt.alignment();
+        Self::uninitialized_aligned_dt(dt, shape, alignment)
     }
 
     /// Create an uninitialized tensor with a given alignment (in bytes).
@@ -148,13 +151,22 @@ impl Tensor {
         let bytes = shape.iter().cloned().product::<usize>() * dt.size_of();
         let layout = alloc::Layout::from_size_align(bytes, alignment)?;
         let data = if bytes == 0 {
-            std::ptr::null()
+            // dangling but aligned, so that empty slices can be built on it
+            alignment as *const u8
         } else {
             let ptr = alloc::alloc(layout);
             assert!(!ptr.is_null());
             ptr
         } as *mut u8;
-        let mut tensor = Tensor { strides: tvec!(), layout, dt, shape: shape.into(), data, len: 0 };
+        let mut tensor = Tensor {
+            strides: tvec!(),
+            layout,
+            dt: dt.clone(),
+            shape: shape.into(),
+            data,
+            len: 0,
+            storage: None,
+        };
         #[cfg(debug_assertions)]
         {
             if dt == DatumType::F32 {
@@ -167,6 +179,59 @@ impl Tensor {
         Ok(tensor)
     }
 
+    /// Create a tensor borrowing its data from a shared buffer, starting at `offset` bytes.
+    ///
+    /// The data is not copied: the tensor keeps the buffer alive, and only makes a private copy
+    /// of it when it gets mutated. The data must be properly aligned for the datum type.
+    pub fn from_shared_bytes<B: AsRef<[u8]> + Send + Sync + 'static>(
+        dt: DatumType,
+        shape: &[usize],
+        storage: Arc<B>,
+        offset: usize,
+    ) -> anyhow::Result<Tensor> {
+        if !dt.is_copy() {
+            anyhow::bail!("Can not borrow {:?} data", dt);
+        }
+        let bytes = shape.iter().cloned().product::<usize>() * dt.size_of();
+        let buffer = (*storage).as_ref();
+        if offset + bytes > buffer.len() {
+            anyhow::bail!(
+                "Can not borrow {} bytes at offset {} from a {} bytes buffer",
+                bytes,
+                offset,
+                buffer.len()
+            );
+        }
+        let data = unsafe { buffer.as_ptr().add(offset) } as *mut u8;
+        if data as usize % dt.alignment() != 0 {
+            anyhow::bail!("Misaligned data for {:?} at offset {}", dt, offset);
+        }
+        let layout = alloc::Layout::from_size_align(bytes, dt.alignment())?;
+        let mut tensor = Tensor {
+            strides: tvec!(),
+            layout,
+            dt,
+            shape: shape.into(),
+            data,
+            len: 0,
+            storage: Some(storage),
+        };
+        tensor.update_strides_and_len();
+        Ok(tensor)
+    }
+
+    /// Is the tensor data borrowed from a shared buffer ?
+    pub fn is_shared(&self) -> bool {
+        self.storage.is_some()
+    }
+
+    /// Make sure the tensor owns its data before it gets mutated.
+    fn make_mut(&mut self) {
+        if self.storage.is_some() {
+            *self = self.deep_clone();
+        }
+    }
+
     pub fn stack_tensors(
         axis: usize,
         tensors: &[impl std::borrow::Borrow<Tensor>],
@@ -292,7 +357,8 @@ impl Tensor {
         shape: &[usize],
         content: &[u8],
     ) -> anyhow::Result<Tensor> {
-        Self::from_raw_dt_align(dt, shape, content, dt.alignment())
+        let alignment = dt.alignment();
+        Self::from_raw_dt_align(dt, shape, content, alignment)
     }
 
     pub unsafe fn from_raw_dt_align(
@@ -563,6 +629,7 @@ impl Tensor {
                         .slice_axis(Axis(axis), Slice::from(from_range)),
                 )
         }
+        self.make_mut();
         unsafe {
             if axis == 0 && self.datum_type().is_copy() {
                 let stride = self.strides[0] as usize * self.datum_type().size_of();
@@ -589,7 +656,7 @@ impl Tensor {
     /// Get the datum type of the tensor.
     #[inline]
     pub fn datum_type(&self) -> DatumType {
-        self.dt
+        self.dt.clone()
     }
 
     /// Set the datum type of the tensor.
@@ -697,6 +764,7 @@ impl Tensor {
 
     /// Transform the data as a mutable `ndarray::Array`.
     pub unsafe fn to_array_view_mut_unchecked<'a, D: Datum>(&'a mut self) -> ArrayViewMutD<'a, D> {
+        self.make_mut();
         if self.len() != 0 {
             ArrayViewMutD::from_shape_ptr(&*self.shape, self.data as *mut D)
         } else {
@@ -717,12 +785,14 @@ impl Tensor {
 
     /// Access the data as a pointer.
     pub unsafe fn as_ptr_mut_unchecked<D: Datum>(&mut self) -> *mut D {
+        self.make_mut();
         self.data as *mut D
     }
 
     /// Access the data as a mutable pointer.
     pub fn as_ptr_mut<D: Datum>(&mut self) -> anyhow::Result<*mut D> {
-        self.as_ptr::<D>().map(|p| p as *mut D)
+        self.check_for_access::<D>()?;
+        unsafe { Ok(self.as_ptr_mut_unchecked()) }
     }
 
     /// Access the data as a slice.
@@ -742,6 +812,7 @@ impl Tensor {
 
     /// Access the data as a mutable slice.
     pub unsafe fn as_slice_mut_unchecked<D: Datum>(&mut self) -> &mut [D] {
+        self.make_mut();
         std::slice::from_raw_parts_mut::<D>(self.data as *mut D, self.len())
     }
 
@@ -760,7 +831,7 @@ impl Tensor {
     }
 
     /// Mutable access the data as a scalar.
-    pub fn to_scalar_mut<'a, D: Datum>(&'a self) -> anyhow::Result<&mut D> {
+    pub fn to_scalar_mut<'a, D: Datum>(&'a mut self) -> anyhow::Result<&'a mut D> {
         self.check_for_access::<D>()?;
         if self.len() == 0 {
             anyhow::bail!("to_scalar_mut called on empty tensor ({:?})", self)
@@ -769,7 +840,8 @@ impl Tensor {
     }
 
     /// Mutable access the data as a scalar.
-    pub unsafe fn to_scalar_mut_unchecked<'a, D: Datum>(&'a self) -> &mut D {
+    pub unsafe fn to_scalar_mut_unchecked<'a, D: Datum>(&'a mut self) -> &'a mut D {
+        self.make_mut();
         &mut *(self.data as *mut D)
     }
 
@@ -778,6 +850,7 @@ impl Tensor {
     }
 
     pub unsafe fn as_bytes_mut(&mut self) -> &mut [u8] {
+        self.make_mut();
         std::slice::from_raw_parts_mut(self.data, self.layout.size())
     }
 
@@ -800,7 +873,12 @@ impl Tensor {
 
     pub fn as_uniform(&self) -> Option<Tensor> {
         if self.len() >= 1 && self.is_uniform() {
-            unsafe { Some(dispatch_datum!(Tensor::as_uniform_t(self.datum_type())(self))) }
+            unsafe {
+                let mut uniform = dispatch_datum!(Tensor::as_uniform_t(self.datum_type())(self));
+                // keep the quantization parameters
+                uniform.set_datum_type(self.datum_type());
+                Some(uniform)
+            }
         } else {
             None
         }
@@ -881,7 +959,7 @@ impl Tensor {
                 }
                 return Ok(Cow::Owned(ints.cast_to_dt(dst_dt)?.into_owned()));
             }
-            let mut result = Self::uninitialized_dt(dst_dt, &self.shape)?;
+            let mut result = Self::uninitialized_dt(dst_dt.clone(), &self.shape)?;
             if self.dt == DatumType::String {
                 dispatch_datum!(Self::cast_from_string(dst_dt)(self, &mut result))?;
                 return Ok(Cow::Owned(result));
@@ -934,8 +1012,11 @@ impl Tensor {
                 n!(f32);
                 n!(f64);
             } else {
-                let (s_zp, s_scale) = self.datum_type().zp_scale();
-                let (d_zp, d_scale) = dst_dt.zp_scale();
+                if let Some(axis) = per_axis(&self.dt).or(per_axis(&dst_dt)) {
+                    return Ok(Cow::Owned(self.cast_per_axis(dst_dt, axis)?));
+                }
+                let (s_zp, s_scale) = self.datum_type().zp_scale()?;
+                let (d_zp, d_scale) = dst_dt.zp_scale()?;
                 //TODO: optimize scale_by
                 macro_rules! q8_to_q8 {
                     ($typ:ty) => {
@@ -1036,6 +1117,35 @@ impl Tensor {
         }
     }
 
+    /// Casts slice by slice along `axis`, with the per-slice quantization parameters.
+    fn cast_per_axis(&self, dst_dt: DatumType, axis: usize) -> anyhow::Result<Tensor> {
+        for dt in [&self.dt, &dst_dt] {
+            match per_axis(dt) {
+                Some(other) if other != axis => {
+                    anyhow::bail!("Incompatible quantization axes: {:?} to {:?}", self.dt, dst_dt)
+                }
+                Some(_) if dt.qparams().unwrap().zp_scale_vecs().0.len() != self.shape[axis] => {
+                    anyhow::bail!("Wrong quantization parameters count {:?} for {:?}", dt, self)
+                }
+                _ => (),
+            }
+        }
+        let slice_dt = |dt: &DatumType, ix: usize| match dt {
+            DatumType::QI8(qp) => DatumType::QI8(qp.at(ix)),
+            DatumType::QU8(qp) => DatumType::QU8(qp.at(ix)),
+            _ => dt.clone(),
+        };
+        let mut slices = vec![];
+        for ix in 0..self.shape[axis] {
+            let mut slice = self.slice(axis, ix, ix + 1)?;
+            unsafe { slice.set_datum_type(slice_dt(&self.dt, ix)) };
+            let mut slice = slice.cast_to_dt(slice_dt(&dst_dt, ix))?.into_owned();
+            unsafe { slice.set_datum_type(dst_dt.clone()) };
+            slices.push(slice);
+        }
+        Tensor::stack_tensors(axis, &slices)
+    }
+
     /// Access the data as a scalar, after a cast.
     pub fn cast_to_scalar<D: Datum + Copy>(&self) -> anyhow::Result<D> {
         let casted = self.cast_to::<D>()?;
@@ -1083,8 +1193,15 @@ impl Tensor {
             let shape = it.shape().into();
             let vec = it.into_raw_vec().into_boxed_slice();
             let data = Box::into_raw(vec) as *mut u8;
-            let mut t =
-                Tensor { dt: T::datum_type(), shape, layout, data, strides: tvec!(), len: 0 };
+            let mut t = Tensor {
+                dt: T::datum_type(),
+                shape,
+                layout,
+                data,
+                strides: tvec!(),
+                len: 0,
+                storage: None,
+            };
             t.update_strides_and_len();
             return t;
         }
@@ -1129,6 +1246,8 @@ impl Tensor {
                 data: data.as_ptr() as *mut u8,
                 shape: self.shape.clone(),
                 strides: self.strides.clone(),
+                storage: None,
+                dt: self.dt.clone(),
                 ..*self
             };
             std::mem::forget(data);
@@ -1139,6 +1258,8 @@ impl Tensor {
                 data: data.as_ptr() as *mut u8,
                 shape: self.shape.clone(),
                 strides: self.strides.clone(),
+                storage: None,
+                dt: self.dt.clone(),
                 ..*self
             };
             std::mem::forget(data);
@@ -1195,11 +1316,13 @@ impl Tensor {
             return self.clone();
         };
 
-        if let DatumType::QU8(qp) = self.dt {
+        if let DatumType::QU8(qp) = &self.dt {
             if let QParams::ZpScale { zero_point, scale } = qp {
-                t.dt = DatumType::QI8(QParams::ZpScale { zero_point: zero_point - 128, scale });
+                t.dt = DatumType::QI8(QParams::ZpScale { zero_point: zero_point - 128, scale: *scale });
+            } else if qp.is_per_axis() {
+                t.dt = DatumType::QI8(qp.map_zero_points(|zp| zp - 128));
             } else {
-                t.dt = DatumType::QI8(qp);
+                t.dt = DatumType::QI8(qp.clone());
             }
         }
 
@@ -1229,6 +1352,13 @@ pub fn natural_strides(shape: &[usize]) -> TVec<isize> {
     strides
 }
 
+fn per_axis(dt: &DatumType) -> Option<usize> {
+    match dt.qparams() {
+        Some(QParams::PerAxis { axis, .. }) => Some(axis),
+        _ => None,
+    }
+}
+
 fn compute_natural_stride_to(strides: &mut TVec<isize>, shape: &[usize]) {
     match shape.len() {
         0 => (),
@@ -1404,4 +1534,38 @@ mod tests {
     fn t_2_2() {
         PermuteAxisProblem { shape: vec![2, 2], permutation: vec![1, 0] }.check().unwrap();
     }
+
+    #[test]
+    fn shared_bytes() {
+        let values = [1f32, 2., 3., 4.];
+        let bytes: Vec<u8> = values.iter().flat_map(|f| f.to_le_bytes()).collect();
+        let storage = Arc::new(bytes);
+        let mut t =
+            Tensor::from_shared_bytes(f32::datum_type(), &[2, 2], storage.clone(), 0).unwrap();
+        assert!(t.is_shared());
+        assert_eq!(unsafe { t.as_bytes() }.as_ptr(), storage.as_ptr());
+        assert_eq!(t, super::litteral::tensor2(&[[1f32, 2.], [3., 4.]]));
+        t.as_slice_mut::<f32>().unwrap()[0] = 0.;
+        assert!(!t.is_shared());
+        assert_eq!(t, super::litteral::tensor2(&[[0f32, 2.], [3., 4.]]));
+        assert_eq!(&storage[0..4], &1f32.to_le_bytes());
+    }
+
+    #[test]
+    fn shared_bytes_checks() {
+        let storage = Arc::new(vec![0u8; 8]);
+        assert!(Tensor::from_shared_bytes(f32::datum_type(), &[3], storage.clone(), 0).is_err());
+        assert!(Tensor::from_shared_bytes(String::datum_type(), &[1], storage.clone(), 0).is_err());
+        assert!(Tensor::from_shared_bytes(f32::datum_type(), &[1], storage, 1).is_err());
+    }
+
+    #[test]
+    fn shared_bytes_scalar_mut() {
+        let storage = Arc::new(2f32.to_le_bytes().to_vec());
+        let mut t = Tensor::from_shared_bytes(f32::datum_type(), &[], storage.clone(), 0).unwrap();
+        *t.to_scalar_mut::<f32>().unwrap() = 3.;
+        assert!(!t.is_shared());
+        assert_eq!(*t.to_scalar::<f32>().unwrap(), 3.);
+        assert_eq!(&storage[..], &2f32.to_le_bytes());
+    }
 }
@@ -77,8 +77,9 @@ impl Arbitrary for ConvPlusConvProblem {
 impl ConvPlusConvProblem {
     pub fn run(&self) -> TestCaseResult {
         let mut model = InferenceModel::default();
+        let s = stream_dim(&model.symbol_scope);
         let input = model
-            .add_source("a", InferenceFact::dt_shape(f32::datum_type(), shapefactoid!(1, 1, S)))
+            .add_source("a", InferenceFact::dt_shape(f32::datum_type(), shapefactoid!(1, 1, s)))
             .unwrap();
         let id = self.conv1.chain("conv1", &mut model, input);
         let _id = self.conv2.chain("conv2", &mut model, id);
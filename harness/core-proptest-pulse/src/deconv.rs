@@ -98,7 +98,7 @@ impl DeconvProblem {
     pub fn run(&self) -> TestCaseResult {
         let mut model = TypedModel::default();
         let mut fact = TypedFact::shape::<f32, _>(self.input.shape());
-        fact.shape.set(2, stream_dim());
+        fact.shape.set(2, stream_dim(&model.symbol_scope));
         let input = model.add_source("a", fact).unwrap();
         let id = self.deconv.chain("deconv1", &mut model, input);
         model.set_output_outlets(&[id]).unwrap();
@@ -41,8 +41,9 @@ impl Arbitrary for DelayPlusPoolProblem {
 impl DelayPlusPoolProblem {
     pub fn run(&self) -> TestCaseResult {
         let mut model = InferenceModel::default();
+        let s = stream_dim(&model.symbol_scope);
         let a = model
-            .add_source("a", InferenceFact::dt_shape(f32::datum_type(), shapefactoid!(1, S, 1)))
+            .add_source("a", InferenceFact::dt_shape(f32::datum_type(), shapefactoid!(1, s, 1)))
             .unwrap();
         let crop =
             model.wire_node("crop", expand(array::Crop::new(1, self.delay, 0)), &[a]).unwrap();
@@ -28,13 +28,13 @@ fn proptest_regular_against_pulse(
     axis: usize,
 ) -> TestCaseResult {
     setup_test_logger();
-    let s = stream_symbol();
+    let s = stream_symbol(&model.symbol_scope);
 
     let len = input_array.shape()[axis];
     let model = model.into_decluttered().unwrap();
     let runnable = model
         .clone()
-        .concretize_dims(&SymbolValues::default().with(s, len as i64))
+        .concretize_dims(&SymbolValues::default().with(s.clone(), len as i64))
         .unwrap()
         .into_runnable()
         .unwrap();
@@ -78,7 +78,7 @@ fn proptest_regular_against_pulse(
                 &[chunk.view(), ArrayD::from_elem(filler_shape, std::f32::NAN).view()],
             )
             .unwrap();
-            state.session_state.resolved_symbols[s] = Some(written as i64);
+            state.session_state.resolved_symbols[s.clone()] = Some(written as i64);
             output_len = output_fact
                 .dim
                 .eval(&state.session_state.resolved_symbols)
@@ -123,8 +123,9 @@ proptest! {
         use tract_hir::ops::array::Slice;
         let full_len = input_len + begin + end;
         let mut model = InferenceModel::default();
+        let s = stream_dim(&model.symbol_scope);
         let a = model
-            .add_source("a", InferenceFact::dt_shape(f32::datum_type(), shapefactoid!(S)))
+            .add_source("a", InferenceFact::dt_shape(f32::datum_type(), shapefactoid!(s)))
             .unwrap();
         let slice = model.wire_node("slice", Slice::new(0, begin as usize, (input_len + begin) as usize), &[a]).unwrap();
         model.set_output_outlets(&slice).unwrap();
@@ -138,8 +139,9 @@ proptest! {
     fn proptest_pad(pulse in 1i32..3, input_len in 0i32..10, begin in 0i32..3, end in 0i32..3) {
         use tract_hir::ops::array::{ Pad, PadMode };
         let mut model = InferenceModel::default();
+        let s = stream_dim(&model.symbol_scope);
         let a = model
-            .add_source("a", InferenceFact::dt_shape(f32::datum_type(), shapefactoid!(S)))
+            .add_source("a", InferenceFact::dt_shape(f32::datum_type(), shapefactoid!(s)))
             .unwrap();
         let pad = model.wire_node("pad",Pad::new(vec![(begin as _, end as _)],
             PadMode::Constant(Arc::new(Tensor::from(-1f32)))), &[a]).unwrap();
@@ -162,8 +164,9 @@ fn test_simple_conv() {
 
     let mut model = InferenceModel::default();
     let ker = model.add_const("kernel", tensor3(&[[[0.5f32, 1.0, -0.1]]])).unwrap();
+    let s = stream_dim(&model.symbol_scope);
     let a = model
-        .add_source("a", InferenceFact::dt_shape(f32::datum_type(), shapefactoid!(1, 1, S))) // NCT
+        .add_source("a", InferenceFact::dt_shape(f32::datum_type(), shapefactoid!(1, 1, s))) // NCT
         .unwrap();
 
     model.wire_node("conv", expand(Conv::default()), &[a, ker]).unwrap();
@@ -178,8 +181,9 @@ fn test_simple_conv() {
 fn test_crop_after_1() {
     use tract_hir::ops::array::Slice;
     let mut model = InferenceModel::default();
+    let s = stream_dim(&model.symbol_scope);
     let a = model
-        .add_source("a", InferenceFact::dt_shape(f32::datum_type(), shapefactoid!(S)))
+        .add_source("a", InferenceFact::dt_shape(f32::datum_type(), shapefactoid!(s)))
         .unwrap();
     model.wire_node("slice", Slice::new(0, 0, 0), &[a]).unwrap();
     model.auto_outputs().unwrap();
@@ -193,8 +197,9 @@ fn test_crop_after_1() {
 fn test_pad_after_1() {
     use tract_hir::ops::array::{Pad, PadMode};
     let mut model = InferenceModel::default();
+    let s = stream_dim(&model.symbol_scope);
     let a = model
-        .add_source("a", InferenceFact::dt_shape(f32::datum_type(), shapefactoid!(S)))
+        .add_source("a", InferenceFact::dt_shape(f32::datum_type(), shapefactoid!(s)))
         .unwrap();
     model
         .wire_node(
@@ -214,8 +219,9 @@ fn test_pad_after_1() {
 fn test_pad_before_1() {
     use tract_hir::ops::array::{Pad, PadMode};
     let mut model = InferenceModel::default();
+    let s = stream_dim(&model.symbol_scope);
     let a = model
-        .add_source("a", InferenceFact::dt_shape(f32::datum_type(), shapefactoid!(S)))
+        .add_source("a", InferenceFact::dt_shape(f32::datum_type(), shapefactoid!(s)))
         .unwrap();
     model
         .wire_node(
@@ -235,8 +241,9 @@ fn test_pad_before_1() {
 fn test_pad_before_2() {
     use tract_hir::ops::array::{Pad, PadMode};
     let mut model = InferenceModel::default();
+    let s = stream_dim(&model.symbol_scope);
     let a = model
-        .add_source("a", InferenceFact::dt_shape(f32::datum_type(), shapefactoid!(S)))
+        .add_source("a", InferenceFact::dt_shape(f32::datum_type(), shapefactoid!(s)))
         .unwrap();
     model
         .wire_node(
@@ -65,8 +65,9 @@ impl PadPlusConvProblem {
     pub fn run(&self) -> TestCaseResult {
         use tract_hir::ops::cnn::*;
         let mut model = InferenceModel::default();
+        let s = stream_dim(&model.symbol_scope);
         let mut wire = model
-            .add_source("a", InferenceFact::dt_shape(f32::datum_type(), shapefactoid!(1, 1, S)))
+            .add_source("a", InferenceFact::dt_shape(f32::datum_type(), shapefactoid!(1, 1, s)))
             .unwrap();
         if self.pad_before > 0 || self.pad_after > 0 {
             wire = model
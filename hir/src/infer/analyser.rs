@@ -8,9 +8,21 @@ use std::collections::{BTreeSet, HashMap};
 #[derive(new)]
 pub struct Analyser<M: BorrowMut<InferenceModel>> {
     model: M,
+    #[new(default)]
+    traces: Option<HashMap<usize, SolverTrace>>,
 }
 
 impl<M: BorrowMut<InferenceModel>> Analyser<M> {
+    /// Record how the rules of each node refine its facts (see `Solver::infer_facts_traced`).
+    pub fn traced(self) -> Analyser<M> {
+        Analyser { traces: Some(HashMap::new()), ..self }
+    }
+
+    /// The traces recorded so far, by node, if tracing.
+    pub fn traces(&self) -> Option<&HashMap<usize, SolverTrace>> {
+        self.traces.as_ref()
+    }
+
     /// Runs the entire analysis at once. Will not stop on error if obstinate is
     /// true.
     pub fn analyse_obstinate(&mut self, obstinate: bool) -> TractResult<bool> {
@@ -129,6 +141,13 @@ impl<M: BorrowMut<InferenceModel>> Analyser<M> {
                 let outputs: TVec<&InferenceFact> = outputs.iter().collect();
                 let observed: TVec<&InferenceFact> = observed.iter().map(|p| &p.1).collect();
 
+                if let Some(traces) = self.traces.as_mut() {
+                    let op = &mut self.model.borrow_mut().node_mut(node).op;
+                    if let Some(trace) = op.explain_facts(inputs.clone(), outputs.clone())? {
+                        traces.entry(node).or_default().merge(trace);
+                    }
+                }
+
                 self.model.borrow_mut().node_mut(node).op.infer(inputs, outputs, observed)?
             };
 
@@ -169,7 +169,7 @@ impl<'a> From<&'a InferenceFact> for InferenceFact {
 
 impl<'a> From<&'a TypedFact> for InferenceFact {
     fn from(t: &'a TypedFact) -> InferenceFact {
-        let mut fact = InferenceFact::dt_shape(t.datum_type, t.shape.iter());
+        let mut fact = InferenceFact::dt_shape(t.datum_type.clone(), t.shape.iter());
         if let Some(k) = &t.konst {
             fact.value = k.clone().into_arc_tensor().into();
         }
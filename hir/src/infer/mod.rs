@@ -21,6 +21,7 @@ pub use self::rules::expr::ToDimExp;
 pub use self::rules::InferenceResult;
 pub use self::rules::InferenceRulesOp;
 pub use self::rules::Solver;
+pub use self::rules::SolverTrace;
 pub use self::rules::TensorProxy;
 pub use wrap;
 
@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 
 use super::factoid::Factoid;
-use super::{InferenceFact, InferenceModel, InferenceNode, InferenceOp};
+use super::{InferenceFact, InferenceModel, InferenceNode, InferenceOp, SolverTrace};
 use crate::internal::*;
 use crate::prelude::TVec;
 
@@ -11,6 +11,14 @@ pub trait InferenceModelExt {
     /// Will stop on first error unless `obstinate` is `true`.
     fn analyse(&mut self, obstinate: bool) -> TractResult<bool>;
 
+    /// Analyse the graph from the facts it holds, recording for each node which rule refined
+    /// each fact and which rules never fired.
+    ///
+    /// Meant to find out why `analyse` leaves some facts unknown: call it on the model before
+    /// `analyse`, so that it starts from the facts given by the model file and the user. Analysis
+    /// errors are ignored.
+    fn explain_analyse(&self) -> TractResult<HashMap<usize, SolverTrace>>;
+
     /// Perform early transformation before going typed.
     fn incorporate(self) -> TractResult<InferenceModel>;
 
@@ -41,6 +49,15 @@ impl InferenceModelExt for InferenceModel {
         super::analyser::Analyser::new(self).analyse_obstinate(obstinate)
     }
 
+    fn explain_analyse(&self) -> TractResult<HashMap<usize, SolverTrace>> {
+        let mut model = self.clone();
+        let mut analyser = super::analyser::Analyser::new(&mut model).traced();
+        if let Err(e) = analyser.analyse_obstinate(true) {
+            debug!("Ignoring analyse error while explaining: {:?}", e);
+        }
+        Ok(analyser.traces().cloned().unwrap_or_default())
+    }
+
     /// Perform early transformation before going typed.
     fn incorporate(self) -> TractResult<InferenceModel> {
         let mut model = self;
@@ -123,10 +140,9 @@ impl InferenceModelExt for InferenceModel {
                         .to_typed(source, node, target, mapping)
                         .with_context(|| format!("translating op {:?}", node.op))?;
                     for output in &outputs {
-                        target
-                            .outlet_fact(*output)?
-                            .consistent()
-                            .with_context(|| format!("Checking consistency after translating op {:?}", node.op))?;
+                        target.outlet_fact(*output)?.consistent().with_context(|| {
+                            format!("Checking consistency after translating op {:?}", node.op)
+                        })?;
                     }
                     Ok(outputs)
                 }
@@ -186,4 +202,22 @@ mod test {
         fn is_sync<T: Sync>() {}
         is_sync::<InferenceModel>();
     }
+
+    #[test]
+    fn explain_analyse() -> TractResult<()> {
+        let mut model = InferenceModel::default();
+        let source = model.add_source("source", InferenceFact::default())?;
+        let abs = model.wire_node("abs", tract_core::ops::math::abs(), &[source])?;
+        let neg = model.wire_node("neg", tract_core::ops::math::neg(), &abs)?;
+        model.set_output_outlets(&neg)?;
+        // given by the model file or the user
+        model.set_outlet_fact(abs[0], InferenceFact::shape(shapefactoid![2, 3]))?;
+        let traces = model.explain_analyse()?;
+        let trace = &traces[&neg[0].node];
+        assert!(trace.refinements.iter().any(|r| r.path == "outputs[0].shape" && r.value == "2,3"));
+        let trace = &traces[&abs[0].node];
+        let unknown = trace.stalled.iter().flat_map(|s| s.unknown.iter()).collect::<Vec<_>>();
+        assert!(unknown.iter().any(|p| *p == "inputs[0].datum_type"));
+        Ok(())
+    }
 }
@@ -82,6 +82,19 @@ pub trait InferenceOp:
         observed: TVec<&InferenceFact>,
     ) -> TractResult<(TVec<InferenceFact>, TVec<InferenceFact>, TVec<InferenceFact>)>;
 
+    /// Infer facts like `infer_facts`, recording which rule refined each fact and which rules
+    /// never fired.
+    ///
+    /// Ops that do not use the rule solver have nothing to explain.
+    #[allow(unused_variables)]
+    fn explain_facts(
+        &mut self,
+        inputs: TVec<&InferenceFact>,
+        outputs: TVec<&InferenceFact>,
+    ) -> TractResult<Option<SolverTrace>> {
+        Ok(None)
+    }
+
     /// Early pass on inference model, after analyse, but before translation to
     /// typed network. Meant to deal with some framework idiosyncrasies that
     /// manifest with temporaries nodes that can run some form of inference but
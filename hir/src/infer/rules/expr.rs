@@ -105,7 +105,7 @@ impl Output for TDim {
 }
 
 /// A wrapper for all the types of values that expressions can produce.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Wrapped {
     Int(IntFactoid),
     Type(TypeFactoid),
@@ -114,6 +114,31 @@ pub enum Wrapped {
     Dim(DimFact),
 }
 
+impl Wrapped {
+    /// Returns whether the wrapped value is fully determined.
+    pub fn is_concrete(&self) -> bool {
+        match self {
+            Wrapped::Int(it) => it.is_concrete(),
+            Wrapped::Type(it) => it.is_concrete(),
+            Wrapped::Shape(it) => it.is_concrete(),
+            Wrapped::Tensor(it) => it.is_concrete(),
+            Wrapped::Dim(it) => it.is_concrete(),
+        }
+    }
+}
+
+impl fmt::Display for Wrapped {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Wrapped::Int(it) => write!(formatter, "{:?}", it),
+            Wrapped::Type(it) => write!(formatter, "{:?}", it),
+            Wrapped::Shape(it) => write!(formatter, "{:?}", it),
+            Wrapped::Tensor(it) => write!(formatter, "{:?}", it),
+            Wrapped::Dim(it) => write!(formatter, "{:?}", it),
+        }
+    }
+}
+
 /// An expression that can be compared by the solver.
 pub trait TExp<T>: fmt::Debug {
     /// Returns the current value of the expression in the given context.
@@ -419,7 +444,7 @@ impl IntoExp<TypeFactoid> for DatumType {
 
 impl<'a> IntoExp<TypeFactoid> for &'a DatumType {
     fn bex(self) -> Exp<TypeFactoid> {
-        ConstantExp((*self).into()).bex()
+        ConstantExp(self.clone().into()).bex()
     }
 }
 
@@ -31,7 +31,7 @@ mod proxies;
 mod solver;
 
 pub use self::proxies::*;
-pub use self::solver::Solver;
+pub use self::solver::{Refinement, Solver, SolverTrace, StalledRule};
 
 pub type InferenceResult = TractResult<()>;
 
@@ -93,6 +93,21 @@ impl<O: InferenceRulesOp + Op> InferenceOp for O {
         Ok((input, output, observed.into_iter().cloned().collect()))
     }
 
+    fn explain_facts(
+        &mut self,
+        inputs: TVec<&InferenceFact>,
+        outputs: TVec<&InferenceFact>,
+    ) -> TractResult<Option<SolverTrace>> {
+        let inputs_proxy: TVec<TensorProxy> =
+            (0..inputs.len()).map(|ix| TensorProxy::new(tvec!(0, ix as isize).into())).collect();
+        let outputs_proxy: TVec<TensorProxy> =
+            (0..outputs.len()).map(|ix| TensorProxy::new(tvec!(1, ix as isize).into())).collect();
+        let mut solver = Solver::default();
+        self.rules(&mut solver, &inputs_proxy, &outputs_proxy)?;
+        let (_, _, trace) = solver.infer_facts_traced((inputs, outputs))?;
+        Ok(Some(trace))
+    }
+
     fn nboutputs(&self) -> TractResult<usize> {
         self.nboutputs()
     }
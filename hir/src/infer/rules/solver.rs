@@ -318,6 +318,55 @@ impl<'s, T: Output + Factoid> fmt::Debug for GivenAllRule<'s, T> {
     }
 }
 
+/// A factoid refined by a rule during a traced solver run.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Refinement {
+    /// Path of the factoid, like `outputs[0].shape[1]`.
+    pub path: String,
+    /// The factoid value after the rule was applied.
+    pub value: String,
+    /// The rule, prefixed by the rules that spawned it.
+    pub rule: String,
+}
+
+/// A rule that never fired during a traced solver run.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StalledRule {
+    /// The rule, prefixed by the rules that spawned it.
+    pub rule: String,
+    /// The paths the rule depends on that are not fully determined.
+    pub unknown: Vec<String>,
+}
+
+/// What a traced solver run (see `Solver::infer_facts_traced`) found out.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SolverTrace {
+    /// Factoids refined by the rules, in order.
+    pub refinements: Vec<Refinement>,
+    /// Rules that never fired because some of their inputs stayed unknown.
+    pub stalled: Vec<StalledRule>,
+}
+
+impl SolverTrace {
+    /// Merge the trace of a later run: refinements are accumulated, stalled rules replaced.
+    pub fn merge(&mut self, later: SolverTrace) {
+        self.refinements.extend(later.refinements);
+        self.stalled = later.stalled;
+    }
+}
+
+impl fmt::Display for SolverTrace {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for r in &self.refinements {
+            writeln!(f, "{} = {} by {}", r.path, r.value, r.rule)?;
+        }
+        for s in &self.stalled {
+            writeln!(f, "{} never fired, waiting for {}", s.rule, s.unknown.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
 /// A declarative constraint solver for tensors.
 #[derive(Default)]
 pub struct Solver<'rules> {
@@ -340,6 +389,25 @@ impl<'rules> Solver<'rules> {
     pub fn infer_facts(
         self,
         facts: (TVec<&InferenceFact>, TVec<&InferenceFact>),
+    ) -> TractResult<(TVec<InferenceFact>, TVec<InferenceFact>)> {
+        self.solve(facts, None)
+    }
+
+    /// Runs the solver like `infer_facts`, also recording which rule refined each factoid and
+    /// which rules never fired.
+    pub fn infer_facts_traced(
+        self,
+        facts: (TVec<&InferenceFact>, TVec<&InferenceFact>),
+    ) -> TractResult<(TVec<InferenceFact>, TVec<InferenceFact>, SolverTrace)> {
+        let mut trace = SolverTrace::default();
+        let (inputs, outputs) = self.solve(facts, Some(&mut trace))?;
+        Ok((inputs, outputs, trace))
+    }
+
+    fn solve(
+        self,
+        facts: (TVec<&InferenceFact>, TVec<&InferenceFact>),
+        mut trace: Option<&mut SolverTrace>,
     ) -> TractResult<(TVec<InferenceFact>, TVec<InferenceFact>)> {
         let mut context = Context::new(
             facts.0.into_iter().cloned().collect(),
@@ -349,18 +417,26 @@ impl<'rules> Solver<'rules> {
         // Apply the rules until reaching a fixed point.
         let mut changed = true;
         let mut added_rules = vec![];
-        let mut rules: Vec<_> = self.rules.into_iter().map(|r| (false, r)).collect();
+        let mut rules: Vec<_> = self.rules.into_iter().map(|r| (false, r, None)).collect();
 
         while changed {
             changed = false;
 
-            for (used, rule) in &mut rules {
+            for (used, rule, origin) in &mut rules {
                 // Don't try to apply rules which have already been used.
                 if *used {
                     continue;
                 }
 
                 trace!("  Applying rule {:?}", rule);
+                let before = if trace.is_some() {
+                    rule.get_paths()
+                        .into_iter()
+                        .map(|p| Ok((p.clone(), get_path(&context, p)?)))
+                        .collect::<TractResult<Vec<_>>>()?
+                } else {
+                    vec![]
+                };
                 let (step_used, mut step_added) = rule
                     .apply(&mut context)
                     .with_context(|| format!("Applying rule {:?}", rule))?;
@@ -370,13 +446,49 @@ impl<'rules> Solver<'rules> {
                 changed |= step_used;
                 changed |= step_added.len() > 0;
 
-                added_rules.append(&mut step_added);
+                if let Some(trace) = trace.as_mut() {
+                    let name = match origin {
+                        Some(origin) => format!("{} > {:?}", origin, rule),
+                        None => format!("{:?}", rule),
+                    };
+                    for (path, old) in before {
+                        let new = get_path(&context, &path)?;
+                        if new != old {
+                            trace.refinements.push(Refinement {
+                                path: format!("{:?}", path),
+                                value: new.to_string(),
+                                rule: name.clone(),
+                            });
+                        }
+                    }
+                    added_rules.extend(step_added.drain(..).map(|r| (r, Some(name.clone()))));
+                } else {
+                    added_rules.extend(step_added.drain(..).map(|r| (r, None)));
+                }
             }
 
             trace!("  Applying all rules");
 
-            for rule in added_rules.drain(..) {
-                rules.push((false, rule));
+            for (rule, origin) in added_rules.drain(..) {
+                rules.push((false, rule, origin));
+            }
+        }
+
+        if let Some(trace) = trace {
+            for (_, rule, origin) in rules.iter().filter(|r| !r.0) {
+                let mut unknown = vec![];
+                for path in rule.get_paths() {
+                    if !get_path(&context, path)?.is_concrete() {
+                        unknown.push(format!("{:?}", path));
+                    }
+                }
+                if unknown.len() > 0 {
+                    let rule = match origin {
+                        Some(origin) => format!("{} > {:?}", origin, rule),
+                        None => format!("{:?}", rule),
+                    };
+                    trace.stalled.push(StalledRule { rule, unknown });
+                }
             }
         }
 
@@ -762,4 +874,22 @@ mod tests {
 
         assert_eq!(facts, expected);
     }
+
+    #[test]
+    fn solver_trace() {
+        let (mut solver, inputs, outputs) = bootstrap();
+        solver.equals(&inputs[0].rank, &outputs[0].rank).unwrap();
+        solver
+            .given(&inputs[0].datum_type, move |s, dt| s.equals(&outputs[0].datum_type, dt))
+            .unwrap();
+
+        let input = InferenceFact::shape(shapefactoid![2, 3]);
+        let any = InferenceFact::new();
+        let (_, _, trace) = solver.infer_facts_traced((tvec![&input], tvec![&any])).unwrap();
+        assert_eq!(trace.refinements.len(), 1);
+        assert_eq!(trace.refinements[0].path, "outputs[0].rank");
+        assert_eq!(trace.refinements[0].value, "2");
+        assert_eq!(trace.stalled.len(), 1);
+        assert_eq!(trace.stalled[0].unknown, vec!["inputs[0].datum_type"]);
+    }
 }
@@ -297,7 +297,7 @@ pub fn broadcast_scalar(
     inputs: &[OutletId],
 ) -> TractResult<Arc<Tensor>> {
     let fact = model.outlet_fact(inputs[0])?;
-    let mut tensor = tensor0(f).cast_to_dt(fact.datum_type)?.into_owned();
+    let mut tensor = tensor0(f).cast_to_dt(fact.datum_type.clone())?.into_owned();
     while tensor.rank() < fact.rank() {
         tensor.insert_axis(0)?;
     }
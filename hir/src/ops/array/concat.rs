@@ -76,7 +76,7 @@ impl Expansion for Concat {
             .collect::<TractResult<TVec<_>>>()?;
 
         let super_type = if let Some(super_type) =
-            DatumType::super_type_for(facts.iter().map(|x| x.datum_type))
+            DatumType::super_type_for(facts.iter().map(|x| x.datum_type.clone()))
         {
             super_type
         } else {
@@ -91,13 +91,13 @@ impl Expansion for Concat {
             match &fact.konst {
                 Some(c_input) => {
                     slices.push(ConcatSlice::Const(
-                        c_input.cast_to_dt(super_type)?.into_owned().into_arc_tensor(),
+                        c_input.cast_to_dt(super_type.clone())?.into_owned().into_arc_tensor(),
                     ));
                 }
                 None => {
                     let casted = target.wire_node(
                         format!("{}.cast-{}", prefix, ix),
-                        crate::ops::cast::cast(super_type),
+                        crate::ops::cast::cast(super_type.clone()),
                         &[*outlet],
                     )?[0];
                     kept_inputs.push(casted);
@@ -109,7 +109,7 @@ impl EvalOp for EyeLike {
 
     fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
         let input = args_1!(inputs);
-        let dt = self.dt.unwrap_or(input.datum_type());
+        let dt = self.dt.clone().unwrap_or(input.datum_type());
         Ok(tvec!(dispatch_numbers!(Self::make(dt)(self, (input.shape()[0], input.shape()[1])))?))
     }
 }
@@ -123,7 +123,7 @@ impl InferenceRulesOp for EyeLike {
     ) -> InferenceResult {
         check_input_arity(&inputs, 1)?;
         check_output_arity(&outputs, 1)?;
-        if let Some(dt) = self.dt {
+        if let Some(dt) = &self.dt {
             s.equals(&outputs[0].datum_type, dt)?;
         } else {
             s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
@@ -133,7 +133,7 @@ impl InferenceRulesOp for EyeLike {
         s.given(&inputs[0].shape, move |s, shape| {
             if let (Ok(r), Ok(c)) = (shape[0].to_usize(), shape[1].to_usize()) {
                 let shape = (r, c);
-                if let Some(dt) = self.dt {
+                if let Some(dt) = &self.dt {
                     let value = dispatch_numbers!(Self::make(dt)(self, shape))?;
                     s.equals(&outputs[0].value, value)?;
                 } else {
@@ -156,7 +156,7 @@ impl TypedOp for EyeLike {
 
     fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
         Ok(tvec!(TypedFact::dt_shape(
-            self.dt.unwrap_or(inputs[0].datum_type),
+            self.dt.clone().unwrap_or(inputs[0].datum_type.clone()),
             inputs[0].shape.iter()
         )))
     }
@@ -149,7 +149,8 @@ mod tests {
     use AxisOp::*;
 
     fn stream() -> TDim {
-        Symbol::from('S').into()
+        thread_local!(static S: Symbol = SymbolScope::default().sym("S"));
+        S.with(|s| s.clone().into())
     }
 
     macro_rules! s {
@@ -192,7 +193,12 @@ mod tests {
 
     #[test]
     fn compute_bug_2() {
-        assert_eq!(&*compute_shape(s!['s', 'b', 2, 128], s!(0, 0, -1)).unwrap(), s!['s', 'b', 256])
+        let scope = SymbolScope::default();
+        let (s, b): (TDim, TDim) = (scope.sym("s").into(), scope.sym("b").into());
+        assert_eq!(
+            &*compute_shape(s![s.clone(), b.clone(), 2, 128], s!(0, 0, -1)).unwrap(),
+            s![s, b, 256]
+        )
     }
 
     #[test]
@@ -57,7 +57,7 @@ impl Expansion for Shape {
         inputs: &[OutletId],
     ) -> TractResult<TVec<OutletId>> {
         let mut shape = tensor1(&model.outlet_fact(inputs[0])?.shape.to_tvec());
-        if let Ok(s) = shape.cast_to_dt(self.dt) {
+        if let Ok(s) = shape.cast_to_dt(self.dt.clone()) {
             shape = s.into_owned();
         };
         let wire = model.add_const(prefix, shape)?;
@@ -22,7 +22,7 @@ impl Expansion for Size {
     ) -> InferenceResult {
         check_input_arity(&inputs, 1)?;
         check_output_arity(&outputs, 1)?;
-        s.equals(&outputs[0].datum_type, self.dt)?;
+        s.equals(&outputs[0].datum_type, self.dt.clone())?;
         s.equals(&outputs[0].rank, 0)?;
         Ok(())
     }
@@ -34,7 +34,7 @@ impl Expansion for Size {
         inputs: &[OutletId],
     ) -> TractResult<TVec<OutletId>> {
         let mut size = tensor0(model.outlet_fact(inputs[0])?.shape.iter().product::<TDim>());
-        if let Ok(s) = size.cast_to_dt(self.dt) {
+        if let Ok(s) = size.cast_to_dt(self.dt.clone()) {
             size = s.into_owned();
         }
         let wire = model.add_const(prefix, size)?;
@@ -26,10 +26,10 @@ impl Dim {
     fn soft_len(&self) -> TractResult<TDim> {
         if let Ok(len) = (self.end.clone() - &self.begin).to_isize() {
             Ok((((self.stride.abs() as i32 - 1) + len.abs() as i32) / self.stride.abs()).to_dim())
-        } else if self.stride == 1 {
-            Ok(self.end.clone() - &self.begin)
+        } else if self.stride > 0 {
+            Ok((self.end.clone() - &self.begin).div_ceil(self.stride as u64))
         } else {
-            bail!("Streaming dimensions with strides are not supported for now")
+            Ok((self.begin.clone() - &self.end).div_ceil(-self.stride as u64))
         }
     }
 }
@@ -81,6 +81,10 @@ impl StridedSlice {
         fn fix_negative(bound: &mut TDim, dim: &TDim) {
             let neg = if let Ok(b) = bound.to_isize() {
                 b < 0
+            } else if bound.prove_positive_or_zero() {
+                false
+            } else if (-bound.clone()).prove_strict_positive() {
+                true
             } else {
                 let symbols = bound.symbols();
                 if symbols.len() == 1 {
@@ -299,9 +303,10 @@ impl Expansion for StridedSlice {
                     AxisOp::Rm(0),
                     &right,
                 )?[0];
+                let len = target.symbol_scope.new_with_prefix("l").into();
                 wire = target.wire_node(
                     format!("{}.slice-axis-{}", prefix, axis),
-                    tract_core::ops::array::DynSlice::new(axis, true, true),
+                    tract_core::ops::array::DynSlice::new(axis, true, true, len),
                     &[wire, left, right],
                 )?[0];
             }
@@ -332,7 +337,8 @@ mod tests {
     use tract_ndarray::{arr1, arr2, arr3};
 
     fn s() -> TDim {
-        Symbol::from('S').into()
+        thread_local!(static S: Symbol = SymbolScope::default().sym("S"));
+        S.with(|s| s.clone().into())
     }
 
     pub fn strided_slice(begin_mask: i64, end_mask: i64, shrink_axis_mask: i64) -> StridedSlice {
@@ -608,6 +614,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn inference_strided_symbolic() {
+        let op = strided_slice(5, 7, 0);
+        let input = InferenceFact::dt_shape(DatumType::F32, shapefactoid!(1, (s()), 16));
+        let begin = InferenceFact::from(tensor1(&[0i32, 1, 0]));
+        let end = InferenceFact::from(tensor1(&[0i32, 0, 0]));
+        let strides = InferenceFact::from(tensor1(&[1i32, 3, 1]));
+        let any = InferenceFact::default();
+
+        let (_, output_facts, _) = expand(op)
+            .infer_facts(tvec![&input, &begin, &end, &strides], tvec![&any], tvec!())
+            .unwrap();
+
+        assert_eq!(
+            output_facts,
+            tvec![InferenceFact::dt_shape(
+                DatumType::F32,
+                shapefactoid!(1, ((s() - 1).div_ceil(3)), 16)
+            )]
+        );
+    }
+
     #[test]
     fn prep_1() {
         let op = strided_slice(0, 0, 0);
@@ -36,8 +36,8 @@ impl Expansion for InferenceBinOp {
         inputs: &[OutletId],
     ) -> TractResult<TVec<OutletId>> {
         let operating_datum_type = self.0.operating_datum_type(
-            target.outlet_fact(inputs[0])?.datum_type,
-            target.outlet_fact(inputs[1])?.datum_type,
+            target.outlet_fact(inputs[0])?.datum_type.clone(),
+            target.outlet_fact(inputs[1])?.datum_type.clone(),
         )?;
         let wires = wire_rank_broadcast(prefix, target, inputs)?;
         let wires = wire_cast(prefix, target, &wires, operating_datum_type)?;
@@ -82,7 +82,7 @@ pub fn wire_cast(
         if target.outlet_fact(wire)?.datum_type != operating_datum_type {
             wire = target.wire_node(
                 format!("{}.cast-{}", prefix, i),
-                mir::cast::cast(operating_datum_type),
+                mir::cast::cast(operating_datum_type.clone()),
                 &[wire],
             )?[0];
         }
@@ -144,7 +144,7 @@ impl EvalOp for Nary {
             let operating_datum_type =
                 self.0.operating_datum_type(t.datum_type(), i.datum_type())?;
             if i.datum_type() != operating_datum_type {
-                i = i.cast_to_dt(operating_datum_type)?.into_owned().into_arc_tensor();
+                i = i.cast_to_dt(operating_datum_type.clone())?.into_owned().into_arc_tensor();
             }
             if t.datum_type() != operating_datum_type {
                 t = t.cast_to_dt(operating_datum_type)?.into_owned();
@@ -14,7 +14,7 @@ impl InferenceRulesOp for Cast {
         check_input_arity(inputs, 1)?;
         check_output_arity(outputs, 1)?;
         s.equals(&inputs[0].shape, &outputs[0].shape)?;
-        s.equals(&outputs[0].datum_type, self.to)?;
+        s.equals(&outputs[0].datum_type, self.to.clone())?;
         Ok(())
     }
 
@@ -141,7 +141,7 @@ impl Expansion for Conv {
         s.equals(&outputs[0].rank, &inputs[0].rank)?;
         check_output_arity(&outputs, 1)?;
         s.equals(&inputs[0].datum_type, &k_input.datum_type)?;
-        if let Some(dt) = self.override_output_datum_type {
+        if let Some(dt) = &self.override_output_datum_type {
             s.equals(&outputs[0].datum_type, dt)?;
         } else {
             s.equals(&outputs[0].datum_type, &inputs[0].datum_type)?;
@@ -228,7 +228,8 @@ impl Expansion for Conv {
             || self.x_scale_input.is_some()
             || self.y_zero_point_input.is_some()
             || self.y_scale_input.is_some();
-        let output_type = self.override_output_datum_type.unwrap_or(input.datum_type);
+        let output_type =
+            self.override_output_datum_type.clone().unwrap_or_else(|| input.datum_type.clone());
         let q_params = if quantized {
             use tract_core::ops::matmul::MatMulQParams;
 
@@ -30,7 +30,7 @@ impl InferenceRulesOp for MaxPool {
         check_output_arity(&outputs, 1 + self.with_index_outputs.is_some() as usize)?;
         s.equals(&outputs[0].rank, &inputs[0].rank)?;
         s.equals(&outputs[0].datum_type, &inputs[0].datum_type)?;
-        if let Some(idt) = self.with_index_outputs {
+        if let Some(idt) = &self.with_index_outputs {
             s.equals(&outputs[1].datum_type, idt)?;
             s.equals(&outputs[1].shape, &outputs[0].shape)?;
         }
@@ -13,7 +13,7 @@ impl InferenceRulesOp for ElementWiseOp {
         check_input_arity(&inputs, 1)?;
         check_output_arity(&outputs, 1)?;
         s.given(&inputs[0].datum_type, move |s, dt| {
-            if let Some(dt) = self.0.output_type(dt) {
+            if let Some(dt) = self.0.output_type(dt.clone()) {
                 s.equals(&outputs[0].datum_type, dt)
             } else {
                 s.equals(&outputs[0].datum_type, dt)
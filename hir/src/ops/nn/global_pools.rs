@@ -37,7 +37,7 @@ impl Expansion for GlobalAvgPool {
         )?;
         let div =
             tensor0((input_fact.shape.iter().skip(2).product::<TDim>().to_i64()? as f64).recip())
-                .cast_to_dt(input_fact.datum_type)?
+                .cast_to_dt(input_fact.datum_type.clone())?
                 .into_owned()
                 .broadcast_into_rank(input_fact.rank())?;
 
@@ -86,7 +86,7 @@ impl Expansion for GlobalLpPool {
             )?;
         } else {
             let pow = tensor0(self.0 as f64)
-                .cast_to_dt(input_fact.datum_type)?
+                .cast_to_dt(input_fact.datum_type.clone())?
                 .into_owned()
                 .broadcast_into_rank(input_fact.rank())?
                 .into_arc_tensor();
@@ -103,7 +103,7 @@ impl Expansion for GlobalLpPool {
         )?;
         let div =
             tensor0((input_fact.shape.iter().skip(2).product::<TDim>().to_i64()? as f64).recip())
-                .cast_to_dt(input_fact.datum_type)?
+                .cast_to_dt(input_fact.datum_type.clone())?
                 .into_owned()
                 .broadcast_into_rank(input_fact.rank())?;
         wire = target.wire_node(
@@ -119,7 +119,7 @@ impl Expansion for GlobalLpPool {
             )?;
         } else {
             let anti_pow = tensor0((self.0 as f64).recip())
-                .cast_to_dt(input_fact.datum_type)?
+                .cast_to_dt(input_fact.datum_type.clone())?
                 .into_owned()
                 .broadcast_into_rank(input_fact.rank())?
                 .into_arc_tensor();
@@ -35,7 +35,7 @@ impl Expansion for LayerHardmax {
         use tract_core::ops::{array, change_axes, nn};
         let input = inputs[0];
         let input_fact = target.outlet_fact(input)?.clone();
-        let input_dt = input_fact.datum_type;
+        let input_dt = input_fact.datum_type.clone();
         let rank = input_fact.rank();
         let axis = if self.axis < 0 { rank as isize + self.axis } else { self.axis } as usize;
         let suffix_dim: TDim = input_fact.shape[axis..].iter().product();
@@ -45,7 +45,7 @@ impl Expansion for LayerHardmax {
             input_fact.shape[axis].to_usize()
         }
         .context("Assumes known dimension on working axes suffix.")?;
-        let off = tensor0(0f32).cast_to_dt(input_dt)?.into_owned().into_arc_tensor();
+        let off = tensor0(0f32).cast_to_dt(input_dt.clone())?.into_owned().into_arc_tensor();
         let on = tensor0(1f32).cast_to_dt(input_dt)?.into_owned().into_arc_tensor();
         let mut wires = inputs.into();
         if self.coerce_to_2d {
@@ -0,0 +1,769 @@
+//! Kaldi feature extraction front-end: fbank and MFCC computation
+//! (compute-fbank-feats, compute-mfcc-feats) and sliding window CMVN
+//! (apply-cmvn-sliding).
+//!
+//! Everything is expressed with strided convolutions and element-wise
+//! operators, so the front-end can be pulsified and run as a streaming model.
+//! Dithering is not supported: the ops behave as Kaldi with `--dither=0`.
+
+use std::f32::consts::PI;
+
+use tract_hir::internal::*;
+use tract_hir::ops::math;
+use tract_hir::tract_core::ops::array::Slice;
+use tract_hir::tract_core::ops::cnn::{ConvUnary, KernelFormat, PaddingSpec, PoolSpec};
+use tract_hir::tract_core::ops::nn::DataFormat;
+use tract_ndarray::{s, Array2};
+
+use crate::op_kaldi;
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum WindowType {
+    Hamming,
+    Hanning,
+    Povey,
+    Rectangular,
+}
+
+/// Framing and windowing options, with Kaldi's defaults.
+#[derive(Clone, Debug, Educe)]
+#[educe(Hash)]
+pub struct FrameOptions {
+    #[educe(Hash(method = "hash_f32"))]
+    pub sample_frequency: f32,
+    #[educe(Hash(method = "hash_f32"))]
+    pub frame_length_ms: f32,
+    #[educe(Hash(method = "hash_f32"))]
+    pub frame_shift_ms: f32,
+    #[educe(Hash(method = "hash_f32"))]
+    pub preemph_coeff: f32,
+    pub remove_dc_offset: bool,
+    pub window_type: WindowType,
+    pub round_to_power_of_two: bool,
+}
+
+impl Default for FrameOptions {
+    fn default() -> FrameOptions {
+        FrameOptions {
+            sample_frequency: 16000.0,
+            frame_length_ms: 25.0,
+            frame_shift_ms: 10.0,
+            preemph_coeff: 0.97,
+            remove_dc_offset: true,
+            window_type: WindowType::Povey,
+            round_to_power_of_two: true,
+        }
+    }
+}
+
+impl FrameOptions {
+    pub fn window_size(&self) -> usize {
+        (self.sample_frequency * 0.001 * self.frame_length_ms) as usize
+    }
+
+    pub fn window_shift(&self) -> usize {
+        (self.sample_frequency * 0.001 * self.frame_shift_ms) as usize
+    }
+
+    pub fn padded_window_size(&self) -> usize {
+        if self.round_to_power_of_two {
+            self.window_size().next_power_of_two()
+        } else {
+            self.window_size()
+        }
+    }
+
+    fn window(&self) -> Vec<f32> {
+        let len = self.window_size();
+        let a = 2.0 * PI / (len - 1) as f32;
+        (0..len)
+            .map(|i| {
+                let cos = (a * i as f32).cos();
+                match self.window_type {
+                    WindowType::Hamming => 0.54 - 0.46 * cos,
+                    WindowType::Hanning => 0.5 - 0.5 * cos,
+                    WindowType::Povey => (0.5 - 0.5 * cos).powf(0.85),
+                    WindowType::Rectangular => 1.0,
+                }
+            })
+            .collect()
+    }
+
+    /// Linear map from the raw samples of a frame to the real and imaginary
+    /// parts of its spectrum (without the Nyquist bin): dc offset removal,
+    /// pre-emphasis, windowing and DFT. Shape is (2 * bins, window_size).
+    fn spectrum_matrix(&self) -> Array2<f32> {
+        let len = self.window_size();
+        let padded = self.padded_window_size();
+        let bins = padded / 2;
+        let mut frame = Array2::<f32>::eye(len);
+        if self.remove_dc_offset {
+            frame -= 1.0 / len as f32;
+        }
+        for i in (1..len).rev() {
+            let previous = frame.row(i - 1).to_owned();
+            frame.row_mut(i).scaled_add(-self.preemph_coeff, &previous);
+        }
+        frame.row_mut(0).mapv_inplace(|x| x * (1.0 - self.preemph_coeff));
+        for (mut row, w) in frame.outer_iter_mut().zip(self.window()) {
+            row *= w;
+        }
+        let dft = Array2::from_shape_fn((2 * bins, len), |(k, n)| {
+            let angle = 2.0 * PI * ((k % bins) * n % padded) as f32 / padded as f32;
+            if k < bins {
+                angle.cos()
+            } else {
+                angle.sin()
+            }
+        });
+        dft.dot(&frame)
+    }
+}
+
+/// Mel filterbank options, with Kaldi's defaults.
+#[derive(Clone, Debug, Educe)]
+#[educe(Hash)]
+pub struct MelOptions {
+    pub num_bins: usize,
+    #[educe(Hash(method = "hash_f32"))]
+    pub low_freq: f32,
+    /// Zero or negative values are relative to the Nyquist frequency.
+    #[educe(Hash(method = "hash_f32"))]
+    pub high_freq: f32,
+}
+
+impl Default for MelOptions {
+    fn default() -> MelOptions {
+        MelOptions { num_bins: 23, low_freq: 20.0, high_freq: 0.0 }
+    }
+}
+
+impl MelOptions {
+    fn mel_scale(freq: f32) -> f32 {
+        1127.0 * (1.0 + freq / 700.0).ln()
+    }
+
+    /// Triangular filters over the spectrum bins, shape is (bins, num_bins).
+    fn banks(&self, frame: &FrameOptions) -> Array2<f32> {
+        let fft_bins = frame.padded_window_size() / 2;
+        let nyquist = 0.5 * frame.sample_frequency;
+        let high_freq =
+            if self.high_freq > 0.0 { self.high_freq } else { nyquist + self.high_freq };
+        let fft_bin_width = frame.sample_frequency / frame.padded_window_size() as f32;
+        let mel_low = Self::mel_scale(self.low_freq);
+        let mel_high = Self::mel_scale(high_freq);
+        let mel_delta = (mel_high - mel_low) / (self.num_bins + 1) as f32;
+        Array2::from_shape_fn((fft_bins, self.num_bins), |(i, bin)| {
+            let left = mel_low + bin as f32 * mel_delta;
+            let center = left + mel_delta;
+            let right = center + mel_delta;
+            let mel = Self::mel_scale(fft_bin_width * i as f32);
+            if mel <= left || mel >= right {
+                0.0
+            } else if mel <= center {
+                (mel - left) / (center - left)
+            } else {
+                (right - mel) / (right - center)
+            }
+        })
+    }
+}
+
+/// Log mel filterbank energies of a mono signal of shape (samples), as
+/// compute-fbank-feats with default energy and power options.
+#[derive(Clone, Debug, Default, Hash)]
+pub struct Fbank {
+    pub frame: FrameOptions,
+    pub mel: MelOptions,
+}
+
+impl_dyn_hash!(Fbank);
+
+impl Expansion for Fbank {
+    fn name(&self) -> std::borrow::Cow<str> {
+        "Fbank".into()
+    }
+
+    op_kaldi!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        frames_rules(s, inputs, outputs, &self.frame, self.mel.num_bins)
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let (log_mel, _) = wire_log_mel(prefix, model, inputs[0], &self.frame, &self.mel, false)?;
+        Ok(tvec!(log_mel))
+    }
+}
+
+/// Mel-frequency cepstral coefficients of a mono signal of shape (samples),
+/// as compute-mfcc-feats.
+#[derive(Clone, Debug, Educe)]
+#[educe(Hash)]
+pub struct Mfcc {
+    pub frame: FrameOptions,
+    pub mel: MelOptions,
+    pub num_ceps: usize,
+    #[educe(Hash(method = "hash_f32"))]
+    pub cepstral_lifter: f32,
+    /// Replace the first coefficient by the log energy of the raw frame.
+    pub use_energy: bool,
+}
+
+impl Default for Mfcc {
+    fn default() -> Mfcc {
+        Mfcc {
+            frame: FrameOptions::default(),
+            mel: MelOptions::default(),
+            num_ceps: 13,
+            cepstral_lifter: 22.0,
+            use_energy: true,
+        }
+    }
+}
+
+impl_dyn_hash!(Mfcc);
+
+impl Mfcc {
+    /// Liftered DCT, shape is (num_bins, num_ceps).
+    fn dct(&self) -> Array2<f32> {
+        let bins = self.mel.num_bins;
+        Array2::from_shape_fn((bins, self.num_ceps), |(n, k)| {
+            let lifter = if self.cepstral_lifter != 0.0 {
+                1.0 + 0.5 * self.cepstral_lifter * (PI * k as f32 / self.cepstral_lifter).sin()
+            } else {
+                1.0
+            };
+            let dct = if k == 0 {
+                (1.0 / bins as f32).sqrt()
+            } else {
+                (2.0 / bins as f32).sqrt() * (PI / bins as f32 * (n as f32 + 0.5) * k as f32).cos()
+            };
+            if k == 0 && self.use_energy {
+                0.0
+            } else {
+                dct * lifter
+            }
+        })
+    }
+}
+
+impl Expansion for Mfcc {
+    fn name(&self) -> std::borrow::Cow<str> {
+        "Mfcc".into()
+    }
+
+    op_kaldi!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        frames_rules(s, inputs, outputs, &self.frame, self.num_ceps)
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let (log_mel, log_energy) =
+            wire_log_mel(prefix, model, inputs[0], &self.frame, &self.mel, self.use_energy)?;
+        let dct = self.dct().insert_axis(tract_ndarray::Axis(0));
+        let ceps = wire_conv(
+            &format!("{}.dct", prefix),
+            model,
+            log_mel,
+            dct.into_tensor(),
+            1,
+            PaddingSpec::Valid,
+            1,
+        )?;
+        if let Some(log_energy) = log_energy {
+            let mut first = Tensor::zero::<f32>(&[1, self.num_ceps])?;
+            first.as_slice_mut::<f32>()?[0] = 1.0;
+            let energy = model.wire_node(
+                format!("{}.energy", prefix),
+                math::mul::unary(first.into_arc_tensor()),
+                &[log_energy],
+            )?;
+            model.wire_node(prefix, math::add::bin_typed(), &[ceps, energy[0]])
+        } else {
+            Ok(tvec!(ceps))
+        }
+    }
+}
+
+/// Sliding window cepstral mean (and optionally variance) normalization over
+/// features of shape (frames, dim), as apply-cmvn-sliding with
+/// `--center=false --min-cmn-window=1`: each frame is normalized by the
+/// statistics of the `window` frames ending with it.
+#[derive(Clone, Debug, Hash)]
+pub struct SlidingCmvn {
+    pub window: usize,
+    pub norm_vars: bool,
+}
+
+impl Default for SlidingCmvn {
+    fn default() -> SlidingCmvn {
+        SlidingCmvn { window: 600, norm_vars: false }
+    }
+}
+
+impl_dyn_hash!(SlidingCmvn);
+
+impl Expansion for SlidingCmvn {
+    fn name(&self) -> std::borrow::Cow<str> {
+        "SlidingCmvn".into()
+    }
+
+    op_kaldi!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].datum_type, f32::datum_type())?;
+        s.equals(&outputs[0].datum_type, f32::datum_type())?;
+        s.equals(&inputs[0].rank, 2)?;
+        s.equals(&inputs[0].shape, &outputs[0].shape)?;
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let dim = model.outlet_fact(inputs[0])?.shape[1].to_usize()?;
+        let padding = PaddingSpec::Explicit(tvec!(self.window - 1), tvec!(0), false);
+        let window_sum =
+            |model: &mut TypedModel, name: &str, wire: OutletId, dim: usize| {
+                let kernel = Tensor::from(Array2::<f32>::ones((self.window, dim)))
+                    .into_shape(&[self.window, dim, 1])?;
+                wire_conv(name, model, wire, kernel, 1, padding.clone(), dim)
+            };
+        // the number of frames in the window, smaller at the start. Ones are
+        // made by a convolution so they keep following the stream.
+        let ones = ConvUnary {
+            pool_spec: PoolSpec::new(
+                DataFormat::HWC,
+                tvec!(1),
+                PaddingSpec::Valid,
+                None,
+                None,
+                Some(1),
+            ),
+            kernel_fmt: KernelFormat::HWIO,
+            kernel: Tensor::zero::<f32>(&[1, dim, 1])?.into_arc_tensor(),
+            group: 1,
+            bias: Some(tensor1(&[1f32]).into_arc_tensor()),
+            q_params: None,
+        };
+        let ones = model.wire_node(format!("{}.ones", prefix), ones, inputs)?;
+        let count = window_sum(model, &format!("{}.count", prefix), ones[0], 1)?;
+        let sum = window_sum(model, &format!("{}.sum", prefix), inputs[0], dim)?;
+        let mean =
+            model.wire_node(format!("{}.mean", prefix), math::div::bin_typed(), &[sum, count])?;
+        if !self.norm_vars {
+            return model.wire_node(prefix, math::sub::bin_typed(), &[inputs[0], mean[0]]);
+        }
+        let centered = model.wire_node(
+            format!("{}.centered", prefix),
+            math::sub::bin_typed(),
+            &[inputs[0], mean[0]],
+        )?;
+        let sqr = model.wire_node(format!("{}.sqr", prefix), math::square(), inputs)?;
+        let sumsq = window_sum(model, &format!("{}.sumsq", prefix), sqr[0], dim)?;
+        let meansq = model.wire_node(
+            format!("{}.meansq", prefix),
+            math::div::bin_typed(),
+            &[sumsq, count],
+        )?;
+        let sqr_mean = model.wire_node(format!("{}.sqr_mean", prefix), math::square(), &mean)?;
+        let var = model.wire_node(
+            format!("{}.var", prefix),
+            math::sub::bin_typed(),
+            &[meansq[0], sqr_mean[0]],
+        )?;
+        let var = model.wire_node(
+            format!("{}.var_floor", prefix),
+            math::max::unary(rctensor2(&[[1e-10f32]])),
+            &var,
+        )?;
+        let rsqrt = model.wire_node(format!("{}.rsqrt", prefix), math::rsqrt(), &var)?;
+        // as Kaldi, output zeros while the window holds a single frame: count - 1 is 0 then,
+        // 1 or more afterwards
+        let count_minus_one = model.wire_node(
+            format!("{}.count_minus_one", prefix),
+            math::add::unary(rctensor2(&[[-1f32]])),
+            &[count],
+        )?;
+        let several_frames = model.wire_node(
+            format!("{}.several_frames", prefix),
+            math::min::unary(rctensor2(&[[1f32]])),
+            &count_minus_one,
+        )?;
+        let scale = model.wire_node(
+            format!("{}.scale", prefix),
+            math::mul::bin_typed(),
+            &[rsqrt[0], several_frames[0]],
+        )?;
+        model.wire_node(prefix, math::mul::bin_typed(), &[centered[0], scale[0]])
+    }
+}
+
+fn frames_rules<'r, 'p: 'r, 's: 'r>(
+    s: &mut Solver<'r>,
+    inputs: &'p [TensorProxy],
+    outputs: &'p [TensorProxy],
+    frame: &'s FrameOptions,
+    dim: usize,
+) -> InferenceResult {
+    check_input_arity(&inputs, 1)?;
+    check_output_arity(&outputs, 1)?;
+    s.equals(&inputs[0].datum_type, f32::datum_type())?;
+    s.equals(&outputs[0].datum_type, f32::datum_type())?;
+    s.equals(&inputs[0].rank, 1)?;
+    s.equals(&outputs[0].rank, 2)?;
+    s.equals(&outputs[0].shape[1], dim.to_dim())?;
+    s.given(&inputs[0].shape[0], move |s, samples| {
+        let frames = PaddingSpec::Valid
+            .compute_one(0, &samples, frame.window_size(), 1, frame.window_shift())
+            .convoluted;
+        s.equals(&outputs[0].shape[0], frames)
+    })
+}
+
+/// Wire the log mel energies of the frames of a (samples) signal, and the
+/// log energy of the raw frames if requested.
+fn wire_log_mel(
+    prefix: &str,
+    model: &mut TypedModel,
+    input: OutletId,
+    frame: &FrameOptions,
+    mel: &MelOptions,
+    with_energy: bool,
+) -> TractResult<(OutletId, Option<OutletId>)> {
+    let len = frame.window_size();
+    let bins = frame.padded_window_size() / 2;
+    let samples = model.wire_node(format!("{}.samples", prefix), AxisOp::Add(1), &[input])?[0];
+    let mut spectrum = frame.spectrum_matrix();
+    if with_energy {
+        // an extra channel for the frame sums
+        spectrum.push_row(Array2::<f32>::ones((1, len)).row(0))?;
+    }
+    let kernel = spectrum.t().insert_axis(tract_ndarray::Axis(1)).to_owned();
+    let frames = wire_conv(
+        &format!("{}.frames", prefix),
+        model,
+        samples,
+        kernel.into_tensor(),
+        frame.window_shift(),
+        PaddingSpec::Valid,
+        1,
+    )?;
+    let dft = if with_energy {
+        model.wire_node(format!("{}.dft", prefix), Slice::new(1, 0, 2 * bins), &[frames])?[0]
+    } else {
+        frames
+    };
+    let power = model.wire_node(format!("{}.power", prefix), math::square(), &[dft])?;
+    let banks = mel.banks(frame);
+    let mut kernel = Array2::<f32>::zeros((2 * bins, mel.num_bins));
+    kernel.slice_mut(s![..bins, ..]).assign(&banks);
+    kernel.slice_mut(s![bins.., ..]).assign(&banks);
+    let mel_energies = wire_conv(
+        &format!("{}.mel", prefix),
+        model,
+        power[0],
+        kernel.insert_axis(tract_ndarray::Axis(0)).into_tensor(),
+        1,
+        PaddingSpec::Valid,
+        1,
+    )?;
+    let log_mel = wire_floored_log(&format!("{}.log_mel", prefix), model, mel_energies)?;
+    if !with_energy {
+        return Ok((log_mel, None));
+    }
+    let sum = model.wire_node(
+        format!("{}.frame_sum", prefix),
+        Slice::new(1, 2 * bins, 2 * bins + 1),
+        &[frames],
+    )?;
+    let sqr = model.wire_node(format!("{}.sqr", prefix), math::square(), &[samples])?;
+    let mut energy = wire_conv(
+        &format!("{}.frame_sumsq", prefix),
+        model,
+        sqr[0],
+        Tensor::from(Array2::<f32>::ones((len, 1))).into_shape(&[len, 1, 1])?,
+        frame.window_shift(),
+        PaddingSpec::Valid,
+        1,
+    )?;
+    if frame.remove_dc_offset {
+        // sum of squares of the centered frame: sumsq - sum^2 / len
+        let sqr_sum = model.wire_node(format!("{}.sqr_sum", prefix), math::square(), &sum)?;
+        let dc = model.wire_node(
+            format!("{}.dc_energy", prefix),
+            math::mul::unary(tensor2(&[[1.0 / len as f32]]).into_arc_tensor()),
+            &sqr_sum,
+        )?;
+        energy = model.wire_node(
+            format!("{}.energy_raw", prefix),
+            math::sub::bin_typed(),
+            &[energy, dc[0]],
+        )?[0];
+    }
+    let log_energy = wire_floored_log(&format!("{}.log_energy", prefix), model, energy)?;
+    Ok((log_mel, Some(log_energy)))
+}
+
+fn wire_floored_log(
+    prefix: &str,
+    model: &mut TypedModel,
+    input: OutletId,
+) -> TractResult<OutletId> {
+    let floored = model.wire_node(
+        format!("{}.floor", prefix),
+        math::max::unary(tensor2(&[[f32::EPSILON]]).into_arc_tensor()),
+        &[input],
+    )?;
+    Ok(model.wire_node(prefix, math::ln(), &floored)?[0])
+}
+
+/// Single-input HWC convolution with a HWIO kernel.
+pub(crate) fn wire_conv(
+    name: &str,
+    model: &mut TypedModel,
+    input: OutletId,
+    kernel: Tensor,
+    stride: usize,
+    padding: PaddingSpec,
+    group: usize,
+) -> TractResult<OutletId> {
+    let output_channels = kernel.shape()[2] * group;
+    let op = ConvUnary {
+        pool_spec: PoolSpec::new(
+            DataFormat::HWC,
+            tvec!(kernel.shape()[0]),
+            padding,
+            None,
+            Some(tvec!(stride)),
+            Some(output_channels),
+        ),
+        kernel_fmt: KernelFormat::HWIO,
+        kernel: kernel.into_arc_tensor(),
+        group,
+        bias: None,
+        q_params: None,
+    };
+    Ok(model.wire_node(name, op, &[input])?[0])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use tract_pulse::internal::*;
+
+    fn signal(len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| ((i * 7919 % 1000) as f32 - 500.0) + 300.0 * (i as f32 * 0.05).sin())
+            .collect()
+    }
+
+    fn model(op: impl Expansion) -> TractResult<TypedModel> {
+        let mut model = InferenceModel::default();
+        let s = stream_dim(&model.symbol_scope);
+        let source =
+            model.add_source("input", InferenceFact::dt_shape(f32::datum_type(), tvec!(s)))?;
+        let output = model.wire_node("op", expand(op), &[source])?;
+        model.set_output_outlets(&output)?;
+        model.into_typed()?.into_decluttered()
+    }
+
+    fn run(model: &TypedModel, input: Tensor) -> TractResult<Tensor> {
+        let len = input.shape()[0] as i64;
+        let s = stream_symbol(&model.symbol_scope);
+        let model = model.concretize_dims(&SymbolValues::default().with(s, len))?;
+        Ok(model.into_runnable()?.run(tvec!(input))?.remove(0).into_tensor())
+    }
+
+    /// Feed the signal pulse by pulse, and return the frames of the output.
+    fn run_pulsed(
+        model: &TypedModel,
+        input: &Tensor,
+        pulse: usize,
+        frames: usize,
+    ) -> TractResult<Tensor> {
+        let pulsed = PulsedModel::new(model, pulse)?;
+        let fact = pulsed.output_fact(0)?.clone();
+        let plan = SimplePlan::new(pulsed)?;
+        let mut state = SimpleState::new(&plan)?;
+        let mut shape = input.shape().to_vec();
+        shape[0] = (shape[0] / pulse + 4 + fact.delay) * pulse;
+        let mut padded = Tensor::zero::<f32>(&shape)?;
+        padded.assign_slice(0..input.shape()[0], input, 0..input.shape()[0], 0)?;
+        let mut got = vec![];
+        for chunk in 0..shape[0] / pulse {
+            let chunk = padded.slice(0, chunk * pulse, (chunk + 1) * pulse)?;
+            let output = state.run(tvec!(chunk))?.remove(0);
+            got.extend(output.as_slice::<f32>()?.iter().cloned());
+        }
+        let dim = fact.shape[1].to_usize()?;
+        let got = &got[fact.delay * dim..][..frames * dim];
+        Ok(tensor1(got).into_shape(&[frames, dim])?)
+    }
+
+    /// Straightforward per-frame computation, following Kaldi's code.
+    fn reference_log_mel(
+        samples: &[f32],
+        frame: &FrameOptions,
+        mel: &MelOptions,
+    ) -> Vec<(Vec<f32>, f32)> {
+        let len = frame.window_size();
+        let padded = frame.padded_window_size();
+        let window = frame.window();
+        let banks = mel.banks(frame);
+        (0..=(samples.len() - len) / frame.window_shift())
+            .map(|f| {
+                let mut w = samples[f * frame.window_shift()..][..len].to_vec();
+                let mean = w.iter().sum::<f32>() / len as f32;
+                w.iter_mut().for_each(|x| *x -= mean);
+                let energy = w.iter().map(|x| x * x).sum::<f32>().max(f32::EPSILON).ln();
+                for i in (1..len).rev() {
+                    w[i] -= frame.preemph_coeff * w[i - 1];
+                }
+                w[0] -= frame.preemph_coeff * w[0];
+                w.iter_mut().zip(window.iter()).for_each(|(x, w)| *x *= w);
+                let power: Vec<f32> = (0..padded / 2)
+                    .map(|k| {
+                        let (mut re, mut im) = (0f32, 0f32);
+                        for (n, x) in w.iter().enumerate() {
+                            let angle = 2.0 * PI * (k * n % padded) as f32 / padded as f32;
+                            re += x * angle.cos();
+                            im += x * angle.sin();
+                        }
+                        re * re + im * im
+                    })
+                    .collect();
+                let log_mel = banks
+                    .columns()
+                    .into_iter()
+                    .map(|bank| {
+                        bank.iter()
+                            .zip(power.iter())
+                            .map(|(b, p)| b * p)
+                            .sum::<f32>()
+                            .max(f32::EPSILON)
+                            .ln()
+                    })
+                    .collect();
+                (log_mel, energy)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn fbank() -> TractResult<()> {
+        let op = Fbank::default();
+        let samples = signal(2000);
+        let expected: Vec<f32> = reference_log_mel(&samples, &op.frame, &op.mel)
+            .into_iter()
+            .flat_map(|(log_mel, _)| log_mel)
+            .collect();
+        let expected = tensor1(&expected).into_shape(&[11, 23])?;
+        let model = model(op)?;
+        let output = run(&model, tensor1(&samples))?;
+        output.close_enough(&expected, true)?;
+        run_pulsed(&model, &tensor1(&samples), 320, 11)?.close_enough(&expected, true)?;
+        Ok(())
+    }
+
+    #[test]
+    fn mfcc() -> TractResult<()> {
+        let op = Mfcc::default();
+        let samples = signal(2000);
+        let dct = op.dct();
+        let expected: Vec<f32> = reference_log_mel(&samples, &op.frame, &op.mel)
+            .into_iter()
+            .flat_map(|(log_mel, energy)| {
+                let mut ceps = tract_ndarray::arr1(&log_mel).dot(&dct).to_vec();
+                ceps[0] = energy;
+                ceps
+            })
+            .collect();
+        let expected = tensor1(&expected).into_shape(&[11, 13])?;
+        let model = model(op)?;
+        let output = run(&model, tensor1(&samples))?;
+        output.close_enough(&expected, true)?;
+        run_pulsed(&model, &tensor1(&samples), 160, 11)?.close_enough(&expected, true)?;
+        Ok(())
+    }
+
+    fn cmvn(norm_vars: bool) -> TractResult<TypedModel> {
+        let mut model = InferenceModel::default();
+        let s = stream_dim(&model.symbol_scope);
+        let source = model
+            .add_source("input", InferenceFact::dt_shape(f32::datum_type(), shapefactoid!(s, 2)))?;
+        let output =
+            model.wire_node("cmvn", expand(SlidingCmvn { window: 3, norm_vars }), &[source])?;
+        model.set_output_outlets(&output)?;
+        model.into_typed()?.into_decluttered()
+    }
+
+    #[test]
+    fn sliding_cmvn() -> TractResult<()> {
+        let features = tensor2(&[[1f32, 2.], [3., 2.], [5., 8.], [7., 0.], [0., 0.]]);
+        let model = cmvn(false)?;
+        let expected =
+            tensor2(&[[0f32, 0.], [1., 0.], [2., 4.], [2., -3.33333333], [-4., -2.666666666]]);
+        run(&model, features.clone())?.close_enough(&expected, true)?;
+        run_pulsed(&model, &features, 2, 5)?.close_enough(&expected, true)?;
+        Ok(())
+    }
+
+    #[test]
+    fn sliding_cmvn_norm_vars() -> TractResult<()> {
+        let features = tensor2(&[[1f32, 2.], [3., 4.], [5., 8.], [7., 0.], [1., 0.]]);
+        let model = cmvn(true)?;
+        let expected = tensor2(&[
+            [0f32, 0.],
+            [1., 1.],
+            [1.2247449, 1.3363062],
+            [1.2247449, -1.2247449],
+            [-1.3363062, -0.70710677],
+        ]);
+        run(&model, features.clone())?.close_enough(&expected, true)?;
+        run_pulsed(&model, &features, 2, 5)?.close_enough(&expected, true)?;
+        Ok(())
+    }
+
+    #[test]
+    fn sliding_cmvn_norm_vars_constant() -> TractResult<()> {
+        // zero variance is floored, the centered values are zeros
+        let features = tensor2(&[[3f32, -1.], [3., -1.], [3., -1.], [3., -1.]]);
+        let model = cmvn(true)?;
+        let expected = Tensor::zero::<f32>(&[4, 2])?;
+        run(&model, features.clone())?.close_enough(&expected, true)?;
+        Ok(())
+    }
+}
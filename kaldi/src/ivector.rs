@@ -0,0 +1,675 @@
+//! Online ivector extraction, as Kaldi's OnlineIvectorFeature
+//! (ivector-extract-online2).
+//!
+//! UBM posteriors and the ivector solve are per-frame ops, the statistics are
+//! projected with convolutions and accumulated with a running sum along the
+//! time axis, so the extraction can be pulsified along with the front-end.
+
+use tract_hir::internal::*;
+use tract_hir::ops::math;
+use tract_hir::tract_core::ops::array::TypedConcat;
+use tract_hir::tract_core::ops::cnn::PaddingSpec;
+use tract_hir::tract_core::ops::downsample::Downsample;
+use tract_hir::tract_core::ops::nn::{Reduce, Reducer, RunningReduce, RunningReducer};
+use tract_ndarray::{s, Array1, Array2, Array3, ArrayView1, Axis, Ix1, Ix2};
+
+use crate::features::wire_conv;
+use crate::op_kaldi;
+
+/// Diagonal covariance GMM, in Kaldi's DiagGmm parametrization.
+#[derive(Clone, Debug, Hash)]
+pub struct DiagGmm {
+    /// (gaussians)
+    pub gconsts: Arc<Tensor>,
+    /// (gaussians, dim)
+    pub means_invvars: Arc<Tensor>,
+    /// (gaussians, dim)
+    pub inv_vars: Arc<Tensor>,
+}
+
+impl DiagGmm {
+    /// Build the GMM from mixture weights (gaussians), means and variances
+    /// (gaussians, dim).
+    pub fn from_moments(
+        weights: &[f32],
+        means: &Array2<f32>,
+        vars: &Array2<f32>,
+    ) -> TractResult<DiagGmm> {
+        ensure!(
+            weights.len() == means.nrows() && means.shape() == vars.shape(),
+            "Inconsistent GMM shapes: {} weights, means {:?}, vars {:?}",
+            weights.len(),
+            means.shape(),
+            vars.shape()
+        );
+        let dim = means.ncols() as f32;
+        let inv_vars = vars.mapv(|v| 1.0 / v);
+        let means_invvars = means * &inv_vars;
+        let gconsts = weights
+            .iter()
+            .enumerate()
+            .map(|(g, w)| {
+                w.ln() - 0.5 * dim * (2.0 * std::f32::consts::PI).ln()
+                    + 0.5 * inv_vars.row(g).iter().map(|iv| iv.ln()).sum::<f32>()
+                    - 0.5 * means_invvars.row(g).dot(&means.row(g))
+            })
+            .collect::<Array1<f32>>();
+        Ok(DiagGmm {
+            gconsts: gconsts.into_arc_tensor(),
+            means_invvars: means_invvars.into_arc_tensor(),
+            inv_vars: inv_vars.into_arc_tensor(),
+        })
+    }
+
+    pub fn num_gaussians(&self) -> usize {
+        self.gconsts.len()
+    }
+
+    pub fn dim(&self) -> usize {
+        self.inv_vars.shape()[1]
+    }
+
+    /// Log-likelihood of one frame for each gaussian.
+    pub fn log_likelihoods(&self, x: ArrayView1<f32>) -> TractResult<Array1<f32>> {
+        let means_invvars =
+            self.means_invvars.to_array_view::<f32>()?.into_dimensionality::<Ix2>()?;
+        let inv_vars = self.inv_vars.to_array_view::<f32>()?.into_dimensionality::<Ix2>()?;
+        let gconsts = self.gconsts.to_array_view::<f32>()?.into_dimensionality::<Ix1>()?;
+        let squares = x.mapv(|x| x * x);
+        Ok(&gconsts + &means_invvars.dot(&x) - inv_vars.dot(&squares) * 0.5)
+    }
+}
+
+/// Ivector extractor without ivector-dependent weights, in the form used by
+/// online extraction.
+#[derive(Clone, Debug, Educe)]
+#[educe(Hash)]
+pub struct IvectorExtractor {
+    /// Σ⁻¹M for each gaussian: (gaussians, dim, ivector dim)
+    pub sigma_inv_m: Arc<Tensor>,
+    /// MᵀΣ⁻¹M for each gaussian: (gaussians, ivector dim, ivector dim)
+    pub u: Arc<Tensor>,
+    #[educe(Hash(method = "hash_f32"))]
+    pub prior_offset: f32,
+}
+
+impl IvectorExtractor {
+    /// Build the extractor from the projections M (gaussians, dim, ivector
+    /// dim) and the inverse covariances Σ⁻¹ (gaussians, dim, dim).
+    pub fn new(
+        m: &Array3<f32>,
+        sigma_inv: &Array3<f32>,
+        prior_offset: f32,
+    ) -> TractResult<IvectorExtractor> {
+        let (g, d, i) = m.dim();
+        ensure!(
+            sigma_inv.dim() == (g, d, d),
+            "Inconsistent extractor shapes: M {:?}, Σ⁻¹ {:?}",
+            m.shape(),
+            sigma_inv.shape()
+        );
+        let mut sigma_inv_m = Array3::<f32>::zeros((g, d, i));
+        let mut u = Array3::<f32>::zeros((g, i, i));
+        for g in 0..g {
+            let sim = sigma_inv.index_axis(Axis(0), g).dot(&m.index_axis(Axis(0), g));
+            u.index_axis_mut(Axis(0), g).assign(&m.index_axis(Axis(0), g).t().dot(&sim));
+            sigma_inv_m.index_axis_mut(Axis(0), g).assign(&sim);
+        }
+        Ok(IvectorExtractor {
+            sigma_inv_m: sigma_inv_m.into_arc_tensor(),
+            u: u.into_arc_tensor(),
+            prior_offset,
+        })
+    }
+
+    pub fn ivector_dim(&self) -> usize {
+        self.u.shape()[1]
+    }
+}
+
+/// Online ivectors of features of shape (frames, dim): one ivector every
+/// `ivector_period` frames, row k estimated from the frames up to and
+/// including frame `k * ivector_period`, with the prior offset removed from
+/// its first component. This matches OnlineIvectorFeature with
+/// `--use-most-recent-ivector=false`, the system being solved exactly
+/// instead of by conjugate gradient.
+///
+/// The first input selects the UBM gaussians (CMVN-normalized features in
+/// Kaldi's recipes), the second one feeds the statistics.
+#[derive(Clone, Debug, Educe)]
+#[educe(Hash)]
+pub struct OnlineIvector {
+    pub ubm: DiagGmm,
+    pub extractor: IvectorExtractor,
+    pub ivector_period: usize,
+    pub num_gselect: usize,
+    #[educe(Hash(method = "hash_f32"))]
+    pub min_post: f32,
+    #[educe(Hash(method = "hash_f32"))]
+    pub posterior_scale: f32,
+    #[educe(Hash(method = "hash_f32"))]
+    pub max_count: f32,
+}
+
+impl_dyn_hash!(OnlineIvector);
+
+impl OnlineIvector {
+    /// Extraction with Kaldi's default options.
+    pub fn new(ubm: DiagGmm, extractor: IvectorExtractor) -> OnlineIvector {
+        OnlineIvector {
+            ubm,
+            extractor,
+            ivector_period: 10,
+            num_gselect: 5,
+            min_post: 0.025,
+            posterior_scale: 0.1,
+            max_count: 0.0,
+        }
+    }
+}
+
+impl Expansion for OnlineIvector {
+    fn name(&self) -> std::borrow::Cow<str> {
+        "OnlineIvector".into()
+    }
+
+    op_kaldi!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 2)?;
+        check_output_arity(&outputs, 1)?;
+        for input in inputs {
+            s.equals(&input.datum_type, f32::datum_type())?;
+            s.equals(&input.rank, 2)?;
+            s.equals(&input.shape[0], &inputs[0].shape[0])?;
+        }
+        s.equals(&inputs[0].shape[1], self.ubm.dim().to_dim())?;
+        s.equals(&inputs[1].shape[1], self.extractor.sigma_inv_m.shape()[1].to_dim())?;
+        s.equals(&outputs[0].datum_type, f32::datum_type())?;
+        s.equals(&outputs[0].rank, 2)?;
+        s.equals(&outputs[0].shape[1], self.extractor.ivector_dim().to_dim())?;
+        let period = self.ivector_period as u64;
+        s.given(&inputs[0].shape[0], move |s, frames| {
+            s.equals(&outputs[0].shape[0], frames.div_ceil(period))
+        })?;
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let gaussians = self.ubm.num_gaussians();
+        let dim = self.extractor.sigma_inv_m.shape()[1];
+        let ivector_dim = self.extractor.ivector_dim();
+        let posteriors = GmmPosteriors {
+            ubm: self.ubm.clone(),
+            num_gselect: self.num_gselect,
+            min_post: self.min_post,
+            scale: self.posterior_scale,
+        };
+        let post = model.wire_node(format!("{}.posteriors", prefix), posteriors, &[inputs[0]])?[0];
+
+        // linear term: Σ_g post_g (Σ⁻¹M_g)ᵀ x
+        let kernel =
+            self.extractor.sigma_inv_m.as_ref().clone().permute_axes(&[1, 0, 2])?.into_shape(&[
+                1,
+                dim,
+                gaussians * ivector_dim,
+            ])?;
+        let projected = wire_conv(
+            &format!("{}.projected", prefix),
+            model,
+            inputs[1],
+            kernel,
+            1,
+            PaddingSpec::Valid,
+            1,
+        )?;
+        let projected = model.wire_node(
+            format!("{}.projected.split", prefix),
+            AxisOp::Reshape(
+                1,
+                tvec!((gaussians * ivector_dim).to_dim()),
+                tvec!(gaussians.to_dim(), ivector_dim.to_dim()),
+            ),
+            &[projected],
+        )?[0];
+        let post_col =
+            model.wire_node(format!("{}.posteriors.col", prefix), AxisOp::Add(2), &[post])?[0];
+        let weighted = model.wire_node(
+            format!("{}.weighted", prefix),
+            math::mul::bin_typed(),
+            &[projected, post_col],
+        )?[0];
+        let linear = model.wire_node(
+            format!("{}.linear.sum", prefix),
+            Reduce::new(tvec!(1), Reducer::Sum),
+            &[weighted],
+        )?[0];
+        let linear = model.wire_node(format!("{}.linear", prefix), AxisOp::Rm(1), &[linear])?[0];
+
+        // quadratic term: Σ_g post_g MᵀΣ⁻¹M_g
+        let kernel = self.extractor.u.as_ref().clone().into_shape(&[
+            1,
+            gaussians,
+            ivector_dim * ivector_dim,
+        ])?;
+        let quadratic = wire_conv(
+            &format!("{}.quadratic", prefix),
+            model,
+            post,
+            kernel,
+            1,
+            PaddingSpec::Valid,
+            1,
+        )?;
+
+        let count = model.wire_node(
+            format!("{}.count", prefix),
+            Reduce::new(tvec!(1), Reducer::Sum),
+            &[post],
+        )?[0];
+        let stats = model.wire_node(
+            format!("{}.stats", prefix),
+            TypedConcat::concat_vars(1, 3),
+            &[linear, quadratic, count],
+        )?;
+        let stats = model.wire_node(
+            format!("{}.stats.running", prefix),
+            RunningReduce { axis: 0, reducer: RunningReducer::Sum },
+            &stats,
+        )?;
+        let stats = model.wire_node(
+            format!("{}.stats.period", prefix),
+            Downsample::new(0, self.ivector_period as isize, 0),
+            &stats,
+        )?;
+        let solve = IvectorSolve {
+            ivector_dim,
+            prior_offset: self.extractor.prior_offset,
+            max_count: self.max_count,
+        };
+        model.wire_node(prefix, solve, &stats)
+    }
+}
+
+/// Pruned and scaled UBM posteriors of features of shape (frames, dim), as
+/// VectorToPosteriorEntry: gaussians less likely than `min_post` times the
+/// best one are dropped, only the `num_gselect` best are kept. Output is
+/// (frames, gaussians), zero for unselected gaussians.
+#[derive(Clone, Debug, Educe)]
+#[educe(Hash)]
+pub struct GmmPosteriors {
+    pub ubm: DiagGmm,
+    pub num_gselect: usize,
+    #[educe(Hash(method = "hash_f32"))]
+    pub min_post: f32,
+    #[educe(Hash(method = "hash_f32"))]
+    pub scale: f32,
+}
+
+impl_dyn_hash!(GmmPosteriors);
+
+impl GmmPosteriors {
+    fn frame_posteriors(&self, log_likes: &Array1<f32>, post: &mut [f32]) {
+        let max = log_likes.iter().fold(std::f32::MIN, |a, l| a.max(*l));
+        let mut selected = if self.min_post > 0.0 {
+            let cutoff = max + self.min_post.ln();
+            log_likes.iter().enumerate().filter(|(_, l)| **l > cutoff).collect::<Vec<_>>()
+        } else {
+            vec![]
+        };
+        if selected.is_empty() {
+            selected = log_likes.iter().enumerate().collect();
+        }
+        selected.sort_by(|a, b| b.1.partial_cmp(a.1).unwrap_or(std::cmp::Ordering::Equal));
+        selected.truncate(self.num_gselect.max(1));
+        let sum: f32 = selected.iter().map(|(_, l)| (*l - max).exp()).sum();
+        for (g, l) in selected {
+            post[g] = (l - max).exp() / sum * self.scale;
+        }
+    }
+}
+
+impl Op for GmmPosteriors {
+    fn name(&self) -> Cow<str> {
+        "GmmPosteriors".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!(
+            "gaussians: {} gselect: {} min_post: {} scale: {}",
+            self.ubm.num_gaussians(),
+            self.num_gselect,
+            self.min_post,
+            self.scale
+        )])
+    }
+
+    op_kaldi!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for GmmPosteriors {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = inputs[0].to_array_view::<f32>()?.into_dimensionality::<Ix2>()?;
+        let mut output = Array2::<f32>::zeros((input.nrows(), self.ubm.num_gaussians()));
+        for (frame, mut post) in input.outer_iter().zip(output.outer_iter_mut()) {
+            let log_likes = self.ubm.log_likelihoods(frame)?;
+            self.frame_posteriors(&log_likes, post.as_slice_mut().unwrap());
+        }
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for GmmPosteriors {
+    as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        ensure!(
+            inputs[0].datum_type == f32::datum_type()
+                && inputs[0].rank() == 2
+                && inputs[0].shape[1] == self.ubm.dim().to_dim(),
+            "GmmPosteriors expects f32 features of dimension {}, got {:?}",
+            self.ubm.dim(),
+            inputs[0]
+        );
+        let shape = tvec!(inputs[0].shape[0].clone(), self.ubm.num_gaussians().to_dim());
+        Ok(tvec!(TypedFact::dt_shape(f32::datum_type(), shape)))
+    }
+
+    fn invariants(
+        &self,
+        _inputs: &[&TypedFact],
+        _outputs: &[&TypedFact],
+    ) -> TractResult<Invariants> {
+        Ok(tvec!(AxisInfo::simple(0)).into())
+    }
+}
+
+/// Ivector from accumulated statistics: each input row is the linear term,
+/// the flattened quadratic term and the posterior count, output rows are the
+/// ivectors with the prior offset removed from their first component.
+#[derive(Clone, Debug, Educe)]
+#[educe(Hash)]
+pub struct IvectorSolve {
+    pub ivector_dim: usize,
+    #[educe(Hash(method = "hash_f32"))]
+    pub prior_offset: f32,
+    #[educe(Hash(method = "hash_f32"))]
+    pub max_count: f32,
+}
+
+impl_dyn_hash!(IvectorSolve);
+
+impl IvectorSolve {
+    fn solve(&self, stats: ArrayView1<f32>, ivector: &mut [f32]) -> TractResult<()> {
+        let n = self.ivector_dim;
+        let count = stats[n + n * n] as f64;
+        // the prior is a unit gaussian centered on prior_offset times the first
+        // axis, weighted more as the count exceeds max_count
+        let prior_scale = if self.max_count > 0.0 {
+            count.max(self.max_count as f64) / self.max_count as f64
+        } else {
+            1.0
+        };
+        let mut a = stats.slice(s![n..n + n * n]).iter().map(|x| *x as f64).collect::<Vec<_>>();
+        let mut b = stats.slice(s![..n]).iter().map(|x| *x as f64).collect::<Vec<_>>();
+        for i in 0..n {
+            a[i * n + i] += prior_scale;
+        }
+        b[0] += prior_scale * self.prior_offset as f64;
+        cholesky_solve(&mut a, &mut b, n)?;
+        for (w, b) in ivector.iter_mut().zip(b) {
+            *w = b as f32;
+        }
+        ivector[0] -= self.prior_offset;
+        Ok(())
+    }
+}
+
+/// Solve a x = b in place for a symmetric positive definite n×n matrix.
+fn cholesky_solve(a: &mut [f64], b: &mut [f64], n: usize) -> TractResult<()> {
+    for j in 0..n {
+        let d = a[j * n + j] - (0..j).map(|k| a[j * n + k] * a[j * n + k]).sum::<f64>();
+        ensure!(d > 0.0, "Ivector system is not positive definite");
+        a[j * n + j] = d.sqrt();
+        for i in j + 1..n {
+            let v = a[i * n + j] - (0..j).map(|k| a[i * n + k] * a[j * n + k]).sum::<f64>();
+            a[i * n + j] = v / a[j * n + j];
+        }
+    }
+    for i in 0..n {
+        b[i] = (b[i] - (0..i).map(|k| a[i * n + k] * b[k]).sum::<f64>()) / a[i * n + i];
+    }
+    for i in (0..n).rev() {
+        b[i] = (b[i] - (i + 1..n).map(|k| a[k * n + i] * b[k]).sum::<f64>()) / a[i * n + i];
+    }
+    Ok(())
+}
+
+impl Op for IvectorSolve {
+    fn name(&self) -> Cow<str> {
+        "IvectorSolve".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!(
+            "dim: {} prior offset: {} max count: {}",
+            self.ivector_dim, self.prior_offset, self.max_count
+        )])
+    }
+
+    op_kaldi!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for IvectorSolve {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let stats = inputs[0].to_array_view::<f32>()?.into_dimensionality::<Ix2>()?;
+        let mut output = Array2::<f32>::zeros((stats.nrows(), self.ivector_dim));
+        for (stats, mut ivector) in stats.outer_iter().zip(output.outer_iter_mut()) {
+            self.solve(stats, ivector.as_slice_mut().unwrap())?;
+        }
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for IvectorSolve {
+    as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let n = self.ivector_dim;
+        ensure!(
+            inputs[0].datum_type == f32::datum_type()
+                && inputs[0].rank() == 2
+                && inputs[0].shape[1] == (n + n * n + 1).to_dim(),
+            "IvectorSolve expects f32 statistics of dimension {}, got {:?}",
+            n + n * n + 1,
+            inputs[0]
+        );
+        let shape = tvec!(inputs[0].shape[0].clone(), n.to_dim());
+        Ok(tvec!(TypedFact::dt_shape(f32::datum_type(), shape)))
+    }
+
+    fn invariants(
+        &self,
+        _inputs: &[&TypedFact],
+        _outputs: &[&TypedFact],
+    ) -> TractResult<Invariants> {
+        Ok(tvec!(AxisInfo::simple(0)).into())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use tract_ndarray::Ix3;
+    use tract_pulse::internal::*;
+
+    fn pseudo_random(len: usize, seed: usize) -> Vec<f32> {
+        (0..len).map(|i| ((i * 7919 + seed * 104729) % 1000) as f32 / 500.0 - 1.0).collect()
+    }
+
+    fn op() -> TractResult<OnlineIvector> {
+        let (g, d, i) = (4, 3, 3);
+        let means = Array2::from_shape_vec((g, d), pseudo_random(g * d, 1))?;
+        let vars = Array2::from_shape_vec((g, d), pseudo_random(g * d, 2))?.mapv(|v| 1.5 + v);
+        let ubm = DiagGmm::from_moments(&[0.1, 0.2, 0.3, 0.4], &means, &vars)?;
+        let mut m = Array3::from_shape_vec((g, d, i), pseudo_random(g * d * i, 3))?;
+        m.slice_mut(s![.., .., 0]).mapv_inplace(|x| x * 10.0);
+        let mut sigma_inv = Array3::<f32>::zeros((g, d, d));
+        for g in 0..g {
+            for d in 0..d {
+                sigma_inv[(g, d, d)] = 1.0 + 0.1 * (g + d) as f32;
+            }
+            sigma_inv[(g, 0, 1)] = 0.2;
+            sigma_inv[(g, 1, 0)] = 0.2;
+        }
+        let extractor = IvectorExtractor::new(&m, &sigma_inv, 10.0)?;
+        let mut op = OnlineIvector::new(ubm, extractor);
+        op.ivector_period = 4;
+        op.num_gselect = 2;
+        Ok(op)
+    }
+
+    /// Per-frame accumulation, following OnlineIvectorEstimationStats.
+    fn reference(op: &OnlineIvector, gmm: &Array2<f32>, feats: &Array2<f32>) -> Array2<f32> {
+        let sigma_inv_m = op
+            .extractor
+            .sigma_inv_m
+            .to_array_view::<f32>()
+            .unwrap()
+            .into_dimensionality::<Ix3>()
+            .unwrap();
+        let u =
+            op.extractor.u.to_array_view::<f32>().unwrap().into_dimensionality::<Ix3>().unwrap();
+        let n = sigma_inv_m.shape()[2];
+        let prior_offset = op.extractor.prior_offset as f64;
+        let mut linear = vec![0f64; n];
+        let mut quadratic = vec![0f64; n * n];
+        linear[0] = prior_offset;
+        for i in 0..n {
+            quadratic[i * n + i] = 1.0;
+        }
+        let mut ivectors = vec![];
+        for t in 0..gmm.nrows() {
+            let log_likes = op.ubm.log_likelihoods(gmm.row(t)).unwrap();
+            let max = log_likes.iter().cloned().fold(f32::MIN, f32::max);
+            let mut post = log_likes
+                .iter()
+                .enumerate()
+                .filter(|(_, l)| **l > max + op.min_post.ln())
+                .map(|(g, l)| (g, (l - max).exp()))
+                .collect::<Vec<_>>();
+            post.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+            post.truncate(op.num_gselect);
+            let sum: f32 = post.iter().map(|p| p.1).sum();
+            for (gauss, p) in post {
+                let w = (p / sum * op.posterior_scale) as f64;
+                for i in 0..n {
+                    for d in 0..feats.ncols() {
+                        linear[i] += w * (sigma_inv_m[(gauss, d, i)] * feats[(t, d)]) as f64;
+                    }
+                    for j in 0..n {
+                        quadratic[i * n + j] += w * u[(gauss, i, j)] as f64;
+                    }
+                }
+            }
+            if t % op.ivector_period == 0 {
+                // plain gaussian elimination
+                let mut a = quadratic.clone();
+                let mut b = linear.clone();
+                for k in 0..n {
+                    for r in k + 1..n {
+                        let f = a[r * n + k] / a[k * n + k];
+                        for c in k..n {
+                            a[r * n + c] -= f * a[k * n + c];
+                        }
+                        b[r] -= f * b[k];
+                    }
+                }
+                for k in (0..n).rev() {
+                    b[k] = (b[k] - (k + 1..n).map(|c| a[k * n + c] * b[c]).sum::<f64>())
+                        / a[k * n + k];
+                }
+                b[0] -= prior_offset;
+                ivectors.extend(b.iter().map(|x| *x as f32));
+            }
+        }
+        Array2::from_shape_vec((ivectors.len() / n, n), ivectors).unwrap()
+    }
+
+    fn model(op: OnlineIvector) -> TractResult<TypedModel> {
+        let mut model = InferenceModel::default();
+        let s = stream_dim(&model.symbol_scope);
+        let gmm = model.add_source(
+            "gmm",
+            InferenceFact::dt_shape(f32::datum_type(), tvec!(s.clone(), 3.to_dim())),
+        )?;
+        let feats = model.add_source(
+            "feats",
+            InferenceFact::dt_shape(f32::datum_type(), tvec!(s, 3.to_dim())),
+        )?;
+        let output = model.wire_node("ivector", expand(op), &[gmm, feats])?;
+        model.set_output_outlets(&output)?;
+        model.into_typed()?.into_decluttered()
+    }
+
+    #[test]
+    fn online_ivector() -> TractResult<()> {
+        let op = op()?;
+        let frames = 23;
+        let gmm = Array2::from_shape_vec((frames, 3), pseudo_random(frames * 3, 4))?;
+        let feats = Array2::from_shape_vec((frames, 3), pseudo_random(frames * 3, 5))?;
+        let expected = reference(&op, &gmm, &feats).into_tensor();
+        assert_eq!(expected.shape(), &[6, 3]);
+        let model = model(op)?;
+
+        let s = stream_symbol(&model.symbol_scope);
+        let batch = model.concretize_dims(&SymbolValues::default().with(s, 23))?;
+        let found = batch
+            .into_runnable()?
+            .run(tvec!(gmm.clone().into_tensor(), feats.clone().into_tensor()))?
+            .remove(0);
+        found.close_enough(&expected, true)?;
+
+        let pulse = 8;
+        let pulsed = PulsedModel::new(&model, pulse)?;
+        let fact = pulsed.output_fact(0)?.clone();
+        let plan = SimplePlan::new(pulsed)?;
+        let mut state = SimpleState::new(&plan)?;
+        let padded_frames = (frames / pulse + 2) * pulse;
+        let mut got = vec![];
+        for chunk in 0..padded_frames / pulse {
+            let chunk = |x: &Array2<f32>| -> TractResult<Tensor> {
+                let mut padded = Array2::<f32>::zeros((pulse, 3));
+                for t in chunk * pulse..((chunk + 1) * pulse).min(frames) {
+                    padded.row_mut(t - chunk * pulse).assign(&x.row(t));
+                }
+                Ok(padded.into_tensor())
+            };
+            let output = state.run(tvec!(chunk(&gmm)?, chunk(&feats)?))?.remove(0);
+            got.extend(output.as_slice::<f32>()?.iter().cloned());
+        }
+        let got = tensor1(&got[fact.delay * 3..][..6 * 3]).into_shape(&[6, 3])?;
+        got.close_enough(&expected, true)?;
+        Ok(())
+    }
+}
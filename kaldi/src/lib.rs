@@ -5,9 +5,12 @@ extern crate educe;
 #[macro_use]
 extern crate log;
 
+pub mod features;
+pub mod ivector;
 pub mod model;
 mod ops;
 pub mod parser;
+pub mod writer;
 
 pub use model::Kaldi;
 pub use model::KaldiProtoModel;
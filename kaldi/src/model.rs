@@ -1,57 +1,137 @@
 use std::collections::BTreeMap;
+use std::fmt;
 
 use tract_hir::internal::*;
+use tract_itertools::Itertools;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct KaldiProtoModel {
     pub config_lines: ConfigLines,
     pub components: HashMap<String, Component>,
     pub adjust_final_offset: isize,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct ConfigLines {
-    pub input_name: String,
-    pub input_dim: usize,
+    pub input_nodes: Vec<(String, usize)>,
     pub nodes: Vec<(String, NodeLine)>,
     pub outputs: Vec<OutputLine>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum NodeLine {
     Component(ComponentNode),
     DimRange(DimRangeNode),
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct OutputLine {
     pub output_alias: String,
     pub descriptor: GeneralDescriptor,
+    pub objective: Option<String>,
 }
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum GeneralDescriptor {
     Append(Vec<GeneralDescriptor>),
+    Const(f32, usize),
+    Failover(Box<GeneralDescriptor>, Box<GeneralDescriptor>),
     IfDefined(Box<GeneralDescriptor>),
     Name(String),
     Offset(Box<GeneralDescriptor>, isize),
+    ReplaceIndex(Box<GeneralDescriptor>, char, isize),
+    Round(Box<GeneralDescriptor>, usize),
+    Scale(f32, Box<GeneralDescriptor>),
+    Sum(Box<GeneralDescriptor>, Box<GeneralDescriptor>),
+    Switch(Vec<GeneralDescriptor>),
+}
+
+/// Time frames covered by a wired tensor, in network time: the input spans
+/// `0..T`, the tensor starts at `first` and ends at `T - trim` (excluded).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Span {
+    pub first: isize,
+    pub trim: isize,
+}
+
+impl Span {
+    pub fn offset(self, offset: isize) -> Span {
+        Span { first: self.first - offset, trim: self.trim + offset }
+    }
+
+    fn intersection(spans: impl IntoIterator<Item = Option<Span>>) -> Option<Span> {
+        spans.into_iter().flatten().fold(None, |acc, span| {
+            Some(acc.map_or(span, |acc: Span| Span {
+                first: acc.first.max(span.first),
+                trim: acc.trim.max(span.trim),
+            }))
+        })
+    }
+}
+
+impl fmt::Display for GeneralDescriptor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use GeneralDescriptor::*;
+        match self {
+            Append(gds) => write!(f, "Append({})", gds.iter().join(", ")),
+            Const(value, dim) => write!(f, "Const({}, {})", value, dim),
+            Failover(a, b) => write!(f, "Failover({}, {})", a, b),
+            IfDefined(gd) => write!(f, "IfDefined({})", gd),
+            Name(n) => write!(f, "{}", n),
+            Offset(gd, o) => write!(f, "Offset({}, {})", gd, o),
+            ReplaceIndex(gd, var, value) => write!(f, "ReplaceIndex({}, {}, {})", gd, var, value),
+            Round(gd, modulus) => write!(f, "Round({}, {})", gd, modulus),
+            Scale(scale, gd) => write!(f, "Scale({}, {})", scale, gd),
+            Sum(a, b) => write!(f, "Sum({}, {})", a, b),
+            Switch(gds) => write!(f, "Switch({})", gds.iter().join(", ")),
+        }
+    }
 }
 
 impl GeneralDescriptor {
+    fn children(&self) -> Vec<&GeneralDescriptor> {
+        use GeneralDescriptor::*;
+        match self {
+            Append(gds) | Switch(gds) => gds.iter().collect(),
+            Failover(a, b) | Sum(a, b) => vec![&**a, &**b],
+            IfDefined(gd)
+            | Offset(gd, _)
+            | ReplaceIndex(gd, _, _)
+            | Round(gd, _)
+            | Scale(_, gd) => {
+                vec![&**gd]
+            }
+            Const(..) | Name(_) => vec![],
+        }
+    }
+
     pub fn inputs(&self) -> TVec<&str> {
+        if let GeneralDescriptor::Name(ref s) = self {
+            return tvec!(&**s);
+        }
+        self.children().iter().fold(tvec!(), |mut acc, gd| {
+            gd.inputs().iter().for_each(|i| {
+                if !acc.contains(i) {
+                    acc.push(i)
+                }
+            });
+            acc
+        })
+    }
+
+    /// Fail on the descriptor forms tract can not wire: ReplaceIndex only substitutes the t
+    /// index, or the x index by 0 (a no-op for the single x of nnet3 streams).
+    pub fn check_supported(&self) -> TractResult<()> {
         match self {
-            GeneralDescriptor::Append(ref gds) => gds.iter().fold(tvec!(), |mut acc, gd| {
-                gd.inputs().iter().for_each(|i| {
-                    if !acc.contains(i) {
-                        acc.push(i)
-                    }
-                });
-                acc
-            }),
-            GeneralDescriptor::IfDefined(ref gd) => gd.inputs(),
-            GeneralDescriptor::Name(ref s) => tvec!(&**s),
-            GeneralDescriptor::Offset(ref gd, _) => gd.inputs(),
+            GeneralDescriptor::ReplaceIndex(_, 'x', 0)
+            | GeneralDescriptor::ReplaceIndex(_, 't', _) => (),
+            GeneralDescriptor::ReplaceIndex(..) => bail!(
+                "Unsupported {}: only ReplaceIndex over t, or over x with index 0, are supported",
+                self
+            ),
+            _ => (),
         }
+        self.children().iter().try_for_each(|gd| gd.check_supported())
     }
 
     pub fn as_conv_shape_dilation(&self) -> Option<(usize, usize)> {
@@ -75,89 +155,305 @@ impl GeneralDescriptor {
         return None;
     }
 
+    fn as_memory(&self) -> Option<(&str, isize)> {
+        if let GeneralDescriptor::IfDefined(ref o) = self {
+            if let GeneralDescriptor::Offset(n, o) = &**o {
+                if let GeneralDescriptor::Name(n) = &**n {
+                    return Some((n.as_str(), *o));
+                }
+            }
+        }
+        None
+    }
+
+    /// Frames this descriptor can be computed for, given the spans of the
+    /// nodes it refers to. None means it adapts to whatever its consumer
+    /// requires: recurrent memories, constants and time-invariant values.
+    pub fn span(&self, spans: &HashMap<String, Span>) -> Option<Span> {
+        use GeneralDescriptor::*;
+        match self {
+            Name(n) => spans.get(n).cloned(),
+            Offset(gd, o) => gd.span(spans).map(|s| s.offset(*o)),
+            IfDefined(_) if self.as_memory().is_some() => None,
+            Append(gds) | Switch(gds) => Span::intersection(gds.iter().map(|gd| gd.span(spans))),
+            Sum(a, b) => Span::intersection(vec![a.span(spans), b.span(spans)]),
+            // without a consumer asking for more frames, only a is used
+            Failover(a, b) => match (a.span(spans), b.span(spans)) {
+                (Some(a), Some(b)) => {
+                    Some(Span { first: a.first.min(b.first), trim: a.trim.min(b.trim) })
+                }
+                (a, _) => a,
+            },
+            IfDefined(gd) | Scale(_, gd) => gd.span(spans),
+            Round(gd, modulus) => gd.span(spans).map(|s| Span {
+                first: s.first + (-s.first).rem_euclid(*modulus as isize),
+                trim: s.trim,
+            }),
+            ReplaceIndex(gd, 'x', _) => gd.span(spans),
+            ReplaceIndex(..) | Const(..) => None,
+        }
+    }
+
+    /// Wire the descriptor to `inlet`, cropped to the `target` span if given.
+    /// Edges are resolved by name later on through `deferred`, so that they
+    /// get added in order.
     fn wire<'a>(
         &'a self,
         inlet: InletId,
         name: &str,
         model: &mut InferenceModel,
         deferred: &mut BTreeMap<InletId, String>,
-        adjust_final_offset: Option<isize>,
+        spans: &HashMap<String, Span>,
+        target: Option<Span>,
     ) -> TractResult<()> {
         use GeneralDescriptor::*;
         match &self {
             &Name(n) => {
+                let inlet = crop(model, name, inlet, deferred, spans.get(n).cloned(), target)?;
                 deferred.insert(inlet, n.to_string());
                 return Ok(());
             }
+            &Offset(n, o) => {
+                return n.wire(inlet, name, model, deferred, spans, target.map(|t| t.offset(-o)));
+            }
             &Append(appendees) => {
+                let target = target.or_else(|| self.span(spans));
                 let name = format!("{}.Append", name);
                 let id = model.add_node(
                     &*name,
                     expand(tract_hir::ops::array::Concat::new(1)),
                     tvec!(InferenceFact::default()),
                 )?;
-                model.add_edge(OutletId::new(id, 0), inlet)?;
+                deferred.insert(inlet, name.clone());
                 for (ix, appendee) in appendees.iter().enumerate() {
                     let name = format!("{}-{}", name, ix);
-                    appendee.wire(
-                        InletId::new(id, ix),
-                        &*name,
-                        model,
-                        deferred,
-                        adjust_final_offset,
-                    )?;
+                    appendee.wire(InletId::new(id, ix), &*name, model, deferred, spans, target)?;
+                }
+                return Ok(());
+            }
+            &Sum(a, b) => {
+                let target = target.or_else(|| self.span(spans));
+                let name = format!("{}.Sum", name);
+                let id = model.add_node(
+                    &*name,
+                    tract_hir::ops::math::Add.into_hir(),
+                    tvec!(InferenceFact::default()),
+                )?;
+                deferred.insert(inlet, name.clone());
+                for (ix, term) in [a, b].iter().enumerate() {
+                    let name = format!("{}-{}", name, ix);
+                    term.wire(InletId::new(id, ix), &*name, model, deferred, spans, target)?;
                 }
                 return Ok(());
             }
-            &IfDefined(ref o) => {
-                if let &Offset(ref n, ref o) = &**o {
-                    if let Name(n) = &**n {
-                        let name = format!("{}.memory", name);
-                        model.add_node(
+            &Scale(scale, gd) => {
+                let name = format!("{}.Scale", name);
+                let id = model.add_node(
+                    &*name,
+                    tract_hir::ops::math::Mul.into_hir(),
+                    tvec!(InferenceFact::default()),
+                )?;
+                deferred.insert(inlet, name.clone());
+                let konst = format!("{}.scale", name);
+                model.add_const(&*konst, tensor2(&[[*scale]]))?;
+                deferred.insert(InletId::new(id, 1), konst);
+                return gd.wire(InletId::new(id, 0), &*name, model, deferred, spans, target);
+            }
+            &Const(value, dim) => {
+                // as many frames as the consumer requires, counted on an input node
+                let reference = model.input_outlets()?.get(0).context("Model has no input")?.node;
+                let reference = model.node(reference).name.clone();
+                let target = target.unwrap_or(Span { first: 0, trim: 0 });
+                let name = format!("{}.Const", name);
+                let id = model.add_node(
+                    &*name,
+                    expand(crate::ops::resample::ConstFrames::new(
+                        *value,
+                        *dim,
+                        -target.first.min(0),
+                        -target.trim.min(0),
+                    )),
+                    tvec!(InferenceFact::default()),
+                )?;
+                deferred.insert(inlet, name.clone());
+                let inner = Span { first: target.first.max(0), trim: target.trim.max(0) };
+                let inlet = crop(
+                    model,
+                    &name,
+                    InletId::new(id, 0),
+                    deferred,
+                    Some(Span { first: 0, trim: 0 }),
+                    Some(inner),
+                )?;
+                deferred.insert(inlet, reference);
+                return Ok(());
+            }
+            &Failover(a, b) => {
+                let target = target.or_else(|| self.span(spans));
+                match (a.span(spans), target) {
+                    (Some(span), Some(target))
+                        if span.first > target.first || span.trim > target.trim =>
+                    {
+                        let head = (span.first - target.first).max(0);
+                        let tail = (span.trim - target.trim).max(0);
+                        let name = format!("{}.Failover", name);
+                        let id = model.add_node(
                             &*name,
-                            crate::ops::memory::Memory::new(n.to_string(), *o),
+                            expand(crate::ops::resample::Failover::new(
+                                head as usize,
+                                tail as usize,
+                            )),
                             tvec!(InferenceFact::default()),
                         )?;
-                        deferred.insert(inlet, name);
-                        return Ok(());
+                        deferred.insert(inlet, name.clone());
+                        let a_target = Span {
+                            first: target.first.max(span.first),
+                            trim: target.trim.max(span.trim),
+                        };
+                        let a_name = format!("{}-0", name);
+                        a.wire(
+                            InletId::new(id, 0),
+                            &a_name,
+                            model,
+                            deferred,
+                            spans,
+                            Some(a_target),
+                        )?;
+                        let b_name = format!("{}-1", name);
+                        return b.wire(
+                            InletId::new(id, 1),
+                            &b_name,
+                            model,
+                            deferred,
+                            spans,
+                            Some(target),
+                        );
                     }
+                    _ => return a.wire(inlet, name, model, deferred, spans, target),
                 }
             }
-            &Offset(ref n, o) if *o > 0 => {
-                let name = format!("{}-Delay", name);
-                let crop = *o as isize + adjust_final_offset.unwrap_or(0);
-                if crop < 0 {
-                    bail!("Invalid offset adjustment (network as {}, adjustment is {}", o, crop)
+            &IfDefined(o) => {
+                if let Some((n, o)) = self.as_memory() {
+                    let name = format!("{}.memory", name);
+                    model.add_node(
+                        &*name,
+                        crate::ops::memory::Memory::new(n.to_string(), o),
+                        tvec!(InferenceFact::default()),
+                    )?;
+                    deferred.insert(inlet, name);
+                    return Ok(());
                 }
+                return o.wire(inlet, name, model, deferred, spans, target);
+            }
+            &Round(gd, modulus) => {
+                let span = gd
+                    .span(spans)
+                    .with_context(|| format!("Round over an unbounded descriptor: {:?}", gd))?;
+                let phase = (-span.first).rem_euclid(*modulus as isize);
+                let output_span = Span { first: span.first + phase, trim: span.trim };
+                let inlet = crop(model, name, inlet, deferred, Some(output_span), target)?;
+                let name = format!("{}.Round", name);
+                let id = model.add_node(
+                    &*name,
+                    expand(crate::ops::resample::Round::new(*modulus, phase as usize)),
+                    tvec!(InferenceFact::default()),
+                )?;
+                deferred.insert(inlet, name.clone());
+                return gd.wire(InletId::new(id, 0), &*name, model, deferred, spans, None);
+            }
+            &Switch(gds) => {
+                let target = target
+                    .or_else(|| self.span(spans))
+                    .with_context(|| format!("Switch over unbounded descriptors: {:?}", gds))?;
+                let name = format!("{}.Switch", name);
+                let offset = target.first.rem_euclid(gds.len() as isize);
                 let id = model.add_node(
                     &*name,
-                    expand(tract_hir::ops::array::Crop::new(0, crop as usize, 0)),
+                    expand(crate::ops::resample::Switch::new(offset as usize)),
                     tvec!(InferenceFact::default()),
                 )?;
-                model.add_edge(OutletId::new(id, 0), inlet)?;
-                n.wire(InletId::new(id, 0), &*name, model, deferred, adjust_final_offset)?;
+                deferred.insert(inlet, name.clone());
+                for (ix, gd) in gds.iter().enumerate() {
+                    let name = format!("{}-{}", name, ix);
+                    gd.wire(InletId::new(id, ix), &*name, model, deferred, spans, Some(target))?;
+                }
                 return Ok(());
             }
+            &ReplaceIndex(gd, 'x', 0) => {
+                return gd.wire(inlet, name, model, deferred, spans, target);
+            }
+            &ReplaceIndex(gd, 't', index) => {
+                let span = gd.span(spans).with_context(|| {
+                    format!("ReplaceIndex over an unbounded descriptor: {:?}", gd)
+                })?;
+                let position = index - span.first;
+                if position < 0 {
+                    bail!("ReplaceIndex at t={} before the first frame of {:?}", index, gd)
+                }
+                // time-invariant: the frame at index, over the frames requested by the consumer
+                let target = target.unwrap_or(span);
+                let name = format!("{}.ReplaceIndex", name);
+                let id = model.add_node(
+                    &*name,
+                    expand(crate::ops::resample::ReplaceIndex::new(
+                        position as usize,
+                        index - target.first,
+                        span.trim - target.trim,
+                    )),
+                    tvec!(InferenceFact::default()),
+                )?;
+                deferred.insert(inlet, name.clone());
+                return gd.wire(InletId::new(id, 0), &*name, model, deferred, spans, None);
+            }
             _ => (),
         }
         bail!("Unhandled input descriptor: {:?}", self)
     }
 }
 
-#[derive(Clone, Debug)]
+/// Insert a crop in front of `inlet` if a tensor spanning `from` needs to be
+/// restricted to the `to` span. Returns the inlet to wire the tensor to.
+fn crop(
+    model: &mut InferenceModel,
+    name: &str,
+    inlet: InletId,
+    deferred: &mut BTreeMap<InletId, String>,
+    from: Option<Span>,
+    to: Option<Span>,
+) -> TractResult<InletId> {
+    match (from, to) {
+        (Some(from), Some(to)) if from != to => {
+            let (start, end) = (to.first - from.first, to.trim - from.trim);
+            if start < 0 || end < 0 {
+                bail!("Can not align {} (spanning {:?}) on {:?}", name, from, to)
+            }
+            let name = format!("{}.Crop", name);
+            let id = model.add_node(
+                &*name,
+                expand(tract_hir::ops::array::Crop::new(0, start as usize, end as usize)),
+                tvec!(InferenceFact::default()),
+            )?;
+            deferred.insert(inlet, name);
+            Ok(InletId::new(id, 0))
+        }
+        _ => Ok(inlet),
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
 pub struct DimRangeNode {
     pub input: GeneralDescriptor,
     pub offset: usize,
     pub dim: usize,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct ComponentNode {
     pub input: GeneralDescriptor,
     pub component: String,
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct Component {
     pub klass: String,
     pub attributes: HashMap<String, Arc<Tensor>>,
@@ -187,6 +483,18 @@ pub struct Kaldi {
     pub op_register: KaldiOpRegister,
 }
 
+impl Kaldi {
+    /// Dump a proto model back to nnet3, in Kaldi binary or text format.
+    pub fn write(
+        &self,
+        proto_model: &KaldiProtoModel,
+        binary: bool,
+        mut w: impl std::io::Write,
+    ) -> TractResult<()> {
+        crate::writer::nnet3(&mut w, proto_model, binary)
+    }
+}
+
 impl Framework<KaldiProtoModel, InferenceModel> for Kaldi {
     fn proto_model_for_read(&self, r: &mut dyn std::io::Read) -> TractResult<KaldiProtoModel> {
         use crate::parser;
@@ -198,14 +506,15 @@ impl Framework<KaldiProtoModel, InferenceModel> for Kaldi {
     fn model_for_proto_model(&self, proto_model: &KaldiProtoModel) -> TractResult<InferenceModel> {
         let ctx = ParsingContext { proto_model };
         let mut model = InferenceModel::default();
-        let s = tract_pulse::internal::stream_dim();
-        model.add_source(
-            proto_model.config_lines.input_name.clone(),
-            InferenceFact::dt_shape(
-                f32::datum_type(),
-                shapefactoid!(s, (proto_model.config_lines.input_dim)),
-            ),
-        )?;
+        let s = tract_pulse::internal::stream_dim(&model.symbol_scope);
+        let mut spans: HashMap<String, Span> = HashMap::default();
+        for (name, dim) in &proto_model.config_lines.input_nodes {
+            model.add_source(
+                name.clone(),
+                InferenceFact::dt_shape(f32::datum_type(), shapefactoid!(s, (*dim))),
+            )?;
+            spans.insert(name.clone(), Span { first: 0, trim: 0 });
+        }
         let mut inputs_to_wire: BTreeMap<InletId, String> = Default::default();
         for (name, node) in &proto_model.config_lines.nodes {
             match node {
@@ -241,9 +550,14 @@ impl Framework<KaldiProtoModel, InferenceModel> for Kaldi {
                             name,
                             &mut model,
                             &mut inputs_to_wire,
+                            &spans,
                             None,
                         )?
                     }
+                    // a valid convolution over the offsets also spans their intersection
+                    if let Some(span) = line.input.span(&spans) {
+                        spans.insert(name.to_string(), span);
+                    }
                 }
                 NodeLine::DimRange(line) => {
                     let op = tract_hir::ops::array::Slice::new(
@@ -258,8 +572,12 @@ impl Framework<KaldiProtoModel, InferenceModel> for Kaldi {
                         name,
                         &mut model,
                         &mut inputs_to_wire,
+                        &spans,
                         None,
-                    )?
+                    )?;
+                    if let Some(span) = line.input.span(&spans) {
+                        spans.insert(name.to_string(), span);
+                    }
                 }
             }
         }
@@ -270,12 +588,19 @@ impl Framework<KaldiProtoModel, InferenceModel> for Kaldi {
                 tract_hir::ops::identity::Identity::default(),
                 tvec!(InferenceFact::default()),
             )?;
+            // frames before the input start are cropped, modulo the final offset adjustment
+            let target = o
+                .descriptor
+                .span(&spans)
+                .filter(|span| span.first < 0)
+                .map(|span| Span { first: proto_model.adjust_final_offset, trim: span.trim });
             o.descriptor.wire(
                 InletId::new(output, 0),
                 "output",
                 &mut model,
                 &mut inputs_to_wire,
-                Some(proto_model.adjust_final_offset),
+                &spans,
+                target,
             )?;
             outputs.push(OutletId::new(output, 0));
         }
@@ -287,3 +612,80 @@ impl Framework<KaldiProtoModel, InferenceModel> for Kaldi {
         Ok(model)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use tract_pulse::internal::{stream_symbol, PulsedModel, PulsedModelExt};
+
+    fn model(config: &str) -> TractResult<TypedModel> {
+        let nnet3 = format!("<Nnet3>\n{}\n\n<NumComponents> 0\n</Nnet3>", config);
+        let kaldi = crate::kaldi();
+        let proto_model = kaldi.proto_model_for_read(&mut nnet3.as_bytes())?;
+        kaldi.model_for_proto_model(&proto_model)?.into_typed()?.into_decluttered()
+    }
+
+    fn run(model: &TypedModel, inputs: TVec<Tensor>) -> TractResult<Arc<Tensor>> {
+        let len = inputs[0].shape()[0] as i64;
+        let s = stream_symbol(&model.symbol_scope);
+        let model = model.concretize_dims(&SymbolValues::default().with(s, len))?;
+        Ok(model.into_runnable()?.run(inputs)?.remove(0))
+    }
+
+    fn frames(v: &[f32]) -> Tensor {
+        tensor1(v).into_shape(&[v.len(), 1]).unwrap()
+    }
+
+    #[test]
+    fn sum_and_scale() -> TractResult<()> {
+        let model = model(
+            "input-node name=input dim=1\n\
+             output-node name=output input=Sum(Offset(input, -1), Scale(0.5, Offset(input, 1)))",
+        )?;
+        let output = run(&model, tvec!(frames(&[0., 1., 2., 3., 4., 5.])))?;
+        assert_eq!(*output, frames(&[1., 2.5, 4., 5.5]));
+        PulsedModel::new(&model, 2)?;
+        Ok(())
+    }
+
+    #[test]
+    fn round_and_replace_index() -> TractResult<()> {
+        let model = model(
+            "input-node name=input dim=1\n\
+             input-node name=ivector dim=1\n\
+             output-node name=output \
+             input=Append(Round(Offset(input, -1), 2), ReplaceIndex(ivector, t, 0))",
+        )?;
+        let output = run(
+            &model,
+            tvec!(frames(&[0., 1., 2., 3., 4., 5.]), frames(&[10., 11., 12., 13., 14., 15.])),
+        )?;
+        assert_eq!(*output, tensor2(&[[1f32, 10.], [1., 10.], [3., 10.], [3., 10.], [5., 10.]]));
+        PulsedModel::new(&model, 2)?;
+        Ok(())
+    }
+
+    #[test]
+    fn failover_where_not_computable() -> TractResult<()> {
+        let model = model(
+            "input-node name=input dim=1\n\
+             output-node name=output input=Failover(Offset(input, 1), input)",
+        )?;
+        let output = run(&model, tvec!(frames(&[0., 1., 2., 3., 4., 5.])))?;
+        assert_eq!(*output, frames(&[1., 2., 3., 4., 5., 5.]));
+        PulsedModel::new(&model, 2)?;
+        Ok(())
+    }
+
+    #[test]
+    fn append_const() -> TractResult<()> {
+        let model = model(
+            "input-node name=input dim=1\n\
+             output-node name=output input=Append(Offset(input, -1), Const(7, 2))",
+        )?;
+        let output = run(&model, tvec!(frames(&[0., 1., 2.])))?;
+        assert_eq!(*output, tensor2(&[[0f32, 7., 7.], [1., 7., 7.], [2., 7., 7.]]));
+        PulsedModel::new(&model, 2)?;
+        Ok(())
+    }
+}
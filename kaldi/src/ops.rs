@@ -14,6 +14,7 @@ pub(crate) mod affine;
 pub(crate) mod lstm_nonlin;
 pub(crate) mod memory;
 mod renorm;
+pub(crate) mod resample;
 
 pub const AFFINE: &'static [&'static str] =
     &["FixedAffineComponent", "NaturalGradientAffineComponent"];
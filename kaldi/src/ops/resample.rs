@@ -0,0 +1,518 @@
+use std::ops::Range;
+
+use tract_hir::internal::*;
+use tract_hir::ops::math;
+use tract_hir::tract_core::ops::array::{Pad, PadMode, Slice};
+use tract_hir::tract_core::ops::cnn::{
+    ConvUnary, DeconvUnary, KernelFormat, PaddingSpec, PoolSpec,
+};
+use tract_hir::tract_core::ops::nn::{DataFormat, RunningReduce, RunningReducer};
+use tract_hir::tract_core::ops::Downsample;
+
+/// Round(x, modulus) descriptor: each frame takes the value of the last frame
+/// whose time index is a multiple of the modulus.
+///
+/// `phase` is the position of the first such frame in the input, the output
+/// starts there.
+#[derive(Clone, Debug, new, Hash)]
+pub struct Round {
+    pub modulus: usize,
+    pub phase: usize,
+}
+
+impl_dyn_hash!(Round);
+
+impl Expansion for Round {
+    fn name(&self) -> std::borrow::Cow<str> {
+        "Round".into()
+    }
+
+    op_kaldi!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[0].rank, 2)?;
+        s.equals(&outputs[0].rank, 2)?;
+        s.equals(&inputs[0].shape[1], &outputs[0].shape[1])?;
+        s.equals(&inputs[0].shape[0], outputs[0].shape[0].bex() + self.phase.to_dim())?;
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        wire_hold(prefix, model, inputs[0], self.modulus, self.phase, 0..self.modulus)
+    }
+}
+
+/// Switch(x0, x1, ...) descriptor: frame at time t is taken from the input
+/// number t modulo the input count.
+///
+/// `offset` is the time index of the first frame, modulo the input count.
+#[derive(Clone, Debug, new, Hash)]
+pub struct Switch {
+    pub offset: usize,
+}
+
+impl_dyn_hash!(Switch);
+
+impl Expansion for Switch {
+    fn name(&self) -> std::borrow::Cow<str> {
+        "Switch".into()
+    }
+
+    op_kaldi!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_output_arity(&outputs, 1)?;
+        s.equals(&outputs[0].rank, 2)?;
+        for input in inputs {
+            s.equals(&input.datum_type, &outputs[0].datum_type)?;
+            s.equals(&input.shape, &outputs[0].shape)?;
+        }
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let period = inputs.len();
+        let mut wire: Option<OutletId> = None;
+        for (ix, input) in inputs.iter().enumerate() {
+            let phase = (ix + period - self.offset % period) % period;
+            let branch = wire_hold(
+                &format!("{}.branch-{}", prefix, ix),
+                model,
+                *input,
+                period,
+                phase,
+                phase..phase + 1,
+            )?[0];
+            wire = Some(if let Some(sum) = wire {
+                model.wire_node(
+                    format!("{}.sum-{}", prefix, ix),
+                    tract_hir::ops::math::add::bin_typed(),
+                    &[sum, branch],
+                )?[0]
+            } else {
+                branch
+            });
+        }
+        Ok(tvec!(wire.context("Switch expects at least one input")?))
+    }
+}
+
+/// ReplaceIndex(x, t, index) descriptor: every frame takes the value of the
+/// input frame at `position`, the one at time `index`.
+///
+/// The output starts `before` frames before `position` and stops `after`
+/// frames after the input, negative values dropping frames. The frame is
+/// latched with running sums so it can be pulsified: the frames before it
+/// repeat it once it has been seen.
+///
+/// The running frame count is a f32 sum: it is exact up to 2^24 frames, then
+/// stalls, away from `position` which must stay below. Streams can be longer.
+/// Input frames must be finite: the others are multiplied by zero.
+#[derive(Clone, Debug, new, Hash)]
+pub struct ReplaceIndex {
+    pub position: usize,
+    pub before: isize,
+    pub after: isize,
+}
+
+impl_dyn_hash!(ReplaceIndex);
+
+impl Expansion for ReplaceIndex {
+    fn name(&self) -> std::borrow::Cow<str> {
+        "ReplaceIndex".into()
+    }
+
+    op_kaldi!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[0].rank, 2)?;
+        s.equals(&outputs[0].rank, 2)?;
+        s.equals(&inputs[0].shape[1], &outputs[0].shape[1])?;
+        let removed = self.position as isize - self.before - self.after;
+        s.equals(&inputs[0].shape[0], outputs[0].shape[0].bex() + (removed as i64).to_dim())?;
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        ensure!(
+            self.position < 1 << 24,
+            "ReplaceIndex can not count up to frame {} with f32 sums",
+            self.position
+        );
+        // one-hot selector of the frame at `position`: max(1 - |count - position - 1|, 0),
+        // count being the running number of frames
+        let mut wire = wire_filled(&format!("{}.ones", prefix), model, inputs[0], &[1.0])?;
+        wire = model.wire_node(
+            format!("{}.count", prefix),
+            RunningReduce { axis: 0, reducer: RunningReducer::Sum },
+            &wire,
+        )?;
+        let selector: [(&str, Box<dyn TypedOp>); 5] = [
+            ("centered", Box::new(math::add::unary(rctensor2(&[[-1.0 - self.position as f32]])))),
+            ("distance", Box::new(math::abs())),
+            ("negated", Box::new(math::mul::unary(rctensor2(&[[-1f32]])))),
+            ("proximity", Box::new(math::add::unary(rctensor2(&[[1f32]])))),
+            ("selector", Box::new(math::max::unary(rctensor2(&[[0f32]])))),
+        ];
+        for (name, op) in selector.iter() {
+            wire = model.wire_node(format!("{}.{}", prefix, name), op.clone(), &wire)?;
+        }
+        let selected = model.wire_node(
+            format!("{}.selected", prefix),
+            math::mul::bin_typed(),
+            &[inputs[0], wire[0]],
+        )?;
+        let latched = model.wire_node(
+            format!("{}.latched", prefix),
+            RunningReduce { axis: 0, reducer: RunningReducer::Sum },
+            &selected,
+        )?[0];
+        let len = model.outlet_fact(latched)?.shape[0].clone();
+        let held = model.wire_node(
+            format!("{}.held", prefix),
+            Slice::new(0, self.position, len),
+            &[latched],
+        )?[0];
+        wire_extend(prefix, model, held, self.before, self.after, PadMode::Edge)
+    }
+}
+
+/// Const(value, dim) descriptor: `dim` channels of `value`, for each frame of
+/// its input, a reference stream.
+///
+/// The output starts `before` frames before the input and stops `after`
+/// frames after it, negative values dropping frames. The input frames must be
+/// finite (see `wire_filled`).
+#[derive(Clone, Debug, new, Educe)]
+#[educe(Hash)]
+pub struct ConstFrames {
+    #[educe(Hash(method = "hash_f32"))]
+    pub value: f32,
+    pub dim: usize,
+    pub before: isize,
+    pub after: isize,
+}
+
+impl_dyn_hash!(ConstFrames);
+
+impl Expansion for ConstFrames {
+    fn name(&self) -> std::borrow::Cow<str> {
+        "ConstFrames".into()
+    }
+
+    op_kaldi!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&outputs[0].datum_type, f32::datum_type())?;
+        s.equals(&inputs[0].rank, 2)?;
+        s.equals(&outputs[0].rank, 2)?;
+        s.equals(&outputs[0].shape[1], self.dim.to_dim())?;
+        let removed = -self.before - self.after;
+        s.equals(&inputs[0].shape[0], outputs[0].shape[0].bex() + (removed as i64).to_dim())?;
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let values = vec![self.value; self.dim];
+        let frames = wire_filled(&format!("{}.frames", prefix), model, inputs[0], &values)?[0];
+        let pad = PadMode::Constant(rctensor0(self.value));
+        wire_extend(prefix, model, frames, self.before, self.after, pad)
+    }
+}
+
+/// Failover(a, b) descriptor: `a` where it is computable, `b` elsewhere.
+///
+/// The second input spans the output, the first one misses its `head` first
+/// and `tail` last frames. The frames of both must be finite: the mask
+/// picking them is multiplied in.
+#[derive(Clone, Debug, new, Hash)]
+pub struct Failover {
+    pub head: usize,
+    pub tail: usize,
+}
+
+impl_dyn_hash!(Failover);
+
+impl Expansion for Failover {
+    fn name(&self) -> std::borrow::Cow<str> {
+        "Failover".into()
+    }
+
+    op_kaldi!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 2)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[1].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[0].rank, 2)?;
+        s.equals(&inputs[1].shape, &outputs[0].shape)?;
+        s.equals(&inputs[0].shape[1], &outputs[0].shape[1])?;
+        s.equals(&inputs[1].shape[0], inputs[0].shape[0].bex() + (self.head + self.tail).to_dim())?;
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let pads = vec![(self.head, self.tail), (0, 0)];
+        let zero = PadMode::Constant(rctensor0(0f32));
+        let a = model.wire_node(
+            format!("{}.a", prefix),
+            Pad::new(pads.clone(), zero.clone()),
+            &inputs[0..1],
+        )?;
+        // one where a is computable, zero elsewhere
+        let ones = wire_filled(&format!("{}.ones", prefix), model, inputs[0], &[1.0])?;
+        let mask = model.wire_node(format!("{}.mask", prefix), Pad::new(pads, zero), &ones)?;
+        let mask = model.wire_node(
+            format!("{}.mask.negated", prefix),
+            math::mul::unary(rctensor2(&[[-1f32]])),
+            &mask,
+        )?;
+        let mask = model.wire_node(
+            format!("{}.mask.inverted", prefix),
+            math::add::unary(rctensor2(&[[1f32]])),
+            &mask,
+        )?;
+        let b = model.wire_node(
+            format!("{}.b", prefix),
+            math::mul::bin_typed(),
+            &[inputs[1], mask[0]],
+        )?;
+        model.wire_node(prefix, math::add::bin_typed(), &[a[0], b[0]])
+    }
+}
+
+/// Frames of `values`, as many as in `input`. They are made by a convolution
+/// so they keep following the stream: its kernel is zero, so the frames are
+/// exact whatever the stream length, as long as the input is finite.
+fn wire_filled(
+    name: &str,
+    model: &mut TypedModel,
+    input: OutletId,
+    values: &[f32],
+) -> TractResult<TVec<OutletId>> {
+    let channels = model.outlet_fact(input)?.shape[1].to_usize()?;
+    let op = ConvUnary {
+        pool_spec: PoolSpec::new(
+            DataFormat::HWC,
+            tvec!(1),
+            PaddingSpec::Valid,
+            None,
+            None,
+            Some(values.len()),
+        ),
+        kernel_fmt: KernelFormat::HWIO,
+        kernel: Tensor::zero::<f32>(&[1, channels, values.len()])?.into_arc_tensor(),
+        group: 1,
+        bias: Some(tensor1(values).into_arc_tensor()),
+        q_params: None,
+    };
+    model.wire_node(name, op, &[input])
+}
+
+/// Add `before` and `after` frames around `input` with `mode` padding, or
+/// drop frames for negative values.
+fn wire_extend(
+    prefix: &str,
+    model: &mut TypedModel,
+    mut input: OutletId,
+    before: isize,
+    after: isize,
+    mode: PadMode,
+) -> TractResult<TVec<OutletId>> {
+    if before < 0 || after < 0 {
+        let len = model.outlet_fact(input)?.shape[0].clone();
+        let end = len - after.min(0).abs() as usize;
+        input = model.wire_node(
+            format!("{}.crop", prefix),
+            Slice::new(0, before.min(0).abs() as usize, end),
+            &[input],
+        )?[0];
+    }
+    if before > 0 || after > 0 {
+        let pads = vec![(before.max(0) as usize, after.max(0) as usize), (0, 0)];
+        input = model.wire_node(format!("{}.pad", prefix), Pad::new(pads, mode), &[input])?[0];
+    }
+    model.rename_node(input.node, prefix)?;
+    Ok(tvec!(input))
+}
+
+/// Keeps the frames at `phase` modulo `period` and copies each of them over
+/// `taps` of its period, leaving zeros elsewhere. Output starts at input frame
+/// `phase - taps.start`, and stops with the input.
+///
+/// This is done with a depthwise strided deconvolution so it can be pulsified.
+fn wire_hold(
+    prefix: &str,
+    model: &mut TypedModel,
+    input: OutletId,
+    period: usize,
+    phase: usize,
+    taps: Range<usize>,
+) -> TractResult<TVec<OutletId>> {
+    let fact = model.outlet_fact(input)?.clone();
+    let len = fact.shape[0].clone();
+    let channels = fact.shape[1].to_usize()?;
+    let down = model.wire_node(
+        format!("{}.downsample", prefix),
+        Downsample::new(0, period as isize, phase),
+        &[input],
+    )?;
+    // long enough to cover the tail past the last kept frame
+    let kernel_len = taps.end.max(period + taps.start);
+    let mut kernel = Tensor::zero::<f32>(&[kernel_len, channels, 1])?;
+    kernel.as_slice_mut::<f32>()?[taps.start * channels..taps.end * channels]
+        .iter_mut()
+        .for_each(|x| *x = 1.0);
+    let deconv = model.wire_node(
+        format!("{}.deconv", prefix),
+        DeconvUnary::new(
+            PoolSpec::new(
+                DataFormat::HWC,
+                tvec!(kernel_len),
+                PaddingSpec::Valid,
+                None,
+                Some(tvec!(period)),
+                Some(channels),
+            ),
+            KernelFormat::HWIO,
+            kernel.cast_to_dt(fact.datum_type)?.into_owned().into_arc_tensor(),
+            None,
+            tvec!(0),
+            channels,
+        ),
+        &down,
+    )?;
+    model.wire_node(prefix, Slice::new(0, 0, len - phase + taps.start), &deconv)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn run(op: impl Expansion, inputs: TVec<Tensor>) -> TractResult<Tensor> {
+        let mut model = InferenceModel::default();
+        let sources = inputs
+            .iter()
+            .enumerate()
+            .map(|(ix, t)| {
+                model.add_source(
+                    format!("input-{}", ix),
+                    InferenceFact::dt_shape(f32::datum_type(), t.shape()),
+                )
+            })
+            .collect::<TractResult<TVec<_>>>()?;
+        let output = model.wire_node("op", expand(op), &sources)?;
+        model.set_output_outlets(&output)?;
+        let mut outputs = model.into_runnable()?.run(inputs)?;
+        Ok(outputs.remove(0).into_tensor())
+    }
+
+    fn frames(v: &[f32]) -> Tensor {
+        tensor1(v).into_shape(&[v.len(), 1]).unwrap()
+    }
+
+    #[test]
+    fn round() -> TractResult<()> {
+        let output = run(Round::new(3, 1), tvec!(frames(&[0., 1., 2., 3., 4., 5., 6., 7.])))?;
+        assert_eq!(output, frames(&[1., 1., 1., 4., 4., 4., 7.]));
+        Ok(())
+    }
+
+    // long streams are slow without optimizations: cargo test --release -- --ignored
+    #[test]
+    #[ignore]
+    fn replace_index_past_f32_counting() -> TractResult<()> {
+        // the running count stalls at 2^24 frames, no other frame may be selected
+        let len = (1 << 24) + 16;
+        let mut input = Tensor::zero::<f32>(&[len, 1])?;
+        input.as_slice_mut::<f32>()?[2] = 7.0;
+        let output = run(ReplaceIndex::new(2, 2, 0), tvec!(input))?;
+        assert_eq!(output.shape(), &[len, 1]);
+        assert!(output.as_slice::<f32>()?.iter().all(|x| *x == 7.0));
+        Ok(())
+    }
+
+    #[test]
+    #[ignore]
+    fn const_frames_and_failover_on_long_inputs() -> TractResult<()> {
+        let len = (1 << 24) + 16;
+        let input = Tensor::zero::<f32>(&[len, 1])?;
+        let output = run(ConstFrames::new(0.1, 1, 0, 0), tvec!(input.clone()))?;
+        assert!(output.as_slice::<f32>()?.iter().all(|x| *x == 0.1));
+        let b = tensor1(&vec![3f32; len + 2]).into_shape(&[len + 2, 1])?;
+        let output = run(Failover::new(1, 1), tvec!(input, b))?;
+        let output = output.as_slice::<f32>()?;
+        assert!(output[0] == 3.0 && output[len + 1] == 3.0);
+        assert!(output[1..=len].iter().all(|x| *x == 0.0));
+        Ok(())
+    }
+
+    #[test]
+    fn switch() -> TractResult<()> {
+        let output = run(
+            Switch::new(1),
+            tvec!(frames(&[0., 1., 2., 3., 4.]), frames(&[10., 11., 12., 13., 14.])),
+        )?;
+        assert_eq!(output, frames(&[10., 1., 12., 3., 14.]));
+        Ok(())
+    }
+}
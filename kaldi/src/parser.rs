@@ -17,8 +17,8 @@ use crate::model::{Component, KaldiProtoModel};
 use tract_itertools::Itertools;
 
 mod bin;
-mod components;
-mod config_lines;
+pub(crate) mod components;
+pub(crate) mod config_lines;
 mod descriptor;
 mod text;
 
@@ -115,7 +115,9 @@ pub fn integer<'a>(bin: bool) -> impl FnMut(&'a [u8]) -> IResult<&'a [u8], i32>
     )
 }
 
-pub fn spaced<I, O, E: nom::error::ParseError<I>, F>(it: F) -> impl FnMut(I) -> nom::IResult<I, O, E>
+pub fn spaced<I, O, E: nom::error::ParseError<I>, F>(
+    it: F,
+) -> impl FnMut(I) -> nom::IResult<I, O, E>
 where
     I: nom::InputTakeAtPosition,
     <I as nom::InputTakeAtPosition>::Item: nom::AsChar + Clone,
@@ -1,4 +1,4 @@
-use tract_hir::prelude::*;
+use tract_hir::internal::*;
 
 use maplit::hashmap;
 use std::collections::HashMap;
@@ -37,6 +37,48 @@ impl KaldiAttributeKind {
         }
     }
 
+    pub fn write_bin(&self, w: &mut dyn std::io::Write, t: &Tensor) -> TractResult<()> {
+        match self {
+            Bool => w.write_all(if t.cast_to_scalar::<bool>()? { b"T" } else { b"F" })?,
+            Int => {
+                w.write_all(&[4])?;
+                w.write_all(&t.cast_to_scalar::<i32>()?.to_le_bytes())?;
+            }
+            Float => {
+                w.write_all(&[4])?;
+                w.write_all(&t.cast_to_scalar::<f32>()?.to_le_bytes())?;
+            }
+            FloatVector => {
+                if t.rank() != 1 {
+                    bail!("Expected a vector, got {:?}", t)
+                }
+                w.write_all(b"FV ")?;
+                Self::write_floats(w, t)?;
+            }
+            FloatMatrix => {
+                if t.rank() != 2 {
+                    bail!("Expected a matrix, got {:?}", t)
+                }
+                w.write_all(b"FM ")?;
+                w.write_all(&[4])?;
+                w.write_all(&(t.shape()[0] as i32).to_le_bytes())?;
+                Self::write_floats(w, t)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Length prefix (last dimension) followed by the data.
+    fn write_floats(w: &mut dyn std::io::Write, t: &Tensor) -> TractResult<()> {
+        let t = t.cast_to::<f32>()?;
+        w.write_all(&[4])?;
+        w.write_all(&(*t.shape().last().unwrap() as i32).to_le_bytes())?;
+        for f in t.as_slice::<f32>()? {
+            w.write_all(&f.to_le_bytes())?;
+        }
+        Ok(())
+    }
+
     fn parse_float_value<'a>(i: &'a [u8]) -> IResult<&'a [u8], f32> {
         alt((preceded(tag([4]), le_f32), map(preceded(tag([8]), le_f64), |f| f as f32)))(i)
     }
@@ -71,74 +113,79 @@ impl KaldiAttributeKind {
 
 use KaldiAttributeKind::*;
 
+pub fn attribute_kind(klass: &str, attribute: &str) -> Option<&'static KaldiAttributeKind> {
+    COMPONENTS.get(klass)?.iter().find(|(name, _)| *name == attribute).map(|(_, kind)| kind)
+}
+
 lazy_static::lazy_static! {
-    pub static ref COMPONENTS: HashMap<&'static str, HashMap<&'static str, KaldiAttributeKind>> = hashmap! {
-        "FixedAffineComponent" => hashmap! {
-            "LinearParams" => FloatMatrix,
-            "BiasParams" => FloatVector,
-        },
-        "NaturalGradientAffineComponent" => hashmap! {
-            "LearningRateFactor" => Float,
-            "MaxChange" => Float,
-            "LearningRate" => Float,
-            "LinearParams" => FloatMatrix,
-            "BiasParams" => FloatVector,
-            "RankIn" => Int,
-            "RankOut" => Int,
-            "UpdatePeriod" => Int,
-            "NumSamplesHistory" => Float,
-            "Alpha" => Float,
-            "IsGradient" => Bool,
-        },
-        "NormalizeComponent" => hashmap!{
-            "InputDim" => Int,
-            "TargetRms" => Float,
-            "AddLogStddev" => Bool,
-        },
-        "FakeQuantizationComponent" => hashmap!{
-            "Activated" => Bool,
-            "Dim" => Int,
-            "MaxValue" => Float,
-            "MinValue" => Float,
-        },
-        "LstmNonlinearityComponent" => hashmap!{
-            "MaxChange" => Float,
-            "LearningRate" => Float,
-            "Params" => FloatMatrix,
-            "ValueAvg" => FloatMatrix,
-            "DerivAvg" => FloatMatrix,
-            "SelfRepairConfig" => FloatVector,
-            "SelfRepairProb" => FloatVector,
-            "Count" => Float,
-        },
-        "BackpropTruncationComponent" => hashmap!{
-            "Dim" => Int,
-            "Scale" => Float,
-            "ClippingThreshold" => Float,
-            "ZeroingThreshold" => Float,
-            "ZeroingInterval" => Int,
-            "RecurrenceInterval" => Int,
-            "NumElementsClipped" => Float,
-            "NumElementsZeroed" => Float,
-            "NumElementsProcessed" => Float,
-            "NumZeroingBoundaries" => Float,
-        },
-        "LogSoftmaxComponent" => hashmap!{
-            "Dim" => Int,
-            "ValueAvg" => FloatVector,
-            "DerivAvg" => FloatVector,
-            "Count" => Int,
-            "NumDimsSelfRepaired" => Int,
-            "NumDimsProcessed" => Int,
-        },
-        "RectifiedLinearComponent" => hashmap!{
-            "Dim" => Int,
-            "ValueAvg" => FloatVector,
-            "DerivAvg" => FloatVector,
-            "Count" => Float,
-            "NumDimsSelfRepaired" => Float,
-            "NumDimsProcessed" => Float,
-            "SelfRepairScale" => Float,
-        }
+    /// Attributes of the supported components, in the order Kaldi serializes them.
+    pub static ref COMPONENTS: HashMap<&'static str, Vec<(&'static str, KaldiAttributeKind)>> = hashmap! {
+        "FixedAffineComponent" => vec![
+            ("LinearParams", FloatMatrix),
+            ("BiasParams", FloatVector),
+        ],
+        "NaturalGradientAffineComponent" => vec![
+            ("LearningRateFactor", Float),
+            ("MaxChange", Float),
+            ("LearningRate", Float),
+            ("LinearParams", FloatMatrix),
+            ("BiasParams", FloatVector),
+            ("RankIn", Int),
+            ("RankOut", Int),
+            ("UpdatePeriod", Int),
+            ("NumSamplesHistory", Float),
+            ("Alpha", Float),
+            ("IsGradient", Bool),
+        ],
+        "NormalizeComponent" => vec![
+            ("InputDim", Int),
+            ("TargetRms", Float),
+            ("AddLogStddev", Bool),
+        ],
+        "FakeQuantizationComponent" => vec![
+            ("Activated", Bool),
+            ("Dim", Int),
+            ("MaxValue", Float),
+            ("MinValue", Float),
+        ],
+        "LstmNonlinearityComponent" => vec![
+            ("MaxChange", Float),
+            ("LearningRate", Float),
+            ("Params", FloatMatrix),
+            ("ValueAvg", FloatMatrix),
+            ("DerivAvg", FloatMatrix),
+            ("SelfRepairConfig", FloatVector),
+            ("SelfRepairProb", FloatVector),
+            ("Count", Float),
+        ],
+        "BackpropTruncationComponent" => vec![
+            ("Dim", Int),
+            ("Scale", Float),
+            ("ClippingThreshold", Float),
+            ("ZeroingThreshold", Float),
+            ("ZeroingInterval", Int),
+            ("RecurrenceInterval", Int),
+            ("NumElementsClipped", Float),
+            ("NumElementsZeroed", Float),
+            ("NumElementsProcessed", Float),
+            ("NumZeroingBoundaries", Float),
+        ],
+        "LogSoftmaxComponent" => vec![
+            ("Dim", Int),
+            ("ValueAvg", FloatVector),
+            ("DerivAvg", FloatVector),
+            ("Count", Int),
+            ("NumDimsSelfRepaired", Int),
+            ("NumDimsProcessed", Int),
+        ],
+        "RectifiedLinearComponent" => vec![
+            ("Dim", Int),
+            ("ValueAvg", FloatVector),
+            ("DerivAvg", FloatVector),
+            ("Count", Float),
+            ("NumDimsSelfRepaired", Float),
+            ("NumDimsProcessed", Float),
+            ("SelfRepairScale", Float),
+        ],
     };
 }
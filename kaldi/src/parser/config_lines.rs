@@ -7,7 +7,7 @@ use crate::model::{ComponentNode, ConfigLines, DimRangeNode, NodeLine, OutputLin
 use crate::parser::spaced;
 
 pub fn parse_config(s: &str) -> TractResult<ConfigLines> {
-    let mut input_node: Option<(String, usize)> = None;
+    let mut input_nodes = vec![];
     let mut nodes = vec![];
     let mut outputs = vec![];
     for line in s.lines() {
@@ -20,35 +20,39 @@ pub fn parse_config(s: &str) -> TractResult<ConfigLines> {
         }
         let line_kind = line.split(" ").next().unwrap();
         match line_kind {
-            "input-node" => {
-                input_node = Some(
-                    parse_input_node_line(line)
-                        .map_err(|e| format_err!("Error {:?} while parsing {}", e, line))?
-                        .1,
-                )
-            }
+            "input-node" => input_nodes.push(
+                parse_input_node_line(line)
+                    .map_err(|e| format_err!("Error {:?} while parsing {}", e, line))?
+                    .1,
+            ),
             "dim-range-node" => {
                 let (name, it) = parse_dim_range_node_line(line)
                     .map_err(|e| format_err!("Error {:?} while parsing {}", e, line))?
                     .1;
+                it.input.check_supported().with_context(|| format!("Parsing {}", line))?;
                 nodes.push((name, NodeLine::DimRange(it)));
             }
             "component-node" => {
                 let (name, it) = parse_component_node_line(line)
                     .map_err(|e| format_err!("Error {:?} while parsing {}", e, line))?
                     .1;
+                it.input.check_supported().with_context(|| format!("Parsing {}", line))?;
                 nodes.push((name, NodeLine::Component(it)));
             }
-            "output-node" => outputs.push(
-                parse_output_node_line(line)
+            "output-node" => {
+                let it = parse_output_node_line(line)
                     .map_err(|e| format_err!("Error {:?} while parsing {}", e, line))?
-                    .1,
-            ),
+                    .1;
+                it.descriptor.check_supported().with_context(|| format!("Parsing {}", line))?;
+                outputs.push(it);
+            }
             _ => bail!("Unknown config line {}", line_kind),
         }
     }
-    let (input_name, input_dim) = input_node.unwrap();
-    Ok(ConfigLines { input_dim, input_name, nodes, outputs })
+    if input_nodes.is_empty() {
+        bail!("No input-node found")
+    }
+    Ok(ConfigLines { input_nodes, nodes, outputs })
 }
 
 fn parse_input_node_line(i: &str) -> IResult<&str, (String, usize)> {
@@ -82,13 +86,20 @@ fn parse_dim_range_node_line(i: &str) -> IResult<&str, (String, DimRangeNode)> {
 
 fn parse_output_node_line(i: &str) -> IResult<&str, OutputLine> {
     let (i, _) = tag("output-node")(i)?;
+    let objective =
+        || spaced(opt(map(preceded(tag("objective="), identifier), |n: &str| n.to_string())));
     map(
         tuple((
             spaced(map(preceded(tag("name="), identifier), |n: &str| n.to_string())),
-            spaced(opt(map(preceded(tag("objective="), identifier), |n: &str| n.to_string()))),
+            objective(),
             spaced(preceded(tag("input="), super::descriptor::parse_general)),
+            objective(),
         )),
-        |(output_alias, _objective, descriptor)| OutputLine { output_alias, descriptor },
+        |(output_alias, before, descriptor, after)| OutputLine {
+            output_alias,
+            descriptor,
+            objective: before.or(after),
+        },
     )(i)
 }
 
@@ -111,4 +122,12 @@ mod test {
     fn identifiet_with_dot() {
         assert_eq!(identifier("lstm.c").unwrap().1, "lstm.c")
     }
+
+    #[test]
+    fn reject_replace_index_over_x() {
+        let config = "input-node name=input dim=1\n\
+                      output-node name=output input=ReplaceIndex(input, x, 1)";
+        let error = format!("{:?}", parse_config(config).unwrap_err());
+        assert!(error.contains("ReplaceIndex(input, x, 1)"), "{}", error);
+    }
 }
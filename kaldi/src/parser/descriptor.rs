@@ -1,9 +1,11 @@
 use nom::IResult;
 use nom::{
-    bytes::complete::*, character::complete::*, combinator::*, multi::separated_list0, sequence::*,
+    bytes::complete::*, character::complete::*, combinator::*, multi::separated_list0,
+    number::complete::float, sequence::*,
 };
 
 use crate::model::GeneralDescriptor;
+use crate::parser::config_lines::uinteger;
 use crate::parser::spaced;
 
 pub fn parse_general(i: &str) -> IResult<&str, GeneralDescriptor> {
@@ -24,7 +26,17 @@ pub fn parse_general(i: &str) -> IResult<&str, GeneralDescriptor> {
                 tag("Offset"),
                 cut(delimited(
                     spaced(tag("(")),
-                    separated_pair(parse_general, spaced(tag(",")), integer),
+                    pair(
+                        parse_general,
+                        preceded(
+                            spaced(tag(",")),
+                            // the optional x offset is meaningless without an x index
+                            terminated(
+                                integer,
+                                opt(preceded(spaced(tag(",")), verify(integer, |x| *x == 0))),
+                            ),
+                        ),
+                    ),
                     spaced(tag(")")),
                 )),
             ),
@@ -37,12 +49,99 @@ pub fn parse_general(i: &str) -> IResult<&str, GeneralDescriptor> {
             ),
             |inner| GeneralDescriptor::IfDefined(Box::new(inner)),
         ),
+        map(
+            preceded(
+                tag("Sum"),
+                cut(delimited(
+                    spaced(tag("(")),
+                    separated_pair(parse_general, spaced(tag(",")), parse_general),
+                    spaced(tag(")")),
+                )),
+            ),
+            |(a, b)| GeneralDescriptor::Sum(Box::new(a), Box::new(b)),
+        ),
+        map(
+            preceded(
+                tag("Failover"),
+                cut(delimited(
+                    spaced(tag("(")),
+                    separated_pair(parse_general, spaced(tag(",")), parse_general),
+                    spaced(tag(")")),
+                )),
+            ),
+            |(a, b)| GeneralDescriptor::Failover(Box::new(a), Box::new(b)),
+        ),
+        map(
+            preceded(
+                tag("Scale"),
+                cut(delimited(
+                    spaced(tag("(")),
+                    separated_pair(spaced(float), spaced(tag(",")), parse_general),
+                    spaced(tag(")")),
+                )),
+            ),
+            |(scale, inner)| GeneralDescriptor::Scale(scale, Box::new(inner)),
+        ),
+        map(
+            preceded(
+                tag("Const"),
+                cut(delimited(
+                    spaced(tag("(")),
+                    separated_pair(spaced(float), spaced(tag(",")), spaced(uinteger)),
+                    spaced(tag(")")),
+                )),
+            ),
+            |(value, dim)| GeneralDescriptor::Const(value, dim),
+        ),
+        map(
+            preceded(
+                tag("Round"),
+                cut(delimited(
+                    spaced(tag("(")),
+                    separated_pair(
+                        parse_general,
+                        spaced(tag(",")),
+                        verify(spaced(uinteger), |m| *m > 0),
+                    ),
+                    spaced(tag(")")),
+                )),
+            ),
+            |(inner, modulus)| GeneralDescriptor::Round(Box::new(inner), modulus),
+        ),
+        map(
+            preceded(
+                tag("ReplaceIndex"),
+                cut(delimited(
+                    spaced(tag("(")),
+                    tuple((
+                        parse_general,
+                        preceded(spaced(tag(",")), spaced(one_of("tx"))),
+                        preceded(spaced(tag(",")), integer),
+                    )),
+                    spaced(tag(")")),
+                )),
+            ),
+            |(inner, var, value)| {
+                GeneralDescriptor::ReplaceIndex(Box::new(inner), var, value as isize)
+            },
+        ),
+        map(
+            preceded(
+                tag("Switch"),
+                cut(delimited(
+                    spaced(tag("(")),
+                    separated_list0(spaced(tag(",")), parse_general),
+                    spaced(tag(")")),
+                )),
+            ),
+            GeneralDescriptor::Switch,
+        ),
         map(super::config_lines::identifier, |i| GeneralDescriptor::Name(i.to_string())),
     )))(i)
 }
 
 pub fn integer(i: &str) -> IResult<&str, i32> {
-    map_res(recognize(pair(opt(tag("-")), digit1)), |s: &str| s.parse::<i32>())(i)
+    spaced(map_res(recognize(pair(opt(tag("-")), digit1)), |s: &str| s.parse::<i32>()))(i)
 }
 
 #[cfg(test)]
@@ -78,4 +177,46 @@ mod test {
             Append(vec!(name("input"), IfDefined(Offset(name("lstm1.c").into(), -1).into())))
         )
     }
+
+    #[test]
+    fn test_tdnnf_bypass() {
+        assert_eq!(
+            parse_general("Sum(Scale(0.66, tdnnf2.noop), tdnnf3.dropout)").unwrap().1,
+            Sum(Scale(0.66, name("tdnnf2.noop").into()).into(), name("tdnnf3.dropout").into())
+        )
+    }
+
+    #[test]
+    fn test_ivector() {
+        assert_eq!(
+            parse_general("Append(Offset(input, -1), input, ReplaceIndex(ivector, t, 0))")
+                .unwrap()
+                .1,
+            Append(vec!(
+                Offset(name("input").into(), -1),
+                name("input"),
+                ReplaceIndex(name("ivector").into(), 't', 0)
+            ))
+        )
+    }
+
+    #[test]
+    fn test_round_switch_failover() {
+        assert_eq!(
+            parse_general("Failover(Switch(a, Round(b, 3)), Const(1.5, 4))").unwrap().1,
+            Failover(
+                Switch(vec!(name("a"), Round(name("b").into(), 3))).into(),
+                Const(1.5, 4).into()
+            )
+        )
+    }
+
+    #[test]
+    fn test_offset_with_x() {
+        assert_eq!(
+            parse_general("Offset(input, 2, 0)").unwrap().1,
+            Offset(name("input").into(), 2)
+        );
+        assert!(parse_general("Offset(input, 2, 1)").is_err());
+    }
 }
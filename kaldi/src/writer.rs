@@ -0,0 +1,217 @@
+use std::io::Write;
+
+use tract_hir::internal::*;
+use tract_itertools::Itertools;
+
+use crate::model::{Component, ConfigLines, KaldiProtoModel, NodeLine};
+use crate::parser::components::COMPONENTS;
+
+pub fn nnet3(w: &mut dyn Write, model: &KaldiProtoModel, binary: bool) -> TractResult<()> {
+    if binary {
+        w.write_all(&[0, b'B'])?;
+    }
+    writeln!(w, "<Nnet3> ")?;
+    config_lines(w, &model.config_lines)?;
+    write!(w, "\n<NumComponents> ")?;
+    if binary {
+        w.write_all(&[4])?;
+        w.write_all(&(model.components.len() as i32).to_le_bytes())?;
+    } else {
+        writeln!(w, "{}", model.components.len())?;
+    }
+    for name in component_order(model) {
+        let component = &model.components[name];
+        write!(w, "<ComponentName> {} <{}> ", name, component.klass)?;
+        if binary {
+            bin_attributes(w, component)?;
+        } else {
+            text_attributes(w, component)?;
+        }
+        write!(w, "</{}> ", component.klass)?;
+        if !binary {
+            writeln!(w)?;
+        }
+    }
+    write!(w, "</Nnet3> ")?;
+    Ok(())
+}
+
+fn config_lines(w: &mut dyn Write, lines: &ConfigLines) -> TractResult<()> {
+    for (name, dim) in &lines.input_nodes {
+        writeln!(w, "input-node name={} dim={}", name, dim)?;
+    }
+    for (name, node) in &lines.nodes {
+        match node {
+            NodeLine::Component(c) => writeln!(
+                w,
+                "component-node name={} component={} input={}",
+                name, c.component, c.input
+            )?,
+            NodeLine::DimRange(d) => writeln!(
+                w,
+                "dim-range-node name={} input-node={} dim-offset={} dim={}",
+                name, d.input, d.offset, d.dim
+            )?,
+        }
+    }
+    for o in &lines.outputs {
+        write!(w, "output-node name={} input={}", o.output_alias, o.descriptor)?;
+        if let Some(objective) = &o.objective {
+            write!(w, " objective={}", objective)?;
+        }
+        writeln!(w)?;
+    }
+    Ok(())
+}
+
+/// Components in the order the nodes use them, unused ones last.
+fn component_order(model: &KaldiProtoModel) -> Vec<&str> {
+    let mut order: Vec<&str> = model
+        .config_lines
+        .nodes
+        .iter()
+        .filter_map(|(_, node)| match node {
+            NodeLine::Component(c) => Some(&*c.component),
+            _ => None,
+        })
+        .filter(|c| model.components.contains_key(*c))
+        .unique()
+        .collect();
+    let unused = model.components.keys().filter(|c| !order.contains(&&***c)).sorted();
+    order.extend(unused.map(|s| &**s).collect::<Vec<_>>());
+    order
+}
+
+/// Known attributes in Kaldi order, then the others by name.
+fn attribute_order(component: &Component) -> Vec<&str> {
+    let known: Vec<&str> = COMPONENTS
+        .get(&*component.klass)
+        .map(|attrs| attrs.iter().map(|(name, _)| *name).collect())
+        .unwrap_or_default();
+    let mut order: Vec<&str> =
+        known.iter().cloned().filter(|name| component.attributes.contains_key(*name)).collect();
+    order.extend(
+        component.attributes.keys().map(|s| &**s).filter(|name| !known.contains(name)).sorted(),
+    );
+    order
+}
+
+fn bin_attributes(w: &mut dyn Write, component: &Component) -> TractResult<()> {
+    for name in attribute_order(component) {
+        let kind = crate::parser::components::attribute_kind(&component.klass, name)
+            .with_context(|| format!("No binary layout for {} in {}", name, component.klass))?;
+        write!(w, "<{}> ", name)?;
+        kind.write_bin(w, &component.attributes[name])?;
+    }
+    Ok(())
+}
+
+fn text_attributes(w: &mut dyn Write, component: &Component) -> TractResult<()> {
+    for name in attribute_order(component) {
+        write!(w, "<{}> ", name)?;
+        text_tensor(w, &component.attributes[name])
+            .with_context(|| format!("Writing {} in {}", name, component.klass))?;
+    }
+    Ok(())
+}
+
+fn text_tensor(w: &mut dyn Write, t: &Tensor) -> TractResult<()> {
+    match t.rank() {
+        0 if t.datum_type() == bool::datum_type() => {
+            write!(w, "{} ", if *t.to_scalar::<bool>()? { "T" } else { "F" })?
+        }
+        0 if t.datum_type().is_integer() => write!(w, "{} ", t.cast_to_scalar::<i64>()?)?,
+        0 => write!(w, "{} ", t.cast_to_scalar::<f32>()?)?,
+        1 => writeln!(w, " [ {} ]", t.cast_to::<f32>()?.as_slice::<f32>()?.iter().join(" "))?,
+        2 => {
+            let t = t.cast_to::<f32>()?;
+            let rows = t.to_array_view::<f32>()?.into_dimensionality::<tract_ndarray::Ix2>()?;
+            let rows =
+                rows.outer_iter().map(|row| format!("  {}", row.iter().join(" "))).join("\n");
+            writeln!(w, " [\n{} ]", rows)?
+        }
+        _ => bail!("Kaldi has no representation for {:?}", t),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::parser;
+
+    fn round_trip(model: &KaldiProtoModel, binary: bool) -> TractResult<Vec<u8>> {
+        let mut buf = vec![];
+        nnet3(&mut buf, model, binary)?;
+        assert_eq!(&parser::nnet3(&buf)?, model);
+        Ok(buf)
+    }
+
+    fn test_cases() -> impl Iterator<Item = std::path::PathBuf> {
+        std::fs::read_dir("test_cases")
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.join("model.raw").exists())
+            .sorted()
+    }
+
+    #[test]
+    fn round_trip_text() -> TractResult<()> {
+        for tc in test_cases() {
+            let model = parser::nnet3(&std::fs::read(tc.join("model.raw.txt"))?)?;
+            round_trip(&model, false).with_context(|| format!("{:?}", tc))?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip_binary() -> TractResult<()> {
+        for tc in test_cases() {
+            let model = parser::nnet3(&std::fs::read(tc.join("model.raw"))?)?;
+            round_trip(&model, true).with_context(|| format!("{:?}", tc))?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn binary_identical_to_kaldi() -> TractResult<()> {
+        for tc in ["ngaffine_10x2_T13_S2", "renorm_tgt0.1_D17_T7"] {
+            let original = std::fs::read(format!("test_cases/{}/model.raw", tc))?;
+            let written = round_trip(&parser::nnet3(&original)?, true)?;
+            assert!(written == original, "{} differs from kaldi's output", tc);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn text_to_binary() -> TractResult<()> {
+        let text = std::fs::read("test_cases/renorm_tgt0.1_D17_T7/model.raw.txt")?;
+        let mut bin = vec![];
+        nnet3(&mut bin, &parser::nnet3(&text)?, true)?;
+        let original = std::fs::read("test_cases/renorm_tgt0.1_D17_T7/model.raw")?;
+        // the text version has no output objective, but the weights match
+        assert_eq!(parser::nnet3(&bin)?.components, parser::nnet3(&original)?.components);
+        Ok(())
+    }
+
+    #[test]
+    fn descriptors() -> TractResult<()> {
+        let config = "input-node name=input dim=3\n\
+                      input-node name=ivector dim=2\n\
+                      component-node name=a component=c input=Append(Offset(input, -1), \
+                      ReplaceIndex(ivector, t, 0), Round(input, 3))\n\
+                      component-node name=b component=c input=Sum(Scale(0.5, a), Const(1, 3))\n\
+                      dim-range-node name=d input-node=Switch(a, Failover(b, a)) dim-offset=1 dim=2\n\
+                      output-node name=output input=IfDefined(Offset(d, 2)) objective=linear\n";
+        let model = KaldiProtoModel {
+            config_lines: crate::parser::config_lines::parse_config(config)?,
+            components: HashMap::new(),
+            adjust_final_offset: 0,
+        };
+        let mut buf = vec![];
+        config_lines(&mut buf, &model.config_lines)?;
+        assert_eq!(std::str::from_utf8(&buf)?, config);
+        round_trip(&model, false)?;
+        Ok(())
+    }
+}
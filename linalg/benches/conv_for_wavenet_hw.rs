@@ -29,7 +29,7 @@ fn conv(c: &mut Criterion, dilation: usize, pulse: usize, ci: usize, co: usize)
                 t,
                 &[
                     FusedSpec::AddMatMul {
-                        a: mm.a_packed(F32.size_of(), k).wrap(&mut a.view()),
+                        a: mm.a_packed(F32.size_of(), k).wrap(&mut a.view()).unwrap(),
                         b: mm.b_packed(F32.size_of(), k).wrap(&input.view()).unwrap(),
                         k,
                     },
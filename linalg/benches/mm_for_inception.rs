@@ -21,7 +21,7 @@ fn mat_mul_smmm(be: &mut criterion::Bencher, &(m, k, n): &(usize, usize, usize))
                 n,
                 &[
                     FusedSpec::AddMatMul {
-                        a: mm.a_packed(F32.size_of(), k).wrap(&pa.view()),
+                        a: mm.a_packed(F32.size_of(), k).wrap(&pa.view()).unwrap(),
                         b: mm.b_packed(F32.size_of(), k).wrap(&pb.view()).unwrap(),
                         k,
                     },
@@ -90,7 +90,7 @@ unsafe fn packedpacked_ops<'a, K: MatMatMulKer<f32>>(
     tvec!(
         FusedSpec::AddMatMul {
             k,
-            a: mmm.a_packed(4, k).wrap(&pa.view()),
+            a: mmm.a_packed(4, k).wrap(&pa.view()).unwrap(),
             b: mmm.b_packed(4, k).wrap(&pb.view()).unwrap(),
         },
         FusedSpec::Store(mmm.c_view(0, 1).wrap(&mut c.view_mut())),
@@ -117,7 +117,7 @@ unsafe fn packedpacking_ops<'a, K: MatMatMulKer<f32>>(
     tvec!(
         FusedSpec::AddMatMul {
             k,
-            a: mmm.a_packed(4, k).wrap(&pa.view()),
+            a: mmm.a_packed(4, k).wrap(&pa.view()).unwrap(),
             b: mmm.b_late_packing().wrap(&b.view()).unwrap(),
         },
         FusedSpec::Store(mmm.c_view(0, 1).wrap(&mut c.view_mut())),
@@ -1,7 +1,7 @@
 #![allow(dead_code)]
 use criterion::*;
 use tract_data::internal::*;
-use tract_linalg::frame::mmm::{FusedSpec, InputStore, PackedStore};
+use tract_linalg::frame::mmm::{FusedSpec, InputStore};
 use tract_linalg::frame::MatMatMul;
 
 use DatumType::*;
@@ -37,7 +37,7 @@ unsafe fn run(
     n: usize,
     be: &mut Bencher,
     mm: &dyn MatMatMul,
-    pa: PackedStore,
+    pa: InputStore,
     pb: InputStore,
     cold: bool,
 ) {
@@ -53,7 +53,7 @@ unsafe fn run(
                 m,
                 n,
                 scratch.as_mut(),
-                &[FusedSpec::AddMatMul { a: pa, b: pb.clone(), k }],
+                &[FusedSpec::AddMatMul { a: pa.clone(), b: pb.clone(), k }],
             )
             .unwrap();
             let time = instant.elapsed();
@@ -64,20 +64,22 @@ unsafe fn run(
 }
 
 fn mat_mat(be: &mut Bencher, params: &(DatumType, usize, usize, usize, bool)) {
-    let (dt, m, k, n, _) = *params;
-    let mm = tract_linalg::ops().mmm(dt, dt, dt, Some(m), Some(k), Some(n)).unwrap();
+    let (ref dt, m, k, n, _) = *params;
+    let mm = tract_linalg::ops()
+        .mmm(dt.clone(), dt.clone(), dt.clone(), Some(m), Some(k), Some(n))
+        .unwrap();
     mat_mat_with_mm(be, &*mm, params)
 }
 
 pub fn mat_mat_with_mm(
     be: &mut Bencher,
     mm: &dyn MatMatMul,
-    &(dt, m, k, n, cold): &(DatumType, usize, usize, usize, bool),
+    &(ref dt, m, k, n, cold): &(DatumType, usize, usize, usize, bool),
 ) {
-    let pa =
-        Tensor::zero_aligned_dt(dt, &[mm.a_pack().len(k, m)], mm.a_pack().alignment()).unwrap();
-    let pb =
-        Tensor::zero_aligned_dt(dt, &[mm.b_pack().len(k, n)], mm.b_pack().alignment()).unwrap();
+    let pa = Tensor::zero_aligned_dt(dt.clone(), &[mm.a_pack().len(k, m)], mm.a_pack().alignment())
+        .unwrap();
+    let pb = Tensor::zero_aligned_dt(dt.clone(), &[mm.b_pack().len(k, n)], mm.b_pack().alignment())
+        .unwrap();
     unsafe {
         run(
             m,
@@ -85,19 +87,21 @@ pub fn mat_mat_with_mm(
             n,
             be,
             &*mm,
-            mm.a_packed(dt.size_of(), k).wrap(&pa.view()),
+            mm.a_packed(dt.size_of(), k).wrap(&pa.view()).unwrap(),
             mm.b_packed(dt.size_of(), k).wrap(&pb.view()).unwrap(),
             cold,
         );
     }
 }
 
-fn mat_vec(be: &mut Bencher, &(dt, m, k, n, cold): &(DatumType, usize, usize, usize, bool)) {
+fn mat_vec(be: &mut Bencher, &(ref dt, m, k, n, cold): &(DatumType, usize, usize, usize, bool)) {
     assert_eq!(n, 1);
-    let mm = tract_linalg::ops().mmm(dt, dt, dt, Some(m), Some(k), Some(n)).unwrap();
-    let pa =
-        Tensor::zero_aligned_dt(dt, &[mm.a_pack().len(k, m)], mm.a_pack().alignment()).unwrap();
-    let pb = Tensor::zero_dt(dt, &[k, 1]).unwrap();
+    let mm = tract_linalg::ops()
+        .mmm(dt.clone(), dt.clone(), dt.clone(), Some(m), Some(k), Some(n))
+        .unwrap();
+    let pa = Tensor::zero_aligned_dt(dt.clone(), &[mm.a_pack().len(k, m)], mm.a_pack().alignment())
+        .unwrap();
+    let pb = Tensor::zero_dt(dt.clone(), &[k, 1]).unwrap();
     unsafe {
         run(
             m,
@@ -105,7 +109,7 @@ fn mat_vec(be: &mut Bencher, &(dt, m, k, n, cold): &(DatumType, usize, usize, us
             n,
             be,
             &*mm,
-            mm.a_packed(dt.size_of(), k).wrap(&pa.view()),
+            mm.a_packed(dt.size_of(), k).wrap(&pa.view()).unwrap(),
             mm.b_packed(dt.size_of(), k).wrap(&pb.view()).unwrap(),
             cold,
         );
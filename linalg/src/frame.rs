@@ -1,3 +1,4 @@
+pub mod block_quant;
 #[macro_use]
 pub mod element_wise;
 #[macro_use]
@@ -10,6 +11,7 @@ pub mod sigmoid;
 #[macro_use]
 pub mod tanh;
 
+pub use block_quant::{BlockQuantFormat, BlockQuantWeights};
 pub use pack::Packer;
 pub use pack::PackingWriter;
 
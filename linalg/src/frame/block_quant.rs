@@ -0,0 +1,281 @@
+//! Weights quantized symmetrically by blocks of consecutive values along k, each block of
+//! each row with its own scale.
+use std::ops::Range;
+use std::sync::Arc;
+use tract_data::internal::*;
+
+use crate::frame::mmm::{VirtualInput, VirtualInputSpec};
+use crate::frame::{Packer, PackingWriter};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BlockQuantFormat {
+    /// One i8 per value.
+    Q8,
+    /// Two signed 4-bit values per byte, low nibble first.
+    Q4,
+}
+
+impl BlockQuantFormat {
+    pub fn bits(&self) -> usize {
+        match self {
+            BlockQuantFormat::Q8 => 8,
+            BlockQuantFormat::Q4 => 4,
+        }
+    }
+
+    fn max(&self) -> f32 {
+        match self {
+            BlockQuantFormat::Q8 => 127.0,
+            BlockQuantFormat::Q4 => 7.0,
+        }
+    }
+
+    fn bytes(&self, values: usize) -> usize {
+        (values * self.bits() + 7) / 8
+    }
+}
+
+/// A m x k matrix quantized by blocks of `block_len` values along k.
+#[derive(Clone, Debug, PartialEq, Hash)]
+pub struct BlockQuantWeights {
+    pub format: BlockQuantFormat,
+    pub block_len: usize,
+    pub m: usize,
+    pub k: usize,
+    /// f32 scales, m x blocks_per_row
+    pub scales: Arc<Tensor>,
+    /// u8, the m x k values in row-major order packed according to the format
+    pub data: Arc<Tensor>,
+}
+
+impl BlockQuantWeights {
+    /// Quantize a row-major m x k f32 matrix.
+    pub fn quantize(
+        format: BlockQuantFormat,
+        block_len: usize,
+        m: usize,
+        k: usize,
+        values: &[f32],
+    ) -> TractResult<BlockQuantWeights> {
+        ensure!(values.len() == m * k, "Expected {}x{} values, got {}", m, k, values.len());
+        ensure!(block_len > 0, "Blocks can not be empty");
+        let blocks_per_row = k.divceil(block_len);
+        let mut scales = Vec::with_capacity(m * blocks_per_row);
+        let mut quantized = Vec::with_capacity(m * k);
+        for row in values.chunks(k.max(1)).take(m) {
+            for block in row.chunks(block_len) {
+                let amax = block.iter().fold(0f32, |acc, v| acc.max(v.abs()));
+                let scale = if amax > 0.0 { amax / format.max() } else { 1.0 };
+                scales.push(scale);
+                quantized.extend(
+                    block.iter().map(|v| (v / scale).round().max(-format.max()).min(format.max())),
+                );
+            }
+        }
+        let data: Vec<u8> = match format {
+            BlockQuantFormat::Q8 => quantized.iter().map(|q| *q as i8 as u8).collect(),
+            BlockQuantFormat::Q4 => quantized
+                .chunks(2)
+                .map(|pair| {
+                    let low = pair[0] as i8 as u8 & 0x0F;
+                    let high = pair.get(1).map(|q| *q as i8 as u8 & 0x0F).unwrap_or(0);
+                    low | high << 4
+                })
+                .collect(),
+        };
+        Ok(BlockQuantWeights {
+            format,
+            block_len,
+            m,
+            k,
+            scales: tensor1(&scales).into_shape(&[m, blocks_per_row])?.into_arc_tensor(),
+            data: tensor1(&data).into_arc_tensor(),
+        })
+    }
+
+    pub fn blocks_per_row(&self) -> usize {
+        self.k.divceil(self.block_len)
+    }
+
+    fn value(&self, data: &[u8], ix: usize) -> i8 {
+        match self.format {
+            BlockQuantFormat::Q8 => data[ix] as i8,
+            BlockQuantFormat::Q4 => {
+                let byte = data[ix / 2];
+                let nibble = if ix % 2 == 0 { byte << 4 } else { byte & 0xF0 };
+                // arithmetic shift sign-extends the nibble
+                (nibble as i8) >> 4
+            }
+        }
+    }
+
+    /// Dequantize one row of the matrix in `output` (k values).
+    pub fn dequantize_row(&self, row: usize, output: &mut [f32]) -> TractResult<()> {
+        let data = self.data.as_slice::<u8>()?;
+        let scales = self.scales.as_slice::<f32>()?;
+        let scales = &scales[row * self.blocks_per_row()..][..self.blocks_per_row()];
+        for (col, o) in output.iter_mut().enumerate().take(self.k) {
+            *o = self.value(data, row * self.k + col) as f32 * scales[col / self.block_len];
+        }
+        Ok(())
+    }
+
+    /// Dequantize the whole matrix as a m x k f32 tensor.
+    pub fn dequantize(&self) -> TractResult<Tensor> {
+        let mut values = vec![0f32; self.m * self.k];
+        for (row, output) in values.chunks_mut(self.k.max(1)).take(self.m).enumerate() {
+            self.dequantize_row(row, output)?;
+        }
+        Ok(tensor1(&values).into_shape(&[self.m, self.k])?)
+    }
+
+    /// Tells blobs holding block quantized weights from other blobs.
+    pub const MAGIC: [u8; 4] = *b"TRBQ";
+
+    /// Does this blob hold block quantized weights, as serialized by `to_bytes`?
+    pub fn is_block_quant(bytes: &[u8]) -> bool {
+        bytes.starts_with(&Self::MAGIC)
+    }
+
+    /// Serialize as bytes: MAGIC, bits (u32), block_len, m and k (u32), then scales (f32) and
+    /// the packed values, all little endian.
+    pub fn to_bytes(&self) -> TractResult<Vec<u8>> {
+        let mut bytes = Self::MAGIC.to_vec();
+        for header in &[self.format.bits(), self.block_len, self.m, self.k] {
+            bytes.extend((*header as u32).to_le_bytes().iter());
+        }
+        for scale in self.scales.as_slice::<f32>()? {
+            bytes.extend(scale.to_le_bytes().iter());
+        }
+        bytes.extend(self.data.as_slice::<u8>()?);
+        Ok(bytes)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> TractResult<BlockQuantWeights> {
+        ensure!(Self::is_block_quant(bytes), "Not block quantized weights");
+        let bytes = &bytes[Self::MAGIC.len()..];
+        ensure!(bytes.len() >= 16, "Truncated block quantized weights");
+        let header: Vec<usize> = bytes[0..16]
+            .chunks(4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as usize)
+            .collect();
+        let format = match header[0] {
+            8 => BlockQuantFormat::Q8,
+            4 => BlockQuantFormat::Q4,
+            bits => bail!("Unsupported block quantization to {} bits", bits),
+        };
+        let (block_len, m, k) = (header[1], header[2], header[3]);
+        ensure!(block_len > 0, "Blocks can not be empty");
+        let blocks_per_row = k.divceil(block_len);
+        let scales_len = m * blocks_per_row * 4;
+        ensure!(
+            bytes.len() == 16 + scales_len + format.bytes(m * k),
+            "Wrong size for {}x{} block quantized weights",
+            m,
+            k
+        );
+        let scales: Vec<f32> = bytes[16..][..scales_len]
+            .chunks(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect();
+        Ok(BlockQuantWeights {
+            format,
+            block_len,
+            m,
+            k,
+            scales: tensor1(&scales).into_shape(&[m, blocks_per_row])?.into_arc_tensor(),
+            data: tensor1(&bytes[16 + scales_len..]).into_arc_tensor(),
+        })
+    }
+}
+
+impl_dyn_hash!(BlockQuantWeights);
+
+/// Used as A in a matrix multiplier, the weights stay quantized: each panel is dequantized
+/// in the scratch space when the kernel needs it.
+impl VirtualInputSpec for BlockQuantWeights {
+    fn wrap(&self, _view: &TensorView) -> Box<dyn VirtualInput> {
+        Box::new(self.clone())
+    }
+
+    fn packed_datum_type(&self) -> Option<DatumType> {
+        Some(f32::datum_type())
+    }
+}
+
+impl VirtualInput for BlockQuantWeights {
+    fn input(&self, packer: &Packer, packed_output: *mut u8, k: Range<usize>, mn: Range<usize>) {
+        let rows = mn.start..mn.end.min(self.m);
+        let mut writer = packer.write_with_k_inner(packed_output as *mut f32, k.len(), rows.len());
+        unsafe {
+            let data = self.data.as_slice_unchecked::<u8>();
+            let scales = self.scales.as_slice_unchecked::<f32>();
+            for row in rows {
+                let scales = &scales[row * self.blocks_per_row()..];
+                for col in k.clone() {
+                    let value = self.value(data, row * self.k + col);
+                    writer.write(value as f32 * scales[col / self.block_len]);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn values(m: usize, k: usize) -> Vec<f32> {
+        (0..m * k).map(|i| ((i * 37) % 23) as f32 / 7.0 - 1.5).collect()
+    }
+
+    #[test]
+    fn q8_round_trip() {
+        let values = values(3, 10);
+        let q = BlockQuantWeights::quantize(BlockQuantFormat::Q8, 4, 3, 10, &values).unwrap();
+        assert_eq!(q.data.len(), 30);
+        assert_eq!(q.scales.shape(), &[3, 3]);
+        let deq = q.dequantize().unwrap();
+        for (v, d) in values.iter().zip(deq.as_slice::<f32>().unwrap()) {
+            assert!((v - d).abs() <= 1.5 / 127.0, "{} {}", v, d);
+        }
+    }
+
+    #[test]
+    fn q4_round_trip() {
+        let values = values(3, 9);
+        let q = BlockQuantWeights::quantize(BlockQuantFormat::Q4, 4, 3, 9, &values).unwrap();
+        assert_eq!(q.data.len(), 14);
+        let deq = q.dequantize().unwrap();
+        for (v, d) in values.iter().zip(deq.as_slice::<f32>().unwrap()) {
+            assert!((v - d).abs() <= 1.6 / 7.0, "{} {}", v, d);
+        }
+        assert_eq!(BlockQuantWeights::from_bytes(&q.to_bytes().unwrap()).unwrap(), q);
+    }
+
+    #[test]
+    fn q4_sign_extension() {
+        let q = BlockQuantWeights::quantize(BlockQuantFormat::Q4, 2, 1, 2, &[-7.0, 7.0]).unwrap();
+        assert_eq!(q.dequantize().unwrap(), tensor2(&[[-7f32, 7.0]]));
+    }
+
+    #[test]
+    fn panels_dequantize() {
+        let (m, k) = (5, 7);
+        let q = BlockQuantWeights::quantize(BlockQuantFormat::Q4, 4, m, k, &values(m, k)).unwrap();
+        let packer = Packer::new(4, 4, 0);
+        let mut expected = Tensor::zero::<f32>(&[packer.len(k, m)]).unwrap();
+        let mut found = expected.clone();
+        unsafe {
+            packer.pack(&mut expected.view_mut(), &q.dequantize().unwrap().view(), 1, 0);
+            let input = q.wrap(&found.view());
+            let panel_len = packer.single_panel_len(k);
+            for (ix, panel) in
+                found.as_slice_mut::<f32>().unwrap().chunks_mut(panel_len).enumerate()
+            {
+                input.input(&packer, panel.as_mut_ptr() as _, 0..k, 4 * ix..4 * (ix + 1));
+            }
+        }
+        assert_eq!(found, expected);
+    }
+}
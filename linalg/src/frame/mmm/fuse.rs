@@ -1,6 +1,6 @@
 use std::fmt::Debug;
 
-use super::{InputStore, OutputStore, OutputStoreKer, PackedStore};
+use super::{InputStore, OutputStore, OutputStoreKer};
 use tract_data::internal::*;
 
 #[repr(usize)]
@@ -44,18 +44,30 @@ pub enum FusedSpec<'t> {
     AddRowColProducts(&'t Tensor, &'t Tensor),
     AddUnicast(OutputStore),
     QScale(usize, RoundingPolicy, i32),
+    QScalePerRow(&'t Tensor),
+    // accumulator to f32, times the scale: only a Store can follow
+    ScaleToF32(f32),
     Store(OutputStore),
-    AddMatMul { k: usize, a: PackedStore, b: InputStore },
+    AddMatMul { k: usize, a: InputStore, b: InputStore },
 }
 
 impl<'t> FusedSpec<'t> {
-    pub fn prefer_col_outer(&self) -> bool {
-        if let FusedSpec::AddMatMul { b, .. } = self {
-            match &b {
-                &InputStore::Packed { .. } => false,
-                &InputStore::VirtualPacking { .. } => true,
-                &InputStore::LatePacking { .. } => true,
+    /// QScalePerRow and ScaleToF32 rescale the tile outside of the kernel, which is only
+    /// implemented for i32 and f32 accumulators.
+    pub fn supports_accumulator(&self, acc: DatumType) -> bool {
+        match self {
+            FusedSpec::QScalePerRow(_) | FusedSpec::ScaleToF32(_) => {
+                acc == i32::datum_type() || acc == f32::datum_type()
             }
+            _ => true,
+        }
+    }
+
+    /// Keep the panel that is expensive to get (packed late or virtual) across consecutive
+    /// tiles: B's with a column outer loop, A's with a row outer loop.
+    pub fn prefer_col_outer(&self) -> bool {
+        if let FusedSpec::AddMatMul { a, b, .. } = self {
+            a.is_packed() && !b.is_packed()
         } else {
             false
         }
@@ -123,6 +135,16 @@ pub mod test {
         );
     }
 
+    #[test]
+    fn tile_scaling_needs_i32_or_f32_accumulator() {
+        let scales = tensor1(&[0.5f32]);
+        for spec in &[FusedSpec::QScalePerRow(&scales), FusedSpec::ScaleToF32(0.5)] {
+            assert!(spec.supports_accumulator(i32::datum_type()));
+            assert!(spec.supports_accumulator(f32::datum_type()));
+            assert!(!spec.supports_accumulator(i8::datum_type()));
+        }
+    }
+
     #[macro_export]
     macro_rules! mmm_kernel_fuse_tests {
         ($cond:expr, $ker:ty, $tc:ty, $ti: ty) => {
@@ -7,6 +7,11 @@ use crate::frame::Packer;
 
 pub trait VirtualInputSpec: DynHash + dyn_clone::DynClone + std::fmt::Debug + Sync + Send {
     fn wrap(&self, view: &TensorView) -> Box<dyn VirtualInput>;
+
+    /// Datum type of the packed panels, when it is not the one of the wrapped tensor.
+    fn packed_datum_type(&self) -> Option<DatumType> {
+        None
+    }
 }
 dyn_clone::clone_trait_object!(VirtualInputSpec);
 
@@ -58,7 +63,7 @@ impl InputStoreSpec {
                 packer: packer.clone(),
                 input: func.wrap(tensor),
                 k: *k,
-                dt: tensor.datum_type(),
+                dt: func.packed_datum_type().unwrap_or(tensor.datum_type()),
             }),
         }
     }
@@ -100,7 +105,7 @@ pub enum InputStore {
         packer: Packer,
         input: Box<dyn VirtualInput>,
         k: usize,
-        dt: DatumType, // TODO discard me ?
+        dt: DatumType,
     },
 }
 
@@ -123,8 +128,12 @@ impl InputStore {
         }
     }
 
+    pub fn is_packed(&self) -> bool {
+        matches!(self, InputStore::Packed(_))
+    }
+
     #[inline]
-    pub(super) unsafe fn panel_b(&self, i: usize, buffer: Option<*const u8>) -> *const u8 {
+    pub(super) unsafe fn panel(&self, i: usize, buffer: Option<*const u8>) -> *const u8 {
         match self {
             InputStore::Packed(packed) => packed.panel(i),
             InputStore::LatePacking { packer, ptr, dt, k, mn, mn_stride, k_stride } => {
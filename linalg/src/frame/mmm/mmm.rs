@@ -21,7 +21,8 @@ pub trait MatMatMul:
 
     fn internal_type(&self) -> DatumType;
 
-    unsafe fn a_packed(&self, item_size: usize, k: usize) -> PackedStoreSpec;
+    unsafe fn a_packed(&self, item_size: usize, k: usize) -> InputStoreSpec;
+    unsafe fn a_virtual_input(&self, func: Box<dyn VirtualInputSpec>, k: usize) -> InputStoreSpec;
 
     unsafe fn b_packed(&self, item_size: usize, k: usize) -> InputStoreSpec;
     unsafe fn b_late_packing(&self) -> InputStoreSpec {
@@ -155,8 +156,13 @@ where
         TI::datum_type()
     }
 
-    unsafe fn a_packed(&self, item_size: usize, k: usize) -> PackedStoreSpec {
-        PackedStoreSpec { panel_bytes: (k * K::mr() * item_size) }
+    unsafe fn a_packed(&self, item_size: usize, k: usize) -> InputStoreSpec {
+        let panel_bytes = k * K::mr() * item_size;
+        InputStoreSpec::Prepacked(PackedStoreSpec { panel_bytes })
+    }
+
+    unsafe fn a_virtual_input(&self, func: Box<dyn VirtualInputSpec>, k: usize) -> InputStoreSpec {
+        InputStoreSpec::VirtualPacking { packer: self.a_pack(), func, k }
     }
 
     unsafe fn b_packed(&self, item_size: usize, k: usize) -> InputStoreSpec {
@@ -208,15 +214,15 @@ where
         let scratch = scratch
             .downcast_mut::<ScratchSpaceFusedNonLinear<TI>>()
             .context("Wrong scratch space type")?;
-        scratch.prepare::<K>(non_linear);
+        scratch.prepare::<K>(non_linear)?;
         for ia in 0..m / mr {
             scratch.for_valid_tile::<K>(&non_linear, ia, 0);
-            let err = K::kernel(&scratch.uspecs());
+            let err = scratch.run_kernel::<K>(&non_linear, ia);
             debug_assert_eq!(err, 0, "Kernel return error {}", err);
         }
         if m % mr != 0 {
             scratch.for_border_tile::<K>(&non_linear, m / mr, 0);
-            let err = K::kernel(&scratch.uspecs());
+            let err = scratch.run_kernel::<K>(&non_linear, m / mr);
             debug_assert_eq!(err, 0, "Kernel return error {}", err);
             scratch.postprocess_tile::<K>(&non_linear, m / mr, 0, m % mr, 1);
         }
@@ -235,16 +241,16 @@ where
         let scratch = scratch
             .downcast_mut::<ScratchSpaceFusedNonLinear<TI>>()
             .context("Wrong scratch space type")?;
-        scratch.prepare::<K>(non_linear);
+        scratch.prepare::<K>(non_linear)?;
         for ib in 0..n / nr {
             for ia in 0..m / mr {
                 scratch.for_valid_tile::<K>(&non_linear, ia, ib);
-                let err = K::kernel(&scratch.uspecs());
+                let err = scratch.run_kernel::<K>(&non_linear, ia);
                 debug_assert_eq!(err, 0, "Kernel return error {}", err);
             }
             if m % mr != 0 {
                 scratch.for_border_tile::<K>(&non_linear, m / mr, ib);
-                let err = K::kernel(&scratch.uspecs());
+                let err = scratch.run_kernel::<K>(&non_linear, m / mr);
                 debug_assert_eq!(err, 0, "Kernel return error {}", err);
                 scratch.postprocess_tile::<K>(&non_linear, m / mr, ib, m % mr, nr);
             }
@@ -252,13 +258,13 @@ where
         if n % nr != 0 {
             for ia in 0..m / mr {
                 scratch.for_border_tile::<K>(&non_linear, ia, n / nr);
-                let err = K::kernel(&scratch.uspecs());
+                let err = scratch.run_kernel::<K>(&non_linear, ia);
                 debug_assert_eq!(err, 0, "Kernel return error {}", err);
                 scratch.postprocess_tile::<K>(&non_linear, ia, n / nr, mr, n % nr);
             }
             if m % mr != 0 {
                 scratch.for_border_tile::<K>(&non_linear, m / mr, n / nr);
-                let err = K::kernel(&scratch.uspecs());
+                let err = scratch.run_kernel::<K>(&non_linear, m / mr);
                 debug_assert_eq!(err, 0, "Kernel return error {}", err);
                 scratch.postprocess_tile::<K>(&non_linear, m / mr, n / nr, m % mr, n % nr);
             }
@@ -284,18 +290,18 @@ where
         let scratch = scratch
             .downcast_mut::<ScratchSpaceFusedNonLinear<TI>>()
             .context("Wrong scratch space type")?;
-        scratch.prepare::<K>(non_linear);
+        scratch.prepare::<K>(non_linear)?;
         for ia in 0..m / mr {
             for ib in 0..n / nr {
                 scratch.for_valid_tile::<K>(&non_linear, ia, ib);
-                let err = K::kernel(&scratch.uspecs());
+                let err = scratch.run_kernel::<K>(&non_linear, ia);
                 debug_assert_eq!(err, 0, "Kernel return error {}", err);
             }
         }
         if m % mr != 0 {
             for ib in 0..n / nr {
                 scratch.for_border_tile::<K>(&non_linear, m / mr, ib);
-                let err = K::kernel(&scratch.uspecs());
+                let err = scratch.run_kernel::<K>(&non_linear, m / mr);
                 debug_assert_eq!(err, 0, "Kernel return error {}", err);
                 scratch.postprocess_tile::<K>(&non_linear, m / mr, ib, m % mr, nr);
             }
@@ -303,13 +309,13 @@ where
         if n % nr != 0 {
             for ia in 0..m / mr {
                 scratch.for_border_tile::<K>(&non_linear, ia, n / nr);
-                let err = K::kernel(&scratch.uspecs());
+                let err = scratch.run_kernel::<K>(&non_linear, ia);
                 debug_assert_eq!(err, 0, "Kernel return error {}", err);
                 scratch.postprocess_tile::<K>(&non_linear, ia, n / nr, mr, n % nr);
             }
             if m % mr != 0 {
                 scratch.for_border_tile::<K>(&non_linear, m / mr, n / nr);
-                let err = K::kernel(&scratch.uspecs());
+                let err = scratch.run_kernel::<K>(&non_linear, m / mr);
                 debug_assert_eq!(err, 0, "Kernel return error {}", err);
                 scratch.postprocess_tile::<K>(&non_linear, m / mr, n / nr, m % mr, n % nr);
             }
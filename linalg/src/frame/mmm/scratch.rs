@@ -17,6 +17,7 @@ pub struct ScratchSpaceFusedNonLinear<TI: LADatum> {
     layout: Layout,
     buffer: *const u8,
     loc_dependant: TVec<LocDependant>,
+    splits: TVec<(usize, usize)>,
 }
 
 impl<TI: LADatum> Default for ScratchSpaceFusedNonLinear<TI> {
@@ -26,6 +27,7 @@ impl<TI: LADatum> Default for ScratchSpaceFusedNonLinear<TI> {
             layout: unsafe { Layout::from_size_align_unchecked(0, 1) },
             buffer: std::ptr::null(),
             loc_dependant: tvec!(),
+            splits: tvec!(),
         }
     }
 }
@@ -36,6 +38,7 @@ struct LocDependant {
     uspec: usize,
     loc: *const u8,
     buffer: Option<*const u8>,
+    a_buffer: Option<*const u8>,
 }
 
 impl<TI: LADatum> ScratchSpace for ScratchSpaceFusedNonLinear<TI> {}
@@ -51,20 +54,32 @@ impl<TI: LADatum> Drop for ScratchSpaceFusedNonLinear<TI> {
     }
 }
 
-struct AddMatMulTemp(*const u8, usize);
+/// Current A panel and its row, current B panel and its column.
+struct AddMatMulTemp(*const u8, usize, *const u8, usize);
 
 impl<TI: LADatum> ScratchSpaceFusedNonLinear<TI> {
-    pub unsafe fn prepare<K: MatMatMulKer<TI>>(&mut self, specs: &[FusedSpec]) {
+    pub unsafe fn prepare<K: MatMatMulKer<TI>>(&mut self, specs: &[FusedSpec]) -> TractResult<()> {
         use FusedKerSpec as FKS;
         use FusedSpec as FS;
+        if let Some(spec) = specs.iter().find(|s| !s.supports_accumulator(TI::datum_type())) {
+            bail!("{:?} is not supported with a {:?} accumulator", spec, TI::datum_type())
+        }
+        if let Some(ix) = specs.iter().position(|s| matches!(s, FS::ScaleToF32(_))) {
+            // the tile holds f32 bits from there on: nothing but an f32 Store may read it
+            match &specs[ix + 1..] {
+                [FS::Store(store)] if store.item_size == 4 => (),
+                _ => bail!("ScaleToF32 must be followed by a single f32 Store, got {:?}", specs),
+            }
+        }
         self.uspecs.clear();
         self.loc_dependant.clear();
+        self.splits.clear();
         self.uspecs.reserve(specs.len() + 2);
         self.uspecs.push(FusedKerSpec::Clear);
         let mut offset = 0;
         let mut align = 1;
         fn ld(spec: usize, uspec: usize, loc: *const u8) -> LocDependant {
-            LocDependant { spec, uspec, loc, buffer: None }
+            LocDependant { spec, uspec, loc, buffer: None, a_buffer: None }
         }
         // we're cheating here, storing offset as the buf pointer first
         for ix in 0..specs.len() {
@@ -99,9 +114,26 @@ impl<TI: LADatum> ScratchSpaceFusedNonLinear<TI> {
                     offset += TI::datum_type().size_of() * K::mr() * K::nr();
                     FusedKerSpec::Done
                 }
-                FS::AddMatMul { b, .. } => {
+                FS::QScalePerRow(_) | FS::ScaleToF32(_) => {
+                    // the kernel can not do it: store the tile, scale it, and run the kernel
+                    // again from the scaled tile for the remaining specs
+                    self.loc_dependant.push(ld(ix, self.uspecs.len(), offset as _));
+                    self.splits.push((ix, self.uspecs.len()));
+                    offset += TI::datum_type().size_of() * K::mr() * K::nr();
+                    self.uspecs.push(FKS::Done);
+                    self.uspecs.push(FKS::Done);
+                    self.uspecs.push(FKS::Clear);
+                    FusedKerSpec::Done
+                }
+                FS::AddMatMul { a, b, .. } => {
                     let mut ld = ld(ix, self.uspecs.len(), offset as _);
                     offset += std::mem::size_of::<AddMatMulTemp>();
+                    if let Some(tmp) = a.scratch_panel_buffer_layout() {
+                        align = tmp.align().lcm(&align);
+                        offset = Integer::next_multiple_of(&offset, &tmp.align());
+                        ld.a_buffer = Some(offset as _);
+                        offset += tmp.size();
+                    }
                     if let Some(tmp) = b.scratch_panel_buffer_layout() {
                         align = tmp.align().lcm(&align);
                         offset = Integer::next_multiple_of(&offset, &tmp.align());
@@ -119,20 +151,25 @@ impl<TI: LADatum> ScratchSpaceFusedNonLinear<TI> {
             self.layout = Layout::from_size_align_unchecked(offset, align);
             self.buffer = std::alloc::alloc(self.layout);
         }
-        for LocDependant { loc, buffer, spec, .. } in &mut self.loc_dependant {
+        for LocDependant { loc, buffer, a_buffer, spec, .. } in &mut self.loc_dependant {
             *loc = self.buffer.offset(*loc as _);
             if let Some(b) = buffer {
                 *b = self.buffer.offset(*b as _);
             }
+            if let Some(a) = a_buffer {
+                *a = self.buffer.offset(*a as _);
+            }
             let spec = specs.get_unchecked(*spec);
             match spec {
                 FS::AddMatMul { .. } => {
                     let scratch = *loc as *mut AddMatMulTemp;
                     (*scratch).1 = usize::MAX;
+                    (*scratch).3 = usize::MAX;
                 }
                 _ => (),
             };
         }
+        Ok(())
     }
 
     #[inline(always)]
@@ -144,9 +181,9 @@ impl<TI: LADatum> ScratchSpaceFusedNonLinear<TI> {
     ) {
         use FusedKerSpec as FKS;
         use FusedSpec as FS;
-        let ScratchSpaceFusedNonLinear { uspecs, loc_dependant, .. } = self;
-        debug_assert!(specs.len() + 2 == uspecs.len());
-        for LocDependant { spec, uspec, loc, buffer } in loc_dependant.iter_mut() {
+        let ScratchSpaceFusedNonLinear { uspecs, loc_dependant, splits, .. } = self;
+        debug_assert!(specs.len() + 2 + 3 * splits.len() == uspecs.len());
+        for LocDependant { spec, uspec, loc, buffer, a_buffer } in loc_dependant.iter_mut() {
             let spec = specs.get_unchecked(*spec);
             *uspecs.get_unchecked_mut(*uspec) = match spec {
                 FS::BinPerRow(v, op) => {
@@ -178,15 +215,22 @@ impl<TI: LADatum> ScratchSpaceFusedNonLinear<TI> {
                 }
                 FS::AddUnicast(store) => FKS::AddUnicast(store.tile_c(down, right)),
                 FS::Store(c_store) => FKS::Store(c_store.tile_c(down, right)),
+                FS::QScalePerRow(_) | FS::ScaleToF32(_) => {
+                    Self::split_tile::<K>(uspecs, *uspec, *loc)
+                }
                 FS::AddMatMul { k, a, b } => {
-                    let pa = a.panel(down);
-                    K::prefetch(pa as _, 512);
                     let scratch = *loc as *mut AddMatMulTemp;
-                    if (*scratch).1 != right {
-                        (*scratch).0 = b.panel_b(right, *buffer);
-                        (*scratch).1 = right;
+                    if (*scratch).1 != down {
+                        (*scratch).0 = a.panel(down, *a_buffer);
+                        (*scratch).1 = down;
+                    }
+                    let pa = (*scratch).0;
+                    K::prefetch(pa as _, 512);
+                    if (*scratch).3 != right {
+                        (*scratch).2 = b.panel(right, *buffer);
+                        (*scratch).3 = right;
                     }
-                    FKS::AddMatMul { k: *k, pa, pb: (*scratch).0, cpu_variant: 0 }
+                    FKS::AddMatMul { k: *k, pa, pb: (*scratch).2, cpu_variant: 0 }
                 }
                 _ => std::hint::unreachable_unchecked(),
             };
@@ -202,9 +246,9 @@ impl<TI: LADatum> ScratchSpaceFusedNonLinear<TI> {
     ) {
         use FusedKerSpec as FKS;
         use FusedSpec as FS;
-        let ScratchSpaceFusedNonLinear { uspecs, loc_dependant, .. } = self;
-        debug_assert!(specs.len() + 2 == uspecs.len());
-        for LocDependant { spec, uspec, loc, buffer } in loc_dependant.iter_mut() {
+        let ScratchSpaceFusedNonLinear { uspecs, loc_dependant, splits, .. } = self;
+        debug_assert!(specs.len() + 2 + 3 * splits.len() == uspecs.len());
+        for LocDependant { spec, uspec, loc, buffer, a_buffer } in loc_dependant.iter_mut() {
             let spec = specs.get_unchecked(*spec);
             *uspecs.get_unchecked_mut(*uspec) = match spec {
                 FS::BinPerRow(v, op) => {
@@ -334,15 +378,22 @@ impl<TI: LADatum> ScratchSpaceFusedNonLinear<TI> {
                     };
                     FKS::Store(tmpc)
                 }
+                FS::QScalePerRow(_) | FS::ScaleToF32(_) => {
+                    Self::split_tile::<K>(uspecs, *uspec, *loc)
+                }
                 FS::AddMatMul { k, a, b } => {
-                    let pa = a.panel(down);
-                    K::prefetch(pa as _, 512);
                     let scratch = *loc as *mut AddMatMulTemp;
-                    if (*scratch).1 != right {
-                        (*scratch).0 = b.panel_b(right, *buffer);
-                        (*scratch).1 = right;
+                    if (*scratch).1 != down {
+                        (*scratch).0 = a.panel(down, *a_buffer);
+                        (*scratch).1 = down;
+                    }
+                    let pa = (*scratch).0;
+                    K::prefetch(pa as _, 512);
+                    if (*scratch).3 != right {
+                        (*scratch).2 = b.panel(right, *buffer);
+                        (*scratch).3 = right;
                     }
-                    FKS::AddMatMul { k: *k, pa, pb: (*scratch).0, cpu_variant: 0 }
+                    FKS::AddMatMul { k: *k, pa, pb: (*scratch).2, cpu_variant: 0 }
                 }
                 _ => std::hint::unreachable_unchecked(),
             };
@@ -354,6 +405,52 @@ impl<TI: LADatum> ScratchSpaceFusedNonLinear<TI> {
         &self.uspecs
     }
 
+    #[inline(always)]
+    unsafe fn split_tile<K: MatMatMulKer<TI>>(
+        uspecs: &mut [FusedKerSpec<TI>],
+        uspec: usize,
+        loc: *const u8,
+    ) -> FusedKerSpec<TI> {
+        let tile = OutputStoreKer {
+            ptr: loc as _,
+            item_size: TI::datum_type().size_of(),
+            row_byte_stride: TI::datum_type().size_of() as isize,
+            col_byte_stride: (TI::datum_type().size_of() * K::mr()) as isize,
+        };
+        *uspecs.get_unchecked_mut(uspec + 3) = FusedKerSpec::AddUnicast(tile);
+        FusedKerSpec::Store(tile)
+    }
+
+    #[inline(always)]
+    pub unsafe fn run_kernel<K: MatMatMulKer<TI>>(
+        &mut self,
+        specs: &[FusedSpec],
+        down: usize,
+    ) -> isize {
+        let mut start = 0;
+        for &(spec, uspec) in &self.splits {
+            let err = K::kernel(self.uspecs.get_unchecked(start..));
+            if err != 0 {
+                return err;
+            }
+            if let FusedKerSpec::Store(tile) = self.uspecs.get_unchecked(uspec) {
+                match specs.get_unchecked(spec) {
+                    FusedSpec::QScalePerRow(scales) => {
+                        let have = scales.len().saturating_sub(down * K::mr()).min(K::mr());
+                        let scales = &scales.as_slice_unchecked::<f32>()[down * K::mr()..][..have];
+                        scale_rows::<TI>(tile.ptr, scales, K::mr(), K::nr());
+                    }
+                    FusedSpec::ScaleToF32(scale) => {
+                        scale_to_f32::<TI>(tile.ptr, *scale, K::mr() * K::nr())
+                    }
+                    _ => (),
+                }
+            }
+            start = uspec + 2;
+        }
+        K::kernel(self.uspecs.get_unchecked(start..))
+    }
+
     pub unsafe fn postprocess_tile<K: MatMatMulKer<TI>>(
         &mut self,
         specs: &[FusedSpec],
@@ -373,3 +470,48 @@ impl<TI: LADatum> ScratchSpaceFusedNonLinear<TI> {
         }
     }
 }
+
+/// Tile is column major, mr rows by nr columns.
+unsafe fn scale_rows<TI: LADatum>(tile: *mut u8, scales: &[f32], mr: usize, nr: usize) {
+    match TI::datum_type() {
+        DatumType::I32 => {
+            let tile = std::slice::from_raw_parts_mut(tile as *mut i32, mr * nr);
+            for (r, s) in scales.iter().enumerate() {
+                for c in 0..nr {
+                    let x = tile.get_unchecked_mut(r + c * mr);
+                    let f = *x as f32;
+                    *x = (round_ties_to_even(f.abs() * s) * f.signum()) as i32;
+                }
+            }
+        }
+        DatumType::F32 => {
+            let tile = std::slice::from_raw_parts_mut(tile as *mut f32, mr * nr);
+            for (r, s) in scales.iter().enumerate() {
+                for c in 0..nr {
+                    *tile.get_unchecked_mut(r + c * mr) *= s;
+                }
+            }
+        }
+        dt => unreachable!("Per row scaling of {:?} accumulator", dt),
+    }
+}
+
+/// The kernel resumes from the tile, so the f32 bits reach its Store untouched (prepare
+/// checks that the Store is the only spec left).
+unsafe fn scale_to_f32<TI: LADatum>(tile: *mut u8, scale: f32, len: usize) {
+    match TI::datum_type() {
+        DatumType::I32 => {
+            let tile = std::slice::from_raw_parts_mut(tile as *mut i32, len);
+            for x in tile {
+                *x = (*x as f32 * scale).to_bits() as i32;
+            }
+        }
+        DatumType::F32 => {
+            let tile = std::slice::from_raw_parts_mut(tile as *mut f32, len);
+            for x in tile {
+                *x *= scale;
+            }
+        }
+        dt => unreachable!("Scaling of {:?} accumulator to f32", dt),
+    }
+}
@@ -120,6 +120,41 @@ macro_rules! mmm_frame_tests {
                 }
             }
 
+            #[test]
+            fn row_q_scale_2_1_3() {
+                if $cond {
+                    unsafe { row_q_scale::<$ker, $ta, $tb, $tc, $ti>(2, 3).unwrap() }
+                }
+            }
+
+            #[test]
+            fn row_q_scale_17_1_9() {
+                if $cond {
+                    unsafe { row_q_scale::<$ker, $ta, $tb, $tc, $ti>(17, 9).unwrap() }
+                }
+            }
+
+            #[test]
+            fn scale_to_f32_2_1_3() {
+                if $cond {
+                    unsafe { scale_to_f32::<$ker, $ti>(2, 3).unwrap() }
+                }
+            }
+
+            #[test]
+            fn scale_to_f32_17_1_9() {
+                if $cond {
+                    unsafe { scale_to_f32::<$ker, $ti>(17, 9).unwrap() }
+                }
+            }
+
+            #[test]
+            fn scale_to_f32_stored_last_2_1_3() {
+                if $cond {
+                    unsafe { scale_to_f32_is_stored_last::<$ker, $ti>(2, 3).unwrap() }
+                }
+            }
+
             #[test]
             fn col_mul_2_1_3() {
                 if $cond {
@@ -172,7 +207,7 @@ fn tensor(dt: DatumType, shape: Vec<usize>) -> BoxedStrategy<Tensor> {
             tract_ndarray::ArrayD::from_shape_vec(shape.clone(), vec)
                 .unwrap()
                 .into_tensor()
-                .cast_to_dt(dt)
+                .cast_to_dt(dt.clone())
                 .unwrap()
                 .into_owned()
         })
@@ -241,7 +276,7 @@ where
             m,
             n,
             &[FusedSpec::AddMatMul {
-                a: op.a_packed(TA::datum_type().size_of(), k).wrap(&packed_a.view()),
+                a: op.a_packed(TA::datum_type().size_of(), k).wrap(&packed_a.view()).unwrap(),
                 b: op.b_packed(TB::datum_type().size_of(), k).wrap(&packed_b.view()).unwrap(),
                 k,
             }],
@@ -285,7 +320,7 @@ where
             m,
             n,
             &[FusedSpec::AddMatMul {
-                a: op.a_packed(TA::datum_type().size_of(), k).wrap(&packed_a.view()),
+                a: op.a_packed(TA::datum_type().size_of(), k).wrap(&packed_a.view()).unwrap(),
                 b: op.b_late_packing().wrap(&b.view()).unwrap(),
                 k,
             }],
@@ -328,7 +363,7 @@ where
         let b = b.clone().into_shape(&[k, 1]).unwrap();
         op.b_pack().pack(&mut packed_b.view_mut(), &b.view(), 0, 1);
 
-        let pa = op.a_packed(TA::datum_type().size_of(), k).wrap(&packed_a.view());
+        let pa = op.a_packed(TA::datum_type().size_of(), k).wrap(&packed_a.view()).unwrap();
         let pb = op.b_packed(b.datum_type().size_of(), k).wrap(&packed_b.view()).unwrap();
 
         fused_ops::<K, TA, TB, TC, TI, _>(
@@ -435,6 +470,85 @@ where
     )
 }
 
+pub unsafe fn row_q_scale<K: MatMatMulKer<TI> + 'static, TA, TB, TC, TI>(
+    m: usize,
+    n: usize,
+) -> proptest::test_runner::TestCaseResult
+where
+    TA: LADatum + AsPrimitive<TI> + 'static,
+    TB: LADatum + AsPrimitive<TI> + 'static,
+    TC: LADatum + AsPrimitive<TI> + 'static,
+    TI: LADatum + AsPrimitive<TC> + 'static + Neg<Output = TI>,
+    i32: AsPrimitive<TI>,
+    usize: AsPrimitive<TI>,
+{
+    let scales = (0..m).map(|i| (i + 1) as f32 * 0.5).collect::<Vec<f32>>();
+    let bias = (0..m).map(|i| i.as_()).collect::<Vec<TI>>();
+    let expected = (0..m).map(|i| (5 * (i + 1) + i).as_()).collect::<Vec<TI>>();
+    fused_ops::<K, TA, TB, TC, TI, _>(
+        m,
+        n,
+        &[
+            FusedSpec::BinScalar(&tensor0(10i32.as_()), BinOp::Add),
+            FusedSpec::QScalePerRow(&tensor1(&*scales)),
+            FusedSpec::BinPerRow(&tensor1(&*bias), BinOp::Add),
+        ],
+        |r, _| expected[r].as_(),
+    )
+}
+
+pub unsafe fn scale_to_f32<K: MatMatMulKer<TI> + 'static, TI>(
+    m: usize,
+    n: usize,
+) -> proptest::test_runner::TestCaseResult
+where
+    TI: LADatum,
+    usize: AsPrimitive<TI>,
+{
+    let op = MatMatMulImpl::<K, TI>::new();
+    let bias = (0..m).map(|i| i.as_()).collect::<Vec<TI>>();
+    let mut found = Tensor::zero::<f32>(&[m, n]).unwrap();
+    let c_store = op.c_from_data_and_strides(4, n as isize, 1).wrap(&mut found.view_mut());
+    op.run(
+        m,
+        n,
+        &[
+            FusedSpec::BinPerRow(&tensor1(&*bias), BinOp::Add),
+            FusedSpec::ScaleToF32(0.25),
+            FusedSpec::Store(c_store),
+        ],
+    )
+    .unwrap();
+    let expected = tract_ndarray::prelude::Array2::from_shape_fn((m, n), |(r, _)| r as f32 * 0.25)
+        .into_tensor();
+    found.close_enough(&expected, true).map_err(|e| TestCaseError::Fail(e.to_string().into()))
+}
+
+pub unsafe fn scale_to_f32_is_stored_last<K: MatMatMulKer<TI> + 'static, TI>(
+    m: usize,
+    n: usize,
+) -> proptest::test_runner::TestCaseResult
+where
+    TI: LADatum,
+    usize: AsPrimitive<TI>,
+{
+    let op = MatMatMulImpl::<K, TI>::new();
+    let bias = (0..m).map(|i| i.as_()).collect::<Vec<TI>>();
+    let mut found = Tensor::zero::<f32>(&[m, n]).unwrap();
+    let c_store = op.c_from_data_and_strides(4, n as isize, 1).wrap(&mut found.view_mut());
+    let result = op.run(
+        m,
+        n,
+        &[
+            FusedSpec::ScaleToF32(0.25),
+            FusedSpec::BinPerRow(&tensor1(&*bias), BinOp::Add),
+            FusedSpec::Store(c_store),
+        ],
+    );
+    prop_assert!(result.is_err());
+    Ok(())
+}
+
 pub unsafe fn col_add<K: MatMatMulKer<TI> + 'static, TA, TB, TC, TI>(
     m: usize,
     n: usize,
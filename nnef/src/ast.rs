@@ -27,8 +27,8 @@ impl QuantFormat {
     pub fn datum_type(&self) -> DatumType {
         match self {
             QuantFormat::Linear { params, bits, signed } => match (bits, signed) {
-                (8, true) => DatumType::QI8(*params),
-                (8, false) => DatumType::QU8(*params),
+                (8, true) => DatumType::QI8(params.clone()),
+                (8, false) => DatumType::QU8(params.clone()),
                 (32, true) => DatumType::I32,
                 (32, false) => DatumType::U32,
                 _ => todo!(),
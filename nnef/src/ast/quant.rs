@@ -2,7 +2,8 @@ use std::str::FromStr;
 
 use nom::branch::permutation;
 use nom::character::complete::digit1;
-use nom::combinator::map_res;
+use nom::combinator::{map, map_res, recognize};
+use nom::sequence::{delimited, pair};
 use tract_core::internal::*;
 
 use nom::{bytes::complete::*, multi::*};
@@ -34,6 +35,23 @@ fn integer_numeric<T: FromStr>(i: &str) -> IResult<&str, T> {
     map_res(digit1, |s: &str| s.parse::<T>())(i)
 }
 
+fn signed_integer_numeric<T: FromStr>(i: &str) -> IResult<&str, T> {
+    map_res(recognize(pair(opt(tag("-")), digit1)), |s: &str| s.parse::<T>())(i)
+}
+
+// <values>(<f>) ::= <f> | "[" <f> ("," <f>)* "]"
+fn values<'s, T, F>(f: F) -> impl Fn(&'s str) -> IResult<&'s str, Vec<T>>
+where
+    F: Fn(&'s str) -> IResult<&'s str, T> + Copy,
+{
+    move |i: &str| {
+        nom::branch::alt((
+            delimited(stag("["), separated_list0(stag(","), f), stag("]")),
+            map(f, |v| vec![v]),
+        ))(i)
+    }
+}
+
 // <qparam> ::= "<identifier>": <qparam>
 fn qparam(i: &str) -> IResult<&str, QuantFormat> {
     let (i, id) =
@@ -49,14 +67,35 @@ fn qparam(i: &str) -> IResult<&str, QuantFormat> {
             (i, QParams::MinMax { min, max }, bits, true)
         }
         "zero_point_linear_quantize" => {
-            let (i, (zero_point, scale, bits, signed, _)) = permutation((
-                arg("zero_point", integer_numeric),
-                arg("scale", float),
+            let (i, (zero_points, scales, bits, signed, _, axis)) = permutation((
+                arg("zero_point", values(signed_integer_numeric)),
+                arg("scale", values(float)),
                 arg("bits", integer_numeric),
                 arg("signed", logical_literal),
                 opt(arg("symmetric", logical_literal)),
+                opt(arg("axis", integer_numeric)),
             ))(i)?;
-            (i, QParams::ZpScale { zero_point, scale }, bits, signed)
+            let params = match (axis, &*zero_points, &*scales) {
+                (None, &[zero_point], &[scale]) => QParams::ZpScale { zero_point, scale },
+                (Some(axis), _, _)
+                    if zero_points.len() == scales.len()
+                        || zero_points.len() == 1
+                        || scales.len() == 1 =>
+                {
+                    let len = zero_points.len().max(scales.len());
+                    let zero_points: Vec<i32> =
+                        zero_points.iter().cycle().take(len).copied().collect();
+                    let scales: Vec<f32> = scales.iter().cycle().take(len).copied().collect();
+                    QParams::per_axis(axis, &zero_points, &scales)
+                }
+                _ => {
+                    return Err(nom::Err::Failure(nom::error::Error::new(
+                        i,
+                        nom::error::ErrorKind::Verify,
+                    )))
+                }
+            };
+            (i, params, bits, signed)
         }
         _ => unreachable!(),
     };
@@ -86,10 +125,19 @@ pub(crate) fn write_quant_format(
     match format {
         QuantFormat::Linear {
             params: QParams::ZpScale {zero_point, scale}, bits, signed
-        } => writeln!(w, "{}: zero_point_linear_quantize(zero_point = {}, scale = {:.9}, bits = {}, signed = {}, symmetric = {});", name, zero_point, scale, bits, signed, zero_point == 0)?,
+        } => writeln!(w, "\"{}\": zero_point_linear_quantize(zero_point = {}, scale = {:.9}, bits = {}, signed = {}, symmetric = {});", name, zero_point, scale, bits, signed, zero_point == 0)?,
         QuantFormat::Linear {
             params: QParams::MinMax {min, max}, bits, signed: _
-        } => writeln!(w, "{}: linear_quantize(max = {:.9}, min = {:.9}, bits = {});", name, max, min, bits)?,
+        } => writeln!(w, "\"{}\": linear_quantize(max = {:.9}, min = {:.9}, bits = {});", name, max, min, bits)?,
+        QuantFormat::Linear {
+            params: params @ QParams::PerAxis { axis, .. }, bits, signed
+        } => {
+            let (zero_points, scales) = params.zp_scale_vecs();
+            let symmetric = zero_points.iter().all(|zp| *zp == 0);
+            let zero_points = zero_points.iter().map(|zp| zp.to_string()).collect::<Vec<_>>().join(", ");
+            let scales = scales.iter().map(|s| format!("{:.9}", s)).collect::<Vec<_>>().join(", ");
+            writeln!(w, "\"{}\": zero_point_linear_quantize(zero_point = [{}], scale = [{}], bits = {}, signed = {}, symmetric = {}, axis = {});", name, zero_points, scales, bits, signed, symmetric, axis)?
+        }
     }
     Ok(())
 }
@@ -128,6 +176,34 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_qparam_per_axis() {
+        assert_eq!(
+            p(
+                qparam,
+                "zero_point_linear_quantize(zero_point = [0, -3], scale = [0.5, 0.25], bits = 8, signed = true, symmetric = false, axis = 1)"
+            ),
+            QuantFormat::Linear {
+                params: QParams::per_axis(1, &[0, -3], &[0.5, 0.25]),
+                bits: 8,
+                signed: true
+            }
+        );
+    }
+
+    #[test]
+    fn test_write_per_axis() {
+        let format = QuantFormat::Linear {
+            params: QParams::per_axis(0, &[1, 2, 3], &[0.5, 0.25, 0.125]),
+            bits: 8,
+            signed: false,
+        };
+        let mut buf = vec![];
+        write_quant_format(&mut buf, "t".to_string(), format.clone()).unwrap();
+        let text = String::from_utf8(buf).unwrap();
+        assert_eq!(p(quantization, text.trim()), ("t".to_string(), format));
+    }
+
     #[test]
     fn test_quantization() {
         assert_eq!(
@@ -35,8 +35,42 @@ impl<'mb> ModelBuilder<'mb> {
                 _ => warn!("Ignore unknown extension {}", ext.join(" ")),
             };
         }
+        let properties = self
+            .proto_model
+            .doc
+            .fragments
+            .iter()
+            .find(|f| f.decl.id == "tract_core_properties")
+            .and_then(|f| f.body.as_ref())
+            .and_then(|body| body.get(0))
+            .map(|assignment| &assignment.right);
+        // assertions must be known before the body shapes are computed
+        if let Some(RValue::Array(items)) = properties {
+            let key = RValue::Literal(Literal::String("tract_assert".to_string()));
+            for item in items {
+                if matches!(item, RValue::Tuple(kv) if kv.len() == 2 && kv[0] == key) {
+                    let (_, assertions): (String, TVec<String>) =
+                        item.resolve(self, &[])?.to(self)?;
+                    for assertion in assertions {
+                        let scope = &self.model.symbol_scope;
+                        scope
+                            .add_assertion(scope.parse_assertion(&assertion)?)
+                            .with_context(|| format!("Asserting {}", assertion))?;
+                    }
+                }
+            }
+        }
         self.scopes.push(HashMap::new());
         self.wire_body(&self.proto_model.doc.graph_def.body)?;
+        // properties may refer to variables from the body
+        let mut properties: HashMap<String, Arc<Tensor>> = if let Some(properties) = properties {
+            let properties: TVec<(String, Arc<Tensor>)> =
+                properties.resolve(self, &[])?.to(self).context("Reading properties")?;
+            properties.into_iter().collect()
+        } else {
+            HashMap::new()
+        };
+        properties.remove("tract_assert");
         let vars = self.scopes.pop().unwrap();
 
         let outputs = self
@@ -57,19 +91,7 @@ impl<'mb> ModelBuilder<'mb> {
             .map(|s| s.to::<OutletId>(self))
             .collect::<TractResult<TVec<OutletId>>>()?;
         self.model.set_output_outlets(&outputs)?;
-        if let Some(properties) = self
-            .proto_model
-            .doc
-            .fragments
-            .iter()
-            .find(|f| f.decl.id == "tract_core_properties")
-            .and_then(|f| f.body.as_ref())
-            .and_then(|body| body.get(0))
-        {
-            let properties: TVec<(String, Arc<Tensor>)> =
-                properties.right.resolve(self, &[])?.to(self)?;
-            self.model.properties = properties.into_iter().collect();
-        }
+        self.model.properties = properties;
         Ok(())
     }
 
@@ -315,8 +337,10 @@ impl RValue {
                     .cloned()
                     .ok_or_else(|| format_err!("No value for name {}", id))?;
                 if let Value::Wire(outlet_id) = outlet {
-                    let out_dt =
-                        builder.model.node(outlet_id.node).outputs[outlet_id.slot].fact.datum_type;
+                    let out_dt = builder.model.node(outlet_id.node).outputs[outlet_id.slot]
+                        .fact
+                        .datum_type
+                        .clone();
                     if let Some(Some(dt)) = dt.get(0) {
                         if out_dt.unquantized() != dt.unquantized() {
                             return Err(format_err!(
@@ -327,7 +351,9 @@ impl RValue {
                         }
                         if out_dt != *dt {
                             outlet = Value::Wire(
-                                builder.wire(tract_core::ops::cast::cast(*dt), &[outlet_id])?[0],
+                                builder
+                                    .wire(tract_core::ops::cast::cast(dt.clone()), &[outlet_id])?
+                                    [0],
                             )
                         }
                     }
@@ -367,8 +393,8 @@ impl RValue {
             RValue::Array(array) => Ok(Value::Array(
                 array
                     .iter()
-                    .zip(std::iter::repeat(&dt.get(0).copied().flatten()))
-                    .map(|(i, dt)| i.resolve(builder, &[*dt]))
+                    .zip(std::iter::repeat(&dt.get(0).cloned().flatten()))
+                    .map(|(i, dt)| i.resolve(builder, &[dt.clone()]))
                     .collect::<TractResult<_>>()?,
             )),
             RValue::Tuple(array) => {
@@ -388,7 +414,7 @@ impl RValue {
                             if dt.is_none() {
                                 i.resolve(builder, &[])
                             } else {
-                                i.resolve(builder, &[*dt])
+                                i.resolve(builder, &[dt.clone()])
                             }
                         })
                         .collect::<TractResult<_>>()?,
@@ -410,8 +436,8 @@ impl RValue {
             RValue::Literal(Literal::Array(array)) => Ok(Value::Array(
                 array
                     .iter()
-                    .zip(std::iter::repeat(&dt.get(0).copied().flatten()))
-                    .map(|(i, dt)| RValue::Literal(i.clone()).resolve(builder, &[*dt]))
+                    .zip(std::iter::repeat(&dt.get(0).cloned().flatten()))
+                    .map(|(i, dt)| RValue::Literal(i.clone()).resolve(builder, &[dt.clone()]))
                     .collect::<TractResult<_>>()?,
             )),
             _ => panic!("{:?}", self),
@@ -459,6 +485,7 @@ impl CoerceFrom<Value> for Arc<Tensor> {
             Value::Tensor(t) => Ok(t.clone()),
             Value::Scalar(f) => Ok(rctensor0(*f)),
             Value::String(f) => Ok(rctensor0(f.clone())),
+            Value::Bool(b) => Ok(rctensor0(*b)),
             Value::Wire(o) => builder
                 .model
                 .outlet_fact(*o)?
@@ -491,7 +518,7 @@ impl CoerceFrom<Value> for (Arc<Tensor>, DatumType) {
                 let outlet_fact = builder.model.outlet_fact(*o)?;
                 Ok((
                     outlet_fact.konst.clone().ok_or_else(|| format_err!("Not a const"))?,
-                    outlet_fact.datum_type,
+                    outlet_fact.datum_type.clone(),
                 ))
             }
             _ => bail!("Can not build a tensor from {:?}", from),
@@ -532,9 +559,10 @@ impl CoerceFrom<Value> for i64 {
 }
 
 impl CoerceFrom<Value> for TDim {
-    fn coerce(_builder: &mut ModelBuilder, from: &Value) -> TractResult<Self> {
+    fn coerce(builder: &mut ModelBuilder, from: &Value) -> TractResult<Self> {
         match from {
             Value::Dim(d) => Ok(d.clone()),
+            Value::String(s) => builder.model.symbol_scope.parse_tdim(s),
             _ => bail!("Can not build a TDim from {:?}", from),
         }
     }
@@ -2,7 +2,7 @@ use crate::ast::quant::write_quant_format;
 use crate::ast::{ProtoModel, QuantFormat};
 use crate::internal::*;
 use std::io::Read;
-#[cfg(target_family="unix")]
+#[cfg(target_family = "unix")]
 use std::os::unix::prelude::OsStrExt;
 use std::path::Path;
 
@@ -130,6 +130,13 @@ impl tract_core::prelude::Framework<ProtoModel, TypedModel> for Nnef {
     fn proto_model_for_path(&self, path: impl AsRef<Path>) -> TractResult<ProtoModel> {
         let path = path.as_ref();
         if path.is_file() {
+            #[cfg(unix)]
+            {
+                let mmap = Arc::new(crate::mmap::Mmap::map(&std::fs::File::open(path)?)?);
+                if mmap.get(0..2) != Some(&[0x1f, 0x8b]) {
+                    return proto_model_for_mmapped_tar(mmap);
+                }
+            }
             let mut f = std::fs::File::open(path)?;
             return self.proto_model_for_read(&mut f);
         }
@@ -144,14 +151,19 @@ impl tract_core::prelude::Framework<ProtoModel, TypedModel> for Nnef {
                 .components()
                 .skip(path.components().count())
                 .collect::<std::path::PathBuf>();
+            #[cfg(unix)]
+            if entry.file_type().is_file() && !is_hidden(&subpath) {
+                if let Some(label) = tensor_label(&subpath)? {
+                    let mmap = crate::mmap::Mmap::map(&std::fs::File::open(entry.path())?)?;
+                    let tensor = crate::tensors::read_tensor_from_shared_bytes(Arc::new(mmap), 0)?;
+                    tensors.push((label, tensor.into_arc_tensor()));
+                    continue;
+                }
+            }
             let mut stream = std::fs::File::open(entry.path())?;
             read_stream(&subpath, &mut stream, &mut text, &mut tensors, &mut quantization)?;
         }
-        let text = text.ok_or_else(|| format_err!("Model must contain graph.nnef at top level"))?;
-        let doc = crate::ast::parse::parse_document(&text)?;
-        let proto = ProtoModel { doc, tensors, quantization };
-        proto.validate()?;
-        Ok(proto)
+        proto_model(text, tensors, quantization)
     }
 
     fn proto_model_for_read(&self, reader: &mut dyn std::io::Read) -> TractResult<ProtoModel> {
@@ -178,11 +190,7 @@ impl tract_core::prelude::Framework<ProtoModel, TypedModel> for Nnef {
             let path = entry.path()?.to_path_buf();
             read_stream(&path, &mut entry, &mut text, &mut tensors, &mut quantization)?;
         }
-        let text = text.ok_or_else(|| format_err!("Model must contain graph.nnef at top level"))?;
-        let doc = crate::ast::parse::parse_document(&text)?;
-        let proto = ProtoModel { doc, tensors, quantization };
-        proto.validate()?;
-        Ok(proto)
+        proto_model(text, tensors, quantization)
     }
 
     fn model_for_proto_model(&self, proto: &ProtoModel) -> TractResult<TypedModel> {
@@ -190,6 +198,61 @@ impl tract_core::prelude::Framework<ProtoModel, TypedModel> for Nnef {
     }
 }
 
+/// Uncompressed tar archives are memory mapped: the tensors borrow their data from the mapping.
+#[cfg(unix)]
+fn proto_model_for_mmapped_tar(mmap: Arc<crate::mmap::Mmap>) -> TractResult<ProtoModel> {
+    let mut text: Option<String> = None;
+    let mut tensors: Vec<(String, Arc<Tensor>)> = Default::default();
+    let mut quantization = None;
+    let mut tar = tar::Archive::new(&**mmap);
+    for entry in tar.entries()? {
+        let mut entry = entry?;
+        let path = entry.path()?.to_path_buf();
+        match tensor_label(&path)? {
+            Some(label) if !is_hidden(&path) => {
+                let offset = entry.raw_file_position() as usize;
+                let tensor = crate::tensors::read_tensor_from_shared_bytes(mmap.clone(), offset)?;
+                tensors.push((label, tensor.into_arc_tensor()));
+            }
+            _ => read_stream(&path, &mut entry, &mut text, &mut tensors, &mut quantization)?,
+        }
+    }
+    proto_model(text, tensors, quantization)
+}
+
+fn proto_model(
+    text: Option<String>,
+    tensors: Vec<(String, Arc<Tensor>)>,
+    quantization: Option<HashMap<String, QuantFormat>>,
+) -> TractResult<ProtoModel> {
+    let text = text.ok_or_else(|| format_err!("Model must contain graph.nnef at top level"))?;
+    let doc = crate::ast::parse::parse_document(&text)?;
+    let proto = ProtoModel { doc, tensors, quantization };
+    proto.validate()?;
+    Ok(proto)
+}
+
+// ignore path with any component starting with "." (because OSX's tar is weird)
+fn is_hidden(path: &std::path::Path) -> bool {
+    #[cfg(target_family = "unix")]
+    return path.components().any(|name| name.as_os_str().as_bytes().get(0) == Some(&b'.'));
+    #[cfg(not(target_family = "unix"))]
+    return false;
+}
+
+fn tensor_label(path: &std::path::Path) -> TractResult<Option<String>> {
+    if path.extension().map(|e| e == "dat").unwrap_or(false) {
+        let mut path = path.to_path_buf();
+        path.set_extension("");
+        let id = path
+            .to_str()
+            .ok_or_else(|| format_err!("Badly encoded filename for tensor: {:?}", path))?;
+        Ok(Some(id.to_string()))
+    } else {
+        Ok(None)
+    }
+}
+
 fn read_stream<R: std::io::Read>(
     path: &std::path::Path,
     reader: &mut R,
@@ -197,23 +260,16 @@ fn read_stream<R: std::io::Read>(
     tensors: &mut Vec<(String, Arc<Tensor>)>,
     quantization: &mut Option<HashMap<String, QuantFormat>>,
 ) -> TractResult<()> {
-    // ignore path with any component starting with "." (because OSX's tar is weird)
-    #[cfg(target_family="unix")]
-    if path.components().any(|name| name.as_os_str().as_bytes().get(0) == Some(&b'.')) {
-        return Ok(())
+    if is_hidden(path) {
+        return Ok(());
     }
     if path.file_name().map(|n| n == "graph.nnef").unwrap_or(false) {
         let mut t = String::new();
         reader.read_to_string(&mut t)?;
         *text = Some(t);
-    } else if path.extension().map(|e| e == "dat").unwrap_or(false) {
-        let mut path = path.to_path_buf();
-        path.set_extension("");
-        let id = path
-            .to_str()
-            .ok_or_else(|| format_err!("Badly encoded filename for tensor: {:?}", path))?;
+    } else if let Some(label) = tensor_label(path)? {
         let tensor = crate::tensors::read_tensor(reader)?;
-        tensors.push((id.to_string(), tensor.into_arc_tensor()));
+        tensors.push((label, tensor.into_arc_tensor()));
     } else if path.file_name().map(|n| n == "graph.quant").unwrap_or(false) {
         let mut t = String::new();
         reader.read_to_string(&mut t)?;
@@ -226,3 +282,48 @@ fn read_stream<R: std::io::Read>(
     }
     Ok(())
 }
+
+#[cfg(all(test, unix))]
+mod test {
+    use super::*;
+    use tract_core::ops::konst::Const;
+
+    fn model() -> TractResult<TypedModel> {
+        let mut model = TypedModel::default();
+        let weights = model.add_const("weights", tensor2(&[[1f32, 2.], [3., 4.]]))?;
+        let bias = model.add_const("bias", tensor1(&[1i8, -1]))?;
+        model.set_output_outlets(&[weights, bias])?;
+        Ok(model)
+    }
+
+    fn check_shared(model: &TypedModel) -> TractResult<()> {
+        let original = self::model()?;
+        for name in &["weights", "bias"] {
+            let node = model.node_by_name(name)?;
+            let konst = node.op_as::<Const>().context("Expected a Const")?;
+            assert!(konst.0.is_shared());
+            assert_eq!(konst.0, original.node_by_name(name)?.op_as::<Const>().unwrap().0);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn mmap_tar() -> TractResult<()> {
+        let path = std::env::temp_dir().join(format!("tract-nnef-mmap-{}.tar", std::process::id()));
+        let nnef = Nnef::new();
+        nnef.write_to_tar(&model()?, std::fs::File::create(&path)?)?;
+        let reloaded = nnef.model_for_path(&path);
+        std::fs::remove_file(&path)?;
+        check_shared(&reloaded?)
+    }
+
+    #[test]
+    fn mmap_dir() -> TractResult<()> {
+        let path = std::env::temp_dir().join(format!("tract-nnef-mmap-{}", std::process::id()));
+        let nnef = Nnef::new();
+        nnef.write_to_dir(&model()?, &path)?;
+        let reloaded = nnef.model_for_path(&path);
+        std::fs::remove_dir_all(&path)?;
+        check_shared(&reloaded?)
+    }
+}
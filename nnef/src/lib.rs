@@ -4,6 +4,8 @@ extern crate log;
 pub mod ast;
 pub mod deser;
 pub mod framework;
+#[cfg(unix)]
+pub mod mmap;
 pub mod ops;
 pub mod registry;
 pub mod ser;
@@ -1,6 +1,7 @@
 use crate::internal::*;
 use tract_core::ops;
 
+mod block_quant;
 mod broadcast;
 mod cast;
 mod downsample;
@@ -38,6 +39,7 @@ pub fn register(registry: &mut Registry) {
         &ops::math::ShiftRight,
         &ops::math::FlippedShiftRight,
     );
+    block_quant::register(registry);
     broadcast::register(registry);
     cast::register(registry);
     downsample::register(registry);
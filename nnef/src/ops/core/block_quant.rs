@@ -0,0 +1,50 @@
+use crate::internal::*;
+use crate::ser::*;
+use tract_core::ops::matmul::BlockQuantMatMulUnary;
+use tract_core::tract_linalg::frame::BlockQuantWeights;
+
+pub fn register(registry: &mut Registry) {
+    registry.register_dumper(TypeId::of::<BlockQuantMatMulUnary>(), block_quant_matmul_dump);
+    registry.register_primitive(
+        "tract_core_block_quant_matmul",
+        &[
+            TypeName::Scalar.tensor().named("A"),
+            TypeName::Scalar.tensor().named("B"),
+            TypeName::Logical.spec().named("transposeB"),
+            TypeName::Logical.spec().named("transposeC"),
+        ],
+        block_quant_matmul_load,
+    );
+}
+
+fn block_quant_matmul_dump(
+    ast: &mut IntoAst,
+    node: &TypedNode,
+) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<BlockQuantMatMulUnary>().unwrap();
+    let weights = rctensor0(Blob(op.a.to_bytes()?));
+    let a = ast.konst_variable(format!("{}.a", node.name), &weights)?;
+    let b = ast.mapping[&node.inputs[0]].clone();
+    Ok(Some(invocation(
+        "tract_core_block_quant_matmul",
+        &[],
+        &[
+            ("A", (*a).clone()),
+            ("B", (*b).clone()),
+            ("transposeB", logical(op.b_trans)),
+            ("transposeC", logical(op.c_trans)),
+        ],
+    )))
+}
+
+fn block_quant_matmul_load(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let a: Arc<Tensor> = invocation.named_arg_as(builder, "A")?;
+    let b: OutletId = invocation.named_arg_as(builder, "B")?;
+    let b_trans: bool = invocation.named_arg_as(builder, "transposeB")?;
+    let c_trans: bool = invocation.named_arg_as(builder, "transposeC")?;
+    let a = BlockQuantWeights::from_bytes(a.to_scalar::<Blob>()?)?;
+    builder.wire(BlockQuantMatMulUnary { a, b_trans, c_trans }, &[b])
+}
@@ -14,6 +14,10 @@ fn cast_parameters() -> Vec<Parameter> {
 fn cast_dump(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
     let op = node.op_as::<Cast>().unwrap();
     let input = ast.mapping[&node.inputs[0]].clone();
+    if op.to.is_quantized() {
+        // the quantized type is recorded in the quantization file
+        return Ok(Some(invocation("tract_core_cast", &[input], &[])));
+    }
     Ok(Some(invocation(
         "tract_core_cast",
         &[input],
@@ -26,10 +30,10 @@ fn cast_load(
     invocation: &ResolvedInvocation,
 ) -> TractResult<TVec<OutletId>> {
     let input = invocation.named_arg_as(builder, "input")?;
-    let invocation_dt = invocation.dt_from_quant_file.get(0).copied().flatten();
+    let invocation_dt = invocation.dt_from_quant_file.get(0).cloned().flatten();
     let to = if let Ok(s) = invocation.named_arg_as::<String>(builder, "to") {
         let dt = s.parse()?;
-        if invocation_dt.is_some() && dt != invocation_dt.unwrap() {
+        if invocation_dt.is_some() && dt != invocation_dt.clone().unwrap() {
             bail!("Mismatched cast: expected {:?}, got {:?}", invocation_dt.unwrap(), dt)
         }
         dt
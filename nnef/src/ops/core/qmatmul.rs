@@ -83,7 +83,7 @@ fn qmatmul_dump(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<R
         ("transposeA", logical(op.a_trans)),
         ("transposeB", logical(op.b_trans)),
         ("transposeC", logical(op.c_trans)),
-        ("output_type", string(format!("{:?}", op.output_type))),
+        ("output_type", string(format!("{:?}", op.output_type.unquantized()))),
     ];
     macro_rules! push {
         ($a: ident) => {
@@ -115,7 +115,7 @@ fn qmatmul_unary_dump(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option
         ("transposeA", logical(op.a_trans)),
         ("transposeB", logical(op.b_trans)),
         ("transposeC", logical(op.c_trans)),
-        ("output_type", string(format!("{:?}", op.output_type))),
+        ("output_type", string(format!("{:?}", op.output_type.unquantized()))),
     ];
     macro_rules! push {
         ($a: ident) => {
@@ -133,6 +133,10 @@ fn qmatmul_unary_dump(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option
     if let Some(bias) = &op.bias {
         named_args
             .push(("bias", (&*ast.konst_variable(format!("{}.bias", node.name), bias)?).clone()));
+    } else {
+        // the default bias would be loaded as a TDim, the accumulator is i32
+        let bias = ast.konst(format!("{}.bias", node.name), &rctensor0(0i32))?;
+        named_args.push(("bias", (*bias).clone()));
     }
 
     Ok(Some(invocation("tract_core_qmatmul", &[], &*named_args)))
@@ -191,8 +195,14 @@ fn qmatmul_load(
     let b_scale: Option<Value> = invocation.named_arg_as(builder, "b_scale").ok();
     let c0: Option<Value> = invocation.named_arg_as(builder, "c0").ok();
     let c_scale: Option<Value> = invocation.named_arg_as(builder, "c_scale").ok();
-    let output_type =
+    let mut output_type =
         DatumType::from_str(&*invocation.named_arg_as::<String>(builder, "output_type")?)?;
+    // quantization parameters of the output, if any, come from the quantization file
+    if let Some(Some(dt)) = invocation.dt_from_quant_file.get(0) {
+        if dt.unquantized() == output_type {
+            output_type = dt.clone();
+        }
+    }
     let mut inputs = vec![a, b, bias];
     let params = values_to_qparams(a0, a_scale, b0, b_scale, c0, c_scale, &mut inputs, builder)?;
     builder.wire(QMatMul { a_trans, b_trans, c_trans, output_type, params }, &inputs)
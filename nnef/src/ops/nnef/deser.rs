@@ -19,7 +19,7 @@ pub fn external(
 ) -> TractResult<TVec<OutletId>> {
     let type_name = invocation.invocation.generic_type_name.unwrap_or(TypeName::Scalar);
     let dt = if let Some(Some(dt)) = invocation.dt_from_quant_file.get(0) {
-        *dt
+        dt.clone()
     } else if type_name == TypeName::Scalar {
         f32::datum_type()
     } else if type_name == TypeName::Logical {
@@ -63,7 +63,7 @@ pub fn variable(
                 *dt
             );
             //FIXME: avoid cast by late-loading tensors ?
-            tensor = tensor.cast_to_dt(*dt)?.into_owned().into_arc_tensor()
+            tensor = tensor.cast_to_dt(dt.clone())?.into_owned().into_arc_tensor()
         }
     }
     if tensor.shape() != &*shape {
@@ -128,7 +128,7 @@ pub fn concat(
     if let Some(Some(dt)) = invocation.dt_from_quant_file.get(0) {
         for value in &mut values {
             if builder.model.node(value.node).outputs[value.slot].fact.datum_type != *dt {
-                *value = builder.wire(ops::cast::cast(*dt), &[*value])?[0];
+                *value = builder.wire(ops::cast::cast(dt.clone()), &[*value])?[0];
             }
         }
     }
@@ -499,7 +499,7 @@ pub fn reduce(
     if let Ok(c) = cardinality.to_isize() {
         if fact.datum_type.is_float() {
             let cardinality = tensor0((c as f64).recip())
-                .cast_to_dt(fact.datum_type)?
+                .cast_to_dt(fact.datum_type.clone())?
                 .into_owned()
                 .broadcast_into_rank(input_shape.rank())?;
             return builder.wire(ops::math::mul::unary(cardinality.into_arc_tensor()), &wire);
@@ -524,7 +524,7 @@ pub fn matmul(
             //FIXME: bias is not specified in the nnef format, but whether the bias is a bias or an add later changes the quantized behaviour
             let bias = builder.model.add_const(
                 format!("{}.bias", invocation.invocation.id),
-                Tensor::zero_dt(DatumType::QI8(qparams), &[1])?,
+                Tensor::zero_dt(DatumType::QI8(qparams.clone()), &[1])?,
             )?;
             builder.model.node(a.node);
 
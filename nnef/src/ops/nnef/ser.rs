@@ -9,12 +9,9 @@ pub fn source(
     _node: &TypedNode,
     op: &ops::source::TypedSource,
 ) -> TractResult<Option<Arc<RValue>>> {
-    if op.fact.datum_type == DatumType::F32 {
-        Ok(Some(invocation(
-            "external",
-            &[],
-            &[("shape", ints(&*op.fact.shape.as_concrete().unwrap()))],
-        )))
+    if let (DatumType::F32, Some(shape)) = (op.fact.datum_type.clone(), op.fact.shape.as_concrete())
+    {
+        Ok(Some(invocation("external", &[], &[("shape", ints(shape))])))
     } else {
         Ok(None)
     }
@@ -81,7 +78,7 @@ pub fn pad(
 ) -> TractResult<Option<Arc<RValue>>> {
     use ops::array::PadMode;
     let wire = ast.mapping[&node.inputs[0]].clone();
-    let dt = ast.model.outlet_fact(node.inputs[0])?.datum_type;
+    let dt = ast.model.outlet_fact(node.inputs[0])?.datum_type.clone();
     let padding = array(&op.pads.iter().map(|pair| ints(&[pair.0, pair.1])).collect::<TVec<_>>());
     let mut params = tvec!(("padding", padding));
     let border = match &op.mode {
@@ -258,6 +255,12 @@ pub fn conv(
     if op.kernel_fmt == ops::cnn::KernelFormat::HWIO {
         let geo_rank = op.kernel.rank() - 2;
         kernel = kernel.move_axis(geo_rank, 0)?.move_axis(geo_rank + 1, 0)?;
+        if let Some(qp) = kernel.datum_type().qparams().filter(|qp| qp.is_per_axis()) {
+            // per output channel parameters follow O to the front
+            let (zero_points, scales) = qp.zp_scale_vecs();
+            let dt = kernel.datum_type().with_qparams(QParams::per_axis(0, &zero_points, &scales));
+            unsafe { kernel.set_datum_type(dt) };
+        }
     }
     conv_or_deconv(ast, node, &op.pool_spec, kernel, &op.bias, op.group, false, None)
 }
@@ -161,10 +161,10 @@ impl Registry {
             let outlet = builder
                 .wire(tract_core::ops::element_wise::ElementWiseOp(ew.1.clone()), &[input])?;
             if let Some(Some(assumed_out_dt)) = dt.get(0) {
-                let out_dt = builder.model.outlet_fact(outlet[0])?.datum_type;
+                let out_dt = builder.model.outlet_fact(outlet[0])?.datum_type.clone();
                 if out_dt != *assumed_out_dt {
                     return Ok(Some(Value::Wire(
-                        builder.wire(tract_core::ops::cast::cast(*assumed_out_dt), &outlet)?[0],
+                        builder.wire(tract_core::ops::cast::cast(assumed_out_dt.clone()), &outlet)?[0],
                     )));
                 }
             }
@@ -182,8 +182,8 @@ impl Registry {
                 invocation.arguments[0].rvalue.resolve(builder, &[])?.to::<OutletId>(builder)?;
             let mut b =
                 invocation.arguments[1].rvalue.resolve(builder, &[])?.to::<OutletId>(builder)?;
-            let a_dt = builder.model.outlet_fact(a)?.datum_type;
-            let b_dt = builder.model.outlet_fact(b)?.datum_type;
+            let a_dt = builder.model.outlet_fact(a)?.datum_type.clone();
+            let b_dt = builder.model.outlet_fact(b)?.datum_type.clone();
 
             // mitigation of nnef "scalar" type mismatch with tract-core more
             // strict types
@@ -191,7 +191,7 @@ impl Registry {
                 if builder.model.node(a.node).op_is::<tract_core::ops::konst::Const>() {
                     a = builder.wire(tract_core::ops::cast::cast(b_dt), &[a])?[0];
                 } else {
-                    b = builder.wire(tract_core::ops::cast::cast(a_dt), &[b])?[0];
+                    b = builder.wire(tract_core::ops::cast::cast(a_dt.clone()), &[b])?[0];
                 };
             }
             let inputs = multicast(builder, &[a, b])?;
@@ -199,7 +199,7 @@ impl Registry {
                 builder.wire(tract_core::ops::binary::TypedBinOp(bin.1.clone()), &inputs)?[0];
             if let Some(Some(out_dt)) = dt.get(0) {
                 if out_dt != &a_dt {
-                    wire = builder.wire(tract_core::ops::cast::cast(*out_dt), &[wire])?[0];
+                    wire = builder.wire(tract_core::ops::cast::cast(out_dt.clone()), &[wire])?[0];
                 }
             }
             return Ok(Some(Value::Wire(wire)));
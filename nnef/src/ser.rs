@@ -163,14 +163,15 @@ impl<'a> IntoAst<'a> {
             .model
             .properties
             .iter()
-            .map(|(k, v)| Ok(tuple_2(string(k), self.konst(k, v)?.as_ref().clone())))
+            .sorted_by_key(|(k, _)| k.to_string())
+            .map(|(k, v)| Ok(tuple_2(string(k), self.property(k, v)?)))
             .collect::<TractResult<Vec<_>>>()?;
-        properties.push(tuple_2(
-            string("tract_nnef_format_version".to_string()),
-            self.konst("tract_nnef_format_version", &rctensor0("alpha1".to_string()))?
-                .as_ref()
-                .clone(),
-        ));
+        properties.push(tuple_2(string("tract_nnef_format_version"), string("alpha1")));
+        let assertions = self.model.symbol_scope.assertions();
+        if assertions.len() > 0 {
+            let assertions = assertions.iter().map(|a| string(a.to_string())).collect::<Vec<_>>();
+            properties.push(tuple_2(string("tract_assert"), array(assertions)));
+        }
         let properties: Assignment = assignment("properties", Arc::new(array(properties)));
         let IntoAst { prefix, mut fragments, body, tensors, parameters, results, .. } = self;
         let mut id = prefix
@@ -234,14 +235,7 @@ impl<'a> IntoAst<'a> {
                 };
 
                 for (outlet, name) in node.outputs.iter().zip(names.iter()) {
-                    if let Some(params) = outlet.fact.datum_type.qparams() {
-                        let quant_format = QuantFormat::Linear {
-                            params,
-                            bits: outlet.fact.datum_type.size_of() as i8,
-                            signed: outlet.fact.datum_type.is_signed(),
-                        };
-                        self.quantization.insert(name.to_string(), quant_format);
-                    }
+                    self.record_quantization(name, outlet.fact.datum_type.clone());
                 }
 
                 let mut outputs = tvec!();
@@ -310,13 +304,28 @@ impl<'a> IntoAst<'a> {
         self.do_konst(name, tensor, true)
     }
 
+    fn record_quantization(&mut self, name: &str, dt: DatumType) {
+        if let Some(params) = dt.qparams() {
+            let quant_format = QuantFormat::Linear {
+                params,
+                bits: dt.size_of() as i8 * 8,
+                signed: dt.is_signed(),
+            };
+            self.quantization.insert(name.to_string(), quant_format);
+        }
+    }
+
     fn do_konst(
         &mut self,
         name: impl Into<String>,
         tensor: &Arc<Tensor>,
         force_variable: bool,
     ) -> TractResult<Arc<RValue>> {
-        if !force_variable && tensor.is_uniform() && tensor.len() > 0 {
+        if !force_variable
+            && tensor.is_uniform()
+            && tensor.len() > 0
+            && !tensor.datum_type().is_quantized()
+        {
             if tensor.datum_type() == String::datum_type() {
                 return Ok(string(tensor.to_scalar::<String>().unwrap()).into());
             } else if tensor.datum_type() == DatumType::F32 {
@@ -342,9 +351,42 @@ impl<'a> IntoAst<'a> {
             })
             .into(),
         );
+        self.record_quantization(&id, tensor.datum_type());
         Ok(ident(id).into())
     }
 
+    /// Strings, logicals, i32 and finite f32 properties are written inline as (nested array)
+    /// literals, the others as variables.
+    fn property(&mut self, name: &str, tensor: &Arc<Tensor>) -> TractResult<RValue> {
+        fn literal(view: tract_ndarray::ArrayViewD<RValue>) -> RValue {
+            if view.ndim() == 0 {
+                view.iter().next().unwrap().clone()
+            } else {
+                array(view.outer_iter().map(literal).collect::<Vec<_>>())
+            }
+        }
+        let dt = tensor.datum_type();
+        let items: Option<Vec<RValue>> = if tensor.len() == 0 {
+            None
+        } else if dt == String::datum_type() {
+            Some(tensor.as_slice::<String>()?.iter().map(string).collect())
+        } else if dt == bool::datum_type() {
+            Some(tensor.as_slice::<bool>()?.iter().map(|b| logical(*b)).collect())
+        } else if dt == i32::datum_type() {
+            Some(tensor.as_slice::<i32>()?.iter().map(numeric).collect())
+        } else if dt == f32::datum_type() && tensor.as_slice::<f32>()?.iter().all(|f| f.is_finite())
+        {
+            Some(tensor.as_slice::<f32>()?.iter().map(numeric).collect())
+        } else {
+            None
+        };
+        if let Some(items) = items {
+            Ok(literal(tract_ndarray::ArrayViewD::from_shape(tensor.shape(), &items)?))
+        } else {
+            Ok(self.konst_variable(format!("properties.{}", name), tensor)?.as_ref().clone())
+        }
+    }
+
     fn assignment(&mut self, name: impl Into<String>, right: Arc<RValue>) {
         let name = name.into();
         if &*right == &ident(&name) {
@@ -362,6 +404,16 @@ pub fn ints(shape: &[usize]) -> RValue {
     RValue::Array(shape.iter().map(|s| RValue::Literal(Literal::Numeric(s.to_string()))).collect())
 }
 
+/// Integers for known dimensions, strings for symbolic ones.
+pub fn tdims(shape: &[TDim]) -> RValue {
+    RValue::Array(
+        shape
+            .iter()
+            .map(|d| if let Ok(d) = d.to_i64() { numeric(d) } else { string(d.to_string()) })
+            .collect(),
+    )
+}
+
 pub fn string(s: impl Into<String>) -> RValue {
     RValue::Literal(Literal::String(s.into()))
 }
@@ -410,3 +462,130 @@ pub fn invocation(id: &str, positional: &[Arc<RValue>], named: &[(&str, RValue)]
         .collect();
     RValue::Invocation(Invocation { id: id.to_owned(), generic_type_name: None, arguments }).into()
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::framework::Nnef;
+    use tract_core::prelude::Framework;
+
+    #[test]
+    fn properties_round_trip() -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let source = model.add_source("input", TypedFact::dt_shape(f32::datum_type(), &[2]))?;
+        model.set_output_outlets(&[source])?;
+        let properties = vec![
+            ("version", rctensor0("1.2.0".to_string())),
+            ("labels", rctensor1(&["yes".to_string(), "no".to_string()])),
+            ("normalization.mean", rctensor1(&[0.485f32, 0.456, 0.406])),
+            ("normalization.std", rctensor2(&[[1f32, 1.], [1., 1.]])),
+            ("sample_rate", rctensor0(16000i32)),
+            ("streaming", rctensor0(true)),
+            ("frames", rctensor1(&[3u64, 4])),
+            ("neg_inf", rctensor0(f32::NEG_INFINITY)),
+        ];
+        model.properties = properties.iter().map(|(k, v)| (k.to_string(), v.clone())).collect();
+        let nnef = Nnef::new().with_tract_core();
+        let mut buffer = vec![];
+        nnef.write_to_tar(&model, &mut buffer)?;
+        let reloaded = nnef.model_for_read(&mut &*buffer)?;
+        for (k, v) in properties {
+            assert_eq!(&reloaded.properties[k], &v);
+        }
+        assert_eq!(
+            *reloaded.properties["tract_nnef_format_version"],
+            tensor0("alpha1".to_string())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn symbol_names_round_trip() -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let batch = model.symbol_scope.sym("batch_size");
+        let seq = model.symbol_scope.sym("sequence_length");
+        let shape = [TDim::from(&batch), TDim::from(&seq) * 2 + 1, 3.into()];
+        let source = model.add_source("input", TypedFact::dt_shape(f32::datum_type(), &shape))?;
+        model.set_output_outlets(&[source])?;
+        let nnef = Nnef::new().with_tract_core();
+        let mut buffer = vec![];
+        nnef.write_to_tar(&model, &mut buffer)?;
+        let reloaded = nnef.model_for_read(&mut &*buffer)?;
+        let fact = reloaded.input_fact(0)?;
+        assert_eq!(format!("{:?}", fact.shape), format!("{:?}", model.input_fact(0)?.shape));
+        let names =
+            reloaded.symbol_scope.symbols().iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(names, vec!["batch_size", "sequence_length"]);
+        Ok(())
+    }
+
+    #[test]
+    fn assertions_round_trip() -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let scope = model.symbol_scope.clone();
+        let shape = [TDim::from(scope.sym("n")), scope.sym("m").into()];
+        for assertion in &["n>=m", "(m)%4==0"] {
+            scope.add_assertion(scope.parse_assertion(assertion)?)?;
+        }
+        let source = model.add_source("input", TypedFact::dt_shape(f32::datum_type(), &shape))?;
+        model.set_output_outlets(&[source])?;
+        let nnef = Nnef::new().with_tract_core();
+        let mut buffer = vec![];
+        nnef.write_to_tar(&model, &mut buffer)?;
+        let reloaded = nnef.model_for_read(&mut &*buffer)?;
+        assert!(!reloaded.properties.contains_key("tract_assert"));
+        let assertions =
+            reloaded.symbol_scope.assertions().iter().map(|a| a.to_string()).collect::<Vec<_>>();
+        assert_eq!(assertions, vec!["n>=m", "(m)%4==0"]);
+        let m = TDim::from(reloaded.symbol_scope.sym("m"));
+        assert_eq!(m.clone().div_ceil(4), m / 4);
+        Ok(())
+    }
+
+    #[test]
+    fn quantized_model_round_trip() -> TractResult<()> {
+        use tract_core::ops::cnn::KernelFormat;
+        use tract_core::quantize::*;
+        // HWIO, so the per output channel parameters of the kernel have to move
+        let model = fixture::conv_relu_matmul(KernelFormat::HWIO)?;
+        let input = fixture::sample(0);
+        let ranges = calibrate(&model, &[input.clone()], CalibrationMethod::MinMax)?;
+        let quantized = quantize(&model, &ranges, &QuantizationConfig::default())?;
+
+        let nnef = Nnef::new().with_tract_core();
+        let mut buffer = vec![];
+        nnef.write_to_tar(&quantized, &mut buffer)?;
+        let reloaded = nnef.model_for_read(&mut &*buffer)?;
+        let expected = quantized.into_runnable()?.run(input.clone())?;
+        let found = reloaded.into_runnable()?.run(input)?;
+        expected[0].close_enough(&found[0], true)
+    }
+
+    #[test]
+    fn block_quant_model_round_trip() -> TractResult<()> {
+        use tract_core::tract_linalg::frame::BlockQuantFormat;
+        let mut model = TypedModel::default();
+        let source = model.add_source("input", TypedFact::dt_shape(f32::datum_type(), &[8, 5]))?;
+        let a: Vec<f32> = (0..24).map(|i| ((i * 7) % 11) as f32 / 11.0 - 0.5).collect();
+        let a = Tensor::from_shape(&[3, 8], &a)?;
+        let mm =
+            tract_core::ops::matmul::MatMulUnary::new(a.into_arc_tensor(), false, false, false);
+        let wire = model.wire_node("matmul", mm, &[source])?;
+        model.set_output_outlets(&wire)?;
+        let quantized = tract_core::quantize::quantize_weights(&model, BlockQuantFormat::Q4, 4)?;
+
+        let nnef = Nnef::new().with_tract_core();
+        let mut buffer = vec![];
+        nnef.write_to_tar(&quantized, &mut buffer)?;
+        let reloaded = nnef.model_for_read(&mut &*buffer)?;
+        assert!(reloaded
+            .nodes()
+            .iter()
+            .any(|n| n.op_is::<tract_core::ops::matmul::BlockQuantMatMulUnary>()));
+        let input: Vec<f32> = (0..40).map(|i| ((i * 13) % 17) as f32 / 17.0 * 2.0 - 1.0).collect();
+        let input = tvec!(Tensor::from_shape(&[8, 5], &input)?);
+        let expected = quantized.into_runnable()?.run(input.clone())?;
+        let found = reloaded.into_optimized()?.into_runnable()?.run(input)?;
+        expected[0].close_enough(&found[0], true)
+    }
+}
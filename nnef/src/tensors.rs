@@ -1,7 +1,11 @@
 use byteorder::{ReadBytesExt, WriteBytesExt, LE};
 use tract_core::internal::*;
+use tract_core::tract_linalg::frame::BlockQuantWeights;
 
 const TRACT_ITEM_TYPE_VENDOR: u16 = (b'T' as u16) << 8u16 | b'R' as u16;
+/// Block quantized m x k weights, as serialized by BlockQuantWeights::to_bytes. They are
+/// loaded as a scalar Blob.
+const TRACT_ITEM_TYPE_BLOCK_QUANT: u16 = 0x2000;
 
 #[repr(C)]
 #[derive(Debug)]
@@ -19,7 +23,7 @@ struct Header {
     padding: [u32; 11],
 }
 
-pub fn read_tensor<R: std::io::Read>(mut reader: R) -> TractResult<Tensor> {
+fn read_header<R: std::io::Read>(reader: &mut R) -> TractResult<(DatumType, TVec<usize>, usize)> {
     unsafe {
         let mut header: Header = std::mem::zeroed();
         let buffer: &mut [u8; 128] = std::mem::transmute(&mut header);
@@ -36,7 +40,10 @@ pub fn read_tensor<R: std::io::Read>(mut reader: R) -> TractResult<Tensor> {
         let shape: TVec<usize> =
             header.dims[0..header.rank as usize].iter().map(|d| *d as _).collect();
         let len = shape.iter().product::<usize>();
+        let block_quant = header.item_type_vendor == TRACT_ITEM_TYPE_VENDOR
+            && header.item_type == TRACT_ITEM_TYPE_BLOCK_QUANT;
         if header.bits_per_item != 0xFFFFFFFF
+            && !block_quant
             && len * (header.bits_per_item as usize / 8) != header.data_size_bytes as usize
         {
             bail!(
@@ -62,12 +69,22 @@ pub fn read_tensor<R: std::io::Read>(mut reader: R) -> TractResult<Tensor> {
             (0, 4, 32) => DatumType::I32,
             (0, 4, 64) => DatumType::I64,
             (TRACT_ITEM_TYPE_VENDOR, 0x1000, 0xFFFF) => DatumType::String,
+            (TRACT_ITEM_TYPE_VENDOR, TRACT_ITEM_TYPE_BLOCK_QUANT, 4 | 8) => {
+                return Ok((DatumType::Blob, tvec!(), header.data_size_bytes as usize))
+            }
             _ => bail!(
                 "Unsupported type in tensor type:{} bits_per_item:{}",
                 header.item_type,
                 header.bits_per_item
             ),
         };
+        Ok((dt, shape, header.data_size_bytes as usize))
+    }
+}
+
+pub fn read_tensor<R: std::io::Read>(mut reader: R) -> TractResult<Tensor> {
+    let (dt, shape, data_size) = read_header(&mut reader)?;
+    unsafe {
         if dt.is_copy() {
             let mut tensor = Tensor::uninitialized_dt(dt, &shape)?;
             reader.read_exact(tensor.as_bytes_mut())?;
@@ -82,12 +99,31 @@ pub fn read_tensor<R: std::io::Read>(mut reader: R) -> TractResult<Tensor> {
                 *item = String::from_utf8(bytes)?;
             }
             Ok(tensor)
+        } else if dt == DatumType::Blob {
+            let mut bytes = vec![0u8; data_size];
+            reader.read_exact(&mut bytes)?;
+            Ok(tensor0(Blob(bytes)))
         } else {
             todo!()
         }
     }
 }
 
+/// Read a tensor stored at `offset` in `storage`, borrowing its data instead of copying it
+/// whenever possible.
+pub fn read_tensor_from_shared_bytes<B: AsRef<[u8]> + Send + Sync + 'static>(
+    storage: Arc<B>,
+    offset: usize,
+) -> TractResult<Tensor> {
+    let bytes = (*storage).as_ref().get(offset..).context("Tensor offset beyond end of data")?;
+    let (dt, shape, _) = read_header(&mut &*bytes)?;
+    let data_offset = offset + std::mem::size_of::<Header>();
+    if !dt.is_copy() || (bytes.as_ptr() as usize + data_offset - offset) % dt.alignment() != 0 {
+        return read_tensor(bytes);
+    }
+    Ok(Tensor::from_shared_bytes(dt, &shape, storage, data_offset)?)
+}
+
 pub fn write_tensor<W: std::io::Write>(w: &mut W, tensor: &Tensor) -> TractResult<()> {
     unsafe {
         let mut header: Header = std::mem::zeroed();
@@ -97,6 +133,24 @@ pub fn write_tensor<W: std::io::Write>(w: &mut W, tensor: &Tensor) -> TractResul
         if tensor.rank() > 8 {
             bail!("Only rank up to 8 are supported");
         }
+        if tensor.datum_type() == DatumType::Blob {
+            let blob = tensor.to_scalar::<Blob>()?;
+            if !BlockQuantWeights::is_block_quant(blob) {
+                bail!("Only block quantized weights can be serialized as blobs");
+            }
+            let weights = BlockQuantWeights::from_bytes(blob)?;
+            header.rank = 2;
+            header.dims[0] = weights.m as u32;
+            header.dims[1] = weights.k as u32;
+            header.data_size_bytes = blob.len() as u32;
+            header.bits_per_item = weights.format.bits() as u32;
+            header.item_type = TRACT_ITEM_TYPE_BLOCK_QUANT;
+            header.item_type_vendor = TRACT_ITEM_TYPE_VENDOR;
+            let header_buf: &[u8; 128] = std::mem::transmute(&header);
+            w.write_all(&*header_buf)?;
+            w.write_all(blob)?;
+            return Ok(());
+        }
         header.rank = tensor.rank() as u32;
         for d in 0..tensor.rank() {
             header.dims[d] = tensor.shape()[d] as u32;
@@ -138,4 +192,11 @@ mod test {
     fn header_is_128_bytes() {
         assert_eq!(std::mem::size_of::<Header>(), 128);
     }
+
+    #[test]
+    fn only_block_quant_blobs_are_written() {
+        let mut buffer = vec![];
+        let blob = tensor0(Blob(vec![4, 0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0, 0, 0]));
+        assert!(write_tensor(&mut buffer, &blob).is_err());
+    }
 }
@@ -6,6 +6,7 @@ use std::collections::HashMap;
 use tract_hir::internal::*;
 
 use crate::pb;
+use crate::tensor::translate_inference_fact;
 use prost::Message;
 
 pub fn optional_inputs(pb: &pb::NodeProto) -> impl Iterator<Item = Option<usize>> + '_ {
@@ -38,6 +39,8 @@ pub struct ParsingContext<'a> {
     pub framework: &'a Onnx,
     pub model: &'a pb::ModelProto,
     pub parent_graphs: Vec<&'a pb::GraphProto>,
+    /// Symbols of the model and its subgraphs, named after the ONNX `dim_param`s.
+    pub symbol_scope: SymbolScope,
 }
 
 #[derive(Clone, Debug)]
@@ -51,7 +54,8 @@ impl<'a> ParsingContext<'a> {
     pub fn parse_graph(&self, graph: &pb::GraphProto) -> TractResult<ParseResult> {
         let mut ctx = self.clone();
         ctx.parent_graphs.push(graph);
-        let mut model = InferenceModel::default();
+        let mut model =
+            InferenceModel { symbol_scope: self.symbol_scope.clone(), ..InferenceModel::default() };
         let mut unresolved_inputs = vec![];
         let mut closures_to_wire = vec![];
         let mut initializers: HashMap<&str, Tensor> = graph
@@ -72,7 +76,7 @@ impl<'a> ParsingContext<'a> {
                 let fact = input.r#type.as_ref().unwrap().value.as_ref().unwrap();
                 #[allow(irrefutable_let_patterns)]
                 let fact: InferenceFact = if let pb::type_proto::Value::TensorType(fact) = fact {
-                    fact.try_into()?
+                    translate_inference_fact(Some(&self.symbol_scope), fact)?
                 } else {
                     bail!("Can not parse tensor type");
                 };
@@ -154,7 +158,9 @@ impl<'a> ParsingContext<'a> {
         for output in graph.output.iter() {
             let fact = if let Some(fact) = output.r#type.as_ref().and_then(|t| t.value.as_ref()) {
                 let pb::type_proto::Value::TensorType(fact) = fact;
-                fact.try_into()?
+                // exporters name output dimensions freely, they do not have to match the
+                // expressions computed from the inputs
+                translate_inference_fact(None, fact)?
             } else {
                 InferenceFact::default()
             };
@@ -216,11 +222,20 @@ impl Onnx {
                   operator set 9, 10, 11 and 12 only. Your model may still work so this is not a hard fail.",
                   onnx_operator_set_version);
         }
+        let symbol_scope = SymbolScope::default();
+        for prop in proto.metadata_props.iter().filter(|prop| prop.key == "tract_assert") {
+            for assertion in prop.value.split(';').filter(|a| a.trim().len() > 0) {
+                symbol_scope
+                    .add_assertion(symbol_scope.parse_assertion(assertion)?)
+                    .with_context(|| format!("Asserting {}", assertion))?;
+            }
+        }
         let ctx = ParsingContext {
             framework: self,
             model: proto,
             parent_graphs: vec![],
             onnx_operator_set_version,
+            symbol_scope,
         };
         ctx.parse_graph(graph)
     }
@@ -250,3 +265,101 @@ impl Framework<pb::ModelProto, InferenceModel> for Onnx {
         Ok(model)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pb::tensor_shape_proto::dimension::Value::*;
+
+    fn value_info(
+        name: &str,
+        dims: &[pb::tensor_shape_proto::dimension::Value],
+    ) -> pb::ValueInfoProto {
+        let dim = dims
+            .iter()
+            .map(|d| pb::tensor_shape_proto::Dimension {
+                value: Some(d.clone()),
+                ..Default::default()
+            })
+            .collect();
+        let tensor = pb::type_proto::Tensor {
+            elem_type: pb::tensor_proto::DataType::Float as i32,
+            shape: Some(pb::TensorShapeProto { dim }),
+        };
+        pb::ValueInfoProto {
+            name: name.to_string(),
+            r#type: Some(pb::TypeProto {
+                value: Some(pb::type_proto::Value::TensorType(tensor)),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn output_dim_param_is_not_binding() -> TractResult<()> {
+        let graph = pb::GraphProto {
+            node: vec![pb::NodeProto {
+                input: vec!["x".to_string()],
+                output: vec!["y".to_string()],
+                name: "relu".to_string(),
+                op_type: "Relu".to_string(),
+                ..Default::default()
+            }],
+            input: vec![value_info("x", &[DimParam("N".to_string()), DimValue(3)])],
+            output: vec![value_info("y", &[DimParam("unk__12".to_string()), DimValue(3)])],
+            ..Default::default()
+        };
+        let proto = pb::ModelProto {
+            opset_import: vec![pb::OperatorSetIdProto { domain: "".to_string(), version: 13 }],
+            graph: Some(graph),
+            ..Default::default()
+        };
+        let model = crate::onnx().model_for_proto_model(&proto)?.into_typed()?;
+        let n = model.symbol_scope.sym("N");
+        assert_eq!(model.output_fact(0)?.shape.to_tvec(), tvec!(n.to_dim(), 3.to_dim()));
+        Ok(())
+    }
+
+    #[test]
+    fn slice1_with_symbolic_dim() -> TractResult<()> {
+        let ints = |name: &str, ints: &[i64]| pb::AttributeProto {
+            name: name.to_string(),
+            r#type: pb::attribute_proto::AttributeType::Ints as i32,
+            ints: ints.to_vec(),
+            ..Default::default()
+        };
+        let graph = pb::GraphProto {
+            node: vec![pb::NodeProto {
+                input: vec!["x".to_string()],
+                output: vec!["y".to_string()],
+                name: "slice".to_string(),
+                op_type: "Slice".to_string(),
+                attribute: vec![
+                    ints("axes", &[0]),
+                    ints("starts", &[1]),
+                    ints("ends", &[i64::MAX]),
+                ],
+                ..Default::default()
+            }],
+            input: vec![value_info("x", &[DimParam("N".to_string()), DimValue(3)])],
+            output: vec![value_info("y", &[DimParam("M".to_string()), DimValue(3)])],
+            ..Default::default()
+        };
+        let proto = pb::ModelProto {
+            opset_import: vec![pb::OperatorSetIdProto { domain: "".to_string(), version: 9 }],
+            graph: Some(graph),
+            ..Default::default()
+        };
+        let model = crate::onnx().model_for_proto_model(&proto)?.into_typed()?;
+        let n = model.symbol_scope.sym("N");
+        // the start is clamped to N, the end stays N
+        let expected = n.to_dim() - n.to_dim().mini(1.into());
+        assert_eq!(model.output_fact(0)?.shape.to_tvec(), tvec!(expected, 3.to_dim()));
+        let model = model.concretize_dims(&SymbolValues::default().with(n, 3))?;
+        let input = tensor2(&[[0f32, 1., 2.], [3., 4., 5.], [6., 7., 8.]]);
+        let output = model.into_runnable()?.run(tvec!(input))?.remove(0);
+        assert_eq!(*output, tensor2(&[[3f32, 4., 5.], [6., 7., 8.]]));
+        Ok(())
+    }
+}
@@ -33,6 +33,26 @@ pub struct Slice1 {
 
 impl_dyn_hash!(Slice1);
 
+impl Slice1 {
+    /// Begin and end of the slice along an axis of length `dim`, negative values counting from
+    /// the end, clamped to `0..=dim`. Exporters use the extreme integers for "up to the end"
+    /// or "from the start": they are taken as such even when `dim` is symbolic.
+    fn bounds(b: i64, e: i64, dim: &TDim) -> (TDim, TDim) {
+        let clamp = |x: i64| {
+            if x >= i32::MAX as i64 {
+                dim.clone()
+            } else if x <= i32::MIN as i64 {
+                0.into()
+            } else if x < 0 {
+                (dim.clone() + x).maxi(0.into())
+            } else {
+                x.to_dim().mini(dim.clone())
+            }
+        };
+        (clamp(b), clamp(e))
+    }
+}
+
 impl Expansion for Slice1 {
     fn name(&self) -> Cow<str> {
         "Slice1".into()
@@ -66,17 +86,8 @@ impl Expansion for Slice1 {
                 } else {
                     Some((self.starts[axis].into(), self.ends[axis].into()))
                 };
-                if let Some((mut b, mut e)) = spec {
-                    if let Ok(d) = d.to_i64() {
-                        if b > d {
-                            b = d.into();
-                        }
-                        if e > d {
-                            e = d.into();
-                        }
-                    }
-                    let b = if b < 0 { d.bex() + TDim::from(b) } else { TDim::from(b).bex() };
-                    let e = if e < 0 { d.bex() + TDim::from(e) } else { TDim::from(e).bex() };
+                if let Some((b, e)) = spec {
+                    let (b, e) = Self::bounds(b, e, d);
                     s.equals(&outputs[0].shape[axis], e - b)
                 } else {
                     s.equals(&outputs[0].shape[axis], &shape[axis])
@@ -97,18 +108,13 @@ impl Expansion for Slice1 {
         for (ix, (&b, &e)) in self.starts.iter().zip(self.ends.iter()).enumerate() {
             let axis = self.axes.as_ref().map(|axes| axes[ix]).unwrap_or(ix);
             let dim = &input.shape[axis];
-            if let Ok(dim) = dim.to_i64() {
-                let b = (if b >= 0 { b.min(dim) } else { dim + b }) as usize;
-                let e = (if e >= 0 { e.min(dim) } else { dim + e }) as usize;
-                if b > 0 || e < dim as usize {
-                    wire = target.wire_node(
-                        format!("{}.axis-{}", prefix, axis),
-                        tract_hir::ops::array::Slice::new(axis, b, e),
-                        [wire].as_ref(),
-                    )?[0];
-                }
-            } else {
-                bail!("Can't translate slice: axis={} dim={} b={} e={}", axis, dim, b, e)
+            let (start, end) = Self::bounds(b, e, dim);
+            if start != 0.to_dim() || &end != dim {
+                wire = target.wire_node(
+                    format!("{}.axis-{}", prefix, axis),
+                    tract_hir::ops::array::Slice::new(axis, start, end),
+                    [wire].as_ref(),
+                )?[0];
             }
         }
         target.rename_node(wire.node, &*prefix)?;
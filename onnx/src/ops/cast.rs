@@ -23,8 +23,12 @@ impl ElementWiseMiniOp for Cast {
         "onnx.Cast".into()
     }
 
+    fn same_as(&self, other: &dyn ElementWiseMiniOp) -> bool {
+        other.downcast_ref::<Cast>().map(|other| other.to == self.to).unwrap_or(false)
+    }
+
     fn output_type(&self, _input_type: DatumType) -> Option<DatumType> {
-        Some(self.to)
+        Some(self.to.clone())
     }
 
     fn eval_out_of_place(&self, t: &Tensor) -> TractResult<Tensor> {
@@ -43,7 +47,7 @@ impl ElementWiseMiniOp for Cast {
                 Ok(output)
             }
         } else {
-            t.cast_to_dt(self.to).map(|t| t.into_owned())
+            t.cast_to_dt(self.to.clone()).map(|t| t.into_owned())
         }
     }
 
@@ -52,7 +56,7 @@ impl ElementWiseMiniOp for Cast {
         model: &TypedModel,
         node: &TypedNode,
     ) -> TractResult<Option<TypedModelPatch>> {
-        let from = model.outlet_fact(node.inputs[0])?.datum_type;
+        let from = model.outlet_fact(node.inputs[0])?.datum_type.clone();
         if from == self.to {
             Ok(Some(TypedModelPatch::shunt_one_op(model, node)?))
         } else if from == String::datum_type() && self.to == f32::datum_type() {
@@ -62,7 +66,7 @@ impl ElementWiseMiniOp for Cast {
                 model,
                 node,
                 &node.inputs,
-                tract_hir::ops::cast::cast(self.to),
+                tract_hir::ops::cast::cast(self.to.clone()),
             )?))
         }
     }
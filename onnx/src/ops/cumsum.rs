@@ -49,7 +49,7 @@ impl Expansion for CumSum {
             scan::InputMapping::Scan { slot: 0, axis, chunk },
             scan::InputMapping::State {
                 initializer: scan::StateInitializer::Value(
-                    Tensor::zero_dt(data.datum_type, var_shape)?.into_arc_tensor(),
+                    Tensor::zero_dt(data.datum_type.clone(), var_shape)?.into_arc_tensor(),
                 ),
             },
         ];
@@ -72,7 +72,7 @@ impl Expansion for CumSum {
             },
         ];
         let mut body = TypedModel::default();
-        let var_fact = TypedFact::dt_shape(data.datum_type, var_shape);
+        let var_fact = TypedFact::dt_shape(data.datum_type.clone(), var_shape);
         let a = body.add_source("scan_input", var_fact.clone())?;
         let b = body.add_source("acc_input", var_fact)?;
         let sum = body.wire_node("add", tract_core::ops::math::add::bin_typed(), &[a, b])?[0];
@@ -65,13 +65,13 @@ impl Expansion for MatMulInteger {
         let a0 = if let Some(o) = self.optional_a_zero_point_input {
             (o + 1).into()
         } else {
-            let a_dt = target.outlet_fact(inputs[0])?.datum_type;
+            let a_dt = target.outlet_fact(inputs[0])?.datum_type.clone();
             Tensor::zero_scalar_dt(a_dt)?.into()
         };
         let b0 = if let Some(o) = self.optional_b_zero_point_input {
             (o + 1).into()
         } else {
-            let b_dt = target.outlet_fact(inputs[1])?.datum_type;
+            let b_dt = target.outlet_fact(inputs[1])?.datum_type.clone();
             Tensor::zero_scalar_dt(b_dt)?.into()
         };
         let params = MatMulQParams {
@@ -145,7 +145,7 @@ impl Expansion for QLinearMatMul {
             false,
             false,
             false,
-            target.outlet_fact(inputs[7])?.datum_type,
+            target.outlet_fact(inputs[7])?.datum_type.clone(),
             tract_core::ops::matmul::MatMulQParams::all_dynamic(3),
         );
         let a_and_b =
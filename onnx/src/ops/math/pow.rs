@@ -52,8 +52,8 @@ impl Expansion for Pow {
         inputs: &[OutletId],
     ) -> TractResult<TVec<OutletId>> {
         use DatumType::*;
-        let dta = model.outlet_fact(inputs[0])?.datum_type;
-        let dtb = model.outlet_fact(inputs[1])?.datum_type;
+        let dta = model.outlet_fact(inputs[0])?.datum_type.clone();
+        let dtb = model.outlet_fact(inputs[1])?.datum_type.clone();
         let mut wires = tract_hir::ops::binary::wire_rank_broadcast(name, model, inputs)?;
         if dta.is_integer() != dtb.is_integer() {
             wires = tract_hir::ops::binary::wire_cast(name, model, &wires, F64)?;
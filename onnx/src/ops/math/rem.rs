@@ -33,7 +33,8 @@ impl Expansion for RemInt {
         outputs: &'p [TensorProxy],
     ) -> InferenceResult {
         tract_hir::ops::binary::rules(s, inputs, outputs, move |a, b| {
-            a.common_super_type(b).with_context(|| format!("No super type for {:?} and {:?}", a, b))
+            a.common_super_type(b.clone())
+                .with_context(|| format!("No super type for {:?} and {:?}", a, b))
         })
     }
 
@@ -44,13 +45,13 @@ impl Expansion for RemInt {
         inputs: &[OutletId],
     ) -> TractResult<TVec<OutletId>> {
         let zero = tract_hir::ops::activations::broadcast_scalar(0.0, model, inputs)?;
-        let a = model.outlet_fact(inputs[0])?.datum_type;
-        let b = model.outlet_fact(inputs[1])?.datum_type;
+        let a = model.outlet_fact(inputs[0])?.datum_type.clone();
+        let b = model.outlet_fact(inputs[1])?.datum_type.clone();
         let dt = a
-            .common_super_type(b)
+            .common_super_type(b.clone())
             .with_context(|| format!("No super type for {:?} and {:?}", a, b))?;
         let wires = tract_hir::ops::binary::wire_rank_broadcast(name, model, inputs)?;
-        let wires = tract_hir::ops::binary::wire_cast(name, model, &wires, dt)?;
+        let wires = tract_hir::ops::binary::wire_cast(name, model, &wires, dt.clone())?;
         if dt.is_unsigned() {
             return model.wire_node(name, tract_hir::ops::math::rem::bin_typed(), &wires);
         }
@@ -339,7 +339,7 @@ impl Expansion for Prelu {
                 &[b],
             )?[0];
         }
-        let mut zero = tensor0(0.0).cast_to_dt(model.outlet_fact(a)?.datum_type)?.into_owned();
+        let mut zero = tensor0(0.0).cast_to_dt(model.outlet_fact(a)?.datum_type.clone())?.into_owned();
         while zero.rank() < rank {
             zero.insert_axis(0)?;
         }
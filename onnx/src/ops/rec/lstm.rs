@@ -304,7 +304,7 @@ impl LSTM {
             .add_source(
                 "h_source",
                 TypedFact::dt_shape(
-                    x_fact.datum_type,
+                    x_fact.datum_type.clone(),
                     &[1.to_dim(), b_size.clone(), h_size.clone()],
                 ),
             )?
@@ -26,36 +26,31 @@ impl TryFrom<DataType> for DatumType {
     }
 }
 
-impl<'a> TryFrom<&'a type_proto::Tensor> for InferenceFact {
-    type Error = TractError;
-    fn try_from(t: &'a type_proto::Tensor) -> TractResult<InferenceFact> {
-        let mut fact = InferenceFact::default();
-        fact = fact.with_datum_type(DataType::from_i32(t.elem_type).unwrap().try_into()?);
-        if let Some(shape) = &t.shape {
-            let shape: TVec<DimFact> = shape
-                .dim
-                .iter()
-                .map(|d| {
-                    let mut fact = DimFact::default();
-                    if let Some(tensor_shape_proto::dimension::Value::DimValue(v)) = d.value {
-                        if v > 0 {
-                            fact = DimFact::from(v.to_dim())
-                        }
-                    }
-                    fact
-                })
-                .collect();
-            fact = fact.with_shape(ShapeFactoid::closed(shape));
-        }
-        Ok(fact)
-    }
-}
-
-impl TryFrom<type_proto::Tensor> for InferenceFact {
-    type Error = TractError;
-    fn try_from(t: type_proto::Tensor) -> TractResult<InferenceFact> {
-        (&t).try_into()
+/// Translate an ONNX tensor type. With a `scope`, named dimensions (`dim_param`) become the
+/// symbols of the scope with the same names, else they are left unknown.
+pub fn translate_inference_fact(
+    scope: Option<&SymbolScope>,
+    t: &type_proto::Tensor,
+) -> TractResult<InferenceFact> {
+    let mut fact = InferenceFact::default();
+    fact = fact.with_datum_type(DataType::from_i32(t.elem_type).unwrap().try_into()?);
+    if let Some(shape) = &t.shape {
+        let shape: TVec<DimFact> = shape
+            .dim
+            .iter()
+            .map(|d| match &d.value {
+                Some(tensor_shape_proto::dimension::Value::DimValue(v)) if *v > 0 => {
+                    DimFact::from(v.to_dim())
+                }
+                Some(tensor_shape_proto::dimension::Value::DimParam(p)) if p.len() > 0 => {
+                    scope.map(|s| DimFact::from(TDim::from(s.sym(p)))).unwrap_or_default()
+                }
+                _ => DimFact::default(),
+            })
+            .collect();
+        fact = fact.with_shape(ShapeFactoid::closed(shape));
     }
+    Ok(fact)
 }
 
 impl<'a> TryFrom<&'a TensorProto> for Tensor {
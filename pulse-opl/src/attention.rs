@@ -0,0 +1,150 @@
+use tract_nnef::internal::*;
+use tract_nnef::tract_core::ops::nn::CausalAttention;
+
+pub fn register(registry: &mut Registry) {
+    registry.register_dumper(TypeId::of::<PulsedCausalAttention>(), ser_attention);
+    registry.register_primitive(
+        "tract_pulse_causal_attention",
+        &[
+            TypeName::Scalar.tensor().named("q"),
+            TypeName::Scalar.tensor().named("k"),
+            TypeName::Scalar.tensor().named("v"),
+            TypeName::Integer.named("left_context"),
+            TypeName::Scalar.named("scale"),
+            TypeName::Integer.named("delay"),
+        ],
+        de_attention,
+    );
+}
+
+fn ser_attention(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<PulsedCausalAttention>().unwrap();
+    let inputs = node.inputs.iter().map(|i| ast.mapping[i].clone()).collect::<TVec<_>>();
+    Ok(Some(invocation(
+        "tract_pulse_causal_attention",
+        &inputs,
+        &[
+            ("left_context", numeric(op.attention.left_context)),
+            ("scale", numeric(op.attention.scale)),
+            ("delay", numeric(op.delay)),
+        ],
+    )))
+}
+
+fn de_attention(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let q = invocation.named_arg_as(builder, "q")?;
+    let k = invocation.named_arg_as(builder, "k")?;
+    let v = invocation.named_arg_as(builder, "v")?;
+    let left_context = invocation.named_arg_as::<i64>(builder, "left_context")? as usize;
+    let scale = invocation.named_arg_as(builder, "scale")?;
+    let delay = invocation.named_arg_as::<i64>(builder, "delay")? as usize;
+    let attention = CausalAttention { left_context, scale };
+    builder.wire(PulsedCausalAttention { attention, delay }, &[q, k, v])
+}
+
+/// CausalAttention on pulses along the time axis (rank - 2) of Q, K and V.
+///
+/// The state keeps the last `left_context` keys and values so each pulse of queries can
+/// attend to them. Inputs are valid from frame `delay` on.
+#[derive(Debug, Clone, Hash)]
+pub struct PulsedCausalAttention {
+    pub attention: CausalAttention,
+    pub delay: usize,
+}
+
+impl_dyn_hash!(PulsedCausalAttention);
+
+impl Op for PulsedCausalAttention {
+    fn name(&self) -> Cow<str> {
+        "PulsedCausalAttention".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        let mut info = self.attention.info()?;
+        info.push(format!("delay: {}", self.delay));
+        Ok(info)
+    }
+
+    op_pulse!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for PulsedCausalAttention {
+    fn is_stateless(&self) -> bool {
+        false
+    }
+
+    fn state(
+        &self,
+        _session: &mut SessionState,
+        _node_id: usize,
+    ) -> TractResult<Option<Box<dyn OpState>>> {
+        Ok(Some(Box::new(KvCacheState::default())))
+    }
+}
+
+impl TypedOp for PulsedCausalAttention {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        self.attention.output_facts(inputs)
+    }
+
+    as_op!();
+}
+
+/// Left-context key/value cache of a PulsedCausalAttention.
+#[derive(Debug, Clone, Default)]
+pub struct KvCacheState {
+    keys: Option<Tensor>,
+    values: Option<Tensor>,
+    position: usize,
+}
+
+impl KvCacheState {
+    fn extend(cache: &mut Option<Tensor>, input: &Tensor, context: usize) -> TractResult<Tensor> {
+        let axis = input.rank() - 2;
+        if cache.is_none() {
+            let mut shape: TVec<usize> = input.shape().into();
+            shape[axis] = context;
+            *cache = Some(Tensor::zero_dt(input.datum_type(), &shape)?);
+        }
+        let full = Tensor::stack_tensors(axis, &[cache.as_ref().unwrap(), input])?;
+        *cache = Some(full.slice(axis, full.shape()[axis] - context, full.shape()[axis])?);
+        Ok(full)
+    }
+}
+
+impl OpState for KvCacheState {
+    fn eval(
+        &mut self,
+        _session: &mut SessionState,
+        op: &dyn Op,
+        mut inputs: TVec<Arc<Tensor>>,
+    ) -> TractResult<TVec<Arc<Tensor>>> {
+        let op = op.downcast_ref::<PulsedCausalAttention>().context("Wrong op")?;
+        let (q, k, v) = args_3!(inputs);
+        let context = op.attention.left_context;
+        let keys = Self::extend(&mut self.keys, &k, context)?;
+        let values = Self::extend(&mut self.values, &v, context)?;
+        // the full keys start at frame position - context, valid keys at frame delay
+        let first_key = (op.delay + context).saturating_sub(self.position);
+        self.position += q.shape()[q.rank() - 2];
+        Ok(tvec!(op.attention.attend(&q, &keys, &values, first_key)?.into_arc_tensor()))
+    }
+
+    fn save(&self) -> TractResult<TVec<Tensor>> {
+        let mut tensors = tvec!(tensor0(self.position as i64));
+        tensors.extend(self.keys.iter().chain(self.values.iter()).cloned());
+        Ok(tensors)
+    }
+
+    fn restore(&mut self, tensors: TVec<Tensor>) -> TractResult<()> {
+        ensure!(tensors.len() == 1 || tensors.len() == 3, "Expected position, keys and values");
+        self.position = *tensors[0].to_scalar::<i64>()? as usize;
+        self.keys = tensors.get(1).cloned();
+        self.values = tensors.get(2).cloned();
+        Ok(())
+    }
+}
@@ -2,14 +2,67 @@ use std::ops::Range;
 use tract_core::ndarray::*;
 use tract_nnef::internal::*;
 
+pub fn register(registry: &mut Registry) {
+    registry.register_dumper(TypeId::of::<PulsedSameAxisConcat>(), ser_concat);
+    registry.register_primitive(
+        "tract_pulse_same_axis_concat",
+        &[
+            TypeName::Scalar.tensor().named("input"),
+            TypeName::Integer.named("axis"),
+            TypeName::Scalar.tensor().named("pre_slice"),
+            TypeName::Scalar.tensor().named("post_slice"),
+            TypeName::Integer.named("input_delay"),
+            TypeName::String.named("input_len"),
+        ],
+        de_concat,
+    );
+}
+
+fn ser_concat(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<PulsedSameAxisConcat>().unwrap();
+    let wire = ast.mapping[&node.inputs[0]].clone();
+    let pre =
+        ast.konst_variable(format!("{}.pre", node.name), &op.pre_slice.clone().into_arc_tensor())?;
+    let post = ast
+        .konst_variable(format!("{}.post", node.name), &op.post_slice.clone().into_arc_tensor())?;
+    Ok(Some(invocation(
+        "tract_pulse_same_axis_concat",
+        &[wire],
+        &[
+            ("axis", numeric(op.axis)),
+            ("pre_slice", pre.as_ref().clone()),
+            ("post_slice", post.as_ref().clone()),
+            ("input_delay", numeric(op.input_delay)),
+            ("input_len", string(op.input_len.to_string())),
+        ],
+    )))
+}
+
+fn de_concat(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let wire = invocation.named_arg_as(builder, "input")?;
+    let pre: Arc<Tensor> = invocation.named_arg_as(builder, "pre_slice")?;
+    let post: Arc<Tensor> = invocation.named_arg_as(builder, "post_slice")?;
+    let op = PulsedSameAxisConcat {
+        axis: invocation.named_arg_as::<i64>(builder, "axis")? as usize,
+        pre_slice: pre.into_tensor(),
+        post_slice: post.into_tensor(),
+        input_delay: invocation.named_arg_as::<i64>(builder, "input_delay")? as usize,
+        input_len: invocation.named_arg_as(builder, "input_len")?,
+    };
+    builder.wire(op, &[wire])
+}
+
 /// Concat with pulse along concat axis
 #[derive(Debug, Clone, Hash)]
 pub struct PulsedSameAxisConcat {
-    axis: usize,
-    pre_slice: Tensor,
-    post_slice: Tensor,
-    input_delay: usize,
-    input_len: TDim,
+    pub axis: usize,
+    pub pre_slice: Tensor,
+    pub post_slice: Tensor,
+    pub input_delay: usize,
+    pub input_len: TDim,
 }
 impl_dyn_hash!(PulsedSameAxisConcat);
 
@@ -24,7 +77,7 @@ impl Op for PulsedSameAxisConcat {
 
 impl EvalOp for PulsedSameAxisConcat {
     fn is_stateless(&self) -> bool {
-        true
+        false
     }
 
     fn state(
@@ -89,6 +142,16 @@ impl OpState for PulsedSameAxisConcatState {
 
         return Ok(tvec!(data.into_arc_tensor()));
     }
+
+    fn save(&self) -> TractResult<TVec<Tensor>> {
+        Ok(tvec!(tensor0(self.current_pos as i64)))
+    }
+
+    fn restore(&mut self, tensors: TVec<Tensor>) -> TractResult<()> {
+        ensure!(tensors.len() == 1, "Expected concat position");
+        self.current_pos = *tensors[0].to_scalar::<i64>()? as usize;
+        Ok(())
+    }
 }
 
 unsafe fn overwrite_part_of_pulse<T: Datum>(
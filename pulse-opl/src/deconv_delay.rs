@@ -4,6 +4,59 @@ use tract_ndarray::Axis;
 use tract_nnef::internal::*;
 use tract_num_traits::Zero;
 
+pub fn register(registry: &mut Registry) {
+    registry.register_dumper(TypeId::of::<DeconvDelay>(), ser_deconv_delay);
+    registry.register_primitive(
+        "tract_pulse_deconv_delay",
+        &[
+            TypeName::Scalar.tensor().named("input"),
+            TypeName::Integer.named("axis"),
+            TypeName::Integer.named("overlap"),
+            TypeName::Integer.named("delay"),
+            TypeName::Integer.named("stride"),
+            TypeName::Integer.named("pulse"),
+            TypeName::String.named("deconv_input_dim"),
+            TypeName::String.named("deconv_output_dim"),
+        ],
+        de_deconv_delay,
+    );
+}
+
+fn ser_deconv_delay(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<DeconvDelay>().unwrap();
+    let wire = ast.mapping[&node.inputs[0]].clone();
+    Ok(Some(invocation(
+        "tract_pulse_deconv_delay",
+        &[wire],
+        &[
+            ("axis", numeric(op.axis)),
+            ("overlap", numeric(op.overlap)),
+            ("delay", numeric(op.delay)),
+            ("stride", numeric(op.stride)),
+            ("pulse", numeric(op.pulse)),
+            ("deconv_input_dim", string(op.deconv_input_dim.to_string())),
+            ("deconv_output_dim", string(op.deconv_output_dim.to_string())),
+        ],
+    )))
+}
+
+fn de_deconv_delay(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let wire = invocation.named_arg_as(builder, "input")?;
+    let op = DeconvDelay {
+        axis: invocation.named_arg_as::<i64>(builder, "axis")? as usize,
+        overlap: invocation.named_arg_as::<i64>(builder, "overlap")? as usize,
+        delay: invocation.named_arg_as::<i64>(builder, "delay")? as usize,
+        stride: invocation.named_arg_as::<i64>(builder, "stride")? as usize,
+        pulse: invocation.named_arg_as::<i64>(builder, "pulse")? as usize,
+        deconv_input_dim: invocation.named_arg_as(builder, "deconv_input_dim")?,
+        deconv_output_dim: invocation.named_arg_as(builder, "deconv_output_dim")?,
+    };
+    builder.wire(op, &[wire])
+}
+
 #[derive(Debug, Clone, PartialEq, Hash)]
 pub struct DeconvDelay {
     pub axis: usize,
@@ -79,6 +132,19 @@ impl OpState for DeconvDelayState {
         let output = input.slice(op.axis, 0, input.shape()[op.axis] - op.overlap)?;
         Ok(tvec!(output.into_arc_tensor()))
     }
+
+    fn save(&self) -> TractResult<TVec<Tensor>> {
+        let mut tensors = tvec!(tensor0(self.valid_inputed as i64));
+        tensors.extend(self.buffer.iter().cloned());
+        Ok(tensors)
+    }
+
+    fn restore(&mut self, tensors: TVec<Tensor>) -> TractResult<()> {
+        ensure!(tensors.len() == 1 || tensors.len() == 2, "Expected position and buffer");
+        self.valid_inputed = *tensors[0].to_scalar::<i64>()? as isize;
+        self.buffer = tensors.get(1).cloned();
+        Ok(())
+    }
 }
 
 impl DeconvDelayState {
@@ -1,6 +1,7 @@
 use tract_nnef::internal::*;
 
 pub fn register(registry: &mut Registry) {
+    registry.register_dumper(TypeId::of::<Delay>(), ser_delay);
     registry.register_primitive(
         "tract_pulse_delay",
         &[
@@ -13,6 +14,20 @@ pub fn register(registry: &mut Registry) {
     );
 }
 
+fn ser_delay(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<Delay>().unwrap();
+    let wire = ast.mapping[&node.inputs[0]].clone();
+    Ok(Some(invocation(
+        "tract_pulse_delay",
+        &[wire],
+        &[
+            ("axis", numeric(op.axis)),
+            ("delay", numeric(op.delay)),
+            ("overlap", numeric(op.overlap)),
+        ],
+    )))
+}
+
 fn de_delay(
     builder: &mut ModelBuilder,
     invocation: &ResolvedInvocation,
@@ -90,6 +105,16 @@ impl OpState for DelayState {
             Ok(tvec!(output))
         }
     }
+
+    fn save(&self) -> TractResult<TVec<Tensor>> {
+        Ok(self.buffer.iter().cloned().collect())
+    }
+
+    fn restore(&mut self, mut tensors: TVec<Tensor>) -> TractResult<()> {
+        ensure!(tensors.len() <= 1, "Expected at most one delay buffer");
+        self.buffer = tensors.pop();
+        Ok(())
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Hash)]
@@ -112,7 +137,7 @@ impl Delay {
     ) -> Delay {
         let mut buffer_shape: TVec<TDim> = input_fact.shape.iter().map(|d| d.clone()).collect();
         buffer_shape[axis] = (delay + overlap).to_dim();
-        Delay { datum_type: input_fact.datum_type, buffer_shape, axis, delay, overlap }
+        Delay { datum_type: input_fact.datum_type.clone(), buffer_shape, axis, delay, overlap }
     }
 }
 
@@ -157,6 +182,6 @@ impl TypedOp for Delay {
     }
 
     fn cost(&self, _inputs: &[&TypedFact]) -> TractResult<TVec<(Cost, TDim)>> {
-        Ok(tvec!((Cost::Buffer(self.datum_type), self.buffer_shape.iter().product())))
+        Ok(tvec!((Cost::Buffer(self.datum_type.clone()), self.buffer_shape.iter().product())))
     }
 }
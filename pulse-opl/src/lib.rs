@@ -3,10 +3,13 @@ use tract_nnef::internal::*;
 #[macro_use]
 mod macros;
 
+mod attention;
 mod concat;
 mod deconv_delay;
 mod delay;
+pub mod metadata;
 mod pad;
+mod running_reduce;
 mod slice;
 
 pub use tract_nnef;
@@ -18,9 +21,12 @@ pub mod prelude {
 }
 
 pub mod ops {
+    pub use super::attention::{KvCacheState, PulsedCausalAttention};
+    pub use super::concat::{PulsedSameAxisConcat, PulsedSameAxisConcatState};
     pub use super::deconv_delay::DeconvDelay;
     pub use super::delay::{ Delay, DelayState };
     pub use super::pad::PulsePad;
+    pub use super::running_reduce::PulsedRunningReduce;
     pub use super::slice::PulsedAxisSlice;
 }
 
@@ -38,6 +44,12 @@ impl WithPulse for tract_nnef::framework::Nnef {
 
 pub fn tract_nnef_registry() -> Registry {
     let mut reg = Registry::new("pulse");
+    attention::register(&mut reg);
+    concat::register(&mut reg);
+    deconv_delay::register(&mut reg);
     delay::register(&mut reg);
+    pad::register(&mut reg);
+    running_reduce::register(&mut reg);
+    slice::register(&mut reg);
     reg
 }
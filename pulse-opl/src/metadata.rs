@@ -0,0 +1,67 @@
+//! Streaming description of a pulsed model, kept in the properties of its typed form so that
+//! it survives NNEF serialization.
+use tract_nnef::internal::*;
+
+/// How an input or output of a pulsed model streams.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StreamInfo {
+    /// The streaming axis.
+    pub axis: usize,
+    /// Number of frames before the first valid one.
+    pub delay: usize,
+    /// Length of the whole stream.
+    pub dim: TDim,
+}
+
+fn write(model: &mut TypedModel, prefix: &str, infos: &[StreamInfo]) {
+    let axes = infos.iter().map(|i| i.axis as i64).collect::<Vec<_>>();
+    let delays = infos.iter().map(|i| i.delay as i64).collect::<Vec<_>>();
+    let dims = infos.iter().map(|i| i.dim.to_string()).collect::<Vec<_>>();
+    let properties = &mut model.properties;
+    properties.insert(format!("pulse.{}_axes", prefix), rctensor1(&axes));
+    properties.insert(format!("pulse.{}_delays", prefix), rctensor1(&delays));
+    properties.insert(format!("pulse.{}_dims", prefix), rctensor1(&dims));
+}
+
+fn read(model: &TypedModel, prefix: &str, count: usize) -> TractResult<TVec<StreamInfo>> {
+    let property = |name: &str| {
+        let name = format!("pulse.{}_{}", prefix, name);
+        model.properties.get(&name).with_context(|| format!("Missing property {}", name))
+    };
+    let axes = property("axes")?.cast_to::<i64>()?;
+    let delays = property("delays")?.cast_to::<i64>()?;
+    let dims = property("dims")?;
+    let (axes, delays, dims) =
+        (axes.as_slice::<i64>()?, delays.as_slice::<i64>()?, dims.as_slice::<String>()?);
+    ensure!(
+        axes.len() == count && delays.len() == count && dims.len() == count,
+        "Expected pulse metadata for {} {}s",
+        count,
+        prefix
+    );
+    (0..count)
+        .map(|ix| {
+            Ok(StreamInfo {
+                axis: axes[ix] as usize,
+                delay: delays[ix] as usize,
+                dim: model.symbol_scope.parse_tdim(&dims[ix])?,
+            })
+        })
+        .collect()
+}
+
+/// Record how the inputs and outputs of `model` stream.
+pub fn set_stream_info(model: &mut TypedModel, inputs: &[StreamInfo], outputs: &[StreamInfo]) {
+    write(model, "input", inputs);
+    write(model, "output", outputs);
+    let delays = outputs.iter().map(|o| o.delay as i64).collect::<Vec<_>>();
+    model.properties.insert("pulse.delay".to_string(), rctensor1(&delays));
+}
+
+/// How the inputs and outputs of a model produced by `PulsedModel::into_typed` stream.
+pub fn stream_info(model: &TypedModel) -> TractResult<(TVec<StreamInfo>, TVec<StreamInfo>)> {
+    Ok((
+        read(model, "input", model.input_outlets()?.len())?,
+        read(model, "output", model.output_outlets()?.len())?,
+    ))
+}
@@ -2,6 +2,77 @@ use tract_core::ndarray::*;
 use tract_core::ops::array::PadMode;
 use tract_nnef::internal::*;
 
+pub fn register(registry: &mut Registry) {
+    registry.register_dumper(TypeId::of::<PulsePad>(), ser_pulse_pad);
+    registry.register_primitive(
+        "tract_pulse_pad",
+        &[
+            TypeName::Scalar.tensor().named("input"),
+            TypeName::Integer.named("axis"),
+            TypeName::Integer.named("pulse"),
+            TypeName::Integer.named("before"),
+            TypeName::String.named("after"),
+            TypeName::Integer.named("begin_input"),
+            TypeName::String.named("end_input"),
+            TypeName::String.named("border"),
+            TypeName::Scalar.tensor().named("value").default(0.0),
+        ],
+        de_pulse_pad,
+    );
+}
+
+fn ser_pulse_pad(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<PulsePad>().unwrap();
+    let wire = ast.mapping[&node.inputs[0]].clone();
+    let mut params = vec![
+        ("axis", numeric(op.axis)),
+        ("pulse", numeric(op.pulse)),
+        ("before", numeric(op.before)),
+        ("after", string(op.after.to_string())),
+        ("begin_input", numeric(op.begin_input)),
+        ("end_input", string(op.end_input.to_string())),
+    ];
+    let border = match &op.mode {
+        PadMode::Constant(c) => {
+            let value = ast.konst_variable(format!("{}.value", node.name), c)?;
+            params.push(("value", value.as_ref().clone()));
+            "constant"
+        }
+        PadMode::Reflect => "reflect",
+        PadMode::Edge => "replicated",
+    };
+    params.push(("border", string(border)));
+    Ok(Some(invocation("tract_pulse_pad", &[wire], &params)))
+}
+
+fn de_pulse_pad(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let wire = invocation.named_arg_as(builder, "input")?;
+    let dt = builder.model.outlet_fact(wire)?.datum_type.clone();
+    let border: String = invocation.named_arg_as(builder, "border")?;
+    let mode = match &*border {
+        "constant" => {
+            let value: Arc<Tensor> = invocation.named_arg_as(builder, "value")?;
+            PadMode::Constant(value.cast_to_dt(dt)?.into_owned().into_arc_tensor())
+        }
+        "replicated" => PadMode::Edge,
+        "reflect" => PadMode::Reflect,
+        _ => bail!("unsupported padding mode {}", border),
+    };
+    let op = PulsePad {
+        axis: invocation.named_arg_as::<i64>(builder, "axis")? as usize,
+        pulse: invocation.named_arg_as::<i64>(builder, "pulse")? as usize,
+        before: invocation.named_arg_as::<i64>(builder, "before")? as usize,
+        after: invocation.named_arg_as(builder, "after")?,
+        begin_input: invocation.named_arg_as::<i64>(builder, "begin_input")? as usize,
+        end_input: invocation.named_arg_as(builder, "end_input")?,
+        mode,
+    };
+    builder.wire(op, &[wire])
+}
+
 #[derive(Debug, Clone, Default, Hash)]
 struct PulsePadOpState {
     current_pos: usize,
@@ -20,6 +91,19 @@ impl OpState for PulsePadOpState {
         let tensor = self.pad(session, op, input)?;
         Ok(tvec!(tensor.into_arc_tensor()))
     }
+
+    fn save(&self) -> TractResult<TVec<Tensor>> {
+        let mut tensors = tvec!(tensor0(self.current_pos as i64));
+        tensors.extend(self.last_valid_frame.iter().cloned());
+        Ok(tensors)
+    }
+
+    fn restore(&mut self, tensors: TVec<Tensor>) -> TractResult<()> {
+        ensure!(tensors.len() == 1 || tensors.len() == 2, "Expected position and frame");
+        self.current_pos = *tensors[0].to_scalar::<i64>()? as usize;
+        self.last_valid_frame = tensors.get(1).cloned();
+        Ok(())
+    }
 }
 
 impl PulsePadOpState {
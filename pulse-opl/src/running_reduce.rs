@@ -0,0 +1,147 @@
+use tract_nnef::internal::*;
+use tract_nnef::tract_core::ops::nn::{RunningReduce, RunningReducer};
+
+pub fn register(registry: &mut Registry) {
+    registry.register_dumper(TypeId::of::<PulsedRunningReduce>(), ser_running_reduce);
+    registry.register_primitive(
+        "tract_pulse_running_reduce",
+        &[
+            TypeName::Scalar.tensor().named("input"),
+            TypeName::Integer.named("axis"),
+            TypeName::String.named("reducer"),
+            TypeName::Integer.named("delay"),
+        ],
+        de_running_reduce,
+    );
+}
+
+fn ser_running_reduce(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<PulsedRunningReduce>().unwrap();
+    let wire = ast.mapping[&node.inputs[0]].clone();
+    let reducer = match op.reduce.reducer {
+        RunningReducer::Sum => "sum",
+        RunningReducer::Mean => "mean",
+        RunningReducer::Max => "max",
+        RunningReducer::Min => "min",
+    };
+    Ok(Some(invocation(
+        "tract_pulse_running_reduce",
+        &[wire],
+        &[
+            ("axis", numeric(op.reduce.axis)),
+            ("reducer", string(reducer)),
+            ("delay", numeric(op.delay)),
+        ],
+    )))
+}
+
+fn de_running_reduce(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let wire = invocation.named_arg_as(builder, "input")?;
+    let axis = invocation.named_arg_as::<i64>(builder, "axis")? as usize;
+    let reducer = match &*invocation.named_arg_as::<String>(builder, "reducer")? {
+        "sum" => RunningReducer::Sum,
+        "mean" => RunningReducer::Mean,
+        "max" => RunningReducer::Max,
+        "min" => RunningReducer::Min,
+        other => bail!("Unknown running reducer {}", other),
+    };
+    let delay = invocation.named_arg_as::<i64>(builder, "delay")? as usize;
+    builder.wire(PulsedRunningReduce { reduce: RunningReduce { axis, reducer }, delay }, &[wire])
+}
+
+/// RunningReduce on pulses along its axis. Inputs are valid from frame `delay` on.
+#[derive(Debug, Clone, Hash)]
+pub struct PulsedRunningReduce {
+    pub reduce: RunningReduce,
+    pub delay: usize,
+}
+
+impl_dyn_hash!(PulsedRunningReduce);
+
+impl Op for PulsedRunningReduce {
+    fn name(&self) -> Cow<str> {
+        format!("Pulsed{}", self.reduce.name()).into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("axis: {} delay: {}", self.reduce.axis, self.delay)])
+    }
+
+    op_pulse!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for PulsedRunningReduce {
+    fn is_stateless(&self) -> bool {
+        false
+    }
+
+    fn state(
+        &self,
+        _session: &mut SessionState,
+        _node_id: usize,
+    ) -> TractResult<Option<Box<dyn OpState>>> {
+        Ok(Some(Box::new(RunningReduceState::default())))
+    }
+}
+
+impl TypedOp for PulsedRunningReduce {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        self.reduce.output_facts(inputs)
+    }
+
+    as_op!();
+}
+
+#[derive(Debug, Clone, Default)]
+struct RunningReduceState {
+    acc: Option<Tensor>,
+    seen: usize,
+    position: usize,
+}
+
+impl OpState for RunningReduceState {
+    fn eval(
+        &mut self,
+        _session: &mut SessionState,
+        op: &dyn Op,
+        mut inputs: TVec<Arc<Tensor>>,
+    ) -> TractResult<TVec<Arc<Tensor>>> {
+        let op = op.downcast_ref::<PulsedRunningReduce>().context("Wrong op")?;
+        let axis = op.reduce.axis;
+        let mut data = args_1!(inputs).into_tensor();
+        if self.acc.is_none() {
+            let mut shape: TVec<usize> = data.shape().into();
+            shape[axis] = 1;
+            self.acc = Some(Tensor::zero_dt(data.datum_type(), &shape)?);
+        }
+        let pulse = data.shape()[axis];
+        let skip = op.delay.saturating_sub(self.position).min(pulse);
+        self.seen = op.reduce.reducer.accumulate(
+            axis,
+            &mut data,
+            self.acc.as_mut().unwrap(),
+            self.seen,
+            skip,
+        )?;
+        self.position += pulse;
+        Ok(tvec!(data.into_arc_tensor()))
+    }
+
+    fn save(&self) -> TractResult<TVec<Tensor>> {
+        let mut tensors = tvec!(tensor0(self.seen as i64), tensor0(self.position as i64));
+        tensors.extend(self.acc.iter().cloned());
+        Ok(tensors)
+    }
+
+    fn restore(&mut self, tensors: TVec<Tensor>) -> TractResult<()> {
+        ensure!(tensors.len() == 2 || tensors.len() == 3, "Expected counters and accumulator");
+        self.seen = *tensors[0].to_scalar::<i64>()? as usize;
+        self.position = *tensors[1].to_scalar::<i64>()? as usize;
+        self.acc = tensors.get(2).cloned();
+        Ok(())
+    }
+}
@@ -1,5 +1,44 @@
 use tract_nnef::internal::*;
 
+pub fn register(registry: &mut Registry) {
+    registry.register_dumper(TypeId::of::<PulsedAxisSlice>(), ser_slice);
+    registry.register_primitive(
+        "tract_pulse_axis_slice",
+        &[
+            TypeName::Scalar.tensor().named("input"),
+            TypeName::Integer.named("axis"),
+            TypeName::Integer.named("skip"),
+            TypeName::String.named("take"),
+        ],
+        de_slice,
+    );
+}
+
+fn ser_slice(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<PulsedAxisSlice>().unwrap();
+    let wire = ast.mapping[&node.inputs[0]].clone();
+    Ok(Some(invocation(
+        "tract_pulse_axis_slice",
+        &[wire],
+        &[
+            ("axis", numeric(op.axis)),
+            ("skip", numeric(op.skip)),
+            ("take", string(op.take.to_string())),
+        ],
+    )))
+}
+
+fn de_slice(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let wire = invocation.named_arg_as(builder, "input")?;
+    let axis = invocation.named_arg_as::<i64>(builder, "axis")? as usize;
+    let skip = invocation.named_arg_as::<i64>(builder, "skip")? as usize;
+    let take = invocation.named_arg_as(builder, "take")?;
+    builder.wire(PulsedAxisSlice { axis, skip, take }, &[wire])
+}
+
 #[derive(Debug, Clone, Default, Hash)]
 pub struct PulsedAxisSlice {
     pub axis: usize,
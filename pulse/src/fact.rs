@@ -1,28 +1,32 @@
 use crate::internal::*;
 
-lazy_static::lazy_static! {
-    static ref S: Symbol = Symbol::new('S');
+/// The default streaming symbol: `S` in the model symbol scope.
+pub fn stream_symbol(scope: &SymbolScope) -> Symbol {
+    scope.sym("S")
 }
 
-pub fn stream_symbol() -> Symbol {
-    *S
-}
-
-pub fn stream_dim() -> TDim {
-    (*S).into()
+pub fn stream_dim(scope: &SymbolScope) -> TDim {
+    stream_symbol(scope).into()
 }
 
 pub trait StreamFact {
+    /// The axis streaming over the default symbol `S`, and its full length.
     fn stream_info(&self) -> Option<(usize, &TDim)>;
+
+    /// The only axis whose length depends on `symbol`, and its full length. Other symbolic
+    /// dimensions are not streaming.
+    fn stream_info_for(&self, symbol: &Symbol) -> Option<(usize, &TDim)>;
 }
 
 impl StreamFact for ShapeFact {
     fn stream_info(&self) -> Option<(usize, &TDim)> {
-        let streaming_dims: TVec<(usize, &TDim)> = (&**self)
-            .iter()
-            .enumerate()
-            .filter(|(_ix, d)| d.symbols().contains(&stream_symbol()))
-            .collect();
+        let symbol = default_stream_symbol(self)?;
+        self.stream_info_for(&symbol)
+    }
+
+    fn stream_info_for(&self, symbol: &Symbol) -> Option<(usize, &TDim)> {
+        let streaming_dims: TVec<(usize, &TDim)> =
+            (&**self).iter().enumerate().filter(|(_ix, d)| d.symbols().contains(symbol)).collect();
         if streaming_dims.len() != 1 {
             None
         } else {
@@ -31,6 +35,11 @@ impl StreamFact for ShapeFact {
     }
 }
 
+/// The symbol called `S` in the scope of a shape, if the shape uses it.
+fn default_stream_symbol(shape: &ShapeFact) -> Option<Symbol> {
+    shape.iter().flat_map(|d| d.symbols()).find(|s| s.name() == "S")
+}
+
 #[derive(Clone, PartialEq, Hash)]
 pub struct PulsedFact {
     pub datum_type: DatumType,
@@ -44,11 +53,20 @@ impl_dyn_hash!(PulsedFact);
 
 impl PulsedFact {
     pub fn from_tensor_fact_pulse(tf: &TypedFact, pulse: usize) -> TractResult<PulsedFact> {
-        let datum_type = tf.datum_type;
-        let (axis, len) = tf
-            .shape
-            .stream_info()
-            .ok_or_else(|| format_err!("Can not pulse a tensor with no streaming dim"))?;
+        let symbol = default_stream_symbol(&tf.shape)
+            .ok_or_else(|| format_err!("Can not pulse a tensor with no streaming dim over S"))?;
+        PulsedFact::from_tensor_fact_symbol_pulse(tf, &symbol, pulse)
+    }
+
+    pub fn from_tensor_fact_symbol_pulse(
+        tf: &TypedFact,
+        symbol: &Symbol,
+        pulse: usize,
+    ) -> TractResult<PulsedFact> {
+        let datum_type = tf.datum_type.clone();
+        let (axis, len) = tf.shape.stream_info_for(symbol).ok_or_else(|| {
+            format_err!("Can not pulse a tensor with no single streaming dim over {}", symbol)
+        })?;
         let mut shape: TVec<TDim> = tf.shape.iter().collect();
         shape[axis] = pulse.into();
         Ok(PulsedFact { datum_type, shape: shape.into(), axis, dim: len.clone(), delay: 0 })
@@ -59,7 +77,7 @@ impl PulsedFact {
     }
 
     pub fn to_pulse_fact(&self) -> TypedFact {
-        TypedFact::dt_shape(self.datum_type, self.shape.clone())
+        TypedFact::dt_shape(self.datum_type.clone(), self.shape.clone())
     }
 
     pub fn streaming_shape(&self) -> Vec<TDim> {
@@ -118,6 +136,6 @@ impl From<PulsedFact> for TypedFact {
 
 impl<'a> From<&'a PulsedFact> for TypedFact {
     fn from(fact: &'a PulsedFact) -> TypedFact {
-        TypedFact::dt_shape(fact.datum_type, fact.shape.clone())
+        TypedFact::dt_shape(fact.datum_type.clone(), fact.shape.clone())
     }
 }
@@ -4,6 +4,8 @@ pub mod macros;
 pub mod fact;
 pub mod model;
 pub mod ops;
+pub mod rate;
+pub mod stream;
 
 pub mod internal {
     pub use std::fmt;
@@ -15,6 +17,7 @@ pub mod internal {
     pub use crate::fact::{stream_dim, stream_symbol, PulsedFact};
     pub use crate::model::{PulsedModel, PulsedModelExt};
     pub use crate::ops::{OpPulsifier, PulsedOp};
+    pub use crate::stream::PulsedStream;
     pub use tract_pulse_opl::op_pulse;
 }
 
@@ -35,9 +38,7 @@ impl WithPulse for tract_nnef::framework::Nnef {
 }
 
 fn tract_nnef_registry() -> Registry {
-    let mut reg = tract_pulse_opl::tract_nnef_registry();
-    ops::delay::register(&mut reg);
-    reg
+    tract_pulse_opl::tract_nnef_registry()
 }
 
 #[cfg(test)]
@@ -57,7 +58,7 @@ mod tests {
                 "a",
                 TypedFact::dt_shape(
                     f32::datum_type(),
-                    [1.to_dim(), stream_dim(), 3.to_dim()].as_ref(),
+                    [1.to_dim(), stream_dim(&model.symbol_scope), 3.to_dim()].as_ref(),
                 ),
             )
             .unwrap();
@@ -77,7 +78,7 @@ mod tests {
                 "a",
                 TypedFact::dt_shape(
                     f32::datum_type(),
-                    [stream_dim(), 2.to_dim(), 3.to_dim()].as_ref(),
+                    [stream_dim(&model.symbol_scope), 2.to_dim(), 3.to_dim()].as_ref(),
                 ),
             )
             .unwrap();
@@ -94,4 +95,101 @@ mod tests {
             TypedFact::dt_shape(DatumType::F32, &[4, 2, 3])
         );
     }
+
+    #[test]
+    fn reduction_on_streaming_axis_is_named() {
+        let mut model = TypedModel::default();
+        let s = stream_dim(&model.symbol_scope);
+        let fact = TypedFact::dt_shape(f32::datum_type(), [s, 2.to_dim()].as_ref());
+        let a = model.add_source("a", fact).unwrap();
+        let reducer = tract_core::ops::nn::Reducer::Sum;
+        let sum = model.wire_node("sum", tract_core::ops::nn::Reduce::new(tvec!(0), reducer), &[a]);
+        model.set_output_outlets(&sum.unwrap()).unwrap();
+        let error = format!("{:?}", PulsedModel::new(&model, 4).unwrap_err());
+        assert!(error.contains("Can not pulsify") && error.contains("sum"), "{}", error);
+    }
+
+    #[test]
+    fn softmax_on_streaming_axis_hints_at_attention() {
+        use tract_core::ops::nn::{Reduce, Reducer};
+        let mut model = TypedModel::default();
+        let s = stream_dim(&model.symbol_scope);
+        let fact = TypedFact::dt_shape(f32::datum_type(), [s, 2.to_dim()].as_ref());
+        let a = model.add_source("a", fact).unwrap();
+        let max = model.wire_node("softmax.max", Reduce::new(tvec!(0), Reducer::Max), &[a]);
+        model.set_output_outlets(&max.unwrap()).unwrap();
+        let error = format!("{:?}", PulsedModel::new(&model, 4).unwrap_err());
+        assert!(error.contains("softmax.max") && error.contains("causal attention"), "{}", error);
+    }
+
+    fn run_pulses(model: TypedModel, input: &Tensor, pulse: usize) -> TractResult<Vec<Tensor>> {
+        let mut state = SimpleState::new(model.into_runnable()?)?;
+        (0..input.shape()[0] / pulse)
+            .map(|i| {
+                let chunk = input.slice(0, i * pulse, (i + 1) * pulse)?;
+                Ok(state.run(tvec!(chunk))?.remove(0).into_tensor())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn nnef_round_trip() -> TractResult<()> {
+        use tract_core::ops::array::{ConcatSlice, Pad, PadMode, TypedConcat};
+        use tract_core::ops::nn::{CausalAttention, RunningReduce, RunningReducer};
+        let mut model = TypedModel::default();
+        let s = stream_dim(&model.symbol_scope);
+        let fact = TypedFact::dt_shape(f32::datum_type(), [s, 2.to_dim()].as_ref());
+        let a = model.add_source("a", fact)?;
+        let mode = PadMode::Constant(rctensor0(-1f32));
+        let pad = model.wire_node("pad", Pad::new(vec![(1, 2), (0, 0)], mode), &[a])?;
+        let concat = TypedConcat {
+            axis: 0,
+            slices: tvec!(
+                ConcatSlice::Const(rctensor2(&[[3f32, 4.]])),
+                ConcatSlice::Var,
+                ConcatSlice::Const(rctensor2(&[[5f32, 6.]]))
+            ),
+        };
+        let concat = model.wire_node("concat", concat, &pad)?;
+        let sum = RunningReduce::new(0, RunningReducer::Sum);
+        let sum = model.wire_node("sum", sum, &concat)?[0];
+        let att = CausalAttention::new(2, 0.5);
+        let att = model.wire_node("att", att, &[sum, sum, sum])?;
+        model.set_output_outlets(&att)?;
+
+        let typed = PulsedModel::new(&model, 3)?.into_typed()?;
+        let nnef = tract_nnef::nnef().with_pulse();
+        let buffer = nnef.write_to_tar(&typed, vec![])?;
+        let reloaded = nnef.model_for_read(&mut &*buffer)?;
+        use tract_pulse_opl::metadata::stream_info;
+        // the stream symbol comes back as a symbol named S of the reloaded model
+        assert_eq!(format!("{:?}", stream_info(&reloaded)?), format!("{:?}", stream_info(&typed)?));
+
+        let input = Tensor::from_shape(&[12, 2], &(0..24).map(|i| i as f32).collect::<Vec<_>>())?;
+        assert_eq!(run_pulses(reloaded, &input, 3)?, run_pulses(typed, &input, 3)?);
+        crate::stream::test::check_against_batch(&model, &input, 3)
+    }
+
+    #[test]
+    fn nnef_pulse_pad_value_keeps_its_type() -> TractResult<()> {
+        use tract_core::ops::array::{Pad, PadMode};
+        use tract_pulse_opl::ops::PulsePad;
+        let mut model = TypedModel::default();
+        let s = stream_dim(&model.symbol_scope);
+        let a = model.add_source("a", TypedFact::dt_shape(f64::datum_type(), [s].as_ref()))?;
+        let mode = PadMode::Constant(rctensor0(0.1f64));
+        let pad = model.wire_node("pad", Pad::new(vec![(1, 2)], mode), &[a])?;
+        model.set_output_outlets(&pad)?;
+
+        let typed = PulsedModel::new(&model, 3)?.into_typed()?;
+        let nnef = tract_nnef::nnef().with_pulse();
+        let buffer = nnef.write_to_tar(&typed, vec![])?;
+        let reloaded = nnef.model_for_read(&mut &*buffer)?;
+        let op = reloaded.nodes().iter().find_map(|n| n.op_as::<PulsePad>()).unwrap();
+        match &op.mode {
+            PadMode::Constant(value) => assert_eq!(**value, tensor0(0.1f64)),
+            mode => panic!("Expected a constant padding, got {:?}", mode),
+        }
+        Ok(())
+    }
 }
@@ -1,5 +1,6 @@
 use crate::{internal::*, ops::sync_inputs};
 use tract_core::model::translator::Translate;
+use tract_pulse_opl::metadata::{set_stream_info, StreamInfo};
 
 pub type PulsedModel = Graph<PulsedFact, Box<dyn PulsedOp>>;
 pub type PulsedNode = Node<PulsedFact, Box<dyn PulsedOp>>;
@@ -7,8 +8,17 @@ pub type PulsedNode = Node<PulsedFact, Box<dyn PulsedOp>>;
 pub trait PulsedModelExt {
     fn new(source: &TypedModel, pulse: usize) -> TractResult<PulsedModel>;
 
+    /// Pulsify along the axis depending on `symbol`. Other symbols are left free in the
+    /// pulsed model and resolved from the inputs at run time.
+    fn new_with_symbol(
+        source: &TypedModel,
+        symbol: Symbol,
+        pulse: usize,
+    ) -> TractResult<PulsedModel>;
+
     fn new_with_mapping(
         source: &TypedModel,
+        symbol: Symbol,
         pulse: usize,
     ) -> TractResult<(PulsedModel, HashMap<OutletId, OutletId>)>;
 
@@ -17,27 +27,41 @@ pub trait PulsedModelExt {
 
 impl PulsedModelExt for PulsedModel {
     fn new(source: &TypedModel, pulse: usize) -> TractResult<PulsedModel> {
-        Ok(PulsedModel::new_with_mapping(source, pulse)?.0)
+        PulsedModel::new_with_symbol(source, stream_symbol(&source.symbol_scope), pulse)
+    }
+
+    fn new_with_symbol(
+        source: &TypedModel,
+        symbol: Symbol,
+        pulse: usize,
+    ) -> TractResult<PulsedModel> {
+        Ok(PulsedModel::new_with_mapping(source, symbol, pulse)?.0)
     }
 
     fn new_with_mapping(
         source: &TypedModel,
+        symbol: Symbol,
         pulse: usize,
     ) -> TractResult<(PulsedModel, HashMap<OutletId, OutletId>)> {
+        crate::rate::check_pulse(source, &symbol, pulse)?;
         let pulsifiers = crate::ops::OpPulsifier::inventory();
-        Pulsifier(pulse, pulsifiers).translate_model_with_mappings(source)
+        Pulsifier { symbol, pulse, pulsifiers }.translate_model_with_mappings(source)
     }
 
     fn into_typed(self) -> TractResult<TypedModel> {
         let mut typed = tract_core::model::translator::IntoTranslator.translate_model(&self)?;
-        let delays = tensor1(
-            &self
-                .output_outlets()?
+        let info = |outlets: &[OutletId]| {
+            outlets
                 .iter()
-                .map(|oo| Ok(self.outlet_fact(*oo)?.delay as _))
-                .collect::<TractResult<TVec<i64>>>()?,
-        );
-        typed.properties.insert("pulse.delay".to_string(), delays.into_arc_tensor());
+                .map(|o| {
+                    let fact = self.outlet_fact(*o)?;
+                    Ok(StreamInfo { axis: fact.axis, delay: fact.delay, dim: fact.dim.clone() })
+                })
+                .collect::<TractResult<TVec<_>>>()
+        };
+        let inputs = info(self.input_outlets()?)?;
+        let outputs = info(self.output_outlets()?)?;
+        set_stream_info(&mut typed, &inputs, &outputs);
         Ok(typed)
     }
 }
@@ -76,11 +100,15 @@ impl SpecialOps<PulsedFact, Box<dyn PulsedOp>> for PulsedModel {
     }
 }
 
-struct Pulsifier(usize, HashMap<TypeId, crate::ops::OpPulsifier>);
+struct Pulsifier {
+    symbol: Symbol,
+    pulse: usize,
+    pulsifiers: HashMap<TypeId, crate::ops::OpPulsifier>,
+}
 
 impl std::fmt::Debug for Pulsifier {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Pulsifier({})", self.0)
+        write!(f, "Pulsifier({}, {})", self.symbol, self.pulse)
     }
 }
 
@@ -99,8 +127,11 @@ impl
         target: &mut PulsedModel,
         mapping: &HashMap<OutletId, OutletId>,
     ) -> TractResult<TVec<OutletId>> {
-        if let Some(pulsifier) = self.1.get(&node.op.type_id()) {
-            if let Some(pulsified) = (pulsifier.func)(source, node, target, mapping, self.0)? {
+        if let Some(op) = node.op_as::<tract_core::ops::source::TypedSource>() {
+            return crate::ops::source::pulsify(op, node, target, &self.symbol, self.pulse);
+        }
+        if let Some(pulsifier) = self.pulsifiers.get(&node.op.type_id()) {
+            if let Some(pulsified) = (pulsifier.func)(source, node, target, mapping, self.pulse)? {
                 return Ok(pulsified);
             }
         }
@@ -114,6 +145,24 @@ impl
                 let inputs = sync_inputs(node, target, mapping)?;
                 return target.wire_node(&node.name, pulse_op, &inputs);
             }
+            use tract_core::ops::nn::{Reduce, Reducer};
+            let hint = match node.op_as::<Reduce>().map(|op| op.reducer) {
+                Some(Reducer::Max) => {
+                    " (a softmax along the streaming axis can only be pulsified in a causal \
+                    attention: MatMul(Q, Kᵀ), optional scale, constant banded mask, softmax, \
+                    MatMul with V; other reductions only as RunningReduce)"
+                }
+                Some(_) => {
+                    " (reductions along the streaming axis can only be pulsified as RunningReduce)"
+                }
+                None => "",
+            };
+            bail!(
+                "Can not pulsify {}: it does not preserve the streaming axis {} of its input{}",
+                node,
+                pulse_input_fact.axis,
+                hint
+            );
         }
 
         bail!("No pulsifier nor pulsable axis invariant for {}", node);
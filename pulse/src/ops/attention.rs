@@ -0,0 +1,64 @@
+use crate::internal::*;
+use tract_core::ops::nn::CausalAttention;
+use tract_pulse_opl::ops::PulsedCausalAttention;
+
+register_all!(CausalAttention: pulsify);
+
+fn pulsify(
+    op: &CausalAttention,
+    _source: &TypedModel,
+    node: &TypedNode,
+    target: &mut PulsedModel,
+    mapping: &HashMap<OutletId, OutletId>,
+    _pulse: usize,
+) -> TractResult<Option<TVec<OutletId>>> {
+    let inputs = super::sync_inputs(node, target, mapping)?;
+    let fact = target.outlet_fact(inputs[0])?.clone();
+    for input in &inputs {
+        let input_fact = target.outlet_fact(*input)?;
+        if input_fact.axis != input_fact.shape.rank() - 2 {
+            bail!(
+                "Can not pulsify {}: attention streams along its time axis ({}), not along axis {}",
+                node,
+                input_fact.shape.rank() - 2,
+                input_fact.axis
+            );
+        }
+    }
+    let op = PulsedCausalAttention { attention: op.clone(), delay: fact.delay };
+    Ok(Some(target.wire_node(&*node.name, op, &inputs)?))
+}
+
+impl PulsedOp for PulsedCausalAttention {
+    fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
+        let mut fact = inputs[0].clone();
+        let rank = fact.shape.rank();
+        fact.shape.set(rank - 1, inputs[2].shape[rank - 1].clone());
+        Ok(tvec!(fact))
+    }
+
+    as_op!();
+    pulsed_op_to_typed_op!();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::stream::test::check_against_batch;
+    use tract_core::ops::array::{Pad, PadMode};
+
+    #[test]
+    fn causal_attention_with_bounded_context() -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let fact =
+            TypedFact::dt_shape(f32::datum_type(), &[stream_dim(&model.symbol_scope), 3.to_dim()]);
+        let source = model.add_source("x", fact)?;
+        let pad = Pad::new(vec![(2, 0), (0, 0)], PadMode::Constant(rctensor0(0.5f32)));
+        let x = model.wire_node("pad", pad, &[source])?[0];
+        let op = CausalAttention::new(3, 0.5);
+        let attention = model.wire_node("attention", op, &[x, x, x])?;
+        model.set_output_outlets(&attention)?;
+        let input: Vec<f32> = (0..33).map(|i| ((i * 7) % 11) as f32 / 4.0 - 1.0).collect();
+        check_against_batch(&model, &Tensor::from_shape(&[11, 3], &input)?, 4)
+    }
+}
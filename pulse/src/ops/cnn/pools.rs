@@ -55,9 +55,9 @@ impl PulsedOp for SumPool {
 impl PulsedOp for MaxPool {
     fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
         let mut facts = pulsed_output_facts(&self.pool_spec, inputs)?;
-        if let Some(idt) = self.with_index_outputs {
+        if let Some(idt) = &self.with_index_outputs {
             facts.push(facts[0].clone());
-            facts[1].datum_type = idt;
+            facts[1].datum_type = idt.clone();
         }
         Ok(facts)
     }
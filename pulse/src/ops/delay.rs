@@ -1,24 +1,6 @@
 use crate::internal::*;
 use tract_pulse_opl::ops::Delay;
 
-pub fn register(registry: &mut Registry) {
-    registry.register_dumper(TypeId::of::<Delay>(), ser_delay)
-}
-
-fn ser_delay(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
-    let op = node.op().downcast_ref::<Delay>().unwrap();
-    let wire = ast.mapping[&node.inputs[0]].clone();
-    Ok(Some(invocation(
-        "tract_pulse_delay",
-        &[wire],
-        &[
-            ("axis", numeric(op.axis)),
-            ("delay", numeric(op.delay)),
-            ("overlap", numeric(op.overlap)),
-        ],
-    )))
-}
-
 impl PulsedOp for Delay {
     fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
         let mut fact = inputs[0].clone();
@@ -41,7 +23,7 @@ mod test {
             datum_type: u8::datum_type(),
             shape: (&[pulse]).into(),
             axis: 0,
-            dim: stream_dim(),
+            dim: stream_dim(&model.symbol_scope),
             delay: 0,
         };
         let source = model.add_source("source", fact1.clone()).unwrap();
@@ -92,7 +74,7 @@ mod test {
             datum_type: u8::datum_type(),
             shape: (&[pulse]).into(),
             axis: 0,
-            dim: stream_dim(),
+            dim: stream_dim(&model.symbol_scope),
             delay: 0,
         };
         let source = model.add_source("source", fact_0.clone()).unwrap();
@@ -117,4 +99,48 @@ mod test {
             assert_eq!(&output[0].as_slice::<u8>().unwrap()[skip..], &expect[skip..]);
         }
     }
+
+    #[test]
+    fn save_and_restore_states() {
+        let pulse = 4usize;
+        let mut model = PulsedModel::default();
+        let fact = PulsedFact {
+            datum_type: u8::datum_type(),
+            shape: (&[pulse]).into(),
+            axis: 0,
+            dim: stream_dim(&model.symbol_scope),
+            delay: 0,
+        };
+        let source = model.add_source("source", fact.clone()).unwrap();
+        let delay = model
+            .wire_node("delay", Delay::new_typed(&(&fact).into(), fact.axis, 5, 2), &[source])
+            .unwrap();
+        model.set_output_outlets(&delay).unwrap();
+
+        let plan = SimplePlan::new(model).unwrap();
+        let mut state = tract_core::plan::SimpleState::new(&plan).unwrap();
+        let input = |i: usize| {
+            let data: Vec<u8> = (pulse * i..pulse * (i + 1)).map(|a| a as u8).collect();
+            tvec!(tensor1(&data))
+        };
+        for i in 0..3 {
+            state.run(input(i)).unwrap();
+        }
+        let saved = state.save_states().unwrap();
+        let mut restored = tract_core::plan::SimpleState::new(&plan).unwrap();
+        restored.restore_states(&saved).unwrap();
+        for i in 3..6 {
+            assert_eq!(state.run(input(i)).unwrap(), restored.run(input(i)).unwrap());
+        }
+
+        let mut other = PulsedModel::default();
+        let source = other.add_source("source", fact.clone()).unwrap();
+        let delay = other
+            .wire_node("delay", Delay::new_typed(&(&fact).into(), fact.axis, 3, 2), &[source])
+            .unwrap();
+        other.set_output_outlets(&delay).unwrap();
+        let plan = SimplePlan::new(other).unwrap();
+        let mut other_state = tract_core::plan::SimpleState::new(&plan).unwrap();
+        assert!(other_state.restore_states(&saved).is_err());
+    }
 }
@@ -28,7 +28,7 @@ impl PulsedOp for MatMulUnary {
             self.b_trans,
             self.c_trans,
         )?;
-        fact.datum_type = tract_core::ops::matmul::output_type(inputs[0].datum_type);
+        fact.datum_type = tract_core::ops::matmul::output_type(inputs[0].datum_type.clone());
         fact.shape = c_shape.into();
         Ok(tvec!(fact))
     }
@@ -2,12 +2,14 @@ use crate::internal::*;
 use tract_pulse_opl::ops::Delay;
 
 pub mod array;
+pub mod attention;
 pub mod cnn;
 pub mod delay;
 pub mod downsample;
 pub mod dummy;
 pub mod matmul;
 pub mod qmatmul;
+pub mod running_reduce;
 pub mod scan;
 pub mod slice;
 pub mod source;
@@ -44,12 +46,13 @@ pub(crate) fn sync_inputs(
 
 register_all_mod!(
     array,
+    attention,
     cnn,
     downsample,
     matmul,
     qmatmul,
-    scan,
-    source
+    running_reduce,
+    scan
 );
 
 pub struct OpPulsifier {
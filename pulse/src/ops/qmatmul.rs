@@ -29,7 +29,7 @@ impl PulsedOp for QMatMulUnary {
             self.b_trans,
             self.c_trans,
         )?;
-        fact.datum_type = self.output_type;
+        fact.datum_type = self.output_type.clone();
         fact.shape = c_shape.into();
         Ok(tvec!(fact))
     }
@@ -0,0 +1,58 @@
+use crate::internal::*;
+use tract_core::ops::nn::RunningReduce;
+use tract_pulse_opl::ops::PulsedRunningReduce;
+
+register_all!(RunningReduce: pulsify);
+
+fn pulsify(
+    op: &RunningReduce,
+    _source: &TypedModel,
+    node: &TypedNode,
+    target: &mut PulsedModel,
+    mapping: &HashMap<OutletId, OutletId>,
+    _pulse: usize,
+) -> TractResult<Option<TVec<OutletId>>> {
+    let input = mapping[&node.inputs[0]];
+    let fact = target.outlet_fact(input)?.clone();
+    if fact.axis != op.axis {
+        return Ok(None);
+    }
+    let op = PulsedRunningReduce { reduce: op.clone(), delay: fact.delay };
+    Ok(Some(target.wire_node(&*node.name, op, &[input])?))
+}
+
+impl PulsedOp for PulsedRunningReduce {
+    fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
+        Ok(tvec!(inputs[0].clone()))
+    }
+
+    as_op!();
+    pulsed_op_to_typed_op!();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::stream::test::check_against_batch;
+    use tract_core::ops::array::{Pad, PadMode};
+    use tract_core::ops::nn::RunningReducer;
+
+    #[test]
+    fn running_reduce_after_delay() -> TractResult<()> {
+        for reducer in &[RunningReducer::Sum, RunningReducer::Mean, RunningReducer::Max] {
+            let mut model = TypedModel::default();
+            let fact = TypedFact::dt_shape(
+                f32::datum_type(),
+                &[stream_dim(&model.symbol_scope), 2.to_dim()],
+            );
+            let source = model.add_source("x", fact)?;
+            let pad = Pad::new(vec![(1, 0), (0, 0)], PadMode::Constant(rctensor0(0.5f32)));
+            let pad = model.wire_node("pad", pad, &[source])?;
+            let reduce = model.wire_node("reduce", RunningReduce::new(0, *reducer), &pad)?;
+            model.set_output_outlets(&reduce)?;
+            let input: Vec<f32> = (0..22).map(|i| ((i * 7) % 5) as f32 - 2.0).collect();
+            check_against_batch(&model, &Tensor::from_shape(&[11, 2], &input)?, 4)?;
+        }
+        Ok(())
+    }
+}
@@ -73,7 +73,7 @@ impl PulsedOp for Scan {
                 )
                 .collect();
             let fact = PulsedFact {
-                datum_type: output_body_fact.datum_type,
+                datum_type: output_body_fact.datum_type.clone(),
                 shape: shape.into(),
                 axis: output_mapping.axis,
                 dim: inputs[0].dim.clone(),
@@ -1,19 +1,20 @@
 use crate::internal::*;
 use tract_core::ops::source::*;
 
-register_all!(TypedSource: pulsify);
-
-fn pulsify(
+/// Sources are pulsified by the model translator itself, as they are the only ops that need
+/// to know the streaming symbol.
+pub(crate) fn pulsify(
     _op: &TypedSource,
-    _source: &TypedModel,
     node: &TypedNode,
     target: &mut PulsedModel,
-    _mapping: &HashMap<OutletId, OutletId>,
+    symbol: &Symbol,
     pulse: usize,
-) -> TractResult<Option<TVec<OutletId>>> {
-    let pulsed_fact = PulsedFact::from_tensor_fact_pulse(&node.outputs[0].fact, pulse)?;
+) -> TractResult<TVec<OutletId>> {
+    let pulsed_fact =
+        PulsedFact::from_tensor_fact_symbol_pulse(&node.outputs[0].fact, symbol, pulse)
+            .with_context(|| format!("Pulsifying source {}", node))?;
     let id = target.add_source(node.name.clone(), pulsed_fact)?;
-    Ok(Some(tvec!(id)))
+    Ok(tvec!(id))
 }
 
 #[derive(Debug, Clone, Hash)]
@@ -49,7 +50,10 @@ impl PulsedOp for PulsedSource {
     }
 
     fn to_typed(&self) -> Box<dyn TypedOp> {
-        Box::new(TypedSource::new(TypedFact::dt_shape(self.0.datum_type, self.0.shape.clone())))
+        Box::new(TypedSource::new(TypedFact::dt_shape(
+            self.0.datum_type.clone(),
+            self.0.shape.clone(),
+        )))
     }
 
     as_op!();
@@ -0,0 +1,193 @@
+//! Pulse rates along the streaming axis.
+//!
+//! Strided convolutions and pools and `Downsample` divide the number of frames per pulse by
+//! their stride, deconvolutions multiply it. The pulse given to the model input must divide
+//! evenly through every stride.
+//!
+//! The pulsed model itself always runs on pulses of the same size, a multiple of
+//! `minimal_pulse`: there are no pulses alternating between sizes inside the model. Output
+//! chunks of varying lengths are only obtained from `PulsedStream`, which buffers the input
+//! frames until a whole pulse is available.
+use crate::fact::StreamFact;
+use crate::internal::*;
+use num_integer::Integer;
+use tract_core::ops::cnn::{ConvUnary, DeconvUnary, MaxPool, PoolSpec, SumPool};
+use tract_core::ops::Downsample;
+
+/// A node striding over the streaming axis.
+#[derive(Clone, Debug)]
+struct Strided {
+    node: usize,
+    stride: usize,
+    /// Frames at the node input per frame at the model input, as a reduced fraction.
+    rate: (usize, usize),
+}
+
+impl Strided {
+    /// Smallest input pulse giving a whole multiple of `stride` frames at this node.
+    fn minimal_pulse(&self) -> usize {
+        let (num, den) = self.rate;
+        let target = self.stride * den;
+        target / num.gcd(&target)
+    }
+}
+
+fn reduced(num: usize, den: usize) -> (usize, usize) {
+    let gcd = num.gcd(&den);
+    (num / gcd, den / gcd)
+}
+
+fn pool_stride(spec: &PoolSpec, axis: usize) -> Option<usize> {
+    let h_axis = spec.data_format.h_axis();
+    if axis >= h_axis && axis < h_axis + spec.kernel_shape.len() {
+        Some(spec.stride(axis - h_axis))
+    } else {
+        None
+    }
+}
+
+fn strided_nodes(model: &TypedModel, symbol: &Symbol) -> TractResult<Vec<Strided>> {
+    let mut rates: HashMap<OutletId, (usize, usize)> = HashMap::default();
+    let mut strided = vec![];
+    for node in model.eval_order()?.into_iter().map(|n| model.node(n)) {
+        let rate = if model.input_outlets()?.contains(&OutletId::new(node.id, 0)) {
+            (1, 1)
+        } else if let Some(rate) = node.inputs.iter().find_map(|i| rates.get(i)) {
+            *rate
+        } else {
+            continue;
+        };
+        let axis = if let Some(input) = node.inputs.get(0) {
+            match model.outlet_fact(*input)?.shape.stream_info_for(symbol) {
+                Some((axis, _)) => Some(axis),
+                None => continue,
+            }
+        } else {
+            None
+        };
+        let (down, up) = if let Some(axis) = axis {
+            if let Some(op) = node.op_as::<Downsample>() {
+                (if op.axis == axis { op.stride.max(1) as usize } else { 1 }, 1)
+            } else if let Some(op) = node.op_as::<ConvUnary>() {
+                (pool_stride(&op.pool_spec, axis).unwrap_or(1), 1)
+            } else if let Some(op) = node.op_as::<MaxPool>() {
+                (pool_stride(&op.pool_spec, axis).unwrap_or(1), 1)
+            } else if let Some(op) = node.op_as::<SumPool>() {
+                (pool_stride(&op.pool_spec, axis).unwrap_or(1), 1)
+            } else if let Some(op) = node.op_as::<DeconvUnary>() {
+                (1, pool_stride(&op.pool_spec, axis).unwrap_or(1))
+            } else {
+                (1, 1)
+            }
+        } else {
+            (1, 1)
+        };
+        if down > 1 {
+            strided.push(Strided { node: node.id, stride: down, rate });
+        }
+        let rate = reduced(rate.0 * up, rate.1 * down);
+        for slot in 0..node.outputs.len() {
+            rates.insert(OutletId::new(node.id, slot), rate);
+        }
+    }
+    Ok(strided)
+}
+
+/// The smallest pulse dividing evenly through every stride along the axis streaming over
+/// `symbol`. Valid pulses are its multiples.
+pub fn minimal_pulse(model: &TypedModel, symbol: &Symbol) -> TractResult<usize> {
+    Ok(strided_nodes(model, symbol)?.iter().fold(1, |acc, s| acc.lcm(&s.minimal_pulse())))
+}
+
+/// Check that `pulse` divides evenly through every stride along the axis streaming over
+/// `symbol`, naming the first offending node otherwise.
+pub fn check_pulse(model: &TypedModel, symbol: &Symbol, pulse: usize) -> TractResult<()> {
+    ensure!(pulse > 0, "Pulse must be positive");
+    let strided = strided_nodes(model, symbol)?;
+    if let Some(s) = strided.iter().find(|s| pulse % s.minimal_pulse() != 0) {
+        let minimal = strided.iter().fold(1, |acc, s| acc.lcm(&s.minimal_pulse()));
+        // earlier strides are valid, so the node gets a whole number of frames
+        let frames = pulse * s.rate.0 / s.rate.1;
+        bail!(
+            "Invalid pulse {}: {} strides by {} along the streaming axis but would receive {} \
+             frames per pulse. Valid pulses are multiples of {}.",
+            pulse,
+            model.node(s.node),
+            s.stride,
+            frames,
+            minimal
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use tract_core::ops::cnn::{KernelFormat, PaddingSpec};
+    use tract_core::ops::nn::DataFormat;
+
+    fn model(ops: &[Box<dyn TypedOp>]) -> TractResult<TypedModel> {
+        let mut model = TypedModel::default();
+        let s = stream_dim(&model.symbol_scope);
+        let fact = TypedFact::dt_shape(f32::datum_type(), &[1.to_dim(), 1.to_dim(), s]);
+        let mut wire = model.add_source("a", fact)?;
+        for (ix, op) in ops.iter().enumerate() {
+            wire = model.wire_node(format!("op{}", ix), op.clone(), &[wire])?[0];
+        }
+        model.set_output_outlets(&[wire])?;
+        Ok(model)
+    }
+
+    fn conv(stride: usize) -> Box<dyn TypedOp> {
+        let pool_spec = PoolSpec::new(
+            DataFormat::NCHW,
+            tvec!(1),
+            PaddingSpec::Valid,
+            None,
+            Some(tvec!(stride)),
+            Some(1),
+        );
+        let kernel = rctensor3(&[[[1f32]]]);
+        Box::new(ConvUnary::new(pool_spec, KernelFormat::OIHW, kernel, 1, None, None))
+    }
+
+    #[test]
+    fn strides_multiply() -> TractResult<()> {
+        let model = model(&[conv(2), Box::new(Downsample::new(2, 3, 0))])?;
+        let s = stream_symbol(&model.symbol_scope);
+        assert_eq!(minimal_pulse(&model, &s)?, 6);
+        check_pulse(&model, &s, 12)?;
+        let error = check_pulse(&model, &s, 4).unwrap_err().to_string();
+        assert!(error.contains("op1") && error.contains("multiples of 6"), "{}", error);
+        Ok(())
+    }
+
+    #[test]
+    fn first_offending_node_is_named() -> TractResult<()> {
+        let twice = model(&[conv(2), conv(2)])?;
+        let s = stream_symbol(&twice.symbol_scope);
+        let error = check_pulse(&twice, &s, 6).unwrap_err().to_string();
+        assert!(error.contains("op1") && error.contains("receive 3 frames"), "{}", error);
+        let error = check_pulse(&twice, &s, 3).unwrap_err().to_string();
+        assert!(error.contains("op0") && error.contains("multiples of 4"), "{}", error);
+        Ok(())
+    }
+
+    #[test]
+    fn deconv_multiplies() -> TractResult<()> {
+        let pool_spec = PoolSpec::new(
+            DataFormat::NCHW,
+            tvec!(1),
+            PaddingSpec::Valid,
+            None,
+            Some(tvec!(2)),
+            Some(1),
+        );
+        let kernel = rctensor3(&[[[1f32]]]);
+        let deconv = DeconvUnary::new(pool_spec, KernelFormat::OIHW, kernel, None, tvec!(0), 1);
+        let model = model(&[Box::new(deconv), conv(4)])?;
+        assert_eq!(minimal_pulse(&model, &stream_symbol(&model.symbol_scope))?, 2);
+        Ok(())
+    }
+}
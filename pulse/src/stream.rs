@@ -0,0 +1,426 @@
+use crate::internal::*;
+
+#[derive(Debug, Clone)]
+struct StreamOutput {
+    datum_type: DatumType,
+    shape: ShapeFact,
+    axis: usize,
+    pulse: usize,
+    delay: usize,
+    dim: TDim,
+    emitted: usize,
+}
+
+/// Runs a pulsed model over a whole stream.
+///
+/// `push` feeds chunks of any length, `finish` feeds the last one and flushes what the delays
+/// and paddings still hold. The model runs on whole pulses, frames left over are kept for the
+/// next call, so the number of output frames can vary from call to call. Both return only
+/// valid output frames: concatenated along their streaming axis, they are what the non-pulsed
+/// model computes for the whole stream.
+///
+/// This buffering is the only way to get varying output pulses. The input is buffered up to a
+/// whole pulse, a multiple of the minimal pulse (see `rate::minimal_pulse`), so a model with
+/// strides still waits for that many frames before producing any output.
+#[derive(Debug, Clone)]
+pub struct PulsedStream {
+    state: TypedSimpleState<TypedModel, TypedRunnableModel<TypedModel>>,
+    input_shape: ShapeFact,
+    input_axis: usize,
+    symbol: Symbol,
+    pulse: usize,
+    consumed: usize,
+    pending: Option<Tensor>,
+    outputs: TVec<StreamOutput>,
+}
+
+impl PulsedStream {
+    pub fn new(model: &PulsedModel) -> TractResult<PulsedStream> {
+        ensure!(model.input_outlets()?.len() == 1, "PulsedStream needs a single streaming input");
+        let input = model.input_fact(0)?;
+        let symbol = match &input.dim {
+            TDim::Sym(symbol) => symbol.clone(),
+            _ => bail!(
+                "PulsedStream needs the input stream length to be a symbol, got {}",
+                input.dim
+            ),
+        };
+        let outputs = model
+            .output_outlets()?
+            .iter()
+            .map(|o| {
+                let fact = model.outlet_fact(*o)?;
+                Ok(StreamOutput {
+                    datum_type: fact.datum_type.clone(),
+                    shape: fact.shape.clone(),
+                    axis: fact.axis,
+                    pulse: fact.pulse(),
+                    delay: fact.delay,
+                    dim: fact.dim.clone(),
+                    emitted: 0,
+                })
+            })
+            .collect::<TractResult<_>>()?;
+        let plan = model.clone().into_typed()?.into_optimized()?.into_runnable()?;
+        Ok(PulsedStream {
+            state: SimpleState::new(plan)?,
+            input_shape: input.shape.clone(),
+            input_axis: input.axis,
+            symbol,
+            pulse: input.pulse(),
+            consumed: 0,
+            pending: None,
+            outputs,
+        })
+    }
+
+    /// Feed some frames, and get the output frames they made available.
+    pub fn push(&mut self, input: Tensor) -> TractResult<TVec<Tensor>> {
+        let input = self.buffer(input)?;
+        let chunks = self.run_pulses(input)?;
+        self.stack(chunks)
+    }
+
+    /// Feed the end of the stream (possibly empty), and get all the output frames left.
+    pub fn finish(&mut self, input: Tensor) -> TractResult<TVec<Tensor>> {
+        let input = self.buffer(input)?;
+        self.state.session_state.resolved_symbols[self.symbol.clone()] = Some(self.consumed as i64);
+        let mut chunks = self.run_pulses(input)?;
+        let rest = self.pending.take().unwrap();
+        let len = rest.shape()[self.input_axis];
+        let mut shape: TVec<usize> = rest.shape().into();
+        shape[self.input_axis] = self.pulse;
+        let padding = Tensor::zero_dt(rest.datum_type(), &shape)?;
+        let input = Tensor::stack_tensors(
+            self.input_axis,
+            &[rest, padding.slice(self.input_axis, 0, self.pulse - len)?],
+        )?;
+        for (chunk, t) in chunks.iter_mut().zip(self.run(input)?) {
+            chunk.push(t);
+        }
+        while !self.is_flushed()? {
+            for (chunk, t) in chunks.iter_mut().zip(self.run(padding.clone())?) {
+                chunk.push(t);
+            }
+        }
+        self.stack(chunks)
+    }
+
+    /// Append `input` to the frames left over by the previous call.
+    fn buffer(&mut self, input: Tensor) -> TractResult<Tensor> {
+        ensure!(
+            input.rank() == self.input_shape.rank(),
+            "Expected a tensor of rank {}, got {:?}",
+            self.input_shape.rank(),
+            input.shape()
+        );
+        self.consumed += input.shape()[self.input_axis];
+        if let Some(pending) = self.pending.take() {
+            Tensor::stack_tensors(self.input_axis, &[pending, input])
+        } else {
+            Ok(input)
+        }
+    }
+
+    /// Run all the whole pulses in `input`, and keep the remaining frames. Each output starts
+    /// with an empty chunk so that stacking works even if no pulse was run.
+    fn run_pulses(&mut self, input: Tensor) -> TractResult<TVec<Vec<Tensor>>> {
+        let mut chunks = self.empty_outputs(&input)?;
+        let len = input.shape()[self.input_axis];
+        let mut start = 0;
+        while start + self.pulse <= len {
+            let pulse = input.slice(self.input_axis, start, start + self.pulse)?;
+            for (chunk, t) in chunks.iter_mut().zip(self.run(pulse)?) {
+                chunk.push(t);
+            }
+            start += self.pulse;
+        }
+        self.pending = Some(input.slice(self.input_axis, start, len)?);
+        Ok(chunks)
+    }
+
+    fn empty_outputs(&mut self, input: &Tensor) -> TractResult<TVec<Vec<Tensor>>> {
+        let symbols = &mut self.state.session_state.resolved_symbols;
+        for (dim, len) in self.input_shape.iter().zip(input.shape()) {
+            if let TDim::Sym(s) = dim {
+                symbols[s] = Some(*len as i64);
+            }
+        }
+        self.outputs
+            .iter()
+            .map(|output| {
+                let shape = output
+                    .shape
+                    .iter()
+                    .enumerate()
+                    .map(
+                        |(ix, d)| {
+                            if ix == output.axis {
+                                Ok(0)
+                            } else {
+                                d.eval(symbols).to_usize()
+                            }
+                        },
+                    )
+                    .collect::<TractResult<TVec<usize>>>()?;
+                Ok(vec![Tensor::zero_dt(output.datum_type.clone(), &shape)?])
+            })
+            .collect()
+    }
+
+    fn stack(&self, chunks: TVec<Vec<Tensor>>) -> TractResult<TVec<Tensor>> {
+        chunks
+            .iter()
+            .zip(self.outputs.iter())
+            .map(|(chunk, output)| Tensor::stack_tensors(output.axis, chunk))
+            .collect()
+    }
+
+    fn is_flushed(&self) -> TractResult<bool> {
+        for output in &self.outputs {
+            let dim = output.dim.eval(&self.state.session_state.resolved_symbols).to_usize()?;
+            if output.emitted < output.delay + dim {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Run a pulse, and trim the outputs to the frames that are after the delay and before
+    /// the end of the stream (if it is known).
+    fn run(&mut self, input: Tensor) -> TractResult<TVec<Tensor>> {
+        let results = self.state.run(tvec!(input))?;
+        let symbols = &self.state.session_state.resolved_symbols;
+        results
+            .iter()
+            .zip(self.outputs.iter_mut())
+            .map(|(t, output)| {
+                let start = output.emitted;
+                output.emitted += output.pulse;
+                let end = output
+                    .dim
+                    .eval(symbols)
+                    .to_usize()
+                    .map(|dim| output.delay + dim)
+                    .unwrap_or(std::usize::MAX);
+                let from = output.delay.max(start).min(output.emitted);
+                let to = end.max(from).min(output.emitted);
+                t.slice(output.axis, from - start, to - start)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
+    use crate::fact::StreamFact;
+    use tract_core::ops::array::{Pad, PadMode};
+    use tract_core::ops::cnn::{ConvUnary, KernelFormat, PaddingSpec, PoolSpec};
+    use tract_core::ops::nn::DataFormat;
+    use tract_core::ops::Downsample;
+    use tract_pulse_opl::ops::Delay;
+
+    /// Run a model with a single streaming input on the whole input, then as a stream of
+    /// pulses, and compare the first outputs.
+    pub(crate) fn check_against_batch(
+        model: &TypedModel,
+        input: &Tensor,
+        pulse: usize,
+    ) -> TractResult<()> {
+        check_against_batch_over(model, stream_symbol(&model.symbol_scope), input, pulse)
+    }
+
+    /// Same as `check_against_batch`, streaming over `symbol`.
+    pub(crate) fn check_against_batch_over(
+        model: &TypedModel,
+        symbol: Symbol,
+        input: &Tensor,
+        pulse: usize,
+    ) -> TractResult<()> {
+        let (axis, _) =
+            model.input_fact(0)?.shape.stream_info_for(&symbol).context("No streaming axis")?;
+        let len = input.shape()[axis];
+        let expected = model
+            .concretize_dims(&SymbolValues::default().with(symbol.clone(), len as i64))?
+            .into_runnable()?
+            .run(tvec!(input.clone()))?;
+        let pulsed = PulsedModel::new_with_symbol(model, symbol, pulse)?;
+        let output_axis = pulsed.output_fact(0)?.axis;
+        let mut stream = PulsedStream::new(&pulsed)?;
+        let mut got = vec![];
+        let mut fed = 0;
+        while fed + pulse <= len {
+            got.push(stream.push(input.slice(axis, fed, fed + pulse)?)?.remove(0));
+            fed += pulse;
+        }
+        got.push(stream.finish(input.slice(axis, fed, len)?)?.remove(0));
+        Tensor::stack_tensors(output_axis, &got)?.close_enough(&expected[0], true)
+    }
+
+    fn check_stream(len: usize, pulse: usize) -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let s = stream_symbol(&model.symbol_scope);
+        let source =
+            model.add_source("x", TypedFact::dt_shape(f32::datum_type(), &[s.to_dim()]))?;
+        let mode = PadMode::Constant(rctensor0(-1f32));
+        let pad = model.wire_node("pad", Pad::new(vec![(2, 3)], mode), &[source])?;
+        model.set_output_outlets(&pad)?;
+
+        let input: Vec<f32> = (0..len).map(|i| i as f32).collect();
+        let expected = model
+            .concretize_dims(&SymbolValues::default().with(s, len as i64))?
+            .into_runnable()?
+            .run(tvec!(tensor1(&input)))?;
+
+        let mut stream = PulsedStream::new(&PulsedModel::new(&model, pulse)?)?;
+        let mut got = vec![];
+        let mut chunks = input.chunks(pulse).peekable();
+        while let Some(chunk) = chunks.next() {
+            if chunks.peek().is_none() && chunk.len() < pulse {
+                got.push(stream.finish(tensor1(chunk))?.remove(0));
+                break;
+            }
+            got.push(stream.push(tensor1(chunk))?.remove(0));
+            if chunks.peek().is_none() {
+                got.push(stream.finish(tensor1::<f32>(&[]))?.remove(0));
+            }
+        }
+        assert_eq!(Tensor::stack_tensors(0, &got)?, *expected[0]);
+        Ok(())
+    }
+
+    #[test]
+    fn shorter_final_pulse() -> TractResult<()> {
+        check_stream(10, 4)
+    }
+
+    #[test]
+    fn empty_final_pulse() -> TractResult<()> {
+        check_stream(8, 4)
+    }
+
+    #[test]
+    fn stream_shorter_than_pulse() -> TractResult<()> {
+        check_stream(3, 4)
+    }
+
+    /// Concat constants around the stream, after padding it by `pad` frames.
+    fn check_concat(pad: usize, len: usize, pulse: usize) -> TractResult<()> {
+        use tract_core::ops::array::{ConcatSlice, TypedConcat};
+        let mut model = TypedModel::default();
+        let s = stream_dim(&model.symbol_scope);
+        let source = model.add_source("x", TypedFact::dt_shape(f32::datum_type(), &[s]))?;
+        let mode = PadMode::Constant(rctensor0(-1f32));
+        let pad = model.wire_node("pad", Pad::new(vec![(pad, 0)], mode), &[source])?;
+        let concat = TypedConcat {
+            axis: 0,
+            slices: tvec!(
+                ConcatSlice::Const(rctensor1(&[10f32, 11.])),
+                ConcatSlice::Var,
+                ConcatSlice::Const(rctensor1(&[12f32]))
+            ),
+        };
+        let concat = model.wire_node("concat", concat, &pad)?;
+        model.set_output_outlets(&concat)?;
+        let input: Vec<f32> = (0..len).map(|i| i as f32).collect();
+        check_against_batch(&model, &tensor1(&input), pulse)
+    }
+
+    #[test]
+    fn concat_delays_a_stream_shorter_than_its_prefix() -> TractResult<()> {
+        check_concat(0, 10, 4)
+    }
+
+    #[test]
+    fn concat_after_a_delay_longer_than_its_prefix() -> TractResult<()> {
+        check_concat(3, 10, 4)
+    }
+
+    #[test]
+    fn chosen_symbol_with_free_channels() -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let (t, c) = (model.symbol_scope.sym("T"), model.symbol_scope.sym("C"));
+        let fact = TypedFact::dt_shape(f32::datum_type(), &[TDim::from(&c), (&t).into()]);
+        let source = model.add_source("x", fact)?;
+        let mode = PadMode::Constant(rctensor0(-1f32));
+        let pad = model.wire_node("pad", Pad::new(vec![(0, 0), (2, 1)], mode), &[source])?;
+        model.set_output_outlets(&pad)?;
+        let pulsed = PulsedModel::new_with_symbol(&model, t.clone(), 4)?;
+        assert_eq!(pulsed.output_fact(0)?.shape[0], c.into());
+        let input = Tensor::from_shape(&[3, 10], &(0..30).map(|i| i as f32).collect::<Vec<_>>())?;
+        check_against_batch_over(&model, t, &input, 4)
+    }
+
+    #[test]
+    fn chunks_of_any_length() -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let s = stream_symbol(&model.symbol_scope);
+        let source =
+            model.add_source("x", TypedFact::dt_shape(f32::datum_type(), &[s.to_dim()]))?;
+        let down = model.wire_node("down", Downsample::new(0, 2, 0), &[source])?;
+        model.set_output_outlets(&down)?;
+        assert!(PulsedModel::new(&model, 3).is_err());
+        let pulse = crate::rate::minimal_pulse(&model, &s)?;
+        let mut stream = PulsedStream::new(&PulsedModel::new(&model, pulse)?)?;
+        let input: Vec<f32> = (0..13).map(|i| i as f32).collect();
+        let mut got = vec![];
+        for chunk in input.chunks(3) {
+            got.push(stream.push(tensor1(chunk))?.remove(0));
+        }
+        got.push(stream.finish(tensor1::<f32>(&[]))?.remove(0));
+        assert_eq!(got.iter().map(|t| t.len()).collect::<Vec<_>>(), vec![1, 2, 1, 2, 0, 1]);
+        let expected: Vec<f32> = (0..13).step_by(2).map(|i| i as f32).collect();
+        assert_eq!(Tensor::stack_tensors(0, &got)?, tensor1(&expected));
+        Ok(())
+    }
+
+    fn conv(padding: PaddingSpec) -> ConvUnary {
+        let pool_spec = PoolSpec::new(DataFormat::NCHW, tvec!(3), padding, None, None, Some(1));
+        let kernel = rctensor3(&[[[1f32, 2., -1.]]]);
+        ConvUnary::new(pool_spec, KernelFormat::OIHW, kernel, 1, None, None)
+    }
+
+    fn delays(model: &TypedModel, pulse: usize) -> TractResult<usize> {
+        let pulsed = PulsedModel::new(model, pulse)?;
+        Ok(pulsed.nodes().iter().filter(|n| n.op_is::<Delay>()).count())
+    }
+
+    /// Check streams of every length from `min_len` on, so the final pulse takes all sizes.
+    fn check_lengths(model: &TypedModel, min_len: usize, pulse: usize) -> TractResult<()> {
+        for len in min_len..min_len + 3 * pulse {
+            let data = (0..len).map(|i| ((i * 7) % 5) as f32 - 2.0).collect::<Vec<_>>();
+            let input = Tensor::from_shape(&[1, 1, len], &data)?;
+            check_against_batch(model, &input, pulse).with_context(|| format!("len: {}", len))?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn conv_overlap_flush() -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let s = stream_dim(&model.symbol_scope);
+        let fact = TypedFact::dt_shape(f32::datum_type(), &[1.to_dim(), 1.to_dim(), s]);
+        let source = model.add_source("x", fact)?;
+        let conv = model.wire_node("conv", conv(PaddingSpec::Valid), &[source])?;
+        model.set_output_outlets(&conv)?;
+        assert_eq!(delays(&model, 4)?, 1);
+        check_lengths(&model, 3, 4)
+    }
+
+    #[test]
+    fn padded_conv_and_delayed_branch_flush() -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let s = stream_dim(&model.symbol_scope);
+        let fact = TypedFact::dt_shape(f32::datum_type(), &[1.to_dim(), 1.to_dim(), s]);
+        let source = model.add_source("x", fact)?;
+        let padding = PaddingSpec::Explicit(tvec!(1), tvec!(1), false);
+        let conv = model.wire_node("conv", conv(padding), &[source])?[0];
+        let add =
+            model.wire_node("add", tract_core::ops::math::add::bin_typed(), &[conv, source])?;
+        model.set_output_outlets(&add)?;
+        // the conv overlap, and the source branch delayed to meet the conv output
+        assert!(delays(&model, 4)? >= 2);
+        check_lengths(&model, 1, 4)
+    }
+}
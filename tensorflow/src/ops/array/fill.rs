@@ -56,8 +56,8 @@ impl InferenceRulesOp for Fill {
     ) -> InferenceResult {
         check_input_arity(&inputs, 2)?;
         check_output_arity(&outputs, 1)?;
-        s.equals(&outputs[0].datum_type, self.dt)?;
-        s.equals(&inputs[1].datum_type, self.dt)?;
+        s.equals(&outputs[0].datum_type, self.dt.clone())?;
+        s.equals(&inputs[1].datum_type, self.dt.clone())?;
         s.equals(&inputs[0].rank, 1)?;
         s.equals(&inputs[1].rank, 0)?;
         s.equals(outputs[0].rank.bex().to_dim(), &inputs[0].shape[0])?;
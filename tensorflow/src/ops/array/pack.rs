@@ -71,7 +71,7 @@ impl Expansion for Pack {
     ) -> TractResult<TVec<OutletId>> {
         let dt = inputs
             .iter()
-            .map(|&i| Ok(model.outlet_fact(i)?.datum_type))
+            .map(|&i| Ok(model.outlet_fact(i)?.datum_type.clone()))
             .collect::<TractResult<TVec<DatumType>>>()?;
         let dt = DatumType::super_type_for(dt.iter()).context("No supertype")?;
         let wires = wire_cast(prefix, model, inputs, dt)?;
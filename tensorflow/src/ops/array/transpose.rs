@@ -42,8 +42,8 @@ impl Expansion for Transpose {
     ) -> InferenceResult {
         check_output_arity(&inputs, 2)?;
         check_output_arity(&outputs, 1)?;
-        s.equals(&inputs[0].datum_type, self.t)?;
-        s.equals(&inputs[1].datum_type, self.t_perm)?;
+        s.equals(&inputs[0].datum_type, self.t.clone())?;
+        s.equals(&inputs[1].datum_type, self.t_perm.clone())?;
         s.equals(&outputs[0].datum_type, &inputs[0].datum_type)?;
         s.equals(&outputs[0].rank, &inputs[0].rank)?;
         s.equals(&inputs[1].rank, 1)?;
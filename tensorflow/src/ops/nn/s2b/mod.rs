@@ -207,7 +207,8 @@ mod tests {
 
     #[test]
     fn space_to_batch_nd_infer_2() {
-        use tract_pulse::internal::stream_dim as s;
+        let scope = SymbolScope::default();
+        let s = || tract_pulse::internal::stream_dim(&scope);
         let mut op = SpaceToBatch::new(f32::datum_type());
         let data = InferenceFact::dt_shape(DatumType::F32, shapefactoid!(1, (s() - 4), 16));
         let block_shape = InferenceFact::from(Tensor::from(arr1(&[2])));
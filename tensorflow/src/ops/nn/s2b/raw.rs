@@ -47,7 +47,7 @@ impl InferenceRulesOp for SpaceToBatch {
     ) -> InferenceResult {
         check_input_arity(&inputs, 3)?;
         check_output_arity(&outputs, 1)?;
-        rules(s, self.datum_type, &outputs[0], &inputs[0], &inputs[1], &inputs[2])
+        rules(s, self.datum_type.clone(), &outputs[0], &inputs[0], &inputs[1], &inputs[2])
     }
 
     as_op!();
@@ -78,7 +78,7 @@ impl InferenceRulesOp for SpaceToBatch {
                 paddings.push(pad);
             }
             let op = super::unary::SpaceToBatchUnary::new(
-                self.datum_type,
+                self.datum_type.clone(),
                 target.outlet_fact(mapping[&node.inputs[0]])?.shape.to_tvec(),
                 node.outputs[0]
                     .fact
@@ -144,7 +144,7 @@ impl InferenceRulesOp for BatchToSpace {
     ) -> InferenceResult {
         check_input_arity(&inputs, 3)?;
         check_output_arity(&outputs, 1)?;
-        rules(s, self.datum_type, &inputs[0], &outputs[0], &inputs[1], &inputs[2])
+        rules(s, self.datum_type.clone(), &inputs[0], &outputs[0], &inputs[1], &inputs[2])
     }
 
     fn to_typed(
@@ -174,7 +174,7 @@ impl InferenceRulesOp for BatchToSpace {
                 })
                 .collect::<TractResult<_>>()?;
             let op = super::unary::BatchToSpaceUnary::new(
-                self.datum_type,
+                self.datum_type.clone(),
                 target.outlet_fact(mapping[&node.inputs[0]])?.shape.to_tvec(),
                 node.outputs[0]
                     .fact
@@ -55,7 +55,7 @@ impl EvalOp for RandomUniform {
     fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
         let shape: TVec<usize> =
             inputs[0].cast_to::<i64>()?.as_slice::<i64>()?.iter().map(|&x| x as usize).collect();
-        match self.t {
+        match &self.t {
             DatumType::F32 => Ok(tvec!(make_f32(&*shape, self.seed1, self.seed2)?)),
             dt => bail!("RandomUniform not implemented for {:?}", dt),
         }
@@ -71,7 +71,7 @@ impl InferenceRulesOp for RandomUniform {
     ) -> InferenceResult {
         check_input_arity(&inputs, 1)?;
         check_output_arity(&outputs, 1)?;
-        s.equals(&outputs[0].datum_type, self.t)?;
+        s.equals(&outputs[0].datum_type, self.t.clone())?;
         s.equals(&inputs[0].rank, 1)?;
         s.equals(&inputs[0].shape[0], outputs[0].rank.bex().to_dim())?;
         s.given(&inputs[0].value, move |s, value| {
@@ -93,7 +93,7 @@ impl InferenceRulesOp for RandomUniform {
     ) -> TractResult<TVec<OutletId>> {
         if let Some(ref shape) = target.outlet_fact(mapping[&node.inputs[0]])?.konst {
             let op = TypedRandomUniform::new(
-                self.t,
+                self.t.clone(),
                 self.seed1,
                 self.seed2,
                 shape.cast_to::<TDim>()?.as_slice::<TDim>()?.into(),
@@ -139,7 +139,7 @@ impl EvalOp for TypedRandomUniform {
 
     fn eval(&self, _inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
         let shape = self.shape.iter().map(|d| d.to_usize()).collect::<TractResult<TVec<_>>>()?;
-        match self.t {
+        match &self.t {
             DatumType::F32 => Ok(tvec!(make_f32(&*shape, self.seed1, self.seed2)?)),
             dt => bail!("RandomUniform not implemented for {:?}", dt),
         }
@@ -148,7 +148,7 @@ impl EvalOp for TypedRandomUniform {
 
 impl TypedOp for TypedRandomUniform {
     fn output_facts(&self, _inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
-        Ok(tvec!(TypedFact::dt_shape(self.t, &self.shape)))
+        Ok(tvec!(TypedFact::dt_shape(self.t.clone(), &self.shape)))
     }
 
     as_op!();
@@ -218,7 +218,7 @@ impl EvalOp for RandomUniformInt {
     fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
         let shape: TVec<usize> =
             inputs[0].cast_to::<i64>()?.as_slice::<i64>()?.iter().map(|&x| x as usize).collect();
-        match self.t {
+        match &self.t {
             DatumType::I32 => Ok(tvec!(Self::make_i32(
                 self,
                 &*shape,
@@ -239,9 +239,9 @@ impl InferenceRulesOp for RandomUniformInt {
     ) -> InferenceResult {
         check_input_arity(&inputs, 3)?;
         check_output_arity(&outputs, 1)?;
-        s.equals(&outputs[0].datum_type, self.t)?;
-        s.equals(&inputs[1].datum_type, self.t)?;
-        s.equals(&inputs[2].datum_type, self.t)?;
+        s.equals(&outputs[0].datum_type, self.t.clone())?;
+        s.equals(&inputs[1].datum_type, self.t.clone())?;
+        s.equals(&inputs[2].datum_type, self.t.clone())?;
         s.equals(&inputs[0].rank, 1)?;
         s.equals(&inputs[1].rank, 0)?;
         s.equals(&inputs[2].rank, 0)?;
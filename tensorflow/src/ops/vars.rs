@@ -47,6 +47,11 @@ impl OpState for VariableV2State {
             .with_context(|| format!("Could not find state for variable {}", op.id))?;
         Ok(tvec!(tensor.clone().into()))
     }
+
+    fn save(&self) -> TractResult<TVec<Tensor>> {
+        // the variable lives in the session tensors, saved with them
+        Ok(tvec!())
+    }
 }
 
 #[derive(Clone, Debug, new, Hash)]
@@ -92,7 +97,7 @@ impl EvalOp for VariableV2 {
         let tensor = if let Some(init) = &self.initializer {
             init.clone().into_tensor()
         } else {
-            unsafe { Tensor::uninitialized_dt(self.dt, &self.shape)? }
+            unsafe { Tensor::uninitialized_dt(self.dt.clone(), &self.shape)? }
         };
         state.tensors.insert(self.id.clone(), tensor);
         Ok(Some(Box::new(VariableV2State)))
@@ -108,7 +113,7 @@ impl InferenceRulesOp for VariableV2 {
     ) -> InferenceResult {
         check_input_arity(inputs, 0)?;
         check_output_arity(outputs, 1)?;
-        s.equals(&outputs[0].datum_type, self.dt)?;
+        s.equals(&outputs[0].datum_type, self.dt.clone())?;
         s.equals(&outputs[0].shape, ShapeFactoid::from(&*self.shape))?;
         Ok(())
     }
@@ -121,7 +126,7 @@ impl TypedOp for VariableV2 {
     as_op!();
 
     fn output_facts(&self, _inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
-        Ok(tvec!(TypedFact::dt_shape(self.dt, &self.shape)))
+        Ok(tvec!(TypedFact::dt_shape(self.dt.clone(), &self.shape)))
     }
 }
 
@@ -173,6 +178,11 @@ impl OpState for AssignState {
         *store = new.clone().into_tensor();
         Ok(tvec!(new))
     }
+
+    fn save(&self) -> TractResult<TVec<Tensor>> {
+        // the variable lives in the session tensors, saved with them
+        Ok(tvec!())
+    }
 }
 
 impl EvalOp for Assign {